@@ -0,0 +1,118 @@
+use super::{
+    Graph,
+    Block,
+};
+
+///Builds a graph one block at a time, keeping track of where returning, breaking and continuing flow to.
+///The graph starts out with two blocks, the entry and the exit that returning flows to.
+pub struct Builder<T>{
+    graph: Graph<T>,
+    current: usize,
+    ///Where returning flows to.
+    exit: usize,
+    ///The head of every loop being built and the block after it, innermost last.
+    loops: Vec<(usize, usize)>,
+}
+
+impl<T> Builder<T>{
+    pub fn new() -> Self{
+        Builder{
+            graph: Graph{
+                blocks: vec![Block::default(), Block::default()],
+            },
+            current: 0,
+            exit: 1,
+            loops: vec![],
+        }
+    }
+
+    ///Finish the graph, letting the end of the body flow to the exit.
+    pub fn finish(mut self) -> Graph<T>{
+        let exit = self.exit;
+        self.goto(exit);
+        self.graph
+    }
+
+    ///Add `item` to the end of the current block.
+    pub fn item(&mut self, item: T){
+        self.graph.blocks[self.current].items.push(item);
+    }
+
+    ///Continue building in a new block that control may flow to from the current one.
+    pub fn branch(&mut self) -> usize{
+        let block = self.block();
+        self.goto(block);
+        self.switch_to(block);
+        block
+    }
+
+    ///A new block that nothing flows to yet.
+    pub fn block(&mut self) -> usize{
+        self.graph.blocks.push(Block::default());
+        self.graph.blocks.len() - 1
+    }
+
+    pub fn current(&self) -> usize{
+        self.current
+    }
+
+    ///The block that returning flows to, which is where the body ends.
+    pub fn exit(&self) -> usize{
+        self.exit
+    }
+
+    ///Let control flow from the current block to `block`.
+    pub fn goto(&mut self, block: usize){
+        let current = self.current;
+        self.edge(current, block);
+    }
+
+    pub fn edge(&mut self, from: usize, to: usize){
+        if !self.graph.blocks[from].succs.contains(&to){
+            self.graph.blocks[from].succs.push(to);
+        }
+    }
+
+    ///Continue building in `block`.
+    pub fn switch_to(&mut self, block: usize){
+        self.current = block;
+    }
+
+    pub fn enter_loop(&mut self, head: usize, after: usize){
+        self.loops.push((head, after));
+    }
+
+    pub fn exit_loop(&mut self){
+        self.loops.pop();
+    }
+
+    ///Leave the current block for `target`. Whatever follows is unreachable, so it goes into a block that nothing flows to.
+    fn jump(&mut self, target: usize){
+        self.goto(target);
+        let unreachable = self.block();
+        self.switch_to(unreachable);
+    }
+
+    pub fn jump_break(&mut self){
+        if let Some((_, after)) = self.loops.last().copied(){
+            self.jump(after);
+        }
+    }
+
+    pub fn jump_continue(&mut self){
+        if let Some((head, _)) = self.loops.last().copied(){
+            self.jump(head);
+        }
+    }
+
+    pub fn jump_return(&mut self){
+        let exit = self.exit;
+        self.jump(exit);
+    }
+}
+
+impl<T> Default for Builder<T>{
+    fn default() -> Self{
+        Self::new()
+    }
+}
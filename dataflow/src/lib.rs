@@ -0,0 +1,383 @@
+pub mod builder;
+pub use builder::Builder;
+pub mod tir;
+
+///A control flow graph of basic blocks, with the entry as its first block.
+///What a block is made of is up to the pass that builds the graph, such as the events of memmy, so that each pass can write its analyses as dataflow problems over it.
+#[derive(Debug, Clone)]
+pub struct Graph<T>{
+    pub blocks: Vec<Block<T>>,
+}
+
+///Items that always happen one after the other, followed by the blocks that control may flow to next.
+#[derive(Debug, Clone)]
+pub struct Block<T>{
+    pub items: Vec<T>,
+    pub succs: Vec<usize>,
+}
+
+impl<T> Default for Block<T>{
+    fn default() -> Self{
+        Block{
+            items: vec![],
+            succs: vec![],
+        }
+    }
+}
+
+///Which way facts flow through a graph.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction{
+    ///From the entry along the edges, like whether an object may have been moved.
+    Forward,
+    ///From the exits against the edges, like whether an object may still be used.
+    Backward,
+}
+
+///A dataflow problem, which is what is known at each point of a graph and how each item changes it.
+pub trait Analysis<T>{
+    type Fact: Clone + PartialEq;
+
+    const DIRECTION: Direction;
+
+    ///What is known before anything flows in, which merging any other fact into leaves as that fact.
+    fn bottom(&self) -> Self::Fact;
+
+    ///What is known where control enters the graph: at its entry if it is forward, and at the blocks that have no successors if it is backward.
+    fn boundary(&self) -> Self::Fact{
+        self.bottom()
+    }
+
+    ///Merge what is known along another edge into `fact`.
+    fn merge(&self, fact: &mut Self::Fact, other: &Self::Fact);
+
+    ///Update `fact` across `item`, from what is known before it to what is known after it, in the direction of the analysis.
+    fn transfer(&self, item: &T, fact: &mut Self::Fact);
+}
+
+///What is known at the start and at the end of each block, in the order that the items of the block happen in regardless of the direction of the analysis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution<F>{
+    pub start: Vec<F>,
+    pub end: Vec<F>,
+}
+
+///The immediate dominator of each block, which is the entry itself for the entry, and `None` for blocks that cannot be reached from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dominators{
+    pub idoms: Vec<Option<usize>>,
+}
+
+impl<T> Graph<T>{
+    pub fn new() -> Self{
+        Graph{
+            blocks: vec![],
+        }
+    }
+
+    ///The blocks that control may flow from into each block.
+    pub fn preds(&self) -> Vec<Vec<usize>>{
+        let mut preds = vec![vec![]; self.blocks.len()];
+        for (idx, block) in self.blocks.iter().enumerate(){
+            for succ in block.succs.iter(){
+                preds[*succ].push(idx);
+            }
+        }
+        preds
+    }
+
+    ///The blocks that can be reached from the entry, in reverse postorder, so that a block comes before its successors unless it is reached through a back edge.
+    pub fn reverse_postorder(&self) -> Vec<usize>{
+        let mut order = vec![];
+        if self.blocks.is_empty(){
+            return order
+        }
+        let mut visited = vec![false; self.blocks.len()];
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        while let Some((block, next)) = stack.pop(){
+            match self.blocks[block].succs.get(next){
+                Some(succ) => {
+                    stack.push((block, next + 1));
+                    if !visited[*succ]{
+                        visited[*succ] = true;
+                        stack.push((*succ, 0));
+                    }
+                }
+                None => order.push(block)
+            }
+        }
+        order.reverse();
+        order
+    }
+
+    ///Find the immediate dominator of each block, as described in "A Simple, Fast Dominance Algorithm" by Cooper, Harvey and Kennedy.
+    pub fn dominators(&self) -> Dominators{
+        let order = self.reverse_postorder();
+        let mut rank = vec![usize::MAX; self.blocks.len()];
+        for (idx, block) in order.iter().enumerate(){
+            rank[*block] = idx;
+        }
+        let preds = self.preds();
+        let mut idoms = vec![None; self.blocks.len()];
+        if let Some(entry) = order.first(){
+            idoms[*entry] = Some(*entry);
+        }
+        let mut changed = true;
+        while changed{
+            changed = false;
+            for block in order.iter().skip(1){
+                let mut idom = None;
+                for pred in preds[*block].iter().filter(|pred| idoms[**pred].is_some()){
+                    idom = Some(match idom{
+                        Some(idom) => intersect(&idoms, &rank, idom, *pred),
+                        None => *pred
+                    });
+                }
+                if idom.is_some() && idoms[*block] != idom{
+                    idoms[*block] = idom;
+                    changed = true;
+                }
+            }
+        }
+        Dominators{
+            idoms
+        }
+    }
+
+    ///Solve `analysis` by iterating over the blocks until nothing more is learned.
+    pub fn solve<A: Analysis<T>>(&self, analysis: &A) -> Solution<A::Fact>{
+        let len = self.blocks.len();
+        let mut start = vec![analysis.bottom(); len];
+        let mut end = vec![analysis.bottom(); len];
+        let preds = self.preds();
+        let mut changed = true;
+        while changed{
+            changed = false;
+            for idx in 0..len{
+                //Visiting the blocks in the direction of the analysis learns more on each pass
+                let idx = match A::DIRECTION{
+                    Direction::Forward => idx,
+                    Direction::Backward => len - 1 - idx,
+                };
+                let block = &self.blocks[idx];
+                match A::DIRECTION{
+                    Direction::Forward => {
+                        let mut fact = if idx == 0{ analysis.boundary() }else{ analysis.bottom() };
+                        for pred in preds[idx].iter(){
+                            analysis.merge(&mut fact, &end[*pred]);
+                        }
+                        start[idx] = fact.clone();
+                        for item in block.items.iter(){
+                            analysis.transfer(item, &mut fact);
+                        }
+                        if fact != end[idx]{
+                            end[idx] = fact;
+                            changed = true;
+                        }
+                    }
+                    Direction::Backward => {
+                        let mut fact = if block.succs.is_empty(){ analysis.boundary() }else{ analysis.bottom() };
+                        for succ in block.succs.iter(){
+                            analysis.merge(&mut fact, &start[*succ]);
+                        }
+                        end[idx] = fact.clone();
+                        for item in block.items.iter().rev(){
+                            analysis.transfer(item, &mut fact);
+                        }
+                        if fact != start[idx]{
+                            start[idx] = fact;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+        Solution{
+            start,
+            end
+        }
+    }
+}
+
+impl<T> Default for Graph<T>{
+    fn default() -> Self{
+        Self::new()
+    }
+}
+
+impl Dominators{
+    ///Whether every path from the entry to `block` goes through `dominator`. Every block that can be reached dominates itself.
+    pub fn dominates(&self, dominator: usize, block: usize) -> bool{
+        let mut current = match self.idoms[block]{
+            Some(_) => block,
+            None => return false
+        };
+        loop{
+            if current == dominator{
+                return true
+            }
+            match self.idoms[current]{
+                Some(idom) if idom != current => current = idom,
+                _ => return false
+            }
+        }
+    }
+}
+
+///The nearest common dominator of `a` and `b`, given the dominators found so far.
+fn intersect(idoms: &[Option<usize>], rank: &[usize], mut a: usize, mut b: usize) -> usize{
+    while a != b{
+        while rank[a] > rank[b]{
+            a = idoms[a].unwrap_or(a);
+        }
+        while rank[b] > rank[a]{
+            b = idoms[b].unwrap_or(b);
+        }
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    ///The object an item defines, if any, and the objects it uses, such as `(Some(0), vec![1])`.
+    type Item = (Option<usize>, Vec<usize>);
+
+    fn graph(blocks: Vec<(Vec<Item>, Vec<usize>)>) -> Graph<Item>{
+        Graph{
+            blocks: blocks.into_iter().map(|(items, succs)| Block{ items, succs }).collect()
+        }
+    }
+
+    struct Liveness;
+
+    impl Analysis<Item> for Liveness{
+        type Fact = HashSet<usize>;
+
+        const DIRECTION: Direction = Direction::Backward;
+
+        fn bottom(&self) -> Self::Fact{
+            HashSet::new()
+        }
+
+        fn merge(&self, fact: &mut Self::Fact, other: &Self::Fact){
+            fact.extend(other.iter().copied());
+        }
+
+        fn transfer(&self, (def, uses): &Item, fact: &mut Self::Fact){
+            if let Some(def) = def{
+                fact.remove(def);
+            }
+            fact.extend(uses.iter().copied());
+        }
+    }
+
+    struct Defined;
+
+    impl Analysis<Item> for Defined{
+        type Fact = Option<HashSet<usize>>;
+
+        const DIRECTION: Direction = Direction::Forward;
+
+        ///Nothing has flowed in yet, which is the top of the lattice of a must analysis.
+        fn bottom(&self) -> Self::Fact{
+            None
+        }
+
+        fn boundary(&self) -> Self::Fact{
+            Some(HashSet::new())
+        }
+
+        fn merge(&self, fact: &mut Self::Fact, other: &Self::Fact){
+            *fact = match (fact.take(), other){
+                (Some(fact), Some(other)) => Some(fact.intersection(other).copied().collect()),
+                (fact, None) => fact,
+                (None, other) => other.clone(),
+            }
+        }
+
+        fn transfer(&self, (def, _): &Item, fact: &mut Self::Fact){
+            if let (Some(def), Some(fact)) = (def, fact){
+                fact.insert(*def);
+            }
+        }
+    }
+
+    fn set(objects: &[usize]) -> HashSet<usize>{
+        objects.iter().copied().collect()
+    }
+
+    #[test]
+    fn loops_are_dominated_by_their_heads() {
+        //0 -> 1, 1 -> 2 | 4, 2 -> 3 | 1, 3 -> 1, 5 is unreachable
+        let graph = graph(vec![
+            (vec![], vec![1]),
+            (vec![], vec![2, 4]),
+            (vec![], vec![3, 1]),
+            (vec![], vec![1]),
+            (vec![], vec![]),
+            (vec![], vec![4]),
+        ]);
+        let dominators = graph.dominators();
+        assert_eq!(dominators.idoms, vec![Some(0), Some(0), Some(1), Some(2), Some(1), None]);
+        assert!(dominators.dominates(1, 3));
+        assert!(dominators.dominates(3, 3));
+        assert!(!dominators.dominates(2, 4));
+        assert!(!dominators.dominates(0, 5));
+    }
+
+    #[test]
+    fn live_objects_flow_backwards_around_loops() {
+        //0 defines 0, 1 uses 0 and loops back through 2, which defines 1 and uses 1 afterwards in 3
+        let graph = graph(vec![
+            (vec![(Some(0), vec![])], vec![1]),
+            (vec![(None, vec![0])], vec![2, 3]),
+            (vec![(Some(1), vec![])], vec![1]),
+            (vec![(None, vec![1])], vec![]),
+        ]);
+        let solution = graph.solve(&Liveness);
+        assert_eq!(solution.start, vec![set(&[1]), set(&[0, 1]), set(&[0]), set(&[1])]);
+        assert_eq!(solution.end, vec![set(&[0, 1]), set(&[0, 1]), set(&[0, 1]), set(&[])]);
+    }
+
+    #[test]
+    fn objects_defined_on_one_branch_are_not_defined_after_it() {
+        //0 defines 0 and branches to 1, which defines 1, and to 2, both of which join at 3
+        let graph = graph(vec![
+            (vec![(Some(0), vec![])], vec![1, 2]),
+            (vec![(Some(1), vec![])], vec![3]),
+            (vec![], vec![3]),
+            (vec![], vec![]),
+        ]);
+        let solution = graph.solve(&Defined);
+        assert_eq!(solution.start[3], Some(set(&[0])));
+        assert_eq!(solution.end[1], Some(set(&[0, 1])));
+    }
+
+    #[test]
+    fn whatever_follows_a_jump_is_unreachable() {
+        //A loop that breaks right away, followed by a return
+        let mut flow = Builder::<Item>::new();
+        let head = flow.branch();
+        let after = flow.block();
+        flow.enter_loop(head, after);
+        flow.jump_break();
+        let broken = flow.current();
+        flow.item((None, vec![0]));
+        flow.exit_loop();
+        flow.goto(head);
+        flow.switch_to(after);
+        flow.jump_return();
+        let returned = flow.current();
+        let exit = flow.exit();
+        let graph = flow.finish();
+        let reachable = graph.reverse_postorder();
+        assert!(reachable.contains(&after) && reachable.contains(&exit));
+        assert!(!reachable.contains(&broken) && !reachable.contains(&returned));
+        assert!(graph.dominators().dominates(head, exit));
+    }
+}
@@ -0,0 +1,230 @@
+use super::{
+    Graph,
+    Builder,
+};
+
+use stmt::{
+    Statement,
+    StatementKind,
+    block::Block,
+    expr::{
+        Expr,
+        ExprElement,
+        OpKind,
+    },
+    conditional::If,
+    loops::Loop,
+    pattern::{
+        Match,
+        Pattern,
+    },
+};
+
+use ident::Identifier;
+
+///The control flow of a body of TIR, such as the body of a function or a lambda.
+///The bodies of the functions and lambdas declared in it run on their own, so they are not part of it.
+#[derive(Debug)]
+pub struct Flow<'t>{
+    pub graph: Graph<Item<'t>>,
+    ///The block that control is in when it reaches the end of the body without returning.
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Item<'t>{
+    ///The statement starts, before any of its expressions are evaluated.
+    Statement(&'t Statement),
+    ///Control only gets here when the condition evaluated to the value, such as at the start of either branch of an `if`.
+    Assume(&'t Expr, bool),
+    ///The name is given a new value, by declaring it, assigning it, or binding it to the element of a `for` loop.
+    Define(&'t Identifier),
+    ///The arm whose pattern is this has been chosen, which binds the names in the pattern.
+    Matched(&'t Pattern),
+}
+
+impl<'t> Flow<'t>{
+    pub fn new(body: &'t Block) -> Self{
+        let mut flow = Builder::new();
+        body.build(&mut flow);
+        let end = flow.current();
+        Flow{
+            graph: flow.finish(),
+            end,
+        }
+    }
+
+    ///Whether control can reach the end of the body, rather than every path returning or never finishing.
+    pub fn finishes(&self) -> bool{
+        self.graph.reverse_postorder().contains(&self.end)
+    }
+}
+
+///What can be added to the control flow of a body of TIR.
+pub trait Build<'t>{
+    fn build(&'t self, flow: &mut Builder<Item<'t>>);
+}
+
+impl<'t> Build<'t> for Block{
+    fn build(&'t self, flow: &mut Builder<Item<'t>>){
+        for statement in self.statements.iter(){
+            statement.build(flow);
+        }
+    }
+}
+
+impl<'t> Build<'t> for Statement{
+    fn build(&'t self, flow: &mut Builder<Item<'t>>){
+        flow.item(Item::Statement(self));
+        match &self.kind{
+            StatementKind::Property(property) => {
+                property.expr.build(flow);
+                flow.item(Item::Define(&property.ident));
+            }
+            StatementKind::Local(local) => {
+                local.expr.build(flow);
+                flow.item(Item::Define(&local.ident));
+            }
+            StatementKind::Fun(fun) => flow.item(Item::Define(&fun.ident)),
+            StatementKind::Expr(expr) => expr.build(flow),
+            StatementKind::Break(break_) => {
+                if let Some(value) = &break_.value{
+                    value.build(flow);
+                }
+                flow.jump_break();
+            }
+            StatementKind::Continue => flow.jump_continue(),
+            StatementKind::Return(return_) => {
+                if let Some(value) = &return_.value{
+                    value.build(flow);
+                }
+                flow.jump_return();
+            }
+            StatementKind::Assign(assign) => {
+                if let Some(receiver) = &assign.receiver{
+                    receiver.build(flow);
+                }
+                assign.value.build(flow);
+                //Assigning a member of a receiver leaves whatever `target` names alone
+                if assign.receiver.is_none(){
+                    flow.item(Item::Define(&assign.target));
+                }
+            }
+            StatementKind::Struct(_) | StatementKind::Interface(_) | StatementKind::Enum(_) => {}
+        }
+    }
+}
+
+impl<'t> Build<'t> for Expr{
+    fn build(&'t self, flow: &mut Builder<Item<'t>>){
+        match self.kind.as_ref(){
+            ExprElement::Value(_) | ExprElement::Lambda(_) => {}
+            ExprElement::Grouped(expr)
+            | ExprElement::UnaryOp(_, expr)
+            | ExprElement::ResultOk(expr)
+            | ExprElement::ResultErr(expr)
+            | ExprElement::Cast(expr, _)
+            | ExprElement::Member(expr, _)
+            | ExprElement::SafeAccess(expr, _)
+            | ExprElement::NotNull(expr)
+            | ExprElement::Is(expr, _) => expr.build(flow),
+            //The right operand is only evaluated when the left one does not decide the result already
+            ExprElement::Binary(op @ OpKind::And, left, right)
+            | ExprElement::Binary(op @ OpKind::Or, left, right)
+            | ExprElement::Binary(op @ OpKind::Elvis, left, right) => {
+                left.build(flow);
+                let after = flow.block();
+                flow.goto(after);
+                flow.branch();
+                match op{
+                    OpKind::And => flow.item(Item::Assume(left, true)),
+                    OpKind::Or => flow.item(Item::Assume(left, false)),
+                    _ => {}
+                }
+                right.build(flow);
+                flow.goto(after);
+                flow.switch_to(after);
+            }
+            ExprElement::Binary(_, left, right) => {
+                left.build(flow);
+                right.build(flow);
+            }
+            ExprElement::Call(call) => {
+                call.callee.build(flow);
+                for arg in call.args.iter(){
+                    arg.build(flow);
+                }
+            }
+            ExprElement::If(if_) => if_.build(flow),
+            ExprElement::Loop(loop_) => loop_.build(flow),
+            ExprElement::Match(match_) => match_.build(flow),
+        }
+    }
+}
+
+impl<'t> Build<'t> for If{
+    fn build(&'t self, flow: &mut Builder<Item<'t>>){
+        self.cond.build(flow);
+        let cond = flow.current();
+        let after = flow.block();
+        flow.branch();
+        flow.item(Item::Assume(&self.cond, true));
+        self.then.build(flow);
+        flow.goto(after);
+        flow.switch_to(cond);
+        flow.branch();
+        flow.item(Item::Assume(&self.cond, false));
+        if let Some(otherwise) = &self.otherwise{
+            otherwise.build(flow);
+        }
+        flow.goto(after);
+        flow.switch_to(after);
+    }
+}
+
+impl<'t> Build<'t> for Loop{
+    fn build(&'t self, flow: &mut Builder<Item<'t>>){
+        if let Some(binding) = &self.binding{
+            binding.iterable.build(flow);
+        }
+        let head = flow.branch();
+        let after = flow.block();
+        if let Some(cond) = &self.cond{
+            cond.build(flow);
+            let test = flow.current();
+            flow.branch();
+            flow.item(Item::Assume(cond, false));
+            flow.goto(after);
+            flow.switch_to(test);
+            flow.branch();
+            flow.item(Item::Assume(cond, true));
+        }
+        if let Some(binding) = &self.binding{
+            //Running out of elements leaves the loop
+            flow.goto(after);
+            flow.branch();
+            flow.item(Item::Define(&binding.ident));
+        }
+        flow.enter_loop(head, after);
+        self.body.build(flow);
+        flow.exit_loop();
+        flow.goto(head);
+        flow.switch_to(after);
+    }
+}
+
+impl<'t> Build<'t> for Match{
+    fn build(&'t self, flow: &mut Builder<Item<'t>>){
+        self.scrutinee.build(flow);
+        let scrutinee = flow.current();
+        let after = flow.block();
+        for arm in self.arms.iter(){
+            flow.switch_to(scrutinee);
+            flow.branch();
+            flow.item(Item::Matched(&arm.pattern));
+            arm.body.build(flow);
+            flow.goto(after);
+        }
+        flow.switch_to(after);
+    }
+}
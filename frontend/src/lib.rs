@@ -21,6 +21,8 @@ use std::sync::{ Arc, Mutex };
 
 use std::thread;
 
+pub use memmy::MemmyFlags;
+
 #[allow(dead_code)]
 pub struct Driver{
     lexer_manager: lexer::LexerManager,
@@ -30,7 +32,7 @@ pub struct Driver{
 }
 
 impl Driver {
-    pub fn new() -> Driver{
+    pub fn new(memmy_flags: memmy::MemmyFlags) -> Driver{
         let (_token_tx, _token_rx) = channel::<tokens::LexerToken>();
         let (_hir_tx, _hir_rx) = channel::<Option<Chunk>>();
         let (_typeck_tx, _typeck_rx) = channel::<Option<Chunk>>();
@@ -40,7 +42,7 @@ impl Driver {
         let lexer_manager = lexer::LexerManager::new();
         let parser_manager = parser::ParseManager::new();
         let typeck_manager = TypeckManager::new();
-        let memmy_manager = memmy::MemmyManager::new();
+        let memmy_manager = memmy::MemmyManager::new(memmy_flags);
         
         Driver{
             lexer_manager,
@@ -67,7 +69,6 @@ impl Driver {
         let (typeck_tx, typeck_rx) = channel::<Option<Chunk>>();
         let (mir_tx, mir_rx) = channel::<Option<Chunk>>();
 
-        #[allow(unused_mut)]
         let mut module = ir::Module::new(name.clone().to_string());
 
         self.lexer_manager.enqueue_module(name.clone().to_string(), instr.clone(), diagnostics_tx.clone(), token_tx, master_in_tx.clone(), master_out_rx_arc.clone());
@@ -86,17 +87,19 @@ impl Driver {
             }
         });
 
-        let master_communication = thread::spawn(move ||{
-            while let Ok(ModuleMessage::SourceRequest(pos)) = master_in_rx.recv(){
-                let source = instr.clone()
-                    .lines()
-                    .skip(pos.line_region.0)
-                    .take(pos.line_region.1 - pos.line_region.0)
-                    .collect();
-                master_out_tx.send(ModuleMessage::SourceResponse(source)).unwrap();
+        let master_communication = module_messages::serve_source(instr, master_in_rx, master_out_tx);
+
+        let receive_mir = thread::spawn(move ||{
+            let mut chunk = Chunk::new();
+            while let Ok(Some(mir)) = mir_rx.recv(){
+                chunk.write_chunk(mir);
             }
+            chunk
         });
 
+        if let Ok(chunk) = receive_mir.join(){
+            module.chunk = chunk;
+        }
         let _ = receive_diagnostics.join();
         let _ = master_communication.join();
         
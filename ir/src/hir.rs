@@ -32,8 +32,10 @@ pub enum HIRInstruction {
     ///The end of the function params list
     EndParams,
     //A property which must be given a name and whether it is mutable or not. An expression must follow.
+    //Once it is type checked, whether it is initialized late with `= None` comes before the expression.
     Property,
     //A local variable which must be given a name and whether it is mutable or not. An expression must follow.
+    //Once it is type checked, whether it is initialized late with `= None` comes before the expression.
     LocalVar,
 
     ///The start of a new block
@@ -45,11 +47,37 @@ pub enum HIRInstruction {
     Sub,
     Mult,
     Div,
+    Less,
+    Greater,
+    LessEq,
+    GreaterEq,
+    Eq,
+    NotEq,
+    And,
+    Or,
+    ///Logical negation of the expression that follows
+    Not,
+    ///Arithmetic negation of the expression that follows
+    Neg,
+    ///An exclusive range `start..end`. The start and end expressions must follow.
+    Range,
 
+    ///A 32 bit integer, as a type or as a literal. The literal value is written as 64 bits so that it can be range checked against its inferred type.
     Integer,
+    ///A 32 bit float, as a type or as a literal. The literal value is written as 64 bits so that it can be range checked against its inferred type.
     Float,
     Bool,
     String,
+    Int8,
+    Int16,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Float64,
+    ///A char, as a type or as a literal.
+    Char,
     //No type was specified
     Unknown,
     //A non-primitive type like A, Person, or Device
@@ -64,7 +92,114 @@ pub enum HIRInstruction {
     ///A reference to a function is just a function call
     ///A reference to a type is a type member access, either constructor, or associated member access.
     Reference,
-    
+
+    ///A statement consisting of a single expression. The expression must follow.
+    ExprStmt,
+    ///A loop. All loop forms are desugared into this.
+    ///Whether there is a condition must follow, then the condition itself if there is one.
+    ///Then whether there is a binding, followed by the binding's name and the iterable expression if there is one.
+    ///The loop body block comes last.
+    Loop,
+    ///Break out of the innermost loop. Whether there is a value must follow, then the value expression if there is one.
+    Break,
+    ///Skip to the next iteration of the innermost loop.
+    Continue,
+    ///Return from the enclosing function. Whether there is a value must follow, then the value expression if there is one.
+    Return,
+    ///An if-else. The condition and the then block must follow, followed by whether there is an else block, then the else block if there is one.
+    If,
+    ///A `where` guard on a function, written after its body. The position, the condition and the message to fail with must follow.
+    Guard,
+    ///The success value of a `Result`. The wrapped expression must follow. Only produced by desugaring function guards.
+    ResultOk,
+    ///The failure value of a `Result`. The wrapped message expression must follow. Only produced by desugaring function guards.
+    ResultErr,
+    ///A lambda literal such as `{ x -> x + 1 }`. The position, then its params as `FnParam`s ending with `EndParams`, then the body block must follow.
+    Lambda,
+    ///A call such as `f(a, b)`. The position, the callee expression, each type argument preceded by `FnParam` and each argument expression must follow, ending with `EndCall`.
+    ///A trailing lambda, as in `f(a) { x -> x }`, is written as the last argument. Type arguments are only written when they are given, as in `id<Int>(3)`.
+    Call,
+    EndCall,
+    ///A function type such as `(Int, Int) -> Int`. Each param type preceded by `FnParam` must follow, then `EndParams` and the return type.
+    FnType,
+    ///A generic type applied to type arguments, such as `Result<Int, String>`. The name, then each argument preceded by `FnParam`, then `EndParams` must follow.
+    TyApp,
+    ///A nullable type `T?`. The inner type must follow.
+    Nullable,
+    ///An assignment `a = b`. The position of the operator, the target's name and the value expression must follow.
+    ///Compound assignments such as `a += b` and increments such as `a++` are written as `a = a + b` and `a = a + 1`.
+    Assign,
+    ///A conversion `x as Int64` between numeric types or chars. The position, the expression and the target type must follow.
+    Cast,
+    ///A check `x is Int` of whether the expression has the type. The position, the expression and the type must follow.
+    Is,
+    ///The elvis operator `a ?: b`, which evaluates to `b` when `a` is None. The position and both operands must follow.
+    Elvis,
+    ///A not-None assertion `a!!`. The position and the expression must follow.
+    NotNull,
+    ///A safe member access `a?.b`, which is None when `a` is None. The position, the receiver expression and the member's name must follow.
+    SafeAccess,
+    ///A type param of a generic declaration, such as the `T: Comparable` of `fun max<T: Comparable>`.
+    ///The position and name, then each of its bounds preceded by `FnParam`, then `EndParams` must follow.
+    TyParam,
+    ///A struct declaration. The position and name, then its type params as `TyParam`s, then its constructor fields as `Field`s ending with `EndParams`.
+    ///Then the fields declared in its body as `Property`s and its methods as `Fn`s, ending with `EndStruct`.
+    Struct,
+    ///A field declared in the constructor of a struct, like the `val x: Int` of `struct Point(val x: Int)`. The position, the mutability, the name and the type must follow.
+    Field,
+    EndStruct,
+    ///A member access `a.b` of a field or a method. The position, the receiver expression and the member's name must follow.
+    Member,
+    ///An assignment `a.b = c` to a field. The position of the operator, the `Member` expression that is assigned to and the value expression must follow.
+    ///Compound assignments and increments are expanded like they are for `Assign`.
+    AssignMember,
+    ///A class declaration. The position and name, then `Open` or `Abstract` if the class is either, then its constructor fields as `Field`s ending with `EndParams`.
+    ///Then its base class as `Base` if it has one, then its body like the body of a struct, ending with `EndStruct`. A method in the body may be preceded by its modifier.
+    Class,
+    ///The base class that a class inherits from, like the `Shape(2)` of `class Square(val side: Int) : Shape(2)`. The position and name, then each argument expression to its constructor, ending with `EndCall`.
+    Base,
+    ///The modifier of a class or a method, which may be inherited from or overridden. The position must follow.
+    Open,
+    ///The modifier of a class that cannot be constructed, or of a method without a body that every class inheriting it must override. The position must follow.
+    Abstract,
+    ///The modifier of a method that overrides a method of its base class. The position must follow.
+    Override,
+    ///An interface declaration, which only classes can implement. The position and name, then its type params as `TyParam`s,
+    ///then the methods that a class implementing it must have, as `Fn`s with empty bodies, ending with `EndStruct`.
+    Interface,
+    ///A trait declaration, which only structs can implement. It is written like an interface.
+    Trait,
+    ///An interface or a trait that a class or a struct implements, written after its fields and its base class. The type must follow.
+    With,
+    ///The modifier of a method that implements a method of an interface or a trait. The position must follow.
+    Impl,
+    ///The modifier of a field that only the methods of its own struct can use, written right before the `Field` or the `Property` of the field. The position must follow.
+    Private,
+    ///An enum declaration. The position and name, then each of its variants as a `Variant`, ending with `EndStruct`.
+    Enum,
+    ///A variant of an enum, like the `Circle(Float)` of `enum Shape{ Circle(Float), Empty }`. The position and name, then the type of each of its fields preceded by `FnParam`, then `EndParams` must follow.
+    Variant,
+    ///A match expression. The position and the expression that is matched must follow, then each arm as its pattern followed by its body block, ending with `EndMatch`.
+    Match,
+    EndMatch,
+    ///A pattern `_` that matches anything. The position must follow.
+    PatternWildcard,
+    ///A pattern that matches anything and binds it to a name. The position and the name must follow.
+    ///A name of a variant without fields is written this way too, since which of them it is depends on what is matched.
+    PatternBinding,
+    ///A pattern that matches a single value. The literal expression must follow.
+    PatternLiteral,
+    ///A pattern that matches a variant of an enum, like `Circle(r)`. The position and the name of the variant, then the pattern of each of its fields, ending with `EndParams`.
+    PatternVariant,
+    ///The type that an extension function is declared on, like the `String` of `fun String.shout()`. It comes right after the position of `Fn`, and the type must follow.
+    Receiver,
+    ///An explicit reference `ref x` to an object, whose type is the reference type `r(x)`. The position and the expression naming the object must follow.
+    Ref,
+    ///An explicit move `move x` of a local, which may not be used again until it is assigned. The position and the expression naming the local must follow.
+    Move,
+    ///An explicit copy `copy x` of an object, which must be of a type that can be copied. The position and the expression naming the object must follow.
+    Copy,
+
     Halt,
 }
 
@@ -72,9 +207,18 @@ impl HIRInstruction{
     pub fn from_string(string: String) -> (Self, Option<String>){
         match string.clone().as_str(){
             "String" => (Self::String, None),
-            "Int" => (Self::Integer, None),
-            "Float" => (Self::Float, None),
+            "Int" | "Int32" => (Self::Integer, None),
+            "Float" | "Float32" => (Self::Float, None),
             "Bool" => (Self::Bool, None),
+            "Int8" => (Self::Int8, None),
+            "Int16" => (Self::Int16, None),
+            "Int64" => (Self::Int64, None),
+            "UInt8" => (Self::UInt8, None),
+            "UInt16" => (Self::UInt16, None),
+            "UInt32" => (Self::UInt32, None),
+            "UInt64" => (Self::UInt64, None),
+            "Float64" => (Self::Float64, None),
+            "Char" => (Self::Char, None),
             _ => (Self::Unknown, Some(string))
         }
     }
@@ -238,7 +382,7 @@ impl std::fmt::Display for Chunk{
                             return Err(std::fmt::Error{})
                         }
                     };
-                    let value = self.read_int();
+                    let value = self.read_long();
                     writeln!(f, "{} {}", Colour::Cyan.paint("Integer"), Colour::White.paint(value.to_string()))?;
                 }
                 Some(HIRInstruction::Float) => {
@@ -249,9 +393,20 @@ impl std::fmt::Display for Chunk{
                             return Err(std::fmt::Error{})
                         }
                     };
-                    let value = self.read_float();
+                    let value = self.read_double();
                     writeln!(f, "{}{}Float{}{}", pos, padding(), padding(), value)?;
                 }
+                Some(HIRInstruction::Char) => {
+                    let pos = match self.read_pos(){
+                        Ok(pos) => pos,
+                        Err(msg) => {
+                            println!("{}", msg);
+                            return Err(std::fmt::Error{})
+                        }
+                    };
+                    let value = self.read_char();
+                    writeln!(f, "{}{}Char{}{:?}", pos, padding(), padding(), value)?;
+                }
                 Some(HIRInstruction::String) => {
                     let pos = match self.read_pos(){
                         Ok(pos) => pos,
@@ -79,6 +79,16 @@ impl Chunk{
         return int;
     }
 
+    pub fn read_long(&self) -> i64{
+        self.read_long_at(self.ins_ptr.clone().into_inner())
+    }
+
+    pub fn read_long_at(&self, idx: usize) -> i64{
+        let long = i64::from_be_bytes(unsafe { *(self.code[idx..idx+8].as_ptr() as *const [u8; 8]) });
+        self.inc_ins_ptr(8);
+        return long;
+    }
+
     pub fn write_pos(&mut self, bipos: BiPos){
         self.write_usize(bipos.start.0);
         self.write_usize(bipos.start.1);
@@ -137,10 +147,25 @@ impl Chunk{
 
     pub fn read_float_at(&self, idx: usize) -> f32{
         let float = f32::from_be_bytes(unsafe { *(self.code[idx..idx+4].as_ptr() as *const [u8; 4]) });
-        self.inc_ins_ptr(5);
+        self.inc_ins_ptr(4);
         return float
     }
 
+    pub fn read_double(&self) -> f64{
+        self.read_double_at(self.ins_ptr.clone().into_inner())
+    }
+
+    pub fn read_double_at(&self, idx: usize) -> f64{
+        let double = f64::from_be_bytes(unsafe { *(self.code[idx..idx+8].as_ptr() as *const [u8; 8]) });
+        self.inc_ins_ptr(8);
+        return double
+    }
+
+    ///Read a char written by [write_char]. An invalid char is read as the replacement character.
+    pub fn read_char(&self) -> char{
+        std::char::from_u32(self.read_int() as u32).unwrap_or(std::char::REPLACEMENT_CHARACTER)
+    }
+
     pub fn read_string(&self) -> &str{
         self.read_string_at(self.ins_ptr.clone().into_inner())
     }
@@ -194,6 +219,14 @@ impl Chunk{
         self.code.extend(int.to_be_bytes().iter())
     }
 
+    pub fn write_long(&mut self, long: i64){
+        self.code.extend(long.to_be_bytes().iter())
+    }
+
+    pub fn write_char(&mut self, c: char){
+        self.write_int(c as u32 as i32)
+    }
+
     pub fn write_float(&mut self, float: f32){
         self.code.extend(float.to_be_bytes().iter())
     }
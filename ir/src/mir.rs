@@ -19,7 +19,7 @@ use serde::{Serialize, Deserialize};
 #[derive(FromPrimitive, Instruction, ReadInstruction, WriteInstruction, Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[repr(u8)]
 pub enum MIRInstructions{
-    ///Module start
+    ///Module start. The name of the module must follow, then each of its declarations.
     Module,
     ///End module
     EndModule,
@@ -31,8 +31,8 @@ pub enum MIRInstructions{
     ///This is where all drops to local variables and any references or values passed as arguments will occur.
     EndFun,
 
-    //Function param.
-    //The call to the containing function will handle the pass-by.
+    ///Function param `name` and its size, which comes after the position of `Fun` or `Lambda`. The body block follows the last of them.
+    ///The call to the containing function will handle the pass-by.
     FunParam,
     //Literals
     ///Integer literal
@@ -47,11 +47,13 @@ pub enum MIRInstructions{
     Unit,
     ///Initialize object `name` with `mutability`.
     ///An allocation instruction must precede this with the size of the object.
-    ///Following this will be a call to the initializer and its mutability.
+    ///Following this will be its initializer: a call to the constructor of its struct that is passed the object first, a `SetTag` for a value of an enum,
+    ///or else the expression whose value it is initialized with.
     ObjInit,
     ///Drop `name`. This can either be a value or a reference.
     ///The drop mechanism is smart. If what is being dropped is a reference, 
     ///the reference counter will decrement the count for object `name`.
+    ///If it destroys an object of a struct with drop glue instead, the hooks of its `DropGlue` are called on it first.
     Drop,
 
     //Memory management instructions
@@ -69,6 +71,7 @@ pub enum MIRInstructions{
     HeapAlloc,
     ///Stack allocation of `size` for object `name`.
     ///Either an object contruction or a lateinit instruction must proceed this.
+    ///In the place of an expression, this allocates a temporary object whose value the expression is once it has been constructed.
     StackAlloc,
     ///Uninitialized/late initializer.
     ///This is used for leaving an resource empty until further notice.
@@ -77,10 +80,132 @@ pub enum MIRInstructions{
     ///`None` is an object that can be stretched to fit any place whatsoever, and will simply just be garbage data.
     ///The syntax for this is:
     ///     let something: A = None
+    ///It takes the place of `ObjInit`, followed by `name` and `mutability` but no initializer, since the first `ObjMut` of the object initializes it.
     Lateinit,
     ///Mutate object `name`.
     ///An expression must proceed this instruction.
     ObjMut,
+
+    //Classes
+    ///The vtable of class `name` and its number of slots. Each slot follows as a `VTableSlot`, ending with `EndVTable`.
+    ///The first field of every object of a class points to the vtable of its class, which is how its methods are dispatched and how `is` checks are made at runtime.
+    VTable,
+    ///Slot `index` of a vtable, followed by whether it is filled and then the full path of the method that fills it.
+    ///The slot of an abstract method is left empty, which is never called since an abstract class cannot be constructed.
+    VTableSlot,
+    EndVTable,
+    ///Call the method in slot `index` of the vtable of the object it is called on, followed by whether it is a safe call `x?.method()`.
+    ///The object and then the args must follow, each arg preceded by `true` and the last one followed by `false`.
+    VirtualCall,
+    ///The itable of interface `name` within a vtable, and its number of slots. Each slot follows as an `ITableSlot`.
+    ITable,
+    ///Slot `index` of an itable, followed by the slot of the vtable that the method of the interface at `index` is called through.
+    ITableSlot,
+    ///Call the method at `index` of interface `name` on an object of a class that implements it, through the itable of the class.
+    ///Whether it is a safe call, the object and then the args must follow, like they do for `VirtualCall`.
+    InterfaceCall,
+
+    //Traits
+    ///The trait mask of struct `name`, which has a bit set for each of its traits.
+    ///It is stored in the hidden first field of every object of the struct, so that `is` checks can be made at runtime.
+    TraitMask,
+    ///Whether bit `bit` is set in the trait mask of the object that follows, which is how `x is Trait` is checked.
+    HasTrait,
+
+    //Enums
+    ///The tagged union `name`, with its size and the size of its tag. Each variant follows as a `Variant`, ending with `EndEnum`.
+    ///Every variant starts with the tag, and its fields are laid out after it, so the variants of an enum share the same memory.
+    Enum,
+    ///Variant `name` of an enum and its tag, followed by its number of fields and then the offset of each of them.
+    Variant,
+    EndEnum,
+    ///Write tag `tag` into object `name`, which is how a value of an enum is constructed.
+    ///The MIR of its fields follows, each preceded by `true` and its offset, and the last one followed by `false`.
+    SetTag,
+    ///Whether the tag of the object that follows is `tag`, which is how a pattern that matches a variant is checked.
+    ///As a pattern, the object is the value being matched, and the pattern of each field follows like the fields of `SetTag` do.
+    TestTag,
+
+    //Structs
+    ///The struct `name` with its size and number of fields, followed by the name and offset of each field.
+    ///Its vtable or its trait mask, its constructor and its methods follow as functions named by their full paths, then its `DropGlue` if it has any, ending with `EndStruct`.
+    ///The constructor is named like the struct and takes the object it initializes as `this`, as do its methods.
+    Struct,
+    EndStruct,
+    ///The drop glue of struct `name` and its number of hooks, each followed by the full path of a `drop` method and the place it is called on, such as `this.buffer`.
+    ///The hooks are called in order: the `drop` method of the object itself, then those of the classes it inherits from, then those of its fields in declaration order.
+    ///An object of a class is dropped with the glue of its actual class, which is found through its vtable.
+    DropGlue,
+    ///Field `name` at `offset` of the object that follows.
+    Field,
+    ///Member `name` of the value that follows, for a member that is not a field of a struct.
+    Member,
+    ///Member `name` of the nullable value that follows, which is None when the value is.
+    SafeAccess,
+
+    //Expressions
+    ///A char literal.
+    Char,
+    ///The None of a nullable type.
+    None,
+    ///The function `name`, which is how a function is called and passed around as a value.
+    Function,
+    ///Call the function that follows. Its args follow it, each preceded by `true` and the last one followed by `false`.
+    Call,
+    ///A lambda, followed by what it captures, each preceded by `true` as a `Ref`, `Copy` or `Move` of it, and the last one followed by `false`.
+    ///Its params follow as `FunParam`s, then its body block. The objects that it owns are dropped before `EndLambda`.
+    Lambda,
+    EndLambda,
+    ///Convert the value that follows into type `name`.
+    Cast,
+    ///Whether the value that follows has type `name`.
+    Is,
+    ///The value of the nullable value that follows, which must not be None.
+    NotNull,
+    Add,
+    Sub,
+    Mult,
+    Div,
+    Less,
+    Greater,
+    LessEq,
+    GreaterEq,
+    Eq,
+    NotEq,
+    And,
+    Or,
+    Range,
+    ///The value on the left, or the value on the right if the left one is None.
+    Elvis,
+    Not,
+    Neg,
+
+    //Control flow
+    ///The start of a block. Its statements follow, then the drops of the objects declared in it, ending with `EndBlock`.
+    ///Its value is the value of its last statement.
+    Block,
+    EndBlock,
+    ///A branch, followed by its condition and the block taken when it holds. Then `true` and the block taken otherwise, or `false` if there is none.
+    If,
+    ///A loop, followed by `true` and its condition or `false` if it has none, then `true`, the name of its binding and the range it iterates over,
+    ///or `false` if it has none, then its body block. The objects created anew by each iteration are dropped at the end of the body block.
+    Loop,
+    ///Leave the innermost loop, followed by `true` and the value it leaves with or `false` if there is none.
+    ///Then the number of objects that leaving drops, followed by a `Drop` of each of them.
+    Break,
+    ///Start the next iteration of the innermost loop, followed by the drops of the objects of the iteration like `Break` is.
+    Continue,
+    ///Return from the function, followed by its value and its drops like `Break` is.
+    Return,
+    ///A match, followed by the value that is matched. Then each arm, preceded by `true`, as its pattern and then its body block,
+    ///and the last one followed by `false`.
+    Match,
+    ///A pattern that matches anything.
+    PatternWildcard,
+    ///A pattern that moves the value that it matches into the new object `name` of `size`, which is dropped at the end of the body of its arm.
+    PatternBinding,
+    ///A pattern that matches a value equal to the literal that follows.
+    PatternLiteral,
     ///Halt compiler
     Halt
 }
\ No newline at end of file
@@ -26,6 +26,37 @@ lazy_static! {
         m.insert("native", tokens::TokenType::KwNative);
         m.insert("fun", tokens::TokenType::KwFun);
         m.insert("None", tokens::TokenType::KwNone);
+        m.insert("true", tokens::TokenType::KwTrue);
+        m.insert("false", tokens::TokenType::KwFalse);
+        m.insert("if", tokens::TokenType::KwIf);
+        m.insert("else", tokens::TokenType::KwElse);
+        m.insert("loop", tokens::TokenType::KwLoop);
+        m.insert("while", tokens::TokenType::KwWhile);
+        m.insert("for", tokens::TokenType::KwFor);
+        m.insert("in", tokens::TokenType::KwIn);
+        m.insert("break", tokens::TokenType::KwBreak);
+        m.insert("continue", tokens::TokenType::KwContinue);
+        m.insert("return", tokens::TokenType::KwReturn);
+        m.insert("where", tokens::TokenType::KwWhere);
+        m.insert("as", tokens::TokenType::KwAs);
+        m.insert("is", tokens::TokenType::KwIs);
+        m.insert("with", tokens::TokenType::KwWith);
+        m.insert("null", tokens::TokenType::KwNull);
+        m.insert("struct", tokens::TokenType::KwStruct);
+        m.insert("class", tokens::TokenType::KwClass);
+        m.insert("open", tokens::TokenType::KwOpen);
+        m.insert("abstract", tokens::TokenType::KwAbstract);
+        m.insert("override", tokens::TokenType::KwOverride);
+        m.insert("interface", tokens::TokenType::KwInterface);
+        m.insert("trait", tokens::TokenType::KwTrait);
+        m.insert("impl", tokens::TokenType::KwImpl);
+        m.insert("private", tokens::TokenType::KwPrivate);
+        m.insert("enum", tokens::TokenType::KwEnum);
+        m.insert("match", tokens::TokenType::KwMatch);
+        m.insert("ref", tokens::TokenType::KwRef);
+        m.insert("move", tokens::TokenType::KwMove);
+        m.insert("copy", tokens::TokenType::KwCopy);
+        m.insert("drop", tokens::TokenType::KwDrop);
         m
     };
 }
@@ -124,6 +155,14 @@ impl<'a, 'b> Lexer{
         }
     }
 
+    ///Peek at the character after the next one. This is used for two character operators such as `==` and `..`.
+    fn peek_second(&mut self) -> Option<char>{
+        match &self.source{
+            Some(src) => src.chars().nth(1),
+            None => None
+        }
+    }
+
     fn advance(&mut self) -> Option<char> {
         match &self.source{
             Some(src) => {
@@ -190,6 +229,30 @@ impl<'a, 'b> Lexer{
         }
     }
 
+    fn is_compound_delimiter(&self, c: char, next: char) -> Option<tokens::TokenType> {
+        match (c, next) {
+            ('=', '=') => Some(tokens::TokenType::EqualEqual),
+            ('!', '=') => Some(tokens::TokenType::BangEqual),
+            ('<', '=') => Some(tokens::TokenType::LAngleEqual),
+            ('>', '=') => Some(tokens::TokenType::RAngleEqual),
+            ('&', '&') => Some(tokens::TokenType::AndAnd),
+            ('|', '|') => Some(tokens::TokenType::PipePipe),
+            ('.', '.') => Some(tokens::TokenType::DotDot),
+            ('-', '>') => Some(tokens::TokenType::Arrow),
+            ('+', '=') => Some(tokens::TokenType::PlusEqual),
+            ('-', '=') => Some(tokens::TokenType::MinusEqual),
+            ('*', '=') => Some(tokens::TokenType::StarEqual),
+            ('/', '=') => Some(tokens::TokenType::SlashEqual),
+            ('+', '+') => Some(tokens::TokenType::PlusPlus),
+            ('-', '-') => Some(tokens::TokenType::MinusMinus),
+            ('?', '.') => Some(tokens::TokenType::QMarkDot),
+            ('?', ':') => Some(tokens::TokenType::QMarkColon),
+            ('!', '!') => Some(tokens::TokenType::BangBang),
+            ('=', '>') => Some(tokens::TokenType::FatArrow),
+            _ => None,
+        }
+    }
+
     fn is_keyword(&self, identifier: &str) -> tokens::TokenType {
         match IDENT_MAP.get(identifier) {
             Some(token_type) => *token_type,
@@ -202,6 +265,11 @@ impl<'a, 'b> Lexer{
         let mut is_float = false;
         while let Some(c) = self.peek() {
             if c == '.' {
+                //A range such as `0..10` or a member access such as `3.double()` is not a float
+                match self.peek_second(){
+                    Some(next) if next.is_digit(10) => {}
+                    _ => break
+                }
                 is_float = true;
                 self.advance_end();
             } else if c.is_digit(10) {
@@ -224,9 +292,9 @@ impl<'a, 'b> Lexer{
         let number = num_str.trim();
         // println!("number slice: {}", number);
         Some(tokens::LexerToken {
-            type_: tokens::TokenType::Number,
+            type_: if is_float { tokens::TokenType::Decimal } else { tokens::TokenType::Number },
             data: if is_float {
-                tokens::TokenData::Float(match number.parse::<f32>() {
+                tokens::TokenData::Float(match number.parse::<f64>() {
                     Ok(f) => f,
                     Err(e) => {
                         return Some(tokens::LexerToken {
@@ -240,7 +308,7 @@ impl<'a, 'b> Lexer{
                     }
                 })
             } else {
-                tokens::TokenData::Integer(match number.parse::<i32>() {
+                tokens::TokenData::Integer(match number.parse::<i64>() {
                     Ok(f) => f,
                     Err(e) => {
                         return Some(tokens::LexerToken {
@@ -286,6 +354,35 @@ impl<'a, 'b> Lexer{
         })
     }
 
+    ///Lex a char literal such as `'a'` or `'\n'`.
+    fn char_literal(&mut self) -> Option<tokens::LexerToken> {
+        self.advance_end();
+        let c = match self.advance_end(){
+            Some('\\') => match self.advance_end(){
+                Some('n') => Some('\n'),
+                Some('t') => Some('\t'),
+                Some('r') => Some('\r'),
+                Some('0') => Some('\0'),
+                Some(c) if c == '\\' || c == '\'' => Some(c),
+                _ => None
+            },
+            Some('\'') | None => None,
+            c => c,
+        };
+        match (c, self.advance_end()){
+            (Some(c), Some('\'')) => Some(tokens::LexerToken {
+                type_: tokens::TokenType::Char,
+                data: tokens::TokenData::Char(c),
+                pos: self.current_pos,
+            }),
+            _ => Some(tokens::LexerToken {
+                type_: tokens::TokenType::Err,
+                data: tokens::TokenData::String("Expected a single character between apostrophes".to_string()),
+                pos: self.current_pos,
+            })
+        }
+    }
+
     fn get_token(&mut self) -> Result<Option<tokens::LexerToken>, DiagnosticSource> {
         // println!("Processing char: {:?}", self.peek());
         match self.peek() {
@@ -364,8 +461,25 @@ impl<'a, 'b> Lexer{
                         let number = self.number();
                         return Ok(number);
                     },
+                    '\'' => return Ok(self.char_literal()),
                     c if self.is_delimiter(c).is_some() => {
                         // println!("Found a delimiter: {}", c.clone());
+                        let compound = match self.peek_second(){
+                            Some(next) => self.is_compound_delimiter(c, next).map(|type_| (type_, next)),
+                            None => None,
+                        };
+                        if let Some((type_, next)) = compound{
+                            let mut pos = self.current_pos;
+                            pos.next_col_end();
+                            let token = tokens::LexerToken {
+                                data: tokens::TokenData::String(format!("{}{}", c, next)),
+                                type_,
+                                pos,
+                            };
+                            self.advance();
+                            self.advance();
+                            return Ok(Some(token));
+                        }
                         let token = tokens::LexerToken {
                             data: tokens::TokenData::String(c.to_string()),
                             type_: self.is_delimiter(c).unwrap(),
@@ -40,10 +40,34 @@ pub enum TokenType {
     Hash,
     At,
 
+    EqualEqual,
+    BangEqual,
+    LAngleEqual,
+    RAngleEqual,
+    AndAnd,
+    PipePipe,
+    DotDot,
+    Arrow,
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PlusPlus,
+    MinusMinus,
+    ///`?.`, a safe call
+    QMarkDot,
+    ///`?:`, the elvis operator
+    QMarkColon,
+    ///`!!`, a not-None assertion
+    BangBang,
+    ///`=>`, which separates the pattern of a match arm from its body
+    FatArrow,
+
     Identifier,
     String,
     Number,
     Decimal,
+    Char,
 
     KwVal,
     KwVar,
@@ -54,16 +78,27 @@ pub enum TokenType {
     KwMut,
 
     KwStruct,
+    KwClass,
+    KwOpen,
+    KwAbstract,
+    KwOverride,
+    KwInterface,
+    KwTrait,
+    KwImpl,
+    KwEnum,
     KwReturn,
     KwMod,
     KwNative,
     KwPublic,
+    KwPrivate,
 
     KwIf,
     KwElse,
+    KwMatch,
     KwLoop,
     KwWhile,
     KwFor,
+    KwIn,
     KwBreak,
     KwContinue,
 
@@ -73,7 +108,14 @@ pub enum TokenType {
     KwNone,
 
     KwAs,
+    KwIs,
     KwWith,
+    KwWhere,
+
+    KwRef,
+    KwMove,
+    KwCopy,
+    KwDrop,
 
     Err,
     Eof,
@@ -82,8 +124,9 @@ pub enum TokenType {
 #[derive(Debug, Clone)]
 pub enum TokenData {
     None,
-    Integer(i32),
-    Float(f32),
+    Integer(i64),
+    Float(f64),
+    Char(char),
     String(String),
 }
 
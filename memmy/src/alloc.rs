@@ -0,0 +1,59 @@
+use super::{
+    Unload,
+    layout::Layout,
+};
+
+use core::pos::BiPos;
+
+use ir::{
+    Chunk,
+    mir::MIRInstructions,
+};
+
+use ir_traits::WriteInstruction;
+
+///Where the memory of an object that is constructed lives.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Place{
+    ///Locals live on the stack of the function they are declared in, and so do the properties that never escape it.
+    Stack,
+    ///Properties are allocated on the heap, since references to them may outlive the function that declares them.
+    Heap,
+}
+
+///The memory reserved for the object `name`.
+#[derive(Debug, Clone)]
+pub struct Allocation{
+    pub name: String,
+    pub place: Place,
+    pub layout: Layout,
+    pub mutable: bool,
+    ///Whether the object is initialized late, by its first assignment rather than by an initializer.
+    pub lateinit: bool,
+    pub pos: BiPos,
+}
+
+impl Unload for Allocation{
+    ///Write the `StackAlloc` or `HeapAlloc` of the object's size, followed by its `ObjInit`, which the MIR of its initializer follows, or by its `Lateinit`.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        match self.place{
+            Place::Stack => chunk.write_instruction(MIRInstructions::StackAlloc),
+            Place::Heap => chunk.write_instruction(MIRInstructions::HeapAlloc),
+        }
+        chunk.write_pos(self.pos);
+        chunk.write_str(&self.name);
+        chunk.write_usize(self.layout.size);
+        chunk.write_instruction(if self.lateinit{ MIRInstructions::Lateinit }else{ MIRInstructions::ObjInit });
+        chunk.write_pos(self.pos);
+        chunk.write_str(&self.name);
+        chunk.write_bool(self.mutable);
+        Ok(chunk)
+    }
+}
+
+///The name of the temporary object that the struct or the enum constructed at `pos` is allocated as, when it is not constructed into a named object.
+///It is not a valid identifier, so no local can have it.
+pub fn temporary(pos: BiPos) -> String{
+    format!("<{}:{}>", pos.start.0, pos.start.1)
+}
@@ -0,0 +1,133 @@
+use super::{
+    Load,
+    Unload,
+    ident::Identifier,
+    expr::Expression,
+    cfg::{
+        Build,
+        Builder,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+    MemmyGenerator,
+};
+
+use core::pos::BiPos;
+
+use ir::{
+    Chunk,
+    mir::MIRInstructions,
+};
+
+use ir_traits::WriteInstruction;
+
+use notices::{
+    DiagnosticSourceBuilder,
+    DiagnosticLevel
+};
+
+///A mutation of an existing object or of a field of one. Typeck has already made sure that it is mutable.
+#[derive(Debug, Clone)]
+pub struct Assign{
+    pub target: Identifier,
+    ///The object whose field `target` is, for an assignment to a field.
+    pub receiver: Option<Expression>,
+    pub expression: Expression,
+    ///Whether the object releases the references it held before it is assigned, which drops them.
+    pub releases: bool,
+    pub pos: BiPos,
+}
+
+impl Load for Assign{
+    type Output = Assign;
+
+    fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
+            }
+        };
+        let target = Identifier::load(chunk, memmy)?;
+        let receiver = if chunk.read_bool(){
+            Some(Expression::load(chunk, memmy)?)
+        }else{
+            //Mutating an object from inside of a lambda means that the lambda holds on to it
+            memmy.env.reference(&target);
+            None
+        };
+        let expression = Expression::load(chunk, memmy)?;
+        Ok(Assign{
+            target,
+            receiver,
+            expression,
+            releases: false,
+            pos
+        })
+    }
+}
+
+impl Unload for Assign{
+    ///Write the `ObjMut` for the target, followed by the MIR of the new value.
+    ///A field of a named object is written as the path to it, such as `p.x`.
+    ///An object that held references until it is assigned is dropped first, which releases them.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        if self.releases{
+            chunk.write_instruction(MIRInstructions::Drop);
+            chunk.write_pos(self.pos);
+            chunk.write_str(&self.target.ident);
+        }
+        chunk.write_instruction(MIRInstructions::ObjMut);
+        chunk.write_pos(self.pos);
+        let target = match self.receiver.as_ref().and_then(|receiver| receiver.place()){
+            Some(place) => format!("{}.{}", place, self.target.ident),
+            None => self.target.ident.clone()
+        };
+        chunk.write_str(&target);
+        chunk.write_chunk(self.expression.unload()?);
+        Ok(chunk)
+    }
+}
+
+impl<'t> Build<'t> for Assign{
+    ///Assigning to a local gives it a new value, while assigning to a field of an object only looks into the object.
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        self.expression.build(cfg);
+        match &self.receiver{
+            Some(receiver) => receiver.build_receiver(cfg),
+            None => cfg.assign(&self.target, self.target.pos),
+        }
+    }
+}
+
+impl Count for Assign{
+    ///Assigning to a field of an object makes the object hold what the new value refers to.
+    ///The references are taken where the target is named, which is what the errors about them point at.
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        let referents = self.expression.count(counter);
+        let pos = self.target.pos;
+        match &mut self.receiver{
+            Some(receiver) => {
+                receiver.count(counter);
+                if let Some(root) = receiver.root(){
+                    counter.assign_field(root, referents, pos);
+                }
+            }
+            None => {
+                let borrowed = if self.expression.is_reference(){ referents.clone() }else{ vec![] };
+                self.releases = counter.assign(&self.target, referents, pos);
+                counter.borrow(&self.target, &borrowed, pos);
+            }
+        }
+        vec![]
+    }
+}
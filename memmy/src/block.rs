@@ -0,0 +1,139 @@
+use super::{
+    statements::Statement,
+    cfg::{
+        Build,
+        Builder,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+    Load,
+    Unload,
+    MemmyGenerator,
+};
+
+use core::pos::BiPos;
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+    mir::MIRInstructions,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+use notices::{
+    DiagnosticSourceBuilder,
+    DiagnosticLevel
+};
+
+#[derive(Debug, Clone)]
+pub struct Block{
+    pub statements: Vec<Statement>,
+    pub pos: BiPos,
+    ///The objects declared in the block, in the order they are dropped in at its end.
+    pub drops: Vec<String>,
+}
+
+impl Load for Block{
+    type Output = Block;
+
+    fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
+        if let Some(HIRInstruction::Block) = chunk.read_instruction() as Option<HIRInstruction>{}else{
+            let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                .message("Expected a block.".to_string())
+                .level(DiagnosticLevel::Error)
+                .build();
+            memmy.emit_diagnostic(&[], &[diagnosis]);
+            return Err(())
+        }
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                    .message(msg)
+                    .level(DiagnosticLevel::Error)
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
+            }
+        };
+        let mut statements = vec![];
+        //Whatever is declared in the block is only visible until its end
+        let depth = memmy.env.depth();
+        loop{
+            if let Some(HIRInstruction::EndBlock) = chunk.read_instruction(){
+                break;
+            }
+            chunk.dec_ins_ptr(1);
+            match Statement::load(chunk, memmy){
+                Ok(statement) => statements.push(statement),
+                Err(diag) => {
+                    memmy.env.truncate(depth);
+                    return Err(diag)
+                }
+            }
+        }
+        let drops = memmy.env.drops_since(depth);
+        memmy.env.end_scope(depth, pos);
+        Ok(Block{
+            statements,
+            pos,
+            drops
+        })
+    }
+}
+
+impl Unload for Block{
+    fn unload(&self) -> Result<Chunk, ()> {
+        self.unload_dropping(&[])
+    }
+}
+
+impl Block{
+    ///Write the block, dropping `drops` after what is declared in it. This is how the objects that a loop or an arm of a match declares for its body are dropped.
+    pub fn unload_dropping(&self, drops: &[String]) -> Result<Chunk, ()>{
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(MIRInstructions::Block);
+        chunk.write_pos(self.pos);
+        for statement in self.statements.iter(){
+            chunk.write_chunk(statement.unload()?);
+        }
+        for drop in self.drops.iter().chain(drops.iter()){
+            chunk.write_instruction(MIRInstructions::Drop);
+            chunk.write_pos(self.pos);
+            chunk.write_str(drop);
+        }
+        chunk.write_instruction(MIRInstructions::EndBlock);
+        Ok(chunk)
+    }
+}
+
+impl<'t> Build<'t> for Block{
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        cfg.enter_scope();
+        for statement in self.statements.iter(){
+            statement.build(cfg);
+        }
+        cfg.exit_scope();
+    }
+}
+
+impl Count for Block{
+    ///The objects declared in the block are dropped at its end once nothing refers to them anymore.
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        counter.enter_scope();
+        let mut value = vec![];
+        for statement in self.statements.iter_mut(){
+            value = statement.count(counter);
+        }
+        self.drops = counter.order_drops(&self.drops);
+        counter.exit_scope();
+        value
+    }
+}
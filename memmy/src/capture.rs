@@ -0,0 +1,343 @@
+use super::{
+    ident::Identifier,
+    lifetime::{
+        ObjectLifetime,
+        DropPoint,
+        Reference,
+        RefKind,
+        Flow,
+    },
+};
+
+use core::pos::BiPos;
+
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    rc::Rc,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingKind{
+    Property,
+    ///A local, function param, lambda param or loop binding.
+    Local,
+}
+
+///A named object that is visible at the point being loaded.
+#[derive(Debug, Clone)]
+pub struct Binding{
+    pub ident: String,
+    pub kind: BindingKind,
+    pub ty: Ty,
+    ///The lifetime of the object, which is shared with its declaration so that it keeps growing as the object is referenced.
+    pub lifetime: Rc<RefCell<ObjectLifetime>>,
+}
+
+///How a lambda holds on to an object declared outside of it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaptureMode{
+    ///Properties are borrowed, so a lambda must not outlive the properties it captures, see [crate::refcount::RefCounter].
+    Ref,
+    ///Locals of primitive types are copied into the lambda.
+    Copy,
+    ///Any other local is moved into the lambda, which then becomes responsible for dropping it.
+    Move,
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct Capture{
+    pub ident: Identifier,
+    pub mode: CaptureMode,
+    ///Whether a local that would be moved into the lambda is copied into it instead, since it is used again after the lambda is created.
+    pub copied: Cell<bool>,
+}
+
+impl Binding{
+    fn capture_mode(&self) -> CaptureMode{
+        match self.kind{
+            BindingKind::Property => CaptureMode::Ref,
+            BindingKind::Local if is_copy(&self.ty) => CaptureMode::Copy,
+            BindingKind::Local => CaptureMode::Move,
+        }
+    }
+}
+
+///Whether objects of type `ty` are primitive values, which are copied rather than moved when a lambda captures them, and copied rather than referred to where a property is named.
+pub(crate) fn is_copy(ty: &Ty) -> bool{
+    match ty.kind().as_ref(){
+        TyKind::Unit | TyKind::Bool | TyKind::Char => true,
+        _ => ty.is_numeric()
+    }
+}
+
+///Tracks the bindings visible at the point being loaded, so that the lambdas being loaded know what they capture.
+#[derive(Debug, Default)]
+pub struct Environment{
+    ///Every visible binding, in declaration order.
+    bindings: RefCell<Vec<Binding>>,
+    ///For every lambda being loaded, innermost last: how many bindings are declared outside of it, and what it has captured so far.
+    lambdas: RefCell<Vec<(usize, Vec<Capture>)>>,
+    ///For every function or lambda being loaded, innermost last: how many bindings are declared outside of it.
+    frames: RefCell<Vec<usize>>,
+    ///For every loop being loaded, innermost last: how many bindings are declared outside of it.
+    loops: RefCell<Vec<usize>>,
+    ///For every control flow being loaded, innermost last: how many bindings are declared outside of it.
+    flows: RefCell<Vec<(Flow, usize)>>,
+    ///How many boundaries of control flow have been crossed so far, which tells the stages of lifetimes apart.
+    boundaries: Cell<usize>,
+    ///The lifetime of every object declared so far, in declaration order.
+    lifetimes: RefCell<Vec<Rc<RefCell<ObjectLifetime>>>>,
+}
+
+impl Environment{
+    ///Declare the object `ident`, returning its lifetime.
+    pub fn declare(&self, ident: &Identifier, kind: BindingKind, ty: &Ty) -> Rc<RefCell<ObjectLifetime>>{
+        let lifetime = Rc::new(RefCell::new(ObjectLifetime::new(ident)));
+        self.lifetimes.borrow_mut().push(lifetime.clone());
+        self.bindings.borrow_mut().push(Binding{
+            ident: ident.ident.clone(),
+            kind,
+            ty: ty.clone(),
+            lifetime: lifetime.clone(),
+        });
+        lifetime
+    }
+
+    ///The kind of the innermost visible binding named `ident`, if there is one.
+    pub fn kind(&self, ident: &str) -> Option<BindingKind>{
+        self.bindings.borrow().iter().rev().find(|binding| binding.ident == ident).map(|binding| binding.kind.clone())
+    }
+
+    ///The type of the innermost visible binding named `ident`, if there is one.
+    pub fn ty(&self, ident: &str) -> Option<Ty>{
+        self.bindings.borrow().iter().rev().find(|binding| binding.ident == ident).map(|binding| binding.ty.clone())
+    }
+
+    ///The type of the object `x` that a reference of type `r(x)` binds to. Any other type is its own.
+    pub fn deref(&self, ty: &Ty) -> Ty{
+        let mut ty = ty.clone();
+        let mut seen = vec![];
+        while let TyKind::Ref(term) = ty.kind().as_ref(){
+            if seen.contains(term){
+                break
+            }
+            seen.push(term.clone());
+            ty = match self.ty(term){
+                Some(referent) => referent,
+                None => break
+            };
+        }
+        ty
+    }
+
+    ///The number of visible bindings. Hand this back to `truncate` at the end of a scope to forget what was declared in it.
+    pub fn depth(&self) -> usize{
+        self.bindings.borrow().len()
+    }
+
+    pub fn truncate(&self, depth: usize){
+        self.bindings.borrow_mut().truncate(depth);
+    }
+
+    ///End the scope that starts after the first `depth` bindings at `pos`, which is where what it declares is dropped.
+    pub fn end_scope(&self, depth: usize, pos: BiPos){
+        self.drop_since(depth, DropPoint::EndOfScope(pos));
+        self.truncate(depth);
+    }
+
+    ///Record that the bindings declared after the first `depth` are dropped at `point`.
+    fn drop_since(&self, depth: usize, point: DropPoint){
+        for binding in self.bindings.borrow().iter().skip(depth){
+            binding.lifetime.borrow_mut().drops.push(point.clone());
+        }
+    }
+
+    ///Start loading code that runs in `flow`, which is a boundary of control flow.
+    pub fn enter_flow(&self, flow: Flow){
+        let depth = self.depth();
+        self.flows.borrow_mut().push((flow, depth));
+        self.boundaries.set(self.boundaries.get() + 1);
+    }
+
+    ///Finish loading the innermost control flow. The code after it is past another boundary.
+    pub fn exit_flow(&self){
+        self.flows.borrow_mut().pop();
+        self.boundaries.set(self.boundaries.get() + 1);
+    }
+
+    ///The lifetime of every object declared so far, in declaration order.
+    pub fn lifetimes(&self) -> Vec<ObjectLifetime>{
+        self.lifetimes.borrow().iter().map(|lifetime| lifetime.borrow().clone()).collect()
+    }
+
+    ///Start loading the body of a function or a lambda, after its params are declared.
+    pub fn enter_frame(&self, params: usize){
+        let depth = self.depth() - params;
+        self.frames.borrow_mut().push(depth);
+    }
+
+    pub fn exit_frame(&self){
+        self.frames.borrow_mut().pop();
+    }
+
+    ///Whether the body of a function or a lambda is being loaded, rather than the module itself.
+    pub fn in_frame(&self) -> bool{
+        !self.frames.borrow().is_empty()
+    }
+
+    ///Start loading a loop, before its binding is declared.
+    pub fn enter_loop(&self){
+        let depth = self.depth();
+        self.loops.borrow_mut().push(depth);
+    }
+
+    pub fn exit_loop(&self){
+        self.loops.borrow_mut().pop();
+    }
+
+    ///The objects that returning from the innermost function or lambda at `pos` drops, which are all of its params and the objects declared in it so far.
+    pub fn frame_drops(&self, pos: BiPos) -> Vec<String>{
+        let depth = self.frames.borrow().last().copied().unwrap_or(0);
+        self.drop_since(depth, DropPoint::EarlyExit(pos));
+        self.drops_since(depth)
+    }
+
+    ///The objects that leaving an iteration of the innermost loop at `pos` drops, which are its binding and the objects declared in its body so far.
+    pub fn loop_drops(&self, pos: BiPos) -> Vec<String>{
+        let depth = self.loops.borrow().last().copied().unwrap_or_else(|| self.depth());
+        self.drop_since(depth, DropPoint::EarlyExit(pos));
+        self.drops_since(depth)
+    }
+
+    ///The bindings declared after the first `depth`, in the reverse order that they were declared in, which is the order they are dropped in.
+    pub fn drops_since(&self, depth: usize) -> Vec<String>{
+        self.bindings.borrow().iter().skip(depth).rev().map(|binding| binding.ident.clone()).collect()
+    }
+
+    ///Start loading a lambda. Every binding visible at this point is outside of it.
+    pub fn enter_lambda(&self){
+        let depth = self.depth();
+        self.lambdas.borrow_mut().push((depth, vec![]));
+    }
+
+    ///Finish loading the innermost lambda, returning what it captured.
+    pub fn exit_lambda(&self) -> Vec<Capture>{
+        match self.lambdas.borrow_mut().pop(){
+            Some((depth, captures)) => {
+                self.truncate(depth);
+                captures
+            }
+            None => vec![]
+        }
+    }
+
+    ///Record a reference to `ident` in the lifetime of its object. It is captured by every lambda being loaded that it was declared outside of.
+    pub fn reference(&self, ident: &Identifier){
+        let bindings = self.bindings.borrow();
+        let idx = match bindings.iter().rposition(|binding| binding.ident == ident.ident){
+            Some(idx) => idx,
+            None => return
+        };
+        let kind = match bindings[idx].kind{
+            BindingKind::Property => RefKind::PropertyRef,
+            BindingKind::Local => RefKind::LocalRef,
+        };
+        //Only the control flow entered after the object was declared can keep the reference from happening whenever the object is alive
+        let flow = self.flows.borrow().iter().rev().find(|(_, depth)| idx < *depth).map_or(Flow::Straight, |(flow, _)| *flow);
+        bindings[idx].lifetime.borrow_mut().reference(Reference{
+            pos: ident.pos,
+            kind,
+        }, flow, self.boundaries.get());
+        let mode = bindings[idx].capture_mode();
+        for (depth, captures) in self.lambdas.borrow_mut().iter_mut(){
+            if idx < *depth && !captures.iter().any(|capture| capture.ident.ident == ident.ident){
+                captures.push(Capture{
+                    ident: ident.clone(),
+                    mode: mode.clone(),
+                    copied: Cell::new(false),
+                });
+            }
+        }
+    }
+
+    ///Change the kind of the reference to `ident` that was just recorded, once what it is used for is known.
+    pub fn refer_as(&self, ident: &Identifier, kind: RefKind){
+        if let Some(binding) = self.bindings.borrow().iter().rev().find(|binding| binding.ident == ident.ident){
+            binding.lifetime.borrow_mut().refer_as(ident.pos, kind);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::pos::BiPos;
+
+    fn ty(kind: TyKind) -> Ty{
+        Ty::new(kind, BiPos::default())
+    }
+
+    fn ident(name: &str) -> Identifier{
+        Identifier{
+            ident: name.to_owned(),
+            pos: BiPos::default()
+        }
+    }
+
+    #[test]
+    fn nested_lambdas_capture_from_their_own_outside() {
+        let env = Environment::default();
+        env.declare(&ident("greeting"), BindingKind::Property, &ty(TyKind::String));
+        env.declare(&ident("count"), BindingKind::Local, &ty(TyKind::Int));
+        env.enter_lambda();
+        env.declare(&ident("name"), BindingKind::Local, &ty(TyKind::String));
+        env.enter_lambda();
+        env.reference(&ident("greeting"));
+        env.reference(&ident("name"));
+        env.reference(&ident("count"));
+        env.reference(&ident("count"));
+        let inner = env.exit_lambda();
+        let outer = env.exit_lambda();
+        let modes = |captures: &[Capture]| captures.iter().map(|capture| (capture.ident.ident.clone(), capture.mode.clone())).collect::<Vec<_>>();
+        assert_eq!(modes(&inner), vec![
+            ("greeting".to_owned(), CaptureMode::Ref),
+            ("name".to_owned(), CaptureMode::Move),
+            ("count".to_owned(), CaptureMode::Copy),
+        ]);
+        assert_eq!(modes(&outer), vec![
+            ("greeting".to_owned(), CaptureMode::Ref),
+            ("count".to_owned(), CaptureMode::Copy),
+        ]);
+        assert_eq!(env.depth(), 2);
+    }
+
+    #[test]
+    fn lifetimes_split_into_stages_at_control_flow() {
+        let env = Environment::default();
+        let count = env.declare(&ident("count"), BindingKind::Local, &ty(TyKind::Int));
+        env.reference(&ident("count"));
+        env.reference(&ident("count"));
+        env.enter_flow(Flow::Loop);
+        env.declare(&ident("i"), BindingKind::Local, &ty(TyKind::Int));
+        env.reference(&ident("count"));
+        env.reference(&ident("i"));
+        env.exit_flow();
+        env.end_scope(1, BiPos::default());
+        env.reference(&ident("count"));
+        let stages = count.borrow().stages.iter().map(|stage| (stage.flow, stage.references.len())).collect::<Vec<_>>();
+        assert_eq!(stages, vec![(Flow::Straight, 2), (Flow::Loop, 1), (Flow::Straight, 1)]);
+        assert!(count.borrow().drops.is_empty());
+        let i = &env.lifetimes()[1];
+        assert_eq!(i.stages[0].flow, Flow::Straight);
+        assert_eq!(i.drops, vec![DropPoint::EndOfScope(BiPos::default())]);
+    }
+}
@@ -0,0 +1,183 @@
+use super::{
+    statements::Statement,
+    ident::Identifier,
+    expr::Access,
+    capture::Capture,
+};
+
+use core::pos::BiPos;
+
+use ty::Ty;
+
+use dataflow::Graph;
+
+use std::cell::Cell;
+
+///The control flow of the body of a function, a lambda or a constructor, in terms of what happens to the locals and the properties declared in it.
+#[derive(Debug)]
+pub struct Cfg<'t>{
+    ///Each block is made of events that always happen one after the other.
+    pub graph: Graph<Event<'t>>,
+    ///Every object declared in the body, which the events refer to by index.
+    pub objects: Vec<Object>,
+}
+
+#[derive(Debug)]
+pub struct Object{
+    pub ident: Identifier,
+    pub ty: Ty,
+    ///Whether the object is immutable and declared without a value, so it has to be assigned exactly once.
+    pub once: bool,
+}
+
+#[derive(Debug)]
+pub enum Event<'t>{
+    ///The object is declared with a value.
+    Def(usize),
+    ///The object is declared with `= None`, so it has no value until it is assigned one.
+    Lateinit(usize),
+    ///The object is assigned at the position, so the value it had before is never used again.
+    Assign(usize, BiPos),
+    ///The object is passed by value, which can be a move if it is not used again afterwards.
+    Use(Use<'t>),
+    ///The object is referred to at the position without being passed, such as when one of its members is accessed.
+    Touch(usize, BiPos),
+}
+
+#[derive(Debug)]
+pub struct Use<'t>{
+    pub object: usize,
+    pub pos: BiPos,
+    pub site: Site<'t>,
+}
+
+///Where in the loaded module the decision between copying and moving an object is written to.
+#[derive(Debug)]
+pub enum Site<'t>{
+    ///An object that is named where a value is expected.
+    Access(&'t Cell<Access>),
+    ///An object that a lambda captures by value.
+    Capture(&'t Capture),
+    ///An object that is moved or copied with `move` or `copy`, which leaves nothing to decide.
+    Explicit(Access),
+}
+
+///Build the graph of the module, after the graphs of every body declared in it.
+pub fn graphs<'t>(statements: &'t [Statement]) -> Vec<Cfg<'t>>{
+    let mut module = Builder::new();
+    for statement in statements.iter(){
+        statement.build(&mut module);
+    }
+    module.finish()
+}
+
+///What can be added to a control flow graph.
+pub trait Build<'t>{
+    fn build(&'t self, cfg: &mut Builder<'t>);
+}
+
+///Builds the graph of one body, along with the graphs of the functions and lambdas declared in it.
+pub struct Builder<'t>{
+    ///The blocks of the graph and where control flows between them.
+    pub flow: dataflow::Builder<Event<'t>>,
+    objects: Vec<Object>,
+    ///The objects visible at the point being built, by scope.
+    scopes: Vec<Vec<usize>>,
+    ///The graphs of every body declared in this one.
+    graphs: Vec<Cfg<'t>>,
+}
+
+impl<'t> Builder<'t>{
+    pub fn new() -> Self{
+        Builder{
+            flow: dataflow::Builder::new(),
+            objects: vec![],
+            scopes: vec![vec![]],
+            graphs: vec![],
+        }
+    }
+
+    ///Finish the graph, returning it after the graphs of the bodies declared in it.
+    pub fn finish(mut self) -> Vec<Cfg<'t>>{
+        self.graphs.push(Cfg{
+            graph: self.flow.finish(),
+            objects: self.objects,
+        });
+        self.graphs
+    }
+
+    ///Add the graph of a body that is declared in this one, but runs on its own.
+    pub fn nest(&mut self, graphs: Vec<Cfg<'t>>){
+        self.graphs.extend(graphs);
+    }
+
+    pub fn declare(&mut self, ident: &Identifier, ty: &Ty){
+        let object = self.object(ident, ty, false);
+        self.event(Event::Def(object));
+    }
+
+    ///Declare `ident` without a value, which an immutable object is given by its only assignment.
+    pub fn lateinit(&mut self, ident: &Identifier, ty: &Ty, mutable: bool){
+        let object = self.object(ident, ty, !mutable);
+        self.event(Event::Lateinit(object));
+    }
+
+    fn object(&mut self, ident: &Identifier, ty: &Ty, once: bool) -> usize{
+        let object = self.objects.len();
+        self.objects.push(Object{
+            ident: ident.clone(),
+            ty: ty.clone(),
+            once,
+        });
+        if let Some(scope) = self.scopes.last_mut(){
+            scope.push(object);
+        }
+        object
+    }
+
+    ///The innermost visible object named `ident`.
+    fn resolve(&self, ident: &Identifier) -> Option<usize>{
+        self.scopes.iter().rev().flat_map(|scope| scope.iter().rev()).find(|object| self.objects[**object].ident.ident == ident.ident).copied()
+    }
+
+    ///The type of the innermost visible object named `ident`.
+    pub fn ty(&self, ident: &Identifier) -> Option<Ty>{
+        self.resolve(ident).map(|object| self.objects[object].ty.clone())
+    }
+
+    ///Record that `ident` is assigned a new value at `pos`.
+    pub fn assign(&mut self, ident: &Identifier, pos: BiPos){
+        if let Some(object) = self.resolve(ident){
+            self.event(Event::Assign(object, pos));
+        }
+    }
+
+    ///Record that `ident` is passed by value at `pos`, with where the decision between copying and moving it goes.
+    pub fn use_object(&mut self, ident: &Identifier, pos: BiPos, site: Site<'t>){
+        if let Some(object) = self.resolve(ident){
+            self.event(Event::Use(Use{
+                object,
+                pos,
+                site
+            }));
+        }
+    }
+
+    pub fn touch(&mut self, ident: &Identifier, pos: BiPos){
+        if let Some(object) = self.resolve(ident){
+            self.event(Event::Touch(object, pos));
+        }
+    }
+
+    fn event(&mut self, event: Event<'t>){
+        self.flow.item(event);
+    }
+
+    pub fn enter_scope(&mut self){
+        self.scopes.push(vec![]);
+    }
+
+    pub fn exit_scope(&mut self){
+        self.scopes.pop();
+    }
+}
@@ -0,0 +1,101 @@
+use super::{
+    block::Block,
+    expr::Expression,
+    lifetime::Flow,
+    cfg::{
+        Build,
+        Builder,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+    Load,
+    Unload,
+    MemmyGenerator,
+};
+
+use ir::Chunk;
+
+#[derive(Debug, Clone)]
+pub struct If{
+    cond: Expression,
+    then: Block,
+    otherwise: Option<Block>,
+}
+
+impl Load for If{
+    type Output = If;
+
+    fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
+        let cond = Expression::load(chunk, memmy)?;
+        memmy.env.enter_flow(Flow::Branch);
+        let then = Block::load(chunk, memmy);
+        memmy.env.exit_flow();
+        let then = then?;
+        let otherwise = if chunk.read_bool(){
+            memmy.env.enter_flow(Flow::Branch);
+            let otherwise = Block::load(chunk, memmy);
+            memmy.env.exit_flow();
+            match otherwise{
+                Ok(block) => Some(block),
+                Err(diag) => return Err(diag)
+            }
+        }else{
+            None
+        };
+        Ok(If{
+            cond,
+            then,
+            otherwise
+        })
+    }
+}
+
+
+impl Unload for If{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = self.cond.unload()?;
+        chunk.write_chunk(self.then.unload()?);
+        match &self.otherwise{
+            Some(otherwise) => {
+                chunk.write_bool(true);
+                chunk.write_chunk(otherwise.unload()?);
+            }
+            None => chunk.write_bool(false)
+        }
+        Ok(chunk)
+    }
+}
+
+impl<'t> Build<'t> for If{
+    ///Either branch may run after the condition, and both of them join after the `if`.
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        self.cond.build(cfg);
+        let cond = cfg.flow.current();
+        let after = cfg.flow.block();
+        cfg.flow.branch();
+        self.then.build(cfg);
+        cfg.flow.goto(after);
+        cfg.flow.switch_to(cond);
+        if let Some(otherwise) = &self.otherwise{
+            cfg.flow.branch();
+            otherwise.build(cfg);
+        }
+        cfg.flow.goto(after);
+        cfg.flow.switch_to(after);
+    }
+}
+
+impl Count for If{
+    ///The value of an `if` is the value of either of its branches.
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        self.cond.count(counter);
+        let mut referents = self.then.count(counter);
+        if let Some(otherwise) = &mut self.otherwise{
+            referents.extend(otherwise.count(counter));
+        }
+        referents
+    }
+}
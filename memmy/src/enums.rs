@@ -0,0 +1,166 @@
+use super::{
+    Load,
+    Unload,
+    ident::Identifier,
+    layout::{
+        Layout,
+        StructLayout,
+        EnumLayout,
+    },
+    MemmyGenerator,
+};
+
+use core::pos::BiPos;
+
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+    mir::MIRInstructions,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+use notices::{
+    DiagnosticSourceBuilder,
+    DiagnosticLevel
+};
+
+///An enum, which is laid out as a tagged union of its variants.
+#[derive(Debug, Clone)]
+pub struct Enum{
+    pub ident: Identifier,
+    pub layout: EnumLayout,
+    pub pos: BiPos,
+}
+
+impl Load for Enum{
+    type Output = Enum;
+
+    ///Load an enum and register its layout, so that its variants can be constructed and matched on.
+    fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
+            }
+        };
+        let ident = Identifier::load(chunk, memmy)?;
+        let mut variants = vec![];
+        let mut copyable = true;
+        loop{
+            match chunk.read_instruction(){
+                Some(HIRInstruction::Variant) => {
+                    let variant = Identifier::load(chunk, memmy)?;
+                    let mut fields = vec![];
+                    while let Some(HIRInstruction::FnParam) = chunk.read_instruction() as Option<HIRInstruction>{
+                        let ty = Ty::load(chunk, memmy)?;
+                        copyable &= memmy.is_copyable(&ty);
+                        match memmy.layout(&ty){
+                            Some(layout) => fields.push(layout),
+                            None => {
+                                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), variant.pos.start.0)
+                                    .level(DiagnosticLevel::Error)
+                                    .message(format!("Cannot compute the size of field {} of the variant {} of type {}", fields.len(), variant.ident, ty))
+                                    .range(variant.pos.col_range())
+                                    .build();
+                                memmy.emit_diagnostic(&[], &[diagnosis]);
+                                return Err(())
+                            }
+                        }
+                    }
+                    variants.push((variant.ident, fields));
+                }
+                Some(HIRInstruction::EndStruct) => break,
+                ins => {
+                    let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                        .level(DiagnosticLevel::Error)
+                        .message(format!("Expected a variant but instead got {:?}", ins))
+                        .build();
+                    memmy.emit_diagnostic(&[], &[diagnosis]);
+                    return Err(())
+                }
+            }
+        }
+        let layout = EnumLayout::new(&variants);
+        let path = format!("{}::{}", memmy.module_name, ident.ident);
+        //An enum has no fields of its own, but it is laid out wherever a struct could be
+        memmy.structs.borrow_mut().insert(path.clone(), StructLayout{ layout: layout.layout, offsets: vec![] });
+        if copyable{
+            memmy.copyable.borrow_mut().insert(path.clone());
+        }
+        for (variant, _) in layout.variants.iter(){
+            memmy.variants.borrow_mut().insert(variant.clone(), path.clone());
+        }
+        memmy.enums.borrow_mut().insert(path, layout.clone());
+        Ok(Enum{
+            ident,
+            layout,
+            pos
+        })
+    }
+}
+
+impl Unload for Enum{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(MIRInstructions::Enum);
+        chunk.write_pos(self.pos);
+        chunk.write_str(&self.ident.ident);
+        chunk.write_usize(self.layout.layout.size);
+        chunk.write_usize(self.layout.tag.size);
+        for (tag, (name, variant)) in self.layout.variants.iter().enumerate(){
+            chunk.write_instruction(MIRInstructions::Variant);
+            chunk.write_str(name);
+            chunk.write_usize(tag);
+            //The tag is not a field of the variant
+            chunk.write_usize(variant.offsets.len() - 1);
+            for (_, offset) in variant.offsets.iter().skip(1){
+                chunk.write_usize(*offset);
+            }
+        }
+        chunk.write_instruction(MIRInstructions::EndEnum);
+        Ok(chunk)
+    }
+}
+
+impl MemmyGenerator{
+    ///The layout of `ty` as an enum, if it is an enum of this module, a `Result` or nullable.
+    ///A nullable value is laid out like the enum `None | Some(T)`, so that `None` has the tag 0.
+    pub fn enum_layout(&self, ty: &Ty) -> Option<EnumLayout>{
+        match ty.kind().as_ref(){
+            TyKind::Option(inner) => {
+                let some = self.layout(&ty.child(inner))?;
+                Some(EnumLayout::new(&[("None".to_string(), vec![]), ("Some".to_string(), vec![some])]))
+            }
+            TyKind::App(path, args) if path.len() == 1 && path[0] == "Result" && args.len() == 2 => {
+                let ok = self.layout(&ty.child(&args[0]))?;
+                let err = self.layout(&ty.child(&args[1]))?;
+                Some(EnumLayout::new(&[("Ok".to_string(), vec![ok]), ("Err".to_string(), vec![err])]))
+            }
+            TyKind::Named(path) => self.enums.borrow().get(&path.join("::")).cloned(),
+            _ => None
+        }
+    }
+
+    ///The tag of the variant `name` of an enum of this module, the layout of the enum and the offsets of the fields of the variant.
+    pub fn variant_layout(&self, name: &str) -> Option<(usize, Layout, Vec<usize>)>{
+        let variants = self.variants.borrow();
+        let enums = self.enums.borrow();
+        let layout = enums.get(variants.get(name)?)?;
+        let (tag, variant) = layout.variant(name)?;
+        Some((tag, layout.layout, variant.offsets.iter().skip(1).map(|(_, offset)| *offset).collect()))
+    }
+}
@@ -0,0 +1,91 @@
+use super::lifetime::{
+    ObjectLifetime,
+    at,
+};
+
+use core::pos::BiPos;
+
+use std::fmt;
+
+///Where the memory of a property lives. Properties are allocated on the heap, unless escape analysis proves that none of their references outlive the function that declares them.
+///See [crate::refcount::RefCounter::escape].
+#[derive(Debug, Clone)]
+pub enum Residence{
+    ///On the stack of the function that declares it.
+    Stack,
+    ///On the heap, since it escapes the function that declares it, or is declared in the module.
+    Heap(Escape),
+}
+
+///Why a property has to be allocated on the heap.
+#[derive(Debug, Clone)]
+pub enum Escape{
+    ///It is declared in the module, so it lives as long as the module does.
+    Module,
+    ///It is passed to `callee` at `pos`, which may keep a reference to it after it returns.
+    Passed{
+        callee: String,
+        pos: BiPos,
+    },
+    ///It is returned at `pos` from the function that declares it, so it outlives the function.
+    Returned{
+        pos: BiPos,
+    },
+}
+
+impl ObjectLifetime{
+    ///The line of the allocation report for a property, which tells where it is allocated and why.
+    pub fn residence_report(&self) -> Option<String>{
+        let residence = self.residence.as_ref()?;
+        Some(format!("{} at {} {}", self.ident, at(&self.def), residence))
+    }
+}
+
+impl fmt::Display for Residence{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            Residence::Stack => write!(f, "is allocated on the stack, since no reference to it outlives the function that declares it"),
+            Residence::Heap(Escape::Module) => write!(f, "is allocated on the heap, since it is declared in the module and lives as long as it does"),
+            Residence::Heap(Escape::Passed{ callee, pos }) => write!(f, "is allocated on the heap, since it is passed to {} at {}, which may keep a reference to it", callee, at(pos)),
+            Residence::Heap(Escape::Returned{ pos }) => write!(f, "is allocated on the heap, since it is returned at {} and outlives the function that declares it", at(pos)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MemmyGenerator;
+
+    fn allocations(source: &str) -> Vec<String>{
+        let (mir, messages, allocations) = MemmyGenerator::analyze_source(source);
+        assert_eq!(messages, Vec::<String>::new());
+        assert!(mir.is_some());
+        allocations
+    }
+
+    #[test]
+    fn properties_lent_to_callees_that_keep_them_escape() {
+        assert_eq!(allocations("class Counter(var n: Int)\n\nvar keep = Counter(0)\n\nfun store(x: Counter){\n    keep = x\n}\n\nfun read(x: Counter): Int = x.n\n\nfun main(){\n    val a = Counter(1)\n    val b = Counter(2)\n    val n = read(a)\n    store(b)\n}\n"), vec![
+            format!("keep at 2:5 is allocated on the heap, since it is declared in the module and lives as long as it does"),
+            format!("a at 11:9 is allocated on the stack, since no reference to it outlives the function that declares it"),
+            format!("b at 12:9 is allocated on the heap, since it is passed to store at 14:5, which may keep a reference to it"),
+            format!("n at 13:9 is allocated on the stack, since no reference to it outlives the function that declares it"),
+        ]);
+    }
+
+    #[test]
+    fn returned_properties_escape() {
+        assert_eq!(allocations("class Counter(var n: Int)\n\nfun make(): Counter{\n    val c = Counter(1)\n    return c\n}\n\nfun build(flag: Bool): Counter{\n    val a = Counter(2)\n    if (flag) {\n        return a\n    }\n    val b = Counter(3)\n    b\n}\n"), vec![
+            format!("c at 3:9 is allocated on the heap, since it is returned at 4:5 and outlives the function that declares it"),
+            format!("a at 8:9 is allocated on the heap, since it is returned at 10:9 and outlives the function that declares it"),
+            format!("b at 12:9 is allocated on the heap, since it is returned at 13:5 and outlives the function that declares it"),
+        ]);
+    }
+
+    #[test]
+    fn properties_captured_by_returned_lambdas_escape() {
+        assert_eq!(allocations("fun counter(): () -> Int {\n    var n = 0\n    return { -> n }\n}\n"), vec![
+            format!("n at 1:9 is allocated on the heap, since it is returned at 2:5 and outlives the function that declares it"),
+        ]);
+    }
+}
@@ -1,16 +1,56 @@
 use super::{
     Load,
+    Unload,
     ident::Identifier,
+    loops::Loop,
+    conditional::If,
+    lambda::Lambda,
+    patterns::Match,
+    layout::{
+        Layout,
+        layout_of,
+    },
+    capture::BindingKind,
+    lifetime::RefKind,
+    cfg::{
+        Build,
+        Builder,
+        Site,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+    alloc::{
+        Allocation,
+        Place,
+        temporary,
+    },
     MemmyGenerator
 };
 
 use core::pos::BiPos;
 
+use ty::{
+    Ty,
+    TyKind,
+};
+
 use ir::{
     Chunk,
     hir::HIRInstruction,
+    mir::MIRInstructions,
+};
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+use std::{
+    cell::Cell,
+    collections::HashMap,
 };
-use ir_traits::ReadInstruction;
 
 use notices::{
     DiagnosticSourceBuilder,
@@ -20,11 +60,16 @@ use notices::{
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum Value{
-    Int(i32),
-    Float(f32),
+    ///An integer literal and the integer type it was inferred as.
+    Int(i64, Ty),
+    ///A float literal and the float type it was inferred as.
+    Float(f64, Ty),
+    Char(char),
     String(String),
     Bool(bool),
     Custom(Identifier),
+    Unit,
+    None,
 }
 
 #[derive(Debug, Clone)]
@@ -32,8 +77,51 @@ pub enum Value{
 
 pub enum ExpressionKind{
     Value(Value),
+    ///An object, and how it is passed where it is referred to. Whether a local is copied or moved is only known once the module is loaded, see [MemmyGenerator::infer_moves].
+    Ref(Identifier, Cell<Access>),
+    ///An object that is passed the way that `ref`, `move` or `copy` says, whether or not it is used again.
+    Explicit(Identifier, Access),
+    ///A function of this module, referred to by its name.
+    Function(Identifier),
     Group(Expression),
+    Unary(OpKind, Expression),
     Binary(OpKind, Expression, Expression),
+    Loop(Loop),
+    If(If),
+    Lambda(Lambda),
+    Call(Expression, Vec<Expression>),
+    Cast(Expression, Ty),
+    SafeAccess(Expression, String),
+    Member(Expression, String),
+    ///A field of a struct, with its offset within the struct.
+    Field(Expression, String, usize),
+    ///A method of a struct that is called directly, with the full path of the method such as `main::Point::sum`.
+    Method(Expression, String, String),
+    ///A method of a class that is called through the slot of the vtable of the object it is called on, since a class that inherits it may override it.
+    ///The flag tells whether it is a safe call `x?.member`.
+    VirtualMember(Expression, String, usize, bool),
+    ///A method of an interface, called on an object of a class that implements it through the itable of its class.
+    ///This is followed by the full path of the interface and the index of the method in it, then whether it is a safe call.
+    InterfaceMember(Expression, String, String, usize, bool),
+    ///A call to the constructor of a struct, with the full path and the layout of the struct.
+    ObjInit(Identifier, String, Layout, Vec<Expression>),
+    ///A value of a variant of an enum, with its tag, the layout of the enum and the offsets of its fields. A variant without fields is constructed by naming it.
+    ///The `Ok` and `Err` that a function with a `where` guard returns are constructed this way too.
+    VariantInit(Identifier, usize, Layout, Vec<usize>, Vec<Expression>),
+    Match(Match),
+    NotNull(Expression),
+    Is(Expression, Ty),
+    ///`x is Trait` on a struct, which checks whether bit `bit` of the trait mask of the object is set.
+    HasTrait(Expression, usize),
+}
+
+///How an object is passed where it is referred to. Properties are passed by reference, while locals are passed by value.
+///A local is moved where it is used for the last time, and copied anywhere else, unless `ref`, `move` or `copy` says otherwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Access{
+    Ref,
+    Copy,
+    Move,
 }
 
 #[derive(Debug, Clone)]
@@ -41,7 +129,19 @@ pub enum OpKind{
     Plus,
     Minus,
     Mult,
-    Div
+    Div,
+    Less,
+    Greater,
+    LessEq,
+    GreaterEq,
+    Eq,
+    NotEq,
+    And,
+    Or,
+    Not,
+    Neg,
+    Range,
+    Elvis,
 }
 
 #[derive(Debug, Clone)]
@@ -67,22 +167,68 @@ impl Load for Expression{
         };
 
         let opcode = &chunk.read_instruction();
+        let binary_op = match &opcode{
+            Some(HIRInstruction::Add) => Some(OpKind::Plus),
+            Some(HIRInstruction::Sub) => Some(OpKind::Minus),
+            Some(HIRInstruction::Mult) => Some(OpKind::Mult),
+            Some(HIRInstruction::Div) => Some(OpKind::Div),
+            Some(HIRInstruction::Less) => Some(OpKind::Less),
+            Some(HIRInstruction::Greater) => Some(OpKind::Greater),
+            Some(HIRInstruction::LessEq) => Some(OpKind::LessEq),
+            Some(HIRInstruction::GreaterEq) => Some(OpKind::GreaterEq),
+            Some(HIRInstruction::Eq) => Some(OpKind::Eq),
+            Some(HIRInstruction::NotEq) => Some(OpKind::NotEq),
+            Some(HIRInstruction::And) => Some(OpKind::And),
+            Some(HIRInstruction::Or) => Some(OpKind::Or),
+            Some(HIRInstruction::Range) => Some(OpKind::Range),
+            Some(HIRInstruction::Elvis) => Some(OpKind::Elvis),
+            _ => None
+        };
+        if let Some(op) = binary_op{
+            let left = match Expression::load(chunk, memmy){
+                Ok(left) => left,
+                Err(diag) => return Err(diag)
+            };
+            let right = match Expression::load(chunk, memmy){
+                Ok(right) => right,
+                Err(diag) => return Err(diag)
+            };
+            return Ok(Expression{
+                kind: Box::new(ExpressionKind::Binary(op, left, right)),
+                pos,
+            })
+        }
         match &opcode{
             Some(HIRInstruction::Integer) => {
-                let value = chunk.read_int();
+                let value = chunk.read_long();
+                let ty = match Ty::load(chunk, memmy){
+                    Ok(ty) => ty,
+                    Err(diag) => return Err(diag)
+                };
                 return Ok(Expression{
-                    kind: Box::new(ExpressionKind::Value(Value::Int(value))),
+                    kind: Box::new(ExpressionKind::Value(Value::Int(value, ty))),
                     pos,
                 })
             }
             Some(HIRInstruction::Float) => {
-                let value = chunk.read_float();
+                let value = chunk.read_double();
+                let ty = match Ty::load(chunk, memmy){
+                    Ok(ty) => ty,
+                    Err(diag) => return Err(diag)
+                };
                 return Ok(Expression{
-                    kind: Box::new(ExpressionKind::Value(Value::Float(value))),
+                    kind: Box::new(ExpressionKind::Value(Value::Float(value, ty))),
                     pos,
                 })
 
             }
+            Some(HIRInstruction::Char) => {
+                let value = chunk.read_char();
+                return Ok(Expression{
+                    kind: Box::new(ExpressionKind::Value(Value::Char(value))),
+                    pos,
+                })
+            }
             Some(HIRInstruction::String) => {
                 let value = chunk.read_string();
                 return Ok(Expression{
@@ -98,59 +244,227 @@ impl Load for Expression{
                     pos,
                 })
             }
-            Some(HIRInstruction::Add) => {
-                let left = match Expression::load(chunk, memmy){
-                    Ok(left) => left,
+            Some(HIRInstruction::Unit) => {
+                return Ok(Expression{
+                    kind: Box::new(ExpressionKind::Value(Value::Unit)),
+                    pos,
+                })
+            }
+            Some(HIRInstruction::None) => {
+                return Ok(Expression{
+                    kind: Box::new(ExpressionKind::Value(Value::None)),
+                    pos,
+                })
+            }
+            Some(HIRInstruction::ResultOk) | Some(HIRInstruction::ResultErr) => {
+                let value = match Expression::load(chunk, memmy){
+                    Ok(value) => value,
+                    Err(diag) => return Err(diag)
+                };
+                let ident = Identifier{
+                    ident: if let Some(HIRInstruction::ResultOk) = opcode{ format!("Ok") }else{ format!("Err") },
+                    pos
+                };
+                //Only the functions with a `where` guard return these, so it is a variant of what the function returns
+                let layout = memmy.return_tys.borrow().last().and_then(|ty| memmy.enum_layout(ty));
+                let variant = layout.as_ref().and_then(|layout| layout.variant(&ident.ident).map(|(tag, variant)| (tag, layout.layout, variant.offsets.iter().skip(1).map(|(_, offset)| *offset).collect())));
+                return match variant{
+                    Some((tag, layout, offsets)) => Ok(Expression{
+                        kind: Box::new(ExpressionKind::VariantInit(ident, tag, layout, offsets, vec![value])),
+                        pos,
+                    }),
+                    None => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), pos.start.0)
+                            .level(DiagnosticLevel::Error)
+                            .message(format!("Cannot find the layout of the Result that {} is returned as", ident.ident))
+                            .range(pos.col_range())
+                            .build();
+                        memmy.emit_diagnostic(&[format!("This is a bug in the compiler.")], &[diagnosis]);
+                        Err(())
+                    }
+                }
+            }
+            Some(HIRInstruction::Reference) => {
+                let ident = Identifier{
+                    ident: chunk.read_string().to_owned(),
+                    pos
+                };
+                memmy.env.reference(&ident);
+                let kind = match memmy.env.kind(&ident.ident){
+                    Some(BindingKind::Property) => ExpressionKind::Ref(ident, Cell::new(Access::Ref)),
+                    Some(BindingKind::Local) => ExpressionKind::Ref(ident, Cell::new(Access::Copy)),
+                    None => match memmy.variant_layout(&ident.ident){
+                        Some((tag, layout, offsets)) => ExpressionKind::VariantInit(ident, tag, layout, offsets, vec![]),
+                        None => ExpressionKind::Function(ident)
+                    },
+                };
+                return Ok(Expression{
+                    kind: Box::new(kind),
+                    pos,
+                })
+            }
+            Some(HIRInstruction::Ref) | Some(HIRInstruction::Move) | Some(HIRInstruction::Copy) => {
+                let access = match opcode{
+                    Some(HIRInstruction::Ref) => Access::Ref,
+                    Some(HIRInstruction::Move) => Access::Move,
+                    _ => Access::Copy
+                };
+                let operand = match Expression::load(chunk, memmy){
+                    Ok(operand) => operand,
+                    Err(diag) => return Err(diag)
+                };
+                return Ok(Expression{
+                    kind: Box::new(memmy.explicit(access, operand, pos)?),
+                    pos,
+                })
+            }
+            Some(HIRInstruction::Lambda) => {
+                let lambda = match Lambda::load(chunk, memmy){
+                    Ok(lambda) => lambda,
+                    Err(diag) => return Err(diag)
+                };
+                return Ok(Expression{
+                    kind: Box::new(ExpressionKind::Lambda(lambda)),
+                    pos,
+                })
+            }
+            Some(HIRInstruction::Call) => {
+                let callee = match Expression::load(chunk, memmy){
+                    Ok(callee) => callee,
                     Err(diag) => return Err(diag)
                 };
-                let right = match Expression::load(chunk, memmy){
-                    Ok(right) => right,
+                let mut args = vec![];
+                while chunk.read_bool(){
+                    match Expression::load(chunk, memmy){
+                        Ok(arg) => args.push(arg),
+                        Err(diag) => return Err(diag)
+                    }
+                }
+                for arg in args.iter(){
+                    arg.refer_as(memmy, RefKind::FunctionRef);
+                }
+                let kind = match *callee.kind{
+                    ExpressionKind::Function(ident) => match memmy.struct_layout(&ident.ident){
+                        Some((path, layout)) => ExpressionKind::ObjInit(ident, path, layout, args),
+                        None => ExpressionKind::Call(Expression{
+                            kind: Box::new(ExpressionKind::Function(ident)),
+                            pos: callee.pos
+                        }, args)
+                    },
+                    ExpressionKind::VariantInit(ident, tag, layout, offsets, _) => ExpressionKind::VariantInit(ident, tag, layout, offsets, args),
+                    kind => ExpressionKind::Call(Expression{
+                        kind: Box::new(kind),
+                        pos: callee.pos
+                    }, args)
+                };
+                return Ok(Expression{
+                    kind: Box::new(kind),
+                    pos,
+                })
+            }
+            Some(HIRInstruction::Cast) | Some(HIRInstruction::Is) => {
+                let expr = match Expression::load(chunk, memmy){
+                    Ok(expr) => expr,
                     Err(diag) => return Err(diag)
                 };
+                let ty = match Ty::load(chunk, memmy){
+                    Ok(ty) => ty,
+                    Err(diag) => return Err(diag)
+                };
+                let kind = if let Some(HIRInstruction::Is) = opcode{
+                    match memmy.trait_bit(&ty){
+                        Some(bit) => ExpressionKind::HasTrait(expr, bit),
+                        None => ExpressionKind::Is(expr, ty)
+                    }
+                }else{
+                    ExpressionKind::Cast(expr, ty)
+                };
                 return Ok(Expression{
-                    kind: Box::new(ExpressionKind::Binary(OpKind::Plus, left, right)),
+                    kind: Box::new(kind),
                     pos,
                 })
             }
-            Some(HIRInstruction::Sub) => {
-                let left = match Expression::load(chunk, memmy){
-                    Ok(left) => left,
+            Some(HIRInstruction::NotNull) => {
+                let operand = match Expression::load(chunk, memmy){
+                    Ok(operand) => operand,
                     Err(diag) => return Err(diag)
                 };
-                let right = match Expression::load(chunk, memmy){
-                    Ok(right) => right,
+                return Ok(Expression{
+                    kind: Box::new(ExpressionKind::NotNull(operand)),
+                    pos,
+                })
+            }
+            Some(HIRInstruction::SafeAccess) | Some(HIRInstruction::Member) => {
+                let receiver = match Expression::load(chunk, memmy){
+                    Ok(receiver) => receiver,
+                    Err(diag) => return Err(diag)
+                };
+                let member = chunk.read_string().to_owned();
+                let receiver_ty = match Ty::load(chunk, memmy){
+                    Ok(ty) => ty,
                     Err(diag) => return Err(diag)
                 };
+                receiver.refer_as(memmy, RefKind::RefRef);
+                //A member of a reference is a member of the object it binds to
+                let receiver_ty = memmy.env.deref(&receiver_ty);
+                let safe = opcode == &Some(HIRInstruction::SafeAccess);
+                let kind = match (memmy.slot(&receiver_ty, &member), memmy.interface_method(&receiver_ty, &member), safe){
+                    (Some(slot), _, safe) => ExpressionKind::VirtualMember(receiver, member, slot, safe),
+                    (None, Some((interface, index)), safe) => ExpressionKind::InterfaceMember(receiver, member, interface, index, safe),
+                    (None, None, false) => match (memmy.field_offset(&receiver_ty, &member), memmy.method_path(&receiver_ty, &member)){
+                        (Some(offset), _) => ExpressionKind::Field(receiver, member, offset),
+                        (None, Some(path)) => ExpressionKind::Method(receiver, member, path),
+                        (None, None) => ExpressionKind::Member(receiver, member),
+                    },
+                    (None, None, true) => ExpressionKind::SafeAccess(receiver, member),
+                };
                 return Ok(Expression{
-                    kind: Box::new(ExpressionKind::Binary(OpKind::Minus, left, right)),
+                    kind: Box::new(kind),
                     pos,
                 })
             }
-            Some(HIRInstruction::Mult) => {
-                let left = match Expression::load(chunk, memmy){
-                    Ok(left) => left,
+            Some(HIRInstruction::Not) | Some(HIRInstruction::Neg) => {
+                let op = if let Some(HIRInstruction::Not) = opcode{
+                    OpKind::Not
+                }else{
+                    OpKind::Neg
+                };
+                let operand = match Expression::load(chunk, memmy){
+                    Ok(operand) => operand,
                     Err(diag) => return Err(diag)
                 };
-                let right = match Expression::load(chunk, memmy){
-                    Ok(right) => right,
+                return Ok(Expression{
+                    kind: Box::new(ExpressionKind::Unary(op, operand)),
+                    pos,
+                })
+            }
+            Some(HIRInstruction::Loop) => {
+                let loop_ = match Loop::load(chunk, memmy){
+                    Ok(loop_) => loop_,
                     Err(diag) => return Err(diag)
                 };
                 return Ok(Expression{
-                    kind: Box::new(ExpressionKind::Binary(OpKind::Mult, left, right)),
+                    kind: Box::new(ExpressionKind::Loop(loop_)),
                     pos,
                 })
             }
-            Some(HIRInstruction::Div) => {
-                let left = match Expression::load(chunk, memmy){
-                    Ok(left) => left,
+            Some(HIRInstruction::If) => {
+                let if_ = match If::load(chunk, memmy){
+                    Ok(if_) => if_,
                     Err(diag) => return Err(diag)
                 };
-                let right = match Expression::load(chunk, memmy){
-                    Ok(right) => right,
+                return Ok(Expression{
+                    kind: Box::new(ExpressionKind::If(if_)),
+                    pos,
+                })
+            }
+            Some(HIRInstruction::Match) => {
+                let match_ = match Match::load(chunk, memmy){
+                    Ok(match_) => match_,
                     Err(diag) => return Err(diag)
                 };
                 return Ok(Expression{
-                    kind: Box::new(ExpressionKind::Binary(OpKind::Div, left, right)),
+                    kind: Box::new(ExpressionKind::Match(match_)),
                     pos,
                 })
             }
@@ -165,4 +479,589 @@ impl Load for Expression{
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl Expression{
+    ///The layout of the struct or enum that this expression constructs, if it is a call to a constructor or a variant.
+    pub fn obj_init(&self) -> Option<Layout>{
+        match self.kind.as_ref(){
+            ExpressionKind::ObjInit(_, _, layout, _) | ExpressionKind::VariantInit(_, _, layout, _, _) => Some(*layout),
+            _ => None
+        }
+    }
+
+    ///The name of the object or field that this expression refers to, such as `p` or `p.x`.
+    pub fn place(&self) -> Option<String>{
+        match self.kind.as_ref(){
+            ExpressionKind::Ref(ident, _) => Some(ident.ident.clone()),
+            ExpressionKind::Member(receiver, member) | ExpressionKind::Field(receiver, member, _) => receiver.place().map(|place| format!("{}.{}", place, member)),
+            ExpressionKind::Group(expr) => expr.place(),
+            _ => None
+        }
+    }
+}
+
+impl MemmyGenerator{
+    ///Check that the object named by `operand` can be passed the way that `ref`, `move` or `copy` at `pos` says.
+    ///Only properties can be referenced and only locals can be moved. Whether an object can be copied is checked once every type of the module is known, see [MemmyGenerator::infer_moves].
+    fn explicit(&self, access: Access, operand: Expression, pos: BiPos) -> Result<ExpressionKind, ()>{
+        let verb = match access{
+            Access::Ref => "referenced",
+            Access::Move => "moved",
+            Access::Copy => "copied",
+        };
+        let (ident, kind) = match *operand.kind{
+            ExpressionKind::Ref(ident, _) => {
+                let kind = self.env.kind(&ident.ident);
+                (ident, kind)
+            }
+            ExpressionKind::Function(ident) | ExpressionKind::VariantInit(ident, ..) => (ident, None),
+            _ => {
+                let diagnosis = self.error_source(pos, format!("Cannot find the object that this is applied to"))?;
+                self.emit_diagnostic(&[format!("This is a bug in the compiler.")], &[diagnosis]);
+                return Err(())
+            }
+        };
+        let (message, note) = match (access, kind){
+            (_, None) => (
+                format!("{} cannot be {}, since it is not an object", ident.ident, verb),
+                format!("Only properties and locals can be {}.", verb)
+            ),
+            (Access::Ref, Some(BindingKind::Local)) => (
+                format!("{} cannot be referenced, since it is a local", ident.ident),
+                format!("Only properties can be referenced, since locals are passed by value. Declare {} with `var` or `val` to make it a property.", ident.ident)
+            ),
+            (Access::Move, Some(BindingKind::Property)) => (
+                format!("{} cannot be moved, since it is a property", ident.ident),
+                format!("Only locals can be moved, since properties are passed by reference. Use `copy {}` to pass a copy of it instead.", ident.ident)
+            ),
+            (access, Some(_)) => {
+                if let (Access::Copy, Some(ty)) = (access, self.env.ty(&ident.ident)){
+                    self.copies.borrow_mut().push((ident.clone(), ty));
+                }
+                return Ok(ExpressionKind::Explicit(ident, access))
+            }
+        };
+        let diagnosis = self.error_source(pos, message)?;
+        self.emit_diagnostic(&[note], &[diagnosis]);
+        Err(())
+    }
+
+    ///The slot of the vtable that the method `member` of a receiver of type `ty` is called through, if it is a method of a class that may be overridden.
+    fn slot(&self, ty: &Ty, member: &str) -> Option<usize>{
+        let ty = ty.option_inner().unwrap_or_else(|| ty.clone());
+        match ty.kind().as_ref(){
+            TyKind::Named(path) => self.vtables.borrow().get(&path.join("::"))?.slot(member),
+            _ => None
+        }
+    }
+
+    ///The full path of the interface that a receiver of type `ty` is, and the index of its method `member`, if `member` is called through an itable.
+    fn interface_method(&self, ty: &Ty, member: &str) -> Option<(String, usize)>{
+        let ty = ty.option_inner().unwrap_or_else(|| ty.clone());
+        let path = ty.named_args()?.0.join("::");
+        let index = self.interfaces.borrow().get(&path).filter(|interface| interface.bit.is_none())?.methods.iter().position(|method| method == member)?;
+        Some((path, index))
+    }
+
+    ///The bit of the trait that `ty` is in the trait mask, if it is a trait.
+    fn trait_bit(&self, ty: &Ty) -> Option<usize>{
+        let (path, _) = ty.named_args()?;
+        self.interfaces.borrow().get(&path.join("::"))?.bit
+    }
+
+    ///The full path and the layout of the struct of this module named `name`, if there is one.
+    fn struct_layout(&self, name: &str) -> Option<(String, Layout)>{
+        let path = format!("{}::{}", self.module_name, name);
+        let layout = self.structs.borrow().get(&path)?.layout;
+        Some((path, layout))
+    }
+
+    ///The offset of the field `member` of a receiver of type `ty`, if it is a struct that has it.
+    fn field_offset(&self, ty: &Ty, member: &str) -> Option<usize>{
+        let (path, _) = ty.named_args()?;
+        self.structs.borrow().get(&path.join("::"))?.offsets.iter().find(|(field, _)| field == member).map(|(_, offset)| *offset)
+    }
+
+    ///The full path of the method `member` of a receiver of type `ty`, if it is a struct.
+    ///It is a method of the struct itself, unless a class that it inherits from declares it.
+    fn method_path(&self, ty: &Ty, member: &str) -> Option<String>{
+        let (path, _) = ty.named_args()?;
+        let path = path.join("::");
+        if !self.structs.borrow().contains_key(&path){
+            return None
+        }
+        let methods = self.methods.borrow();
+        let bases = self.bases.borrow();
+        let mut class = Some(&path);
+        while let Some(current) = class{
+            if methods.get(current).is_some_and(|methods| methods.iter().any(|method| method == member)){
+                return Some(format!("{}::{}", current, member))
+            }
+            class = bases.get(current);
+        }
+        //The methods of a struct are registered as they are loaded, so its own methods may call methods that come after them
+        Some(format!("{}::{}", path, member))
+    }
+}
+
+impl Unload for Expression{
+    ///Write the instruction of the expression and its position, followed by the MIR of what it is made of.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        match self.kind.as_ref(){
+            ExpressionKind::Value(value) => value.unload_at(self.pos, &mut chunk),
+            ExpressionKind::Ref(ident, access) => {
+                chunk.write_instruction(access.get().instruction());
+                chunk.write_pos(self.pos);
+                chunk.write_str(&ident.ident);
+            }
+            ExpressionKind::Explicit(ident, access) => {
+                chunk.write_instruction(access.instruction());
+                chunk.write_pos(self.pos);
+                chunk.write_str(&ident.ident);
+            }
+            ExpressionKind::Function(ident) => {
+                chunk.write_instruction(MIRInstructions::Function);
+                chunk.write_pos(self.pos);
+                chunk.write_str(&ident.ident);
+            }
+            ExpressionKind::Group(expr) => return expr.unload(),
+            ExpressionKind::Unary(op, operand) => {
+                chunk.write_instruction(op.instruction());
+                chunk.write_pos(self.pos);
+                chunk.write_chunk(operand.unload()?);
+            }
+            ExpressionKind::Binary(op, left, right) => {
+                chunk.write_instruction(op.instruction());
+                chunk.write_pos(self.pos);
+                chunk.write_chunk(left.unload()?);
+                chunk.write_chunk(right.unload()?);
+            }
+            ExpressionKind::Loop(loop_) => {
+                chunk.write_instruction(MIRInstructions::Loop);
+                chunk.write_pos(self.pos);
+                chunk.write_chunk(loop_.unload()?);
+            }
+            ExpressionKind::If(if_) => {
+                chunk.write_instruction(MIRInstructions::If);
+                chunk.write_pos(self.pos);
+                chunk.write_chunk(if_.unload()?);
+            }
+            ExpressionKind::Lambda(lambda) => {
+                chunk.write_instruction(MIRInstructions::Lambda);
+                chunk.write_pos(self.pos);
+                chunk.write_chunk(lambda.unload()?);
+            }
+            ExpressionKind::Match(match_) => {
+                chunk.write_instruction(MIRInstructions::Match);
+                chunk.write_pos(self.pos);
+                chunk.write_chunk(match_.unload()?);
+            }
+            ExpressionKind::Call(callee, args) => match callee.kind.as_ref(){
+                ExpressionKind::VirtualMember(receiver, _, slot, safe) => {
+                    chunk.write_instruction(MIRInstructions::VirtualCall);
+                    chunk.write_pos(self.pos);
+                    chunk.write_usize(*slot);
+                    chunk.write_bool(*safe);
+                    chunk.write_chunk(receiver.unload_receiver()?);
+                    chunk.write_chunk(unload_args(args)?);
+                }
+                ExpressionKind::InterfaceMember(receiver, _, interface, index, safe) => {
+                    chunk.write_instruction(MIRInstructions::InterfaceCall);
+                    chunk.write_pos(self.pos);
+                    chunk.write_str(interface);
+                    chunk.write_usize(*index);
+                    chunk.write_bool(*safe);
+                    chunk.write_chunk(receiver.unload_receiver()?);
+                    chunk.write_chunk(unload_args(args)?);
+                }
+                //The object that a method is called on is passed to it first
+                ExpressionKind::Method(receiver, _, path) => {
+                    chunk.write_instruction(MIRInstructions::Call);
+                    chunk.write_pos(self.pos);
+                    chunk.write_instruction(MIRInstructions::Function);
+                    chunk.write_pos(callee.pos);
+                    chunk.write_str(path);
+                    chunk.write_bool(true);
+                    chunk.write_chunk(receiver.unload_receiver()?);
+                    chunk.write_chunk(unload_args(args)?);
+                }
+                _ => {
+                    chunk.write_instruction(MIRInstructions::Call);
+                    chunk.write_pos(self.pos);
+                    chunk.write_chunk(callee.unload()?);
+                    chunk.write_chunk(unload_args(args)?);
+                }
+            },
+            ExpressionKind::Cast(expr, ty) | ExpressionKind::Is(expr, ty) => {
+                chunk.write_instruction(match self.kind.as_ref(){
+                    ExpressionKind::Cast(..) => MIRInstructions::Cast,
+                    _ => MIRInstructions::Is,
+                });
+                chunk.write_pos(self.pos);
+                chunk.write_string(ty.to_string());
+                chunk.write_chunk(match self.kind.as_ref(){
+                    ExpressionKind::Cast(..) => expr.unload()?,
+                    _ => expr.unload_receiver()?,
+                });
+            }
+            ExpressionKind::SafeAccess(receiver, member) => {
+                chunk.write_instruction(MIRInstructions::SafeAccess);
+                chunk.write_pos(self.pos);
+                chunk.write_str(member);
+                chunk.write_chunk(receiver.unload_receiver()?);
+            }
+            ExpressionKind::Field(receiver, member, offset) => {
+                chunk.write_instruction(MIRInstructions::Field);
+                chunk.write_pos(self.pos);
+                chunk.write_str(member);
+                chunk.write_usize(*offset);
+                chunk.write_chunk(receiver.unload_receiver()?);
+            }
+            //A method that is not called is only named by its member
+            ExpressionKind::Member(receiver, member) |
+            ExpressionKind::Method(receiver, member, _) |
+            ExpressionKind::VirtualMember(receiver, member, _, _) |
+            ExpressionKind::InterfaceMember(receiver, member, _, _, _) => {
+                chunk.write_instruction(MIRInstructions::Member);
+                chunk.write_pos(self.pos);
+                chunk.write_str(member);
+                chunk.write_chunk(receiver.unload_receiver()?);
+            }
+            ExpressionKind::ObjInit(_, _, layout, _) | ExpressionKind::VariantInit(_, _, layout, _, _) => {
+                let name = temporary(self.pos);
+                let allocation = Allocation{
+                    name: name.clone(),
+                    place: Place::Stack,
+                    layout: *layout,
+                    mutable: false,
+                    lateinit: false,
+                    pos: self.pos
+                };
+                chunk.write_chunk(allocation.unload()?);
+                chunk.write_chunk(self.unload_init(&name)?);
+            }
+            ExpressionKind::NotNull(expr) => {
+                chunk.write_instruction(MIRInstructions::NotNull);
+                chunk.write_pos(self.pos);
+                chunk.write_chunk(expr.unload()?);
+            }
+            ExpressionKind::HasTrait(expr, bit) => {
+                chunk.write_instruction(MIRInstructions::HasTrait);
+                chunk.write_pos(self.pos);
+                chunk.write_usize(*bit);
+                chunk.write_chunk(expr.unload_receiver()?);
+            }
+        }
+        Ok(chunk)
+    }
+}
+
+impl Expression{
+    ///If this names an object, change the kind of the reference to it in its lifetime to `kind`, which is what the expression this is in uses it for.
+    fn refer_as(&self, memmy: &MemmyGenerator, kind: RefKind){
+        if let ExpressionKind::Ref(ident, _) = self.kind.as_ref(){
+            memmy.env.refer_as(ident, kind);
+        }
+    }
+
+    ///Write the MIR of an object that is only looked into, such as one that a member is accessed on or that is tested with `is`. It is referenced rather than copied when it is named.
+    fn unload_receiver(&self) -> Result<Chunk, ()>{
+        match self.kind.as_ref(){
+            ExpressionKind::Ref(ident, _) => {
+                let mut chunk = Chunk::new();
+                chunk.write_instruction(MIRInstructions::Ref);
+                chunk.write_pos(self.pos);
+                chunk.write_str(&ident.ident);
+                Ok(chunk)
+            }
+            _ => self.unload(),
+        }
+    }
+
+    ///Write the MIR that initializes the object `name` with this expression, which comes right after its `ObjInit`.
+    ///A struct is constructed by calling its constructor on the object, and a value of an enum by writing its tag and its fields into the object.
+    pub fn unload_init(&self, name: &str) -> Result<Chunk, ()>{
+        let mut chunk = Chunk::new();
+        match self.kind.as_ref(){
+            ExpressionKind::ObjInit(ident, path, _, args) => {
+                chunk.write_instruction(MIRInstructions::Call);
+                chunk.write_pos(self.pos);
+                chunk.write_instruction(MIRInstructions::Function);
+                chunk.write_pos(ident.pos);
+                chunk.write_str(path);
+                chunk.write_bool(true);
+                chunk.write_instruction(MIRInstructions::Ref);
+                chunk.write_pos(self.pos);
+                chunk.write_str(name);
+                chunk.write_chunk(unload_args(args)?);
+            }
+            ExpressionKind::VariantInit(_, tag, _, offsets, args) => {
+                chunk.write_instruction(MIRInstructions::SetTag);
+                chunk.write_pos(self.pos);
+                chunk.write_str(name);
+                chunk.write_usize(*tag);
+                for (offset, arg) in offsets.iter().zip(args.iter()){
+                    chunk.write_bool(true);
+                    chunk.write_usize(*offset);
+                    chunk.write_chunk(arg.unload()?);
+                }
+                chunk.write_bool(false);
+            }
+            _ => return self.unload()
+        }
+        Ok(chunk)
+    }
+}
+
+///Write each of `args` preceded by `true`, and `false` after the last of them.
+fn unload_args(args: &[Expression]) -> Result<Chunk, ()>{
+    let mut chunk = Chunk::new();
+    for arg in args.iter(){
+        chunk.write_bool(true);
+        chunk.write_chunk(arg.unload()?);
+    }
+    chunk.write_bool(false);
+    Ok(chunk)
+}
+
+impl<'t> Build<'t> for Expression{
+    ///A named local is used where it is passed by value. The objects that are only looked into are touched instead, see [Expression::unload_receiver].
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        match self.kind.as_ref(){
+            ExpressionKind::Value(_) | ExpressionKind::Function(_) => {}
+            //A property is only ever referred to, so naming it only touches it
+            ExpressionKind::Ref(ident, access) => if access.get() != Access::Ref{
+                cfg.use_object(ident, self.pos, Site::Access(access));
+            }else{
+                cfg.touch(ident, self.pos);
+            },
+            ExpressionKind::Explicit(ident, access) => if *access != Access::Ref{
+                cfg.use_object(ident, self.pos, Site::Explicit(*access));
+            }else{
+                cfg.touch(ident, self.pos);
+            },
+            ExpressionKind::Group(expr) |
+            ExpressionKind::Unary(_, expr) |
+            ExpressionKind::Cast(expr, _) |
+            ExpressionKind::NotNull(expr) => expr.build(cfg),
+            ExpressionKind::Binary(_, left, right) => {
+                left.build(cfg);
+                right.build(cfg);
+            }
+            ExpressionKind::Loop(loop_) => loop_.build(cfg),
+            ExpressionKind::If(if_) => if_.build(cfg),
+            ExpressionKind::Lambda(lambda) => lambda.build(cfg),
+            ExpressionKind::Match(match_) => match_.build(cfg),
+            ExpressionKind::Call(callee, args) => {
+                match callee.kind.as_ref(){
+                    ExpressionKind::VirtualMember(receiver, ..) |
+                    ExpressionKind::InterfaceMember(receiver, ..) |
+                    ExpressionKind::Method(receiver, ..) => receiver.build_receiver(cfg),
+                    _ => callee.build(cfg),
+                }
+                for arg in args.iter(){
+                    arg.build(cfg);
+                }
+            }
+            ExpressionKind::SafeAccess(receiver, _) |
+            ExpressionKind::Member(receiver, _) |
+            ExpressionKind::Field(receiver, _, _) |
+            ExpressionKind::Method(receiver, _, _) |
+            ExpressionKind::VirtualMember(receiver, ..) |
+            ExpressionKind::InterfaceMember(receiver, ..) |
+            ExpressionKind::Is(receiver, _) |
+            ExpressionKind::HasTrait(receiver, _) => receiver.build_receiver(cfg),
+            ExpressionKind::ObjInit(_, _, _, args) | ExpressionKind::VariantInit(_, _, _, _, args) => for arg in args.iter(){
+                arg.build(cfg);
+            },
+        }
+    }
+}
+
+impl Expression{
+    ///Add an object that is only looked into to `cfg`, which touches it when it is named.
+    pub fn build_receiver<'t>(&'t self, cfg: &mut Builder<'t>){
+        match self.kind.as_ref(){
+            ExpressionKind::Ref(ident, _) => cfg.touch(ident, self.pos),
+            _ => self.build(cfg),
+        }
+    }
+}
+
+impl Count for Expression{
+    ///Naming a property refers to it, unless it is of a primitive type, and naming a local refers to what it holds. A new object refers to what it is constructed with, and a copy to what its original holds.
+    ///What a call of a function of the module refers to is followed through the function, while a method or a lambda may keep anything it is passed.
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        match self.kind.as_mut(){
+            ExpressionKind::Value(_) | ExpressionKind::Function(_) => vec![],
+            //A property of a primitive type is copied where it is named, unless it is taken with `ref`
+            ExpressionKind::Ref(ident, access) if counter.is_by_value(ident) => {
+                access.set(Access::Copy);
+                counter.copy(ident)
+            }
+            ExpressionKind::Ref(ident, _) | ExpressionKind::Explicit(ident, Access::Ref) | ExpressionKind::Explicit(ident, Access::Move) => counter.refer(ident),
+            ExpressionKind::Explicit(ident, Access::Copy) => counter.copy(ident),
+            ExpressionKind::Group(expr) |
+            ExpressionKind::Cast(expr, _) |
+            ExpressionKind::NotNull(expr) => expr.count(counter),
+            ExpressionKind::Unary(_, expr) => {
+                expr.count(counter);
+                vec![]
+            }
+            ExpressionKind::Binary(op, left, right) => {
+                let mut referents = left.count(counter);
+                referents.extend(right.count(counter));
+                match op{
+                    OpKind::Elvis => referents,
+                    _ => vec![]
+                }
+            }
+            ExpressionKind::Loop(loop_) => loop_.count(counter),
+            ExpressionKind::If(if_) => if_.count(counter),
+            ExpressionKind::Lambda(lambda) => lambda.count(counter),
+            ExpressionKind::Match(match_) => match_.count(counter),
+            ExpressionKind::Call(callee, args) => {
+                callee.count(counter);
+                let args = args.iter_mut().map(|arg| arg.count(counter)).collect();
+                match callee.kind.as_ref(){
+                    ExpressionKind::Function(ident) => counter.call(&ident.ident, args, self.pos),
+                    _ => {
+                        counter.lend(&callee.callee_name(), args, self.pos);
+                        vec![]
+                    }
+                }
+            }
+            ExpressionKind::SafeAccess(receiver, _) |
+            ExpressionKind::Member(receiver, _) |
+            ExpressionKind::Field(receiver, _, _) |
+            ExpressionKind::Method(receiver, _, _) |
+            ExpressionKind::VirtualMember(receiver, ..) |
+            ExpressionKind::InterfaceMember(receiver, ..) |
+            ExpressionKind::Is(receiver, _) |
+            ExpressionKind::HasTrait(receiver, _) => {
+                receiver.count(counter);
+                vec![]
+            }
+            ExpressionKind::ObjInit(_, _, _, args) | ExpressionKind::VariantInit(_, _, _, _, args) => args.iter_mut().flat_map(|arg| arg.count(counter)).collect(),
+        }
+    }
+}
+
+impl Expression{
+    ///The name of what this expression calls when it is a callee, for reports.
+    fn callee_name(&self) -> String{
+        match self.kind.as_ref(){
+            ExpressionKind::Function(ident) | ExpressionKind::Ref(ident, _) => ident.ident.clone(),
+            ExpressionKind::Member(_, member) |
+            ExpressionKind::SafeAccess(_, member) |
+            ExpressionKind::Method(_, member, _) |
+            ExpressionKind::VirtualMember(_, member, ..) |
+            ExpressionKind::InterfaceMember(_, member, ..) => member.clone(),
+            ExpressionKind::Group(expr) => expr.callee_name(),
+            _ => format!("a lambda"),
+        }
+    }
+
+    ///Whether the value of this expression is a reference to what it names, which is when it names a property or takes a `ref` of one.
+    pub fn is_reference(&self) -> bool{
+        match self.kind.as_ref(){
+            ExpressionKind::Ref(_, access) => access.get() == Access::Ref,
+            ExpressionKind::Explicit(_, access) => *access == Access::Ref,
+            ExpressionKind::Group(expr) => expr.is_reference(),
+            _ => false
+        }
+    }
+
+    ///The object that this expression names, or whose field it is, such as `p` for `p.x`.
+    pub fn root(&self) -> Option<&Identifier>{
+        match self.kind.as_ref(){
+            ExpressionKind::Ref(ident, _) => Some(ident),
+            ExpressionKind::Member(receiver, _) | ExpressionKind::Field(receiver, _, _) | ExpressionKind::Group(receiver) => receiver.root(),
+            _ => None
+        }
+    }
+}
+
+impl Access{
+    fn instruction(&self) -> MIRInstructions{
+        match self{
+            Access::Ref => MIRInstructions::Ref,
+            Access::Copy => MIRInstructions::Copy,
+            Access::Move => MIRInstructions::Move,
+        }
+    }
+}
+
+impl Value{
+    fn unload_at(&self, pos: BiPos, chunk: &mut Chunk){
+        //Literals have primitive types, which are laid out without any structs
+        let size = |ty: &Ty| layout_of(ty, &HashMap::new()).map_or(0, |layout| layout.size);
+        match self{
+            Value::Int(value, ty) => {
+                chunk.write_instruction(MIRInstructions::Integer);
+                chunk.write_pos(pos);
+                chunk.write_long(*value);
+                chunk.write_usize(size(ty));
+            }
+            Value::Float(value, ty) => {
+                chunk.write_instruction(MIRInstructions::Float);
+                chunk.write_pos(pos);
+                chunk.write_double(*value);
+                chunk.write_usize(size(ty));
+            }
+            Value::Char(value) => {
+                chunk.write_instruction(MIRInstructions::Char);
+                chunk.write_pos(pos);
+                chunk.write_char(*value);
+            }
+            Value::String(value) => {
+                chunk.write_instruction(MIRInstructions::String);
+                chunk.write_pos(pos);
+                chunk.write_str(value);
+            }
+            Value::Bool(value) => {
+                chunk.write_instruction(MIRInstructions::Bool);
+                chunk.write_pos(pos);
+                chunk.write_bool(*value);
+            }
+            Value::Custom(ident) => {
+                chunk.write_instruction(MIRInstructions::Ref);
+                chunk.write_pos(pos);
+                chunk.write_str(&ident.ident);
+            }
+            Value::Unit => {
+                chunk.write_instruction(MIRInstructions::Unit);
+                chunk.write_pos(pos);
+            }
+            Value::None => {
+                chunk.write_instruction(MIRInstructions::None);
+                chunk.write_pos(pos);
+            }
+        }
+    }
+}
+
+impl OpKind{
+    fn instruction(&self) -> MIRInstructions{
+        match self{
+            OpKind::Plus => MIRInstructions::Add,
+            OpKind::Minus => MIRInstructions::Sub,
+            OpKind::Mult => MIRInstructions::Mult,
+            OpKind::Div => MIRInstructions::Div,
+            OpKind::Less => MIRInstructions::Less,
+            OpKind::Greater => MIRInstructions::Greater,
+            OpKind::LessEq => MIRInstructions::LessEq,
+            OpKind::GreaterEq => MIRInstructions::GreaterEq,
+            OpKind::Eq => MIRInstructions::Eq,
+            OpKind::NotEq => MIRInstructions::NotEq,
+            OpKind::And => MIRInstructions::And,
+            OpKind::Or => MIRInstructions::Or,
+            OpKind::Not => MIRInstructions::Not,
+            OpKind::Neg => MIRInstructions::Neg,
+            OpKind::Range => MIRInstructions::Range,
+            OpKind::Elvis => MIRInstructions::Elvis,
+        }
+    }
+}
@@ -1,13 +1,32 @@
 use super::{
     ident::Identifier,
-    statements::Statement,
+    block::Block,
     Load,
-    MemmyGenerator
+    Unload,
+    MemmyGenerator,
+    capture::BindingKind,
+    cfg::{
+        Build,
+        Builder,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+    layout::REF_SIZE,
 };
 
-use ir::{ Chunk, hir::HIRInstruction };
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+    mir::MIRInstructions,
+};
 
-use ir_traits::{ ReadInstruction };
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
 
 use core::{
     pos::BiPos
@@ -21,16 +40,16 @@ use notices::{
 use ty::Ty;
 
 #[derive(Debug, Clone)]
-pub struct Fun<'a>{
-    ident: Identifier,
+pub struct Fun{
+    pub ident: Identifier,
     params: Vec<FunParam>,
     pos: BiPos,
     return_type: Ty,
-    body: Vec<Statement<'a>>
+    body: Block
 }
 
-impl<'a> Load for Fun<'a>{
-    type Output = Fun<'a>;
+impl Load for Fun{
+    type Output = Fun;
 
     fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
         let pos = match chunk.read_pos(){
@@ -67,17 +86,24 @@ impl<'a> Load for Fun<'a>{
                 return Err(())
             }
         };
-        let mut body = vec![];
-        loop{
-            if let Some(HIRInstruction::EndFn) = chunk.read_instruction(){
-                break;
-            }
-            chunk.dec_ins_ptr(1);
-            match Statement::load(chunk, memmy){
-                Ok(statement) => body.push(statement),
-                Err(diag) => return Err(diag)
-            }
-
+        let depth = memmy.env.depth();
+        for param in params.iter(){
+            memmy.env.declare(&param.ident, BindingKind::Local, &param.typename);
+        }
+        memmy.env.enter_frame(params.len());
+        memmy.return_tys.borrow_mut().push(return_type.clone());
+        let body = Block::load(chunk, memmy);
+        memmy.return_tys.borrow_mut().pop();
+        memmy.env.exit_frame();
+        let body = body?;
+        memmy.env.end_scope(depth, body.pos);
+        if let Some(HIRInstruction::EndFn) = chunk.read_instruction(){}else{
+            let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), pos.start.0)
+                .message(format!("Expected the end of function {} after its body.", ident.ident))
+                .level(DiagnosticLevel::Error)
+                .build();
+            memmy.emit_diagnostic(&[], &[diagnosis]);
+            return Err(())
         }
         Ok(Fun{
             ident,
@@ -91,8 +117,9 @@ impl<'a> Load for Fun<'a>{
 
 #[derive(Debug, Clone)]
 pub struct FunParam{
-    ident: Identifier,
-    typename: Ty,
+    pub ident: Identifier,
+    pub typename: Ty,
+    pub size: usize,
     pos: BiPos,
 }
 
@@ -119,11 +146,111 @@ impl Load for FunParam{
             Ok(ty) => ty,
             Err(()) => return Err(())
         };
+        let size = match memmy.layout(&typename){
+            Some(layout) => layout.size,
+            None => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), ident.pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Cannot compute the size of the param {} of type {}", ident.ident, typename))
+                    .range(ident.pos.col_range())
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
+            }
+        };
 
         Ok(FunParam{
             ident,
             typename,
+            size,
             pos
         })
     }
-}
\ No newline at end of file
+}
+
+impl Unload for Fun{
+    fn unload(&self) -> Result<Chunk, ()> {
+        self.unload_as(&self.ident.ident, false)
+    }
+}
+
+impl Fun{
+    ///Write the function as `name`. A method takes a reference to the object it is called on as `this`, before its params.
+    ///Its params are dropped after its body, in the reverse order that they are declared in.
+    pub fn unload_as(&self, name: &str, method: bool) -> Result<Chunk, ()>{
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(MIRInstructions::Fun);
+        chunk.write_pos(self.pos);
+        chunk.write_str(name);
+        if method{
+            chunk.write_instruction(MIRInstructions::FunParam);
+            chunk.write_pos(self.pos);
+            chunk.write_str("this");
+            chunk.write_usize(REF_SIZE);
+        }
+        for param in self.params.iter(){
+            chunk.write_chunk(param.unload()?);
+        }
+        chunk.write_chunk(self.body.unload()?);
+        for param in self.params.iter().rev(){
+            chunk.write_instruction(MIRInstructions::Drop);
+            chunk.write_pos(param.pos);
+            chunk.write_str(&param.ident.ident);
+        }
+        chunk.write_instruction(MIRInstructions::EndFun);
+        Ok(chunk)
+    }
+}
+
+impl Unload for FunParam{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(MIRInstructions::FunParam);
+        chunk.write_pos(self.pos);
+        chunk.write_str(&self.ident.ident);
+        chunk.write_usize(self.size);
+        Ok(chunk)
+    }
+}
+
+impl<'t> Build<'t> for Fun{
+    ///A function is built as a graph of its own, which starts by declaring its params.
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        let mut body = Builder::new();
+        for param in self.params.iter(){
+            body.declare(&param.ident, &param.typename);
+        }
+        self.body.build(&mut body);
+        cfg.nest(body.finish());
+    }
+}
+
+impl Count for Fun{
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        let name = self.ident.ident.clone();
+        self.count_as(counter, Some(&name));
+        vec![]
+    }
+}
+
+impl Fun{
+    ///Count the references taken in a method, which is only ever called on an object rather than by its name.
+    pub fn count_method(&mut self, counter: &mut RefCounter){
+        self.count_as(counter, None);
+    }
+
+    ///Each param refers to whatever the function is passed for it, so that what the function returns can be followed through its calls.
+    fn count_as(&mut self, counter: &mut RefCounter, name: Option<&str>){
+        counter.enter_frame();
+        counter.enter_scope();
+        for (idx, param) in self.params.iter().enumerate(){
+            counter.declare(&param.ident, None, false, vec![Referent::Param(idx)], param.pos);
+        }
+        let value = self.body.count(counter);
+        counter.exit_scope();
+        //The value that the body ends with is returned where it is written
+        let pos = self.body.statements.last().map_or(self.body.pos, |statement| statement.pos);
+        let returned = counter.exit_frame(name, value, pos);
+        self.body.drops.retain(|drop| !returned.contains(drop));
+    }
+}
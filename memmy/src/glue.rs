@@ -0,0 +1,144 @@
+use super::Unload;
+
+use core::pos::BiPos;
+
+use ir::{
+    Chunk,
+    mir::MIRInstructions,
+};
+
+use ir_traits::WriteInstruction;
+
+///The `drop` methods that are called when an object of a struct is dropped, in the order they are called.
+///The object's own `drop` method comes first, then those of the class it inherits from, and then those of its fields in declaration order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DropGlue{
+    ///The full path of the struct, such as `main::File`.
+    pub path: String,
+    pub hooks: Vec<Hook>,
+    pub pos: BiPos,
+}
+
+///A call of the `drop` method `method` on `place`, which is either `this` or a field of it such as `this.buffer`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hook{
+    pub method: String,
+    pub place: String,
+}
+
+impl DropGlue{
+    ///The glue of the struct at `path`, which has a `drop` method if `has_drop` is set, inherits from the class whose glue is `base`,
+    ///and declares `fields`, each with the glue of its struct if it has any.
+    pub fn new(path: String, has_drop: bool, base: Option<&DropGlue>, fields: &[(&str, Option<&DropGlue>)], pos: BiPos) -> DropGlue{
+        let mut hooks = vec![];
+        if has_drop{
+            hooks.push(Hook{
+                method: format!("{}::drop", path),
+                place: "this".to_string()
+            });
+        }
+        if let Some(base) = base{
+            hooks.extend(base.hooks.iter().cloned());
+        }
+        for (field, glue) in fields.iter(){
+            let glue = match glue{
+                Some(glue) => glue,
+                None => continue
+            };
+            for hook in glue.hooks.iter(){
+                hooks.push(Hook{
+                    method: hook.method.clone(),
+                    place: hook.place.replacen("this", &format!("this.{}", field), 1)
+                });
+            }
+        }
+        DropGlue{
+            path,
+            hooks,
+            pos
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.hooks.is_empty()
+    }
+}
+
+impl Unload for DropGlue{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(MIRInstructions::DropGlue);
+        chunk.write_pos(self.pos);
+        chunk.write_str(&self.path);
+        chunk.write_usize(self.hooks.len());
+        for hook in self.hooks.iter(){
+            chunk.write_str(&hook.method);
+            chunk.write_str(&hook.place);
+        }
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        MemmyGenerator,
+        MirPattern,
+    };
+
+    fn hooks(glue: &DropGlue) -> Vec<(&str, &str)>{
+        glue.hooks.iter().map(|hook| (hook.method.as_str(), hook.place.as_str())).collect()
+    }
+
+    #[test]
+    fn owners_are_dropped_before_their_fields() {
+        let leaf = DropGlue::new("main::Leaf".to_string(), true, None, &[], BiPos::default());
+        let inner = DropGlue::new("main::Inner".to_string(), true, None, &[("leaf", Some(&leaf))], BiPos::default());
+        let plain = DropGlue::new("main::Plain".to_string(), false, None, &[("leaf", Some(&leaf)), ("n", None)], BiPos::default());
+        let outer = DropGlue::new("main::Outer".to_string(), true, None, &[("a", Some(&inner)), ("n", None), ("b", Some(&plain))], BiPos::default());
+        assert_eq!(hooks(&outer), vec![
+            ("main::Outer::drop", "this"),
+            ("main::Inner::drop", "this.a"),
+            ("main::Leaf::drop", "this.a.leaf"),
+            ("main::Leaf::drop", "this.b.leaf"),
+        ]);
+    }
+
+    #[test]
+    fn classes_are_dropped_before_the_classes_they_inherit_from() {
+        let leaf = DropGlue::new("main::Leaf".to_string(), true, None, &[], BiPos::default());
+        let base = DropGlue::new("main::Base".to_string(), true, None, &[("inherited", Some(&leaf))], BiPos::default());
+        let derived = DropGlue::new("main::Derived".to_string(), true, Some(&base), &[("own", Some(&leaf))], BiPos::default());
+        assert_eq!(hooks(&derived), vec![
+            ("main::Derived::drop", "this"),
+            ("main::Base::drop", "this"),
+            ("main::Leaf::drop", "this.inherited"),
+            ("main::Leaf::drop", "this.own"),
+        ]);
+        assert!(DropGlue::new("main::Plain".to_string(), false, None, &[("n", None)], BiPos::default()).is_empty());
+    }
+
+    ///A `DropGlue` of `path` with `hooks`, which ends the MIR of its struct.
+    fn glue(path: &str, hooks: &[(&str, &str)]) -> MirPattern{
+        let mut glue = MirPattern::new().named(MIRInstructions::DropGlue, path).usize(hooks.len());
+        for (method, place) in hooks.iter(){
+            glue = glue.str(method).str(place);
+        }
+        glue.ins(MIRInstructions::EndStruct)
+    }
+
+    #[test]
+    fn drops_call_the_drop_blocks_of_objects_and_their_fields_in_order() {
+        let source = "struct Leaf(val n: Int){\n    drop{\n        let m = this.n\n    }\n}\n\nstruct Holder(val leaf: Leaf, val k: Int){\n    drop{\n        let m = this.k\n    }\n}\n\nopen class Base(val leaf: Leaf){\n    drop{\n        let m = 1\n    }\n}\n\nclass Derived(val other: Leaf) : Base(Leaf(2)){\n    drop{\n        let m = 2\n    }\n}\n\nfun main(){\n    let a = Leaf(1)\n    let h = Holder(Leaf(3), 4)\n    let d = Derived(Leaf(5))\n}\n";
+        let (mir, messages, _) = MemmyGenerator::analyze_source(source);
+        assert_eq!(messages, Vec::<String>::new());
+        let mir = mir.unwrap();
+        assert!(glue("test::Leaf", &[("test::Leaf::drop", "this")]).ends(&mir[1]));
+        assert!(glue("test::Holder", &[("test::Holder::drop", "this"), ("test::Leaf::drop", "this.leaf")]).ends(&mir[2]));
+        assert!(glue("test::Base", &[("test::Base::drop", "this"), ("test::Leaf::drop", "this.leaf")]).ends(&mir[3]));
+        assert!(glue("test::Derived", &[("test::Derived::drop", "this"), ("test::Base::drop", "this"), ("test::Leaf::drop", "this.leaf"), ("test::Leaf::drop", "this.other")]).ends(&mir[4]));
+        //Locals are dropped in reverse declaration order, each through the glue of its struct
+        assert!(MirPattern::new().named(MIRInstructions::Drop, "d").named(MIRInstructions::Drop, "h").named(MIRInstructions::Drop, "a").ins(MIRInstructions::EndBlock).ins(MIRInstructions::EndFun).ends(&mir[5]));
+    }
+}
@@ -0,0 +1,200 @@
+use super::{
+    cfg::{
+        Cfg,
+        Event,
+        Object,
+        Use,
+    },
+    safety::Violation,
+    MemmyGenerator,
+};
+
+use core::pos::BiPos;
+
+use dataflow::{
+    Analysis,
+    Direction,
+};
+
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+///What is known about the objects declared with `= None` at a point in a graph.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Init{
+    ///The objects that may not have been assigned yet.
+    uninit: HashSet<usize>,
+    ///The immutable objects that may have been assigned already, and where.
+    assigned: HashMap<usize, BiPos>,
+}
+
+impl Init{
+    fn merge(&mut self, other: &Init){
+        self.uninit.extend(other.uninit.iter().copied());
+        for (object, pos) in other.assigned.iter(){
+            self.assigned.entry(*object).or_insert(*pos);
+        }
+    }
+
+    ///Update what is known after `event`, given what is known before it, returning the violation it is if it is one.
+    fn transfer(&mut self, event: &Event, objects: &[Object]) -> Option<Violation>{
+        match event{
+            Event::Def(object) => {
+                self.uninit.remove(object);
+                self.assigned.remove(object);
+                None
+            }
+            Event::Lateinit(object) => {
+                self.uninit.insert(*object);
+                self.assigned.remove(object);
+                None
+            }
+            Event::Assign(object, pos) => {
+                self.uninit.remove(object);
+                if !objects[*object].once{
+                    return None
+                }
+                self.assigned.insert(*object, *pos).map(|first| Violation::Reassigned{
+                    ident: objects[*object].ident.ident.clone(),
+                    pos: *pos,
+                    first
+                })
+            }
+            Event::Use(Use{ object, pos, .. }) | Event::Touch(object, pos) => if self.uninit.contains(object){
+                Some(Violation::Uninitialized{
+                    ident: objects[*object].ident.ident.clone(),
+                    pos: *pos,
+                    decl: objects[*object].ident.pos
+                })
+            }else{
+                None
+            },
+        }
+    }
+}
+
+impl MemmyGenerator{
+    ///Check that every object declared with `= None` is assigned before it is used on every path through its body, and that an immutable one is never assigned twice.
+    pub(crate) fn check_init(&self, graphs: &[Cfg]) -> Result<(), ()>{
+        let mut result = Ok(());
+        for violation in graphs.iter().flat_map(|cfg| cfg.check_init()){
+            result = Err(());
+            self.report(&violation)?;
+        }
+        result
+    }
+}
+
+impl<'t> Cfg<'t>{
+    ///Find every use of an object declared with `= None` where it may not be assigned yet, and every assignment of an immutable one where it may be assigned already.
+    pub fn check_init(&self) -> Vec<Violation>{
+        let analysis = Initialization{
+            objects: &self.objects
+        };
+        let init_in = self.graph.solve(&analysis).start;
+        let mut violations = vec![];
+        for (block, mut init) in self.graph.blocks.iter().zip(init_in){
+            for event in block.items.iter(){
+                if let Some(violation) = init.transfer(event, &self.objects){
+                    if !violations.contains(&violation){
+                        violations.push(violation);
+                    }
+                }
+            }
+        }
+        violations
+    }
+}
+
+///What is known about the objects declared with `= None` in a graph whose objects are `objects`.
+struct Initialization<'a>{
+    objects: &'a [Object],
+}
+
+impl<'a, 't> Analysis<Event<'t>> for Initialization<'a>{
+    type Fact = Init;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn bottom(&self) -> Self::Fact{
+        Init::default()
+    }
+
+    fn merge(&self, fact: &mut Self::Fact, other: &Self::Fact){
+        fact.merge(other);
+    }
+
+    fn transfer(&self, event: &Event<'t>, init: &mut Self::Fact){
+        init.transfer(event, self.objects);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cfg::Builder,
+        ident::Identifier,
+    };
+    use core::pos::Position;
+    use ty::{
+        Ty,
+        TyKind,
+    };
+
+    fn ident(name: &str) -> Identifier{
+        Identifier{
+            ident: name.to_owned(),
+            pos: BiPos::default()
+        }
+    }
+
+    fn at(line: usize) -> BiPos{
+        BiPos{
+            start: Position(line, 0),
+            end: Position(line, 1),
+            ..BiPos::default()
+        }
+    }
+
+    #[test]
+    fn uses_before_assignment_on_some_path_are_found() {
+        let mut cfg = Builder::new();
+        let int = Ty::new(TyKind::Int, BiPos::default());
+        cfg.lateinit(&ident("a"), &int, false);
+        //a is only assigned in one of two branches
+        let cond = cfg.flow.current();
+        let join = cfg.flow.block();
+        cfg.flow.branch();
+        cfg.assign(&ident("a"), at(0));
+        cfg.touch(&ident("a"), at(1));
+        cfg.flow.goto(join);
+        cfg.flow.switch_to(cond);
+        cfg.flow.goto(join);
+        cfg.flow.switch_to(join);
+        cfg.touch(&ident("a"), at(2));
+        let graphs = cfg.finish();
+        assert_eq!(graphs[0].check_init(), vec![Violation::Uninitialized{ ident: "a".to_owned(), pos: at(2), decl: BiPos::default() }]);
+    }
+
+    #[test]
+    fn immutable_objects_assigned_in_loops_are_reassigned() {
+        let mut cfg = Builder::new();
+        let int = Ty::new(TyKind::Int, BiPos::default());
+        cfg.lateinit(&ident("a"), &int, false);
+        cfg.lateinit(&ident("b"), &int, true);
+        //while(cond){ a = 0; b = 0 }
+        let head = cfg.flow.branch();
+        let after = cfg.flow.block();
+        cfg.flow.goto(after);
+        cfg.flow.branch();
+        cfg.assign(&ident("a"), at(0));
+        cfg.assign(&ident("b"), at(1));
+        cfg.flow.goto(head);
+        cfg.flow.switch_to(after);
+        let graphs = cfg.finish();
+        assert_eq!(graphs[0].check_init(), vec![Violation::Reassigned{ ident: "a".to_owned(), pos: at(0), first: at(0) }]);
+    }
+}
@@ -0,0 +1,134 @@
+use super::{
+    Load,
+    Unload,
+    ident::Identifier,
+    MemmyGenerator,
+    layout::{
+        Layout,
+        StructLayout,
+        REF_SIZE,
+    },
+};
+
+use core::pos::BiPos;
+
+use ir::{
+    Chunk,
+    mir::MIRInstructions,
+};
+
+use ir_traits::WriteInstruction;
+
+use notices::{
+    DiagnosticSourceBuilder,
+    DiagnosticLevel
+};
+
+///A trait mask has a bit for each trait, so this is how many traits a module can have.
+const MAX_TRAITS: usize = 64;
+
+///An interface or a trait. Only what calls through an interface and `is` checks need is kept.
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct Interface{
+    pub ident: Identifier,
+    ///The names of the methods, in the order of the slots of the itables of the classes that implement it.
+    pub methods: Vec<String>,
+    ///The bit of a trait in the trait mask of the structs that implement it, or `None` if this is an interface.
+    pub bit: Option<usize>,
+    pub pos: BiPos,
+}
+
+///The trait mask of a struct, with the bit of each of its traits set.
+#[derive(Debug, Clone)]
+pub struct TraitMask{
+    ///The full path of the struct, such as `main::Array`.
+    pub structure: String,
+    pub mask: u64,
+}
+
+impl Interface{
+    ///Load an interface, or a trait if `is_trait`, and register it. Each trait gets the next free bit of the trait mask.
+    pub fn load(chunk: &Chunk, memmy: &MemmyGenerator, is_trait: bool) -> Result<Self, ()>{
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
+            }
+        };
+        let ident = Identifier::load(chunk, memmy)?;
+        let mut methods = vec![];
+        while chunk.read_bool(){
+            methods.push(chunk.read_string().to_owned());
+        }
+        let bit = match is_trait{
+            true => Some(memmy.interfaces.borrow().values().filter(|interface| interface.bit.is_some()).count()),
+            false => None
+        };
+        if bit == Some(MAX_TRAITS){
+            let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), ident.pos.start.0)
+                .level(DiagnosticLevel::Error)
+                .message(format!("{} is one trait more than fits in the trait mask of a struct", ident.ident))
+                .range(ident.pos.col_range())
+                .build();
+            memmy.emit_diagnostic(&[
+                format!("A module can have at most {} traits.", MAX_TRAITS)
+            ], &[diagnosis]);
+            return Err(())
+        }
+        let interface = Interface{
+            ident,
+            methods,
+            bit,
+            pos
+        };
+        let path = format!("{}::{}", memmy.module_name, interface.ident.ident);
+        //An object of an interface is a reference to an object of a class that implements it
+        if bit.is_none(){
+            memmy.structs.borrow_mut().insert(path.clone(), StructLayout{ layout: Layout{ size: REF_SIZE, align: REF_SIZE }, offsets: vec![] });
+            memmy.copyable.borrow_mut().insert(path.clone());
+        }
+        memmy.interfaces.borrow_mut().insert(path, interface.clone());
+        Ok(interface)
+    }
+}
+
+impl Unload for TraitMask{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(MIRInstructions::TraitMask);
+        chunk.write_str(&self.structure);
+        chunk.write_usize(self.mask as usize);
+        Ok(chunk)
+    }
+}
+
+impl MemmyGenerator{
+    ///The trait mask of the struct at `structure`, which implements the traits at `traits`.
+    pub(crate) fn trait_mask(&self, structure: String, traits: &[String], pos: BiPos) -> Result<TraitMask, ()>{
+        let mut mask = 0;
+        for path in traits.iter(){
+            match self.interfaces.borrow().get(path).and_then(|interface| interface.bit){
+                Some(bit) => mask |= 1 << bit,
+                None => {
+                    let diagnosis = DiagnosticSourceBuilder::new(self.module_name.clone(), pos.start.0)
+                        .level(DiagnosticLevel::Error)
+                        .message(format!("The trait {} of {} must be loaded before it", path, structure))
+                        .range(pos.col_range())
+                        .build();
+                    self.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diagnosis]);
+                    return Err(())
+                }
+            }
+        }
+        Ok(TraitMask{
+            structure,
+            mask
+        })
+    }
+}
@@ -0,0 +1,154 @@
+use super::{
+    block::Block,
+    capture::{
+        BindingKind,
+        Capture,
+        CaptureMode,
+    },
+    fun::FunParam,
+    lifetime::Flow,
+    cfg::{
+        Build,
+        Builder,
+        Site,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+    Load,
+    Unload,
+    MemmyGenerator,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+    mir::MIRInstructions,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct Lambda{
+    params: Vec<FunParam>,
+    body: Block,
+    ///The objects from outside of the lambda that its body uses, in the order they are first used.
+    captures: Vec<Capture>,
+}
+
+impl Load for Lambda{
+    type Output = Lambda;
+
+    fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
+        memmy.env.enter_lambda();
+        memmy.env.enter_flow(Flow::Lambda);
+        let depth = memmy.env.depth();
+        let mut params = vec![];
+        while let Some(HIRInstruction::FnParam) = chunk.read_instruction() as Option<HIRInstruction>{
+            let param = match FunParam::load(chunk, memmy){
+                Ok(param) => param,
+                Err(diag) => {
+                    memmy.env.exit_flow();
+                    memmy.env.exit_lambda();
+                    return Err(diag)
+                }
+            };
+            memmy.env.declare(&param.ident, BindingKind::Local, &param.typename);
+            params.push(param);
+        }
+        memmy.env.enter_frame(params.len());
+        let body = Block::load(chunk, memmy);
+        memmy.env.exit_frame();
+        if let Ok(body) = &body{
+            memmy.env.end_scope(depth, body.pos);
+        }
+        memmy.env.exit_flow();
+        let captures = memmy.env.exit_lambda();
+        Ok(Lambda{
+            params,
+            body: body?,
+            captures,
+        })
+    }
+}
+
+
+impl Unload for Lambda{
+    ///A lambda owns what it copies or moves into it along with its params, so it drops them after its body. It only borrows what it refers to.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        for capture in self.captures.iter(){
+            chunk.write_bool(true);
+            chunk.write_instruction(match capture.mode{
+                CaptureMode::Ref => MIRInstructions::Ref,
+                CaptureMode::Copy => MIRInstructions::Copy,
+                CaptureMode::Move if capture.copied.get() => MIRInstructions::Copy,
+                CaptureMode::Move => MIRInstructions::Move,
+            });
+            chunk.write_pos(capture.ident.pos);
+            chunk.write_str(&capture.ident.ident);
+        }
+        chunk.write_bool(false);
+        for param in self.params.iter(){
+            chunk.write_chunk(param.unload()?);
+        }
+        chunk.write_chunk(self.body.unload()?);
+        let owned = self.captures.iter().filter(|capture| capture.mode != CaptureMode::Ref).map(|capture| &capture.ident);
+        for ident in self.params.iter().map(|param| &param.ident).chain(owned).collect::<Vec<_>>().into_iter().rev(){
+            chunk.write_instruction(MIRInstructions::Drop);
+            chunk.write_pos(self.body.pos);
+            chunk.write_str(&ident.ident);
+        }
+        chunk.write_instruction(MIRInstructions::EndLambda);
+        Ok(chunk)
+    }
+}
+
+impl<'t> Build<'t> for Lambda{
+    ///Creating the lambda uses what it moves into it and touches everything else it captures. Its body is built as a graph of its own, which starts by declaring what it captures by value.
+    ///The body can run again every time the lambda is called, so its end flows back to where its params are declared.
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        let mut body = Builder::new();
+        for capture in self.captures.iter(){
+            if capture.mode != CaptureMode::Ref{
+                if let Some(ty) = cfg.ty(&capture.ident){
+                    body.declare(&capture.ident, &ty);
+                }
+            }
+            match capture.mode{
+                CaptureMode::Move => cfg.use_object(&capture.ident, capture.ident.pos, Site::Capture(capture)),
+                _ => cfg.touch(&capture.ident, capture.ident.pos),
+            }
+        }
+        let head = body.flow.branch();
+        for param in self.params.iter(){
+            body.declare(&param.ident, &param.typename);
+        }
+        self.body.build(&mut body);
+        let exit = body.flow.exit();
+        body.flow.edge(exit, head);
+        cfg.nest(body.finish());
+    }
+}
+
+impl Count for Lambda{
+    ///A lambda holds a reference to every property it captures, for as long as it is alive.
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        let referents = self.captures.iter().filter(|capture| capture.mode == CaptureMode::Ref).flat_map(|capture| counter.refer(&capture.ident)).collect();
+        counter.enter_frame();
+        counter.enter_scope();
+        for param in self.params.iter(){
+            counter.declare(&param.ident, None, false, vec![], param.ident.pos);
+        }
+        self.body.count(counter);
+        counter.exit_scope();
+        counter.exit_frame(None, vec![], self.body.pos);
+        referents
+    }
+}
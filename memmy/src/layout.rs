@@ -0,0 +1,213 @@
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use std::collections::HashMap;
+
+///How many bytes an object takes up, and what its address must be a multiple of.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout{
+    pub size: usize,
+    pub align: usize,
+}
+
+///The layout of a struct, along with where each of its fields starts within it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructLayout{
+    pub layout: Layout,
+    pub offsets: Vec<(String, usize)>,
+}
+
+impl Layout{
+    fn scalar(size: usize) -> Layout{
+        Layout{
+            size,
+            align: size.max(1)
+        }
+    }
+
+    ///A value tagged with a byte that tells whether it holds `payloads`, such as an `Option`.
+    fn tagged(payloads: &[Layout]) -> Layout{
+        let align = payloads.iter().map(|payload| payload.align).max().unwrap_or(1);
+        let size = payloads.iter().map(|payload| payload.size).max().unwrap_or(0);
+        Layout{
+            size: round_up(round_up(1, align) + size, align),
+            align
+        }
+    }
+}
+
+///The size of a reference, which is a pointer to what it refers to.
+pub const REF_SIZE: usize = 8;
+///The name that the pointer to the vtable of an object of a class is laid out under. It is not a valid identifier, so no field can have it.
+pub const VTABLE_FIELD: &str = "<vtable>";
+///The name that the trait mask of an object of a struct with traits is laid out under.
+pub const TRAITS_FIELD: &str = "<traits>";
+///The name that the tag of a value of an enum is laid out under, which tells which of its variants it is.
+pub const TAG_FIELD: &str = "<tag>";
+
+///The layout of an enum, which is big enough to hold any of its variants.
+///Each variant is laid out as a struct that starts with the tag, so the fields of every variant start after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumLayout{
+    pub layout: Layout,
+    pub tag: Layout,
+    ///The layout of each variant in the order they are declared, which is also the order of their tags. Its fields are named by their index.
+    pub variants: Vec<(String, StructLayout)>,
+}
+
+impl EnumLayout{
+    ///Lay out an enum whose variants have fields of the layouts in `variants`. The tag is as small as can tell them all apart.
+    pub fn new(variants: &[(String, Vec<Layout>)]) -> EnumLayout{
+        let tag = match variants.len(){
+            0..=0x100 => Layout::scalar(1),
+            0x101..=0x10000 => Layout::scalar(2),
+            _ => Layout::scalar(4),
+        };
+        let variants = variants.iter().map(|(name, fields)| {
+            let fields = fields.iter().enumerate().map(|(index, field)| (index.to_string(), *field)).collect::<Vec<_>>();
+            (name.clone(), StructLayout::new(&[(TAG_FIELD.to_string(), tag)]).extend(&fields))
+        }).collect::<Vec<_>>();
+        let align = variants.iter().map(|(_, variant)| variant.layout.align).max().unwrap_or(1);
+        let size = variants.iter().map(|(_, variant)| variant.layout.size).max().unwrap_or(0);
+        EnumLayout{
+            layout: Layout{
+                size: round_up(size, align),
+                align
+            },
+            tag,
+            variants
+        }
+    }
+
+    ///The tag of the variant `name` and its layout, if the enum has it.
+    pub fn variant(&self, name: &str) -> Option<(usize, &StructLayout)>{
+        self.variants.iter().enumerate().find(|(_, (variant, _))| variant == name).map(|(tag, (_, layout))| (tag, layout))
+    }
+}
+
+impl StructLayout{
+    ///Lay out `fields` in the order they are declared, each at the first offset that suits its alignment.
+    pub fn new(fields: &[(String, Layout)]) -> StructLayout{
+        StructLayout{
+            layout: Layout{ size: 0, align: 1 },
+            offsets: vec![]
+        }.extend(fields)
+    }
+
+    ///Lay out `fields` after the fields of this layout, which keep their offsets.
+    ///This is how a class lays out the fields it declares after the fields it inherits, so that it can be used as any class it inherits from.
+    pub fn extend(&self, fields: &[(String, Layout)]) -> StructLayout{
+        let mut offsets = self.offsets.clone();
+        let mut size = self.layout.size;
+        let mut align = self.layout.align;
+        for (name, layout) in fields.iter(){
+            let offset = round_up(size, layout.align);
+            offsets.push((name.clone(), offset));
+            size = offset + layout.size;
+            align = align.max(layout.align);
+        }
+        StructLayout{
+            layout: Layout{
+                size: round_up(size, align),
+                align
+            },
+            offsets
+        }
+    }
+}
+
+///The layout of a value of type `ty`, or `None` if it could not be inferred.
+///The layout of a struct or an enum is found in `structs`, by its full path such as `main::Point`.
+pub fn layout_of(ty: &Ty, structs: &HashMap<String, StructLayout>) -> Option<Layout>{
+    let layout = match ty.kind().as_ref(){
+        TyKind::Unit => Layout{ size: 0, align: 1 },
+        TyKind::Bool | TyKind::Int8 | TyKind::UInt8 => Layout::scalar(1),
+        TyKind::Int16 | TyKind::UInt16 => Layout::scalar(2),
+        TyKind::Int | TyKind::UInt32 | TyKind::Float | TyKind::Char => Layout::scalar(4),
+        TyKind::Int64 | TyKind::UInt64 | TyKind::Float64 | TyKind::Ref(_) => Layout::scalar(8),
+        //A pointer and a length, or a pointer to the code and a pointer to what it captures
+        TyKind::String | TyKind::Function(..) => Layout{ size: 16, align: 8 },
+        TyKind::Option(inner) => Layout::tagged(&[layout_of(&ty.child(inner), structs)?]),
+        TyKind::App(path, args) => {
+            let args = args.iter().map(|arg| layout_of(&ty.child(arg), structs)).collect::<Option<Vec<Layout>>>()?;
+            match (path.join("::").as_str(), args.as_slice()){
+                ("Range", [bound]) => StructLayout::new(&[("start".to_string(), *bound), ("end".to_string(), *bound)]).layout,
+                ("Result", [ok, err]) => EnumLayout::new(&[("Ok".to_string(), vec![*ok]), ("Err".to_string(), vec![*err])]).layout,
+                _ => return None
+            }
+        }
+        TyKind::Named(path) => structs.get(&path.join("::"))?.layout,
+        TyKind::Param(_) | TyKind::Var(_) => return None,
+    };
+    Some(layout)
+}
+
+fn round_up(offset: usize, align: usize) -> usize{
+    offset.div_ceil(align) * align
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::pos::BiPos;
+
+    fn ty(kind: TyKind) -> Ty{
+        Ty::new(kind, BiPos::default())
+    }
+
+    #[test]
+    fn fields_are_padded_to_their_alignment() {
+        let mut structs = HashMap::new();
+        let flags = StructLayout::new(&[
+            ("on".to_string(), layout_of(&ty(TyKind::Bool), &structs).unwrap()),
+            ("count".to_string(), layout_of(&ty(TyKind::Int64), &structs).unwrap()),
+            ("tag".to_string(), layout_of(&ty(TyKind::Char), &structs).unwrap()),
+        ]);
+        assert_eq!(flags.offsets, vec![("on".to_string(), 0), ("count".to_string(), 8), ("tag".to_string(), 16)]);
+        assert_eq!(flags.layout, Layout{ size: 24, align: 8 });
+        structs.insert("main::Flags".to_string(), flags);
+        let named = ty(TyKind::Named(vec![format!("main"), format!("Flags")]));
+        assert_eq!(layout_of(&named, &structs), Some(Layout{ size: 24, align: 8 }));
+        assert_eq!(layout_of(&Ty::option(&named, BiPos::default()), &structs), Some(Layout{ size: 32, align: 8 }));
+        assert_eq!(layout_of(&Ty::option(&ty(TyKind::Int16), BiPos::default()), &structs), Some(Layout{ size: 4, align: 2 }));
+        assert_eq!(layout_of(&ty(TyKind::Named(vec![format!("main"), format!("Missing")])), &structs), None);
+    }
+
+    #[test]
+    fn inherited_fields_keep_their_offsets() {
+        let pointer = Layout{ size: 8, align: 8 };
+        let shape = StructLayout::new(&[(VTABLE_FIELD.to_string(), pointer), ("sides".to_string(), Layout::scalar(1))]);
+        assert_eq!(shape.layout, Layout{ size: 16, align: 8 });
+        let square = shape.extend(&[("side".to_string(), Layout::scalar(4))]);
+        assert_eq!(square.offsets, vec![(VTABLE_FIELD.to_string(), 0), ("sides".to_string(), 8), ("side".to_string(), 16)]);
+        assert_eq!(square.layout, Layout{ size: 24, align: 8 });
+    }
+
+    #[test]
+    fn variants_share_the_memory_after_their_tag() {
+        let shape = EnumLayout::new(&[
+            ("Circle".to_string(), vec![Layout::scalar(8)]),
+            ("Rect".to_string(), vec![Layout::scalar(4), Layout::scalar(4)]),
+            ("Empty".to_string(), vec![]),
+        ]);
+        assert_eq!(shape.layout, Layout{ size: 16, align: 8 });
+        assert_eq!(shape.tag, Layout{ size: 1, align: 1 });
+        let (tag, rect) = shape.variant("Rect").unwrap();
+        assert_eq!(tag, 1);
+        assert_eq!(rect.offsets, vec![(TAG_FIELD.to_string(), 0), ("0".to_string(), 4), ("1".to_string(), 8)]);
+        assert_eq!(shape.variant("Empty").map(|(tag, empty)| (tag, empty.layout)), Some((2, Layout{ size: 1, align: 1 })));
+        assert_eq!(shape.variant("Square"), None);
+        let result = Ty::app("Result", &[ty(TyKind::Int16), ty(TyKind::String)], BiPos::default());
+        assert_eq!(layout_of(&result, &HashMap::new()), Some(Layout{ size: 24, align: 8 }));
+    }
+
+    #[test]
+    fn nullable_values_are_laid_out_like_an_enum_of_none_and_some() {
+        let int16 = ty(TyKind::Int16);
+        let option = EnumLayout::new(&[("None".to_string(), vec![]), ("Some".to_string(), vec![layout_of(&int16, &HashMap::new()).unwrap()])]);
+        assert_eq!(Some(option.layout), layout_of(&Ty::option(&int16, BiPos::default()), &HashMap::new()));
+        assert_eq!(option.variant("Some").map(|(tag, some)| (tag, some.offsets[1].1)), Some((1, 2)));
+    }
+}
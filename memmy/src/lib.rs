@@ -1,7 +1,10 @@
 use ir::{
     Chunk,
+    mir::MIRInstructions,
 };
 
+use ir_traits::WriteInstruction;
+
 use std::{
     sync::{
         mpsc::{
@@ -9,7 +12,12 @@ use std::{
             Receiver
         },
         Arc, Mutex
-    }
+    },
+    cell::RefCell,
+    collections::{
+        HashMap,
+        HashSet,
+    },
 };
 
 use notices::{
@@ -17,6 +25,7 @@ use notices::{
     DiagnosticBuilder,
     DiagnosticLevel,
     DiagnosticSource,
+    DiagnosticSourceBuilder,
 };
 
 use core::pos::BiPos;
@@ -31,10 +40,30 @@ mod property;
 mod fun;
 mod local;
 mod expr;
+mod block;
+mod loops;
+mod conditional;
 mod module;
 mod lifetime;
 mod ty;
 mod mutability;
+mod lambda;
+mod capture;
+mod assign;
+mod structs;
+mod layout;
+mod alloc;
+mod vtable;
+mod glue;
+mod interfaces;
+mod enums;
+mod patterns;
+mod cfg;
+mod moves;
+mod init;
+mod refcount;
+mod escape;
+mod safety;
 
 pub trait Load{
     type Output;
@@ -55,16 +84,27 @@ struct Mutability{
     pos: BiPos,
 }
 
+///What memmy reports about the modules it analyzes, besides its diagnostics.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemmyFlags{
+    ///Print the lifetime of every object once its module is loaded. See [lifetime::ObjectLifetime].
+    pub lifetimes: bool,
+    ///Print where every property is allocated, and why those on the heap escape. See [escape::Residence].
+    pub report: bool,
+}
+
 pub struct MemmyManager{
     ///The threadpool of typeck instances. This is populated by [enqueueModule].
     thread_pool: ThreadPool,
+    flags: MemmyFlags,
 }
 
 impl MemmyManager{
     ///Create a new memmy manager with the given notice sender channel.
-    pub fn new() -> Self{
+    pub fn new(flags: MemmyFlags) -> Self{
         MemmyManager{
             thread_pool: ThreadPool::new().unwrap(),
+            flags,
         }
     }
 
@@ -72,8 +112,9 @@ impl MemmyManager{
     ///This will spawn a new task/thread in thread_pool which executes [Typeck::start_checking].
     pub fn enqueue_module(&self, module_name: String, diagnostics_tx: Sender<Option<Diagnostic>>, typeck_rx: Receiver<Option<Chunk>>, mir_tx: Sender<Option<Chunk>>, master_tx: Sender<ModuleMessage>, master_rx: Arc<Mutex<Receiver<ModuleMessage>>>){
         let module_name_clone = module_name.clone();
+        let flags = self.flags;
         self.thread_pool.spawn_ok(async move{
-            let typeck = MemmyGenerator::start(module_name_clone.clone(), mir_tx, diagnostics_tx, typeck_rx, master_tx, master_rx);
+            let typeck = MemmyGenerator::start(module_name_clone.clone(), flags, mir_tx, diagnostics_tx, typeck_rx, master_tx, master_rx);
             if let Err(()) = typeck{
                 return
             };
@@ -90,6 +131,30 @@ pub struct MemmyGenerator{
     diagnostic_tx: Sender<Option<Diagnostic>>,
     master_tx: Sender<ModuleMessage>,
     master_rx: Arc<Mutex<Receiver<ModuleMessage>>>,
+    ///The objects visible at the point being loaded.
+    env: capture::Environment,
+    ///The layout of every struct loaded so far, by its full path such as `main::Point`.
+    structs: RefCell<HashMap<String, layout::StructLayout>>,
+    ///The vtable of every class loaded so far, by its full path.
+    vtables: RefCell<HashMap<String, vtable::VTable>>,
+    ///The interfaces and traits loaded so far, by their full paths.
+    interfaces: RefCell<HashMap<String, interfaces::Interface>>,
+    ///The layout of every enum loaded so far, by its full path.
+    enums: RefCell<HashMap<String, layout::EnumLayout>>,
+    ///The full path of the enum of every variant loaded so far, by the name of the variant.
+    variants: RefCell<HashMap<String, String>>,
+    ///The names of the methods of every struct loaded so far, by its full path.
+    methods: RefCell<HashMap<String, Vec<String>>>,
+    ///The full path of the base class of every class loaded so far that has one, by the full path of the class.
+    bases: RefCell<HashMap<String, String>>,
+    ///The drop glue of every struct loaded so far, by its full path.
+    drop_glue: RefCell<HashMap<String, glue::DropGlue>>,
+    ///The return type of every function being loaded, innermost last.
+    return_tys: RefCell<Vec<::ty::Ty>>,
+    ///The full path of every struct, enum and interface loaded so far whose objects can be copied.
+    copyable: RefCell<HashSet<String>>,
+    ///Every object that is copied with `copy`, which is checked to be copyable once every type of the module is known.
+    copies: RefCell<Vec<(ident::Identifier, ::ty::Ty)>>,
 }
 
 impl MemmyGenerator{
@@ -103,24 +168,94 @@ impl MemmyGenerator{
         self.diagnostic_tx.send(Some(diagnostic)).unwrap();
     }
 
-    pub fn start(module_name: String, mir_tx: Sender<Option<Chunk>>, diagnostic_tx: Sender<Option<Diagnostic>>, typeck_rx: Receiver<Option<Chunk>>, master_tx: Sender<ModuleMessage>, master_rx: Arc<Mutex<Receiver<ModuleMessage>>>) -> Result<(),()>{
-        let memmy = Self{
+    ///Request the source snippet for `pos` from the master thread.
+    pub fn request_source_snippet(&self, pos: BiPos) -> Result<String, DiagnosticSource>{
+        if let Err(_) = self.master_tx.send(ModuleMessage::SourceRequest(pos)){
+            let diag = DiagnosticSourceBuilder::new(self.module_name.clone(), 0)
+                .level(DiagnosticLevel::Error)
+                .message(format!("The master channel was closed??"))
+                .build();
+            return Err(diag);
+        }
+        let master_rx_lock = match self.master_rx.lock(){
+            Ok(lock) => lock,
+            Err(err) => {
+                return Err(DiagnosticSourceBuilder::new(self.module_name.clone(), pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(err.to_string())
+                    .build());
+            }
+        };
+        match master_rx_lock.recv(){
+            Ok(ModuleMessage::SourceResponse(source_snip)) => Ok(source_snip),
+            Ok(thing) => Err(DiagnosticSourceBuilder::new(self.module_name.clone(), 0)
+                .level(DiagnosticLevel::Error)
+                .message(format!("Not sure what we got but we shouldn't have: {:?}", thing))
+                .build()),
+            Err(_) => Err(DiagnosticSourceBuilder::new(self.module_name.clone(), 0)
+                .level(DiagnosticLevel::Error)
+                .message(format!("The master channel was closed??"))
+                .build()),
+        }
+    }
+
+    ///Build an error diagnostic source that points at `pos`, with the source snippet for `pos` attached.
+    ///If the snippet could not be retrieved, that failure is emitted instead and `Err(())` is returned.
+    pub fn error_source(&self, pos: BiPos, message: String) -> Result<DiagnosticSource, ()>{
+        let source = match self.request_source_snippet(pos){
+            Ok(source) => source,
+            Err(diag) => {
+                self.emit_diagnostic(&[], &[diag]);
+                return Err(())
+            }
+        };
+        Ok(DiagnosticSourceBuilder::new(self.module_name.clone(), pos.start.0)
+            .level(DiagnosticLevel::Error)
+            .message(message)
+            .range(pos.col_range())
+            .source(source)
+            .build())
+    }
+
+    pub fn start(module_name: String, flags: MemmyFlags, mir_tx: Sender<Option<Chunk>>, diagnostic_tx: Sender<Option<Diagnostic>>, typeck_rx: Receiver<Option<Chunk>>, master_tx: Sender<ModuleMessage>, master_rx: Arc<Mutex<Receiver<ModuleMessage>>>) -> Result<(),()>{
+        let mut memmy = Self::new(module_name, mir_tx, diagnostic_tx, typeck_rx, master_tx, master_rx);
+        memmy.analyze_module(flags)
+    }
+
+    fn new(module_name: String, mir_tx: Sender<Option<Chunk>>, diagnostic_tx: Sender<Option<Diagnostic>>, typeck_rx: Receiver<Option<Chunk>>, master_tx: Sender<ModuleMessage>, master_rx: Arc<Mutex<Receiver<ModuleMessage>>>) -> Self{
+        Self{
             module_name,
             mir_tx,
-            diagnostic_tx: diagnostic_tx.clone(),
+            diagnostic_tx,
             typeck_rx,
             final_chunk: Chunk::new(),
             master_tx,
-            master_rx
-        };
+            master_rx,
+            env: capture::Environment::default(),
+            structs: RefCell::new(HashMap::new()),
+            vtables: RefCell::new(HashMap::new()),
+            interfaces: RefCell::new(HashMap::new()),
+            enums: RefCell::new(HashMap::new()),
+            variants: RefCell::new(HashMap::new()),
+            methods: RefCell::new(HashMap::new()),
+            bases: RefCell::new(HashMap::new()),
+            drop_glue: RefCell::new(HashMap::new()),
+            return_tys: RefCell::new(vec![]),
+            copyable: RefCell::new(HashSet::new()),
+            copies: RefCell::new(vec![]),
+        }
+    }
+
+    ///Load the module from its TIR, analyze the memory of its objects, and lower it to MIR.
+    fn analyze_module(&mut self, flags: MemmyFlags) -> Result<(), ()>{
         let mut statements = vec![];
         loop{
-            let chunk = if let Ok(Some(chunk)) = memmy.typeck_rx.recv(){
+            let chunk = if let Ok(Some(chunk)) = self.typeck_rx.recv(){
                 chunk
             }else{
                 break
             };
-            let statement = match statements::Statement::load(&chunk, &memmy){
+            let statement = match statements::Statement::load(&chunk, self){
                 Ok(statement) => statement,
                 Err(()) => {
                     return Err(())
@@ -128,9 +263,202 @@ impl MemmyGenerator{
             };
             statements.push(statement);
         }
+        {
+            let graphs = cfg::graphs(&statements);
+            let moves = self.infer_moves(&graphs);
+            let init = self.check_init(&graphs);
+            moves.and(init)?;
+        }
+        let drops = self.count_refs(&mut statements)?;
+        if flags.lifetimes{
+            println!("Lifetimes of the objects of {}:", self.module_name);
+            for lifetime in self.env.lifetimes(){
+                println!("{}", lifetime);
+            }
+        }
+        if flags.report{
+            println!("Allocations of the properties of {}:", self.module_name);
+            for report in self.env.lifetimes().iter().filter_map(|lifetime| lifetime.residence_report()){
+                println!("{}", report);
+            }
+        }
+        self.lower(&statements, &drops)
+    }
+
+    ///Lower the statements of the module to MIR, streaming the MIR of each of them downstream as it is written.
+    ///The properties of the module live as long as it does, so they are dropped at its end in the order of `drops`.
+    fn lower(&mut self, statements: &[statements::Statement], drops: &[String]) -> Result<(), ()>{
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(MIRInstructions::Module);
+        chunk.write_str(&self.module_name);
+        self.send_mir(chunk);
         for statement in statements.iter(){
-            println!("{:?}", statement);
+            let chunk = statement.unload()?;
+            //Declarations that only type checking needs, such as interfaces, have no MIR
+            if chunk.length() != 0{
+                self.send_mir(chunk);
+            }
         }
+        let mut chunk = Chunk::new();
+        for drop in drops.iter(){
+            let pos = statements.iter().find(|statement| match &statement.kind{
+                statements::StatementKind::Property(property) => &property.ident.ident == drop,
+                _ => false
+            }).map(|statement| statement.pos);
+            if let Some(pos) = pos{
+                chunk.write_instruction(MIRInstructions::Drop);
+                chunk.write_pos(pos);
+                chunk.write_str(drop);
+            }
+        }
+        chunk.write_instruction(MIRInstructions::EndModule);
+        self.send_mir(chunk);
+        self.mir_tx.send(None).unwrap();
         Ok(())
     }
+
+    ///Send `chunk` downstream, and add it to the MIR of the whole module.
+    fn send_mir(&mut self, chunk: Chunk){
+        self.final_chunk.write_chunk(chunk.clone());
+        self.mir_tx.send(Some(chunk)).unwrap();
+    }
+}
+
+#[cfg(test)]
+impl MemmyGenerator{
+    ///Check and analyze `source` as the module `test`, returning the MIR of each of its statements if it is analyzed without errors, the message of every label of the diagnostics emitted on the way in order, and the allocation report of its properties.
+    pub(crate) fn analyze_source(source: &str) -> (Option<Vec<Chunk>>, Vec<String>, Vec<String>){
+        let (mir, diagnostics, allocations) = Self::analyze_diagnosed(source);
+        let messages = diagnostics.iter().flat_map(|diag| diag.sources.iter().map(|source| source.message().to_owned()).collect::<Vec<_>>()).collect();
+        (mir, messages, allocations)
+    }
+
+    ///Like [MemmyGenerator::analyze_source], but returning the diagnostics themselves.
+    pub(crate) fn analyze_diagnosed(source: &str) -> (Option<Vec<Chunk>>, Vec<Diagnostic>, Vec<String>){
+        use std::sync::mpsc::channel;
+        let (token_tx, token_rx) = channel();
+        let (hir_tx, hir_rx) = channel();
+        let (typeck_tx, typeck_rx) = channel();
+        let (mir_tx, mir_rx) = channel();
+        let (diagnostics_tx, diagnostics_rx) = channel();
+        let (request_tx, request_rx) = channel();
+        let (response_tx, response_rx) = channel();
+        let response_rx = Arc::new(Mutex::new(response_rx));
+        module_messages::serve_source(source.to_owned(), request_rx, response_tx);
+        let mut lexer = lexer::Lexer::new(format!("test"), source.to_owned(), token_tx, request_tx.clone(), response_rx.clone());
+        if let Err(diag) = lexer.start_tokenizing(){
+            diagnostics_tx.send(Some(diag)).unwrap();
+        }
+        drop(lexer);
+        let _ = parser::Parser::parse(format!("test"), hir_tx, token_rx, diagnostics_tx.clone(), request_tx.clone(), response_rx.clone());
+        let checked = typeck::Typeck::start_checking(format!("test"), hir_rx, typeck_tx, request_tx.clone(), response_rx.clone(), diagnostics_tx.clone());
+        let mut memmy = MemmyGenerator::new(format!("test"), mir_tx, diagnostics_tx, typeck_rx, request_tx, response_rx);
+        let analyzed = checked.and_then(|()| memmy.analyze_module(MemmyFlags::default()));
+        let mir = analyzed.ok().map(|()| mir_rx.try_iter().flatten().collect());
+        let allocations = memmy.env.lifetimes().iter().filter_map(|lifetime| lifetime.residence_report()).collect();
+        drop(memmy);
+        let diagnostics = diagnostics_rx.try_iter().flatten().collect();
+        (mir, diagnostics, allocations)
+    }
+}
+
+///MIR to look for in the MIR of a module analyzed by [MemmyGenerator::analyze_source], which matches MIR that is written the same way whatever the positions written in it are.
+#[cfg(test)]
+pub(crate) struct MirPattern{
+    chunk: Chunk,
+    ///Where each position starts in the pattern.
+    positions: Vec<usize>,
+}
+
+#[cfg(test)]
+impl MirPattern{
+    pub fn new() -> Self{
+        MirPattern{
+            chunk: Chunk::new(),
+            positions: vec![],
+        }
+    }
+
+    pub fn ins(mut self, instruction: MIRInstructions) -> Self{
+        self.chunk.write_instruction(instruction);
+        self
+    }
+
+    ///Match any position.
+    pub fn pos(mut self) -> Self{
+        self.positions.push(self.chunk.length());
+        self.chunk.write_pos(BiPos::default());
+        self
+    }
+
+    pub fn str(mut self, str: &str) -> Self{
+        self.chunk.write_str(str);
+        self
+    }
+
+    pub fn usize(mut self, size: usize) -> Self{
+        self.chunk.write_usize(size);
+        self
+    }
+
+    pub fn long(mut self, long: i64) -> Self{
+        self.chunk.write_long(long);
+        self
+    }
+
+    pub fn bool(mut self, boolean: bool) -> Self{
+        self.chunk.write_bool(boolean);
+        self
+    }
+
+    ///An instruction followed by its position and a name, such as `Drop pos name`.
+    pub fn named(self, instruction: MIRInstructions, name: &str) -> Self{
+        self.ins(instruction).pos().str(name)
+    }
+
+    fn matches_at(&self, code: &[u8], at: usize) -> bool{
+        let pattern = &self.chunk.code;
+        if at + pattern.len() > code.len(){
+            return false
+        }
+        let size = BiPos::default().start.0.to_be_bytes().len() * 8;
+        (0..pattern.len()).all(|idx| {
+            let in_pos = self.positions.iter().any(|start| (*start..*start + size).contains(&idx));
+            in_pos || pattern[idx] == code[at + idx]
+        })
+    }
+
+    pub fn starts(&self, chunk: &Chunk) -> bool{
+        self.matches_at(&chunk.code, 0)
+    }
+
+    pub fn ends(&self, chunk: &Chunk) -> bool{
+        chunk.code.len() >= self.chunk.length() && self.matches_at(&chunk.code, chunk.code.len() - self.chunk.length())
+    }
+
+    pub fn found_in(&self, chunk: &Chunk) -> bool{
+        (0..chunk.code.len()).any(|at| self.matches_at(&chunk.code, at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modules_are_lowered_one_statement_at_a_time() {
+        let (mir, messages, _) = MemmyGenerator::analyze_source("var origin = 1\n\nfun main(){\n    let x = 2\n    val p = origin\n}\n");
+        assert_eq!(messages, Vec::<String>::new());
+        let mir = mir.unwrap();
+        assert_eq!(mir.len(), 4);
+        assert!(MirPattern::new().ins(MIRInstructions::Module).str("test").starts(&mir[0]));
+        //A property of the module lives on the heap
+        assert!(MirPattern::new().named(MIRInstructions::HeapAlloc, "origin").usize(4).named(MIRInstructions::ObjInit, "origin").bool(true).ins(MIRInstructions::Integer).pos().long(1).usize(4).ends(&mir[1]));
+        assert!(MirPattern::new().named(MIRInstructions::Fun, "main").ins(MIRInstructions::Block).pos().starts(&mir[2]));
+        //Locals and the properties that never escape live on the stack, and a property of a primitive type is copied
+        assert!(MirPattern::new().named(MIRInstructions::StackAlloc, "x").usize(4).named(MIRInstructions::ObjInit, "x").bool(false).ins(MIRInstructions::Integer).pos().long(2).usize(4).found_in(&mir[2]));
+        assert!(MirPattern::new().named(MIRInstructions::StackAlloc, "p").usize(4).named(MIRInstructions::ObjInit, "p").bool(false).named(MIRInstructions::Copy, "origin").found_in(&mir[2]));
+        assert!(MirPattern::new().named(MIRInstructions::Drop, "p").named(MIRInstructions::Drop, "x").ins(MIRInstructions::EndBlock).ins(MIRInstructions::EndFun).ends(&mir[2]));
+        assert!(MirPattern::new().named(MIRInstructions::Drop, "origin").ins(MIRInstructions::EndModule).ends(&mir[3]));
+    }
 }
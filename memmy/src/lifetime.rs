@@ -1,27 +1,173 @@
 use crate::{
-    expr::Expression
+    ident::Identifier,
+    escape::{
+        Residence,
+        Escape,
+    },
 };
 
+use core::pos::BiPos;
+
+use std::fmt;
+
+///Everything that happens to an object between where it is declared and where it is dropped.
+///Its references are split into stages at the boundaries of control flow, so that whenever one reference of a stage happens, so do all of the others.
 #[derive(Debug, Clone)]
-pub struct ObjectLifetime<'a>{
-    pub stages: Vec<LifetimeStage<'a>>
+pub struct ObjectLifetime{
+    pub ident: String,
+    ///Where the object is declared.
+    pub def: BiPos,
+    pub stages: Vec<LifetimeStage>,
+    ///Where the object is dropped. This is empty for a property of a module, which is dropped at the end of the module, and for a property that is returned on every path.
+    pub drops: Vec<DropPoint>,
+    ///The objects that hold a reference to a property, which is its static reference count. See [crate::refcount].
+    pub holders: Vec<Holder>,
+    ///Where the memory of a property lives, which is only known once its references are counted. Locals have none, since they always live on the stack.
+    pub residence: Option<Residence>,
 }
 
+///An object that holds a reference to a property, and where it takes the reference.
 #[derive(Debug, Clone)]
-pub struct LifetimeStage<'a>{
-    pub references: Vec<Reference<'a>>,
+pub struct Holder{
+    pub ident: String,
+    pub pos: BiPos,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DropPoint{
+    ///The end of the scope that declares the object, which starts at the position.
+    EndOfScope(BiPos),
+    ///A `return`, `break` or `continue` that leaves the scope that declares the object early.
+    EarlyExit(BiPos),
 }
 
 #[derive(Debug, Clone)]
-pub struct Reference<'a>{
-    pub reference: &'a Expression,
-    pub kind: RefKind
+pub struct LifetimeStage{
+    ///The innermost control flow that the stage is in, of those entered after the object was declared.
+    pub flow: Flow,
+    pub references: Vec<Reference>,
+    ///The boundary of control flow that the stage starts after.
+    boundary: usize,
 }
 
 #[derive(Debug, Clone)]
+pub struct Reference{
+    pub pos: BiPos,
+    pub kind: RefKind
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum RefKind{
+    ///The object is a property, which is referenced wherever it is named.
     PropertyRef,
+    ///The object is a local, which is copied or moved wherever it is named.
     LocalRef,
+    ///The object is passed to a function, which refers to it until it returns.
     FunctionRef,
+    ///The object is referred to through a reference to it, to access one of its members or to call one of its methods.
     RefRef
-}
\ No newline at end of file
+}
+
+///Control flow that code may or may not run in, relative to the scope that declares an object.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flow{
+    ///Code that runs whenever the scope that declares the object does.
+    Straight,
+    ///A branch of an `if` or an arm of a `match`, which may not run at all.
+    Branch,
+    ///The body or the condition of a loop, which may run any number of times.
+    Loop,
+    ///The body of a lambda, which runs whenever the lambda is called.
+    Lambda,
+}
+
+impl ObjectLifetime{
+    pub fn new(ident: &Identifier) -> ObjectLifetime{
+        ObjectLifetime{
+            ident: ident.ident.clone(),
+            def: ident.pos,
+            stages: vec![],
+            drops: vec![],
+            holders: vec![],
+            residence: None,
+        }
+    }
+
+    ///Record a reference that is made in `flow`, after the boundary of control flow `boundary`.
+    ///This starts a new stage if control flow has crossed a boundary since the last reference.
+    pub fn reference(&mut self, reference: Reference, flow: Flow, boundary: usize){
+        match self.stages.last_mut(){
+            Some(stage) if stage.boundary == boundary => stage.references.push(reference),
+            _ => self.stages.push(LifetimeStage{
+                flow,
+                references: vec![reference],
+                boundary,
+            }),
+        }
+    }
+
+    ///Change the kind of the reference at `pos`, once what it is used for is known.
+    pub fn refer_as(&mut self, pos: BiPos, kind: RefKind){
+        if let Some(reference) = self.stages.iter_mut().rev().flat_map(|stage| stage.references.iter_mut().rev()).find(|reference| reference.pos == pos){
+            reference.kind = kind;
+        }
+    }
+
+    pub fn last_use(&self) -> Option<&Reference>{
+        self.stages.last().and_then(|stage| stage.references.last())
+    }
+}
+
+impl fmt::Display for RefKind{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            RefKind::PropertyRef => write!(f, "referenced"),
+            RefKind::LocalRef => write!(f, "used by value"),
+            RefKind::FunctionRef => write!(f, "passed to a function"),
+            RefKind::RefRef => write!(f, "accessed through a reference"),
+        }
+    }
+}
+
+impl fmt::Display for Flow{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            Flow::Straight => write!(f, "straight"),
+            Flow::Branch => write!(f, "in a branch"),
+            Flow::Loop => write!(f, "in a loop"),
+            Flow::Lambda => write!(f, "in a lambda"),
+        }
+    }
+}
+
+pub fn at(pos: &BiPos) -> String{
+    format!("{}:{}", pos.start.0, pos.start.1)
+}
+
+impl fmt::Display for ObjectLifetime{
+    ///The report of the lifetime, which lists where the object is declared, each of its stages, where it is last used and where it is dropped.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} is declared at {}", self.ident, at(&self.def))?;
+        for (idx, stage) in self.stages.iter().enumerate(){
+            let references = stage.references.iter().map(|reference| format!("{} at {}", reference.kind, at(&reference.pos))).collect::<Vec<_>>();
+            writeln!(f, "    stage {} ({}): {}", idx, stage.flow, references.join(", "))?;
+        }
+        match self.last_use(){
+            Some(reference) => writeln!(f, "    last used at {}", at(&reference.pos))?,
+            None => writeln!(f, "    never used")?,
+        }
+        if !self.holders.is_empty(){
+            let holders = self.holders.iter().map(|holder| format!("held by {} at {}", holder.ident, at(&holder.pos))).collect::<Vec<_>>();
+            writeln!(f, "    reference count {}: {}", holders.len(), holders.join(", "))?;
+        }
+        let drops = self.drops.iter().map(|drop| match drop{
+            DropPoint::EndOfScope(pos) => format!("at the end of the scope at {}", at(pos)),
+            DropPoint::EarlyExit(pos) => format!("early at {}", at(pos)),
+        }).collect::<Vec<_>>();
+        match (drops.is_empty(), &self.residence){
+            (true, Some(Residence::Heap(Escape::Returned{ .. }))) => write!(f, "    dropped by whatever it is returned to"),
+            (true, _) => write!(f, "    dropped at the end of the module"),
+            (false, _) => write!(f, "    dropped {}", drops.join(", ")),
+        }
+    }
+}
@@ -2,14 +2,36 @@ use super::{
     ident::Identifier,
     expr::Expression,
     Load,
+    Unload,
     MemmyGenerator,
+    capture::BindingKind,
+    cfg::{
+        Build,
+        Builder,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+    lifetime::ObjectLifetime,
+    alloc::{
+        Allocation,
+        Place,
+    },
 };
 
 use core::pos::BiPos;
 
-use ir::{ Chunk, hir::HIRInstruction };
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+
+use ty::Ty;
+
+use ir::Chunk;
 
-use ir_traits::ReadInstruction;
 
 use notices::{
     DiagnosticSourceBuilder,
@@ -24,11 +46,17 @@ pub struct LocalMut{
 
 #[derive(Debug, Clone)]
 pub struct Local{
-    ident: Identifier,
+    pub ident: Identifier,
     mutable: LocalMut,
-    typename: Identifier,
+    ty: Ty,
     pos: BiPos,
     expr: Expression,
+    ///Whether the local is declared with `= None` and initialized by its first assignment.
+    lateinit: bool,
+    ///The lifetime of the local, which is complete once the scope that declares it is loaded.
+    lifetime: Rc<RefCell<ObjectLifetime>>,
+    ///The stack memory of the local.
+    allocation: Allocation,
 }
 
 impl Load for Local{
@@ -52,7 +80,6 @@ impl Load for Local{
                 return Err(diag)
             }
         };
-        let mutable = chunk.read_bool();
         let mut_pos = match chunk.read_pos(){
             Ok(pos) => pos,
             Err(msg) => {
@@ -64,49 +91,83 @@ impl Load for Local{
                 return Err(())
             }
         };
+        let mutable = chunk.read_bool();
 
-        let typename_pos = match chunk.read_pos(){
-            Ok(pos) => pos,
-            Err(msg) => {
-                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
-                    .level(DiagnosticLevel::Error)
-                    .message(msg)
-                    .build();
-                memmy.emit_diagnostic(&[], &[diagnosis]);
-                return Err(())
-            }
+        let ty = match Ty::load(chunk, memmy){
+            Ok(ty) => ty,
+            Err(diag) => return Err(diag)
         };
-
-        match chunk.read_instruction(){
-            Some(HIRInstruction::Integer) | Some(HIRInstruction::Float) | Some(HIRInstruction::String) | Some(HIRInstruction::Unit) | Some(HIRInstruction::Custom) => {},
-            Some(_) => {}
-            None =>{
-                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+        let lateinit = chunk.read_bool();
+        let expr = match Expression::load(chunk, memmy){
+            Ok(expr) => expr,
+            Err(diag) => return Err(diag)
+        };
+        let lifetime = memmy.env.declare(&ident, BindingKind::Local, &ty);
+        //A local that is constructed as a class that its type inherits from takes up as much memory as the class
+        let layout = match expr.obj_init().or_else(|| memmy.layout(&ty)){
+            Some(layout) => layout,
+            None => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), ident.pos.start.0)
                     .level(DiagnosticLevel::Error)
-                    .message(format!("Attempted to read type information from typeck while loading property into memmy, found None."))
+                    .message(format!("Cannot compute the size of the local {} of type {}", ident.ident, ty))
+                    .range(ident.pos.col_range())
                     .build();
                 memmy.emit_diagnostic(&[], &[diagnosis]);
                 return Err(())
             }
         };
-        
-        let typename = chunk.read_string();
-        let expr = match Expression::load(chunk, memmy){
-            Ok(expr) => expr,
-            Err(diag) => return Err(diag)
+        let allocation = Allocation{
+            name: ident.ident.clone(),
+            place: Place::Stack,
+            layout,
+            mutable,
+            lateinit,
+            pos
         };
         Ok(Local{
             ident,
-            typename: Identifier{
-                ident: typename.to_owned(),
-                pos: typename_pos
-            },
+            ty,
             mutable: LocalMut{
                 mutable,
                 pos: mut_pos
             },
             pos,
             expr,
+            lateinit,
+            lifetime,
+            allocation,
         })
     }
-}
\ No newline at end of file
+}
+
+impl Unload for Local{
+    ///Write the allocation of the local on the stack, followed by its initializer unless it is initialized late.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = self.allocation.unload()?;
+        if !self.lateinit{
+            chunk.write_chunk(self.expr.unload_init(&self.ident.ident)?);
+        }
+        Ok(chunk)
+    }
+}
+
+impl<'t> Build<'t> for Local{
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        self.expr.build(cfg);
+        if self.lateinit{
+            cfg.lateinit(&self.ident, &self.ty, self.mutable.mutable);
+        }else{
+            cfg.declare(&self.ident, &self.ty);
+        }
+    }
+}
+
+impl Count for Local{
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        let referents = self.expr.count(counter);
+        let borrowed = if self.expr.is_reference(){ referents.clone() }else{ vec![] };
+        counter.declare(&self.ident, None, self.mutable.mutable, referents, self.pos);
+        counter.borrow(&self.ident, &borrowed, self.ident.pos);
+        vec![]
+    }
+}
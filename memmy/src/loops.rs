@@ -0,0 +1,152 @@
+use super::{
+    block::Block,
+    expr::Expression,
+    lifetime::Flow,
+    ident::Identifier,
+    capture::BindingKind,
+    cfg::{
+        Build,
+        Builder,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+    Load,
+    Unload,
+    MemmyGenerator,
+};
+
+use ir::Chunk;
+
+use ty::{
+    Ty,
+    TyKind,
+};
+
+#[derive(Debug, Clone)]
+pub struct Loop{
+    cond: Option<Expression>,
+    binding: Option<(Identifier, Expression)>,
+    body: Block,
+}
+
+impl Load for Loop{
+    type Output = Loop;
+
+    fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
+        let cond = if chunk.read_bool(){
+            //The condition of a loop is checked before every iteration
+            memmy.env.enter_flow(Flow::Loop);
+            match Expression::load(chunk, memmy){
+                Ok(cond) => Some(cond),
+                Err(diag) => return Err(diag)
+            }
+        }else{
+            None
+        };
+        let binding = if chunk.read_bool(){
+            let ident = Identifier::load(chunk, memmy)?;
+            let iterable = Expression::load(chunk, memmy)?;
+            Some((ident, iterable))
+        }else{
+            None
+        };
+        //What is iterated over is only evaluated once, before the first iteration
+        if cond.is_none(){
+            memmy.env.enter_flow(Flow::Loop);
+        }
+        let depth = memmy.env.depth();
+        memmy.env.enter_loop();
+        if let Some((ident, _)) = &binding{
+            //Only ranges of integers can be iterated over so far
+            memmy.env.declare(ident, BindingKind::Local, &Ty::new(TyKind::Int, ident.pos));
+        }
+        let body = Block::load(chunk, memmy);
+        memmy.env.exit_loop();
+        memmy.env.exit_flow();
+        let body = body?;
+        memmy.env.end_scope(depth, body.pos);
+        Ok(Loop{
+            cond,
+            binding,
+            body,
+        })
+    }
+}
+
+
+impl Unload for Loop{
+    ///The objects that are created anew on every iteration must be dropped at the end of every iteration rather than when the loop exits.
+    ///These are the objects declared in the body, followed by the loop's binding.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        match &self.cond{
+            Some(cond) => {
+                chunk.write_bool(true);
+                chunk.write_chunk(cond.unload()?);
+            }
+            None => chunk.write_bool(false)
+        }
+        let drops = match &self.binding{
+            Some((ident, iterable)) => {
+                chunk.write_bool(true);
+                chunk.write_str(&ident.ident);
+                chunk.write_chunk(iterable.unload()?);
+                vec![ident.ident.clone()]
+            }
+            None => {
+                chunk.write_bool(false);
+                vec![]
+            }
+        };
+        chunk.write_chunk(self.body.unload_dropping(&drops)?);
+        Ok(chunk)
+    }
+}
+
+impl<'t> Build<'t> for Loop{
+    ///The body flows back to the head of the loop, so whatever is declared outside of the loop and used in it is never used there for the last time.
+    ///Only a loop with a condition or a binding can be left from its head, any other loop is only left by a `break`.
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        if let Some((_, iterable)) = &self.binding{
+            iterable.build(cfg);
+        }
+        let head = cfg.flow.branch();
+        if let Some(cond) = &self.cond{
+            cond.build(cfg);
+        }
+        let after = cfg.flow.block();
+        if self.cond.is_some() || self.binding.is_some(){
+            cfg.flow.goto(after);
+        }
+        cfg.flow.branch();
+        cfg.enter_scope();
+        cfg.flow.enter_loop(head, after);
+        if let Some((ident, _)) = &self.binding{
+            cfg.declare(ident, &Ty::new(TyKind::Int, ident.pos));
+        }
+        self.body.build(cfg);
+        cfg.flow.goto(head);
+        cfg.flow.exit_loop();
+        cfg.exit_scope();
+        cfg.flow.switch_to(after);
+    }
+}
+
+impl Count for Loop{
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        if let Some(cond) = &mut self.cond{
+            cond.count(counter);
+        }
+        counter.enter_scope();
+        if let Some((ident, iterable)) = &mut self.binding{
+            iterable.count(counter);
+            counter.declare(ident, None, false, vec![], ident.pos);
+        }
+        self.body.count(counter);
+        counter.exit_scope();
+        vec![]
+    }
+}
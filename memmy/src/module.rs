@@ -10,14 +10,14 @@ use core::pos::BiPos;
 use ir::{ Chunk };
 
 #[allow(dead_code)]
-pub struct Module<'a>{
+pub struct Module{
     ident: Identifier,
-    statements: Vec<Statement<'a>>,
+    statements: Vec<Statement>,
     pos: BiPos,
 }
 
-impl<'a> Load for Module<'a>{
-    type Output = Module<'a>;
+impl Load for Module{
+    type Output = Module;
 
     #[allow(unused_variables)]
     fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()>{
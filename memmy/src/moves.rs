@@ -0,0 +1,261 @@
+use super::{
+    expr::Access,
+    cfg::{
+        Cfg,
+        Event,
+        Site,
+        Use,
+    },
+    safety::Violation,
+    MemmyGenerator,
+};
+
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use core::pos::BiPos;
+
+use dataflow::{
+    Analysis,
+    Direction,
+};
+
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+impl MemmyGenerator{
+    ///Decide whether each local that is passed by value is moved or copied. A local is moved where it cannot be used again afterwards, on any path through its body, and copied anywhere else.
+    ///Every copy of an object that cannot be copied is reported, along with every use of a local after it is moved with `move`.
+    pub(crate) fn infer_moves(&self, graphs: &[Cfg]) -> Result<(), ()>{
+        let mut result = Ok(());
+        for (ident, ty) in self.copies.borrow().iter(){
+            if self.is_copyable(ty){
+                continue
+            }
+            result = Err(());
+            let diagnosis = self.error_source(ident.pos, format!("{} is copied here, but objects of type {} cannot be copied", ident.ident, ty))?;
+            self.emit_diagnostic(&["Only structs and enums whose fields can all be copied, and that have no drop block, can be copied.".to_string()], &[diagnosis]);
+        }
+        for cfg in graphs.iter(){
+            for (pos, object, moved) in cfg.uses_after_moves(){
+                result = Err(());
+                self.report(&Violation::UseAfterMove{
+                    ident: cfg.objects[object].ident.ident.clone(),
+                    pos,
+                    moved
+                })?;
+            }
+            for copy in cfg.infer_moves(){
+                let object = &cfg.objects[copy.object];
+                if self.is_copyable(&object.ty){
+                    continue
+                }
+                result = Err(());
+                let diagnosis = self.error_source(copy.pos, format!("{} would have to be copied here since it is used again afterwards, but objects of type {} cannot be copied", object.ident.ident, object.ty))?;
+                self.emit_diagnostic(&["Only structs and enums whose fields can all be copied, and that have no drop block, can be copied.".to_string()], &[diagnosis]);
+            }
+        }
+        result
+    }
+
+    ///Whether objects of type `ty` can be copied. Objects of classes cannot, since they may be of a class that inherits the class of their type.
+    pub fn is_copyable(&self, ty: &Ty) -> bool{
+        match ty.kind().as_ref(){
+            TyKind::Named(path) => self.copyable.borrow().contains(&path.join("::")),
+            TyKind::Option(inner) => self.is_copyable(&ty.child(inner)),
+            TyKind::App(_, args) => args.iter().all(|arg| self.is_copyable(&ty.child(arg))),
+            _ => true
+        }
+    }
+}
+
+impl<'t> Cfg<'t>{
+    ///Decide whether each use in the graph is a move or a copy, returning the uses that are copies.
+    pub fn infer_moves(&self) -> Vec<&Use<'t>>{
+        let live_out = self.graph.solve(&Liveness).end;
+        let mut copies = vec![];
+        for (block, live) in self.graph.blocks.iter().zip(live_out){
+            let mut live = live;
+            for event in block.items.iter().rev(){
+                if let Event::Use(use_) = event{
+                    let copied = live.contains(&use_.object);
+                    match use_.site{
+                        Site::Access(access) => access.set(if copied{ Access::Copy }else{ Access::Move }),
+                        Site::Capture(capture) => capture.copied.set(copied),
+                        //Explicit copies are checked on their own, and explicit moves are checked for uses after them
+                        Site::Explicit(_) => {}
+                    }
+                    if copied && !matches!(use_.site, Site::Explicit(_)){
+                        copies.push(use_);
+                    }
+                }
+                Liveness.transfer(event, &mut live);
+            }
+        }
+        copies.reverse();
+        copies
+    }
+
+    ///Find every use of a local after it may have been moved with `move`, as where it is used, the local and where it is moved.
+    pub fn uses_after_moves(&self) -> Vec<(BiPos, usize, BiPos)>{
+        let moved_in = self.graph.solve(&Moved).start;
+        let mut uses = vec![];
+        for (block, mut moved) in self.graph.blocks.iter().zip(moved_in){
+            for event in block.items.iter(){
+                let used = match event{
+                    Event::Use(Use{ object, pos, .. }) | Event::Touch(object, pos) => Some((*object, *pos)),
+                    Event::Def(_) | Event::Lateinit(_) | Event::Assign(..) => None
+                };
+                if let Some((object, pos)) = used{
+                    if let Some(moved) = moved.get(&object){
+                        if !uses.contains(&(pos, object, *moved)){
+                            uses.push((pos, object, *moved));
+                        }
+                    }
+                }
+                Moved.transfer(event, &mut moved);
+            }
+        }
+        uses
+    }
+}
+
+///The objects that may still be used before they are given a new value.
+struct Liveness;
+
+impl<'t> Analysis<Event<'t>> for Liveness{
+    type Fact = HashSet<usize>;
+
+    const DIRECTION: Direction = Direction::Backward;
+
+    fn bottom(&self) -> Self::Fact{
+        HashSet::new()
+    }
+
+    fn merge(&self, fact: &mut Self::Fact, other: &Self::Fact){
+        fact.extend(other.iter().copied());
+    }
+
+    ///Update the objects that are live before `event`, given those that are live after it.
+    fn transfer(&self, event: &Event<'t>, live: &mut Self::Fact){
+        match event{
+            Event::Def(object) | Event::Lateinit(object) | Event::Assign(object, _) => {
+                live.remove(object);
+            }
+            Event::Use(Use{ object, .. }) | Event::Touch(object, _) => {
+                live.insert(*object);
+            }
+        }
+    }
+}
+
+///The locals that may have been moved with `move`, and where.
+struct Moved;
+
+impl<'t> Analysis<Event<'t>> for Moved{
+    type Fact = HashMap<usize, BiPos>;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn bottom(&self) -> Self::Fact{
+        HashMap::new()
+    }
+
+    fn merge(&self, fact: &mut Self::Fact, other: &Self::Fact){
+        for (object, pos) in other.iter(){
+            fact.entry(*object).or_insert(*pos);
+        }
+    }
+
+    ///Update the objects that may have been moved after `event`, given those that may have been moved before it.
+    fn transfer(&self, event: &Event<'t>, moved: &mut Self::Fact){
+        match event{
+            Event::Def(object) | Event::Lateinit(object) | Event::Assign(object, _) => {
+                moved.remove(object);
+            }
+            Event::Use(Use{ object, pos, site: Site::Explicit(Access::Move) }) => {
+                moved.insert(*object, *pos);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::Builder;
+    use crate::ident::Identifier;
+    use core::pos::Position;
+    use std::cell::Cell;
+
+    fn ident(name: &str) -> Identifier{
+        Identifier{
+            ident: name.to_owned(),
+            pos: BiPos::default()
+        }
+    }
+
+    #[test]
+    fn uses_in_loops_and_before_other_uses_are_copies() {
+        let (before, in_loop, in_branch) = (Cell::new(Access::Copy), Cell::new(Access::Copy), Cell::new(Access::Copy));
+        let mut cfg = Builder::new();
+        let int = Ty::new(TyKind::Int, BiPos::default());
+        cfg.declare(&ident("a"), &int);
+        cfg.declare(&ident("b"), &int);
+        cfg.use_object(&ident("a"), BiPos::default(), Site::Access(&before));
+        //while(cond){ a }, where a is not used after the loop
+        let head = cfg.flow.branch();
+        let after = cfg.flow.block();
+        cfg.flow.goto(after);
+        cfg.flow.branch();
+        cfg.use_object(&ident("a"), BiPos::default(), Site::Access(&in_loop));
+        cfg.flow.goto(head);
+        cfg.flow.switch_to(after);
+        //b is only used in one of two branches
+        let cond = cfg.flow.current();
+        let join = cfg.flow.block();
+        cfg.flow.branch();
+        cfg.use_object(&ident("b"), BiPos::default(), Site::Access(&in_branch));
+        cfg.flow.goto(join);
+        cfg.flow.switch_to(cond);
+        cfg.flow.goto(join);
+        cfg.flow.switch_to(join);
+        let graphs = cfg.finish();
+        assert_eq!(graphs[0].infer_moves().len(), 2);
+        assert_eq!(before.get(), Access::Copy);
+        assert_eq!(in_loop.get(), Access::Copy);
+        assert_eq!(in_branch.get(), Access::Move);
+    }
+
+    #[test]
+    fn uses_after_explicit_moves_are_found_until_reassigned() {
+        let mut cfg = Builder::new();
+        let int = Ty::new(TyKind::Int, BiPos::default());
+        let at = |line: usize| BiPos{
+            start: Position(line, 0),
+            end: Position(line, 1),
+            ..BiPos::default()
+        };
+        let (moved, used, reassigned) = (at(0), at(1), at(2));
+        cfg.declare(&ident("a"), &int);
+        cfg.use_object(&ident("a"), moved, Site::Explicit(Access::Move));
+        //a is only used again on one of two paths
+        let cond = cfg.flow.current();
+        let join = cfg.flow.block();
+        cfg.flow.branch();
+        cfg.touch(&ident("a"), used);
+        cfg.flow.goto(join);
+        cfg.flow.switch_to(cond);
+        cfg.flow.goto(join);
+        cfg.flow.switch_to(join);
+        cfg.assign(&ident("a"), reassigned);
+        cfg.touch(&ident("a"), reassigned);
+        let graphs = cfg.finish();
+        assert_eq!(graphs[0].uses_after_moves(), vec![(used, 0, moved)]);
+    }
+}
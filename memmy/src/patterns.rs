@@ -0,0 +1,299 @@
+use super::{
+    block::Block,
+    expr::Expression,
+    lifetime::Flow,
+    ident::Identifier,
+    capture::BindingKind,
+    cfg::{
+        Build,
+        Builder,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+    Load,
+    Unload,
+    MemmyGenerator,
+};
+
+use core::pos::BiPos;
+
+use ty::Ty;
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+    mir::MIRInstructions,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+use notices::{
+    DiagnosticSourceBuilder,
+    DiagnosticLevel
+};
+
+#[derive(Debug, Clone)]
+pub struct Match{
+    scrutinee: Expression,
+    arms: Vec<Arm>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Arm{
+    pattern: Pattern,
+    body: Block,
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub enum Pattern{
+    Wildcard(BiPos),
+    ///A binding, which is a new local that the value it matches is moved into, with its size.
+    Binding(Identifier, Ty, usize),
+    Literal(Expression),
+    ///A variant of an enum, which is matched by testing its tag. Each field is matched at its offset within the value of the enum.
+    Variant{
+        variant: Identifier,
+        tag: usize,
+        fields: Vec<(usize, Pattern)>,
+    },
+}
+
+impl Load for Match{
+    type Output = Match;
+
+    fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
+        let scrutinee = Expression::load(chunk, memmy)?;
+        let mut arms = vec![];
+        while chunk.read_bool(){
+            //The bindings of a pattern are only visible in the body of its arm
+            let depth = memmy.env.depth();
+            memmy.env.enter_flow(Flow::Branch);
+            let arm = Pattern::load(chunk, memmy).and_then(|pattern| Ok(Arm{
+                pattern,
+                body: Block::load(chunk, memmy)?
+            }));
+            memmy.env.exit_flow();
+            match arm{
+                Ok(arm) => {
+                    memmy.env.end_scope(depth, arm.body.pos);
+                    arms.push(arm)
+                }
+                Err(diag) => return Err(diag)
+            }
+        }
+        Ok(Match{
+            scrutinee,
+            arms
+        })
+    }
+}
+
+impl Load for Pattern{
+    type Output = Pattern;
+
+    fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
+        match chunk.read_instruction(){
+            Some(HIRInstruction::PatternWildcard) => match chunk.read_pos(){
+                Ok(pos) => Ok(Pattern::Wildcard(pos)),
+                Err(msg) => {
+                    let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                        .level(DiagnosticLevel::Error)
+                        .message(msg)
+                        .build();
+                    memmy.emit_diagnostic(&[], &[diagnosis]);
+                    Err(())
+                }
+            },
+            Some(HIRInstruction::PatternBinding) => {
+                let ident = Identifier::load(chunk, memmy)?;
+                let ty = Ty::load(chunk, memmy)?;
+                let size = match memmy.layout(&ty){
+                    Some(layout) => layout.size,
+                    None => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), ident.pos.start.0)
+                            .level(DiagnosticLevel::Error)
+                            .message(format!("Cannot compute the size of the binding {} of type {}", ident.ident, ty))
+                            .range(ident.pos.col_range())
+                            .build();
+                        memmy.emit_diagnostic(&[], &[diagnosis]);
+                        return Err(())
+                    }
+                };
+                memmy.env.declare(&ident, BindingKind::Local, &ty);
+                Ok(Pattern::Binding(ident, ty, size))
+            }
+            Some(HIRInstruction::PatternLiteral) => Ok(Pattern::Literal(Expression::load(chunk, memmy)?)),
+            Some(HIRInstruction::PatternVariant) => {
+                let ty = Ty::load(chunk, memmy)?;
+                let variant = Identifier::load(chunk, memmy)?;
+                let mut patterns = vec![];
+                while chunk.read_bool(){
+                    patterns.push(Pattern::load(chunk, memmy)?);
+                }
+                let layout = memmy.enum_layout(&ty);
+                let (tag, offsets) = match layout.as_ref().and_then(|layout| layout.variant(&variant.ident)){
+                    Some((tag, layout)) if layout.offsets.len() == patterns.len() + 1 => (tag, layout.offsets.iter().skip(1).map(|(_, offset)| *offset).collect::<Vec<_>>()),
+                    _ => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), variant.pos.start.0)
+                            .level(DiagnosticLevel::Error)
+                            .message(format!("Cannot find the layout of the variant {} of {}", variant.ident, ty))
+                            .range(variant.pos.col_range())
+                            .build();
+                        memmy.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diagnosis]);
+                        return Err(())
+                    }
+                };
+                Ok(Pattern::Variant{
+                    variant,
+                    tag,
+                    fields: offsets.into_iter().zip(patterns).collect()
+                })
+            }
+            ins => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected a pattern but instead got {:?}", ins))
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                Err(())
+            }
+        }
+    }
+}
+
+
+impl Unload for Match{
+    ///The bindings of the pattern of an arm are dropped at the end of its body.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = self.scrutinee.unload()?;
+        for arm in self.arms.iter(){
+            chunk.write_bool(true);
+            chunk.write_chunk(arm.pattern.unload()?);
+            let mut drops = vec![];
+            arm.pattern.bindings(&mut drops);
+            drops.reverse();
+            chunk.write_chunk(arm.body.unload_dropping(&drops)?);
+        }
+        chunk.write_bool(false);
+        Ok(chunk)
+    }
+}
+
+impl Unload for Pattern{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        match self{
+            Pattern::Wildcard(pos) => {
+                chunk.write_instruction(MIRInstructions::PatternWildcard);
+                chunk.write_pos(*pos);
+            }
+            Pattern::Binding(ident, _, size) => {
+                chunk.write_instruction(MIRInstructions::PatternBinding);
+                chunk.write_pos(ident.pos);
+                chunk.write_str(&ident.ident);
+                chunk.write_usize(*size);
+            }
+            Pattern::Literal(literal) => {
+                chunk.write_instruction(MIRInstructions::PatternLiteral);
+                chunk.write_chunk(literal.unload()?);
+            }
+            Pattern::Variant{ variant, tag, fields } => {
+                chunk.write_instruction(MIRInstructions::TestTag);
+                chunk.write_pos(variant.pos);
+                chunk.write_usize(*tag);
+                for (offset, field) in fields.iter(){
+                    chunk.write_bool(true);
+                    chunk.write_usize(*offset);
+                    chunk.write_chunk(field.unload()?);
+                }
+                chunk.write_bool(false);
+            }
+        }
+        Ok(chunk)
+    }
+}
+
+impl Pattern{
+    ///Add the names of the bindings of the pattern to `bindings`, in the order they are declared in.
+    fn bindings(&self, bindings: &mut Vec<String>){
+        match self{
+            Pattern::Binding(ident, _, _) => bindings.push(ident.ident.clone()),
+            Pattern::Variant{ fields, .. } => for (_, field) in fields.iter(){
+                field.bindings(bindings);
+            },
+            Pattern::Wildcard(_) | Pattern::Literal(_) => {}
+        }
+    }
+}
+
+impl<'t> Build<'t> for Match{
+    ///Any one of the arms may run after the scrutinee, and all of them join after the `match`.
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        self.scrutinee.build(cfg);
+        let scrutinee = cfg.flow.current();
+        let after = cfg.flow.block();
+        if self.arms.is_empty(){
+            cfg.flow.goto(after);
+        }
+        for arm in self.arms.iter(){
+            cfg.flow.switch_to(scrutinee);
+            cfg.flow.branch();
+            cfg.enter_scope();
+            arm.pattern.build(cfg);
+            arm.body.build(cfg);
+            cfg.exit_scope();
+            cfg.flow.goto(after);
+        }
+        cfg.flow.switch_to(after);
+    }
+}
+
+impl<'t> Build<'t> for Pattern{
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        match self{
+            Pattern::Binding(ident, ty, _) => cfg.declare(ident, ty),
+            Pattern::Literal(literal) => literal.build(cfg),
+            Pattern::Variant{ fields, .. } => for (_, field) in fields.iter(){
+                field.build(cfg);
+            },
+            Pattern::Wildcard(_) => {}
+        }
+    }
+}
+
+impl Count for Match{
+    ///The bindings of a pattern refer to whatever the scrutinee does, and the value of a `match` is the value of any of its arms.
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        let scrutinee = self.scrutinee.count(counter);
+        let mut referents = vec![];
+        for arm in self.arms.iter_mut(){
+            counter.enter_scope();
+            arm.pattern.count_bindings(counter, &scrutinee);
+            referents.extend(arm.body.count(counter));
+            counter.exit_scope();
+        }
+        referents
+    }
+}
+
+impl Pattern{
+    fn count_bindings(&mut self, counter: &mut RefCounter, scrutinee: &[Referent]){
+        match self{
+            Pattern::Binding(ident, _, _) => counter.declare(ident, None, false, scrutinee.to_vec(), ident.pos),
+            Pattern::Literal(literal) => {
+                literal.count(counter);
+            }
+            Pattern::Variant{ fields, .. } => for (_, field) in fields.iter_mut(){
+                field.count_bindings(counter, scrutinee);
+            },
+            Pattern::Wildcard(_) => {}
+        }
+    }
+}
@@ -1,20 +1,42 @@
 use super::{
     Load,
+    Unload,
     ident::Identifier,
     MemmyGenerator,
     expr::Expression,
     Mutability,
-    lifetime::ObjectLifetime
+    lifetime::ObjectLifetime,
+    capture::{
+        BindingKind,
+        is_copy,
+    },
+    cfg::{
+        Build,
+        Builder,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+    alloc::{
+        Allocation,
+        Place,
+    },
+    escape::Residence,
 };
 
 use core::pos::BiPos;
 
-use ir::{
-    Chunk,
-    hir::HIRInstruction,
+use std::{
+    cell::RefCell,
+    rc::Rc,
 };
 
-use ir_traits::ReadInstruction;
+use ty::Ty;
+
+use ir::Chunk;
+
 
 use notices::{
     DiagnosticSourceBuilder,
@@ -22,17 +44,22 @@ use notices::{
 };
 
 #[derive(Debug, Clone)]
-pub struct Property<'a>{
-    ident: Identifier,
-    typename: Identifier,
+pub struct Property{
+    pub ident: Identifier,
+    ty: Ty,
     pos: BiPos,
     mutable: Mutability,
     expression: Expression,
-    lifetime: ObjectLifetime<'a>
+    ///Whether the property is declared with `= None` and initialized by its first assignment.
+    lateinit: bool,
+    ///The lifetime of the property, which is complete once the module is loaded.
+    lifetime: Rc<RefCell<ObjectLifetime>>,
+    ///The memory of the property, which is on the heap unless escape analysis finds that it can be on the stack.
+    allocation: Allocation,
 }
 
-impl<'a> Load for Property<'a>{
-    type Output = Property<'a>;
+impl Load for Property{
+    type Output = Property;
     fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
         let pos = match chunk.read_pos(){
             Ok(pos) => pos,
@@ -64,51 +91,94 @@ impl<'a> Load for Property<'a>{
         };
         let mutable = chunk.read_bool();
 
-        let typename_pos = match chunk.read_pos(){
-            Ok(pos) => pos,
-            Err(msg) => {
-                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
-                    .level(DiagnosticLevel::Error)
-                    .message(msg)
-                    .build();
-                memmy.emit_diagnostic(&[], &[diagnosis]);
-                return Err(())
-            }
+        let ty = match Ty::load(chunk, memmy){
+            Ok(ty) => ty,
+            Err(diag) => return Err(diag)
         };
-
-        match chunk.read_instruction(){
-            Some(HIRInstruction::Integer) | Some(HIRInstruction::Float) | Some(HIRInstruction::String) | Some(HIRInstruction::Unit) | Some(HIRInstruction::Custom) => {},
-            Some(_) => {}
-            None =>{
-                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+        let lateinit = chunk.read_bool();
+        let expr = match Expression::load(chunk, memmy){
+            Ok(expr) => expr,
+            Err(diag) => return Err(diag)
+        };
+        //Nothing tells which function of the module runs first, so no read of a property of the module can be proven to come after its initialization
+        if lateinit && !memmy.env.in_frame(){
+            let diagnosis = memmy.error_source(ident.pos, format!("{} cannot be initialized late, since it is a property of the module", ident.ident))?;
+            memmy.emit_diagnostic(&[format!("Only the properties and locals of functions can be declared with `= None` when their type is not nullable.")], &[diagnosis]);
+            return Err(())
+        }
+        let lifetime = memmy.env.declare(&ident, BindingKind::Property, &ty);
+        let layout = match expr.obj_init().or_else(|| memmy.layout(&ty)){
+            Some(layout) => layout,
+            None => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), ident.pos.start.0)
                     .level(DiagnosticLevel::Error)
-                    .message(format!("Attempted to read type information from typeck while loading property into memmy, found None."))
+                    .message(format!("Cannot compute the size of the property {} of type {}", ident.ident, ty))
+                    .range(ident.pos.col_range())
                     .build();
                 memmy.emit_diagnostic(&[], &[diagnosis]);
                 return Err(())
             }
         };
-        
-        let typename = chunk.read_string();
-        let expr = match Expression::load(chunk, memmy){
-            Ok(expr) => expr,
-            Err(diag) => return Err(diag)
+        let allocation = Allocation{
+            name: ident.ident.clone(),
+            place: Place::Heap,
+            layout,
+            mutable,
+            lateinit,
+            pos
         };
         Ok(Property{
             ident,
-            typename: Identifier{
-                ident: typename.to_owned(),
-                pos: typename_pos
-            },
+            ty,
             mutable: Mutability{
                 mutable,
                 pos: mut_pos
             },
             pos,
             expression: expr,
-            lifetime: ObjectLifetime{
-                stages: Vec::new()
-            }
+            lateinit,
+            lifetime,
+            allocation,
         })
     }
-}
\ No newline at end of file
+}
+
+impl Unload for Property{
+    ///Write the allocation of the property, followed by its initializer unless it is initialized late. It is allocated on the stack if it never escapes the function that declares it.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut allocation = self.allocation.clone();
+        if let Some(Residence::Stack) = self.lifetime.borrow().residence{
+            allocation.place = Place::Stack;
+        }
+        let mut chunk = allocation.unload()?;
+        if !self.lateinit{
+            chunk.write_chunk(self.expression.unload_init(&self.ident.ident)?);
+        }
+        Ok(chunk)
+    }
+}
+
+impl<'t> Build<'t> for Property{
+    ///A property is never moved, but it is declared so that reading it before it is initialized can be found.
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        self.expression.build(cfg);
+        if self.lateinit{
+            cfg.lateinit(&self.ident, &self.ty, self.mutable.mutable);
+        }else{
+            cfg.declare(&self.ident, &self.ty);
+        }
+    }
+}
+
+impl Count for Property{
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        let referents = self.expression.count(counter);
+        let borrowed = if self.expression.is_reference(){ referents.clone() }else{ vec![] };
+        counter.declare(&self.ident, Some(self.lifetime.clone()), self.mutable.mutable, referents, self.pos);
+        if is_copy(&self.ty){
+            counter.pass_by_value(&self.ident);
+        }
+        counter.borrow(&self.ident, &borrowed, self.ident.pos);
+        vec![]
+    }
+}
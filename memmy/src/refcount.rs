@@ -0,0 +1,528 @@
+use super::{
+    ident::Identifier,
+    lifetime::{
+        ObjectLifetime,
+        Holder,
+        DropPoint,
+    },
+    escape::{
+        Residence,
+        Escape,
+    },
+    statements::{
+        Statement,
+        StatementKind,
+    },
+    safety::Violation,
+    MemmyGenerator,
+};
+
+use core::pos::BiPos;
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+
+///What the value of an expression refers to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Referent{
+    ///A property, by its index among the objects counted so far.
+    Property(usize),
+    ///A param of the function being counted, which refers to whatever the function is passed for it.
+    Param(usize),
+}
+
+///What the references to properties can be counted in.
+pub trait Count{
+    ///Count the references that are taken in this, returning what its value refers to.
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent>;
+}
+
+///An object that has been declared at the point being counted.
+struct Counted{
+    ident: Identifier,
+    ///The lifetime of a property, which its holders are recorded in. Locals cannot be referenced, so they have none.
+    lifetime: Option<Rc<RefCell<ObjectLifetime>>>,
+    ///What the object holds references to, and where it takes each of them.
+    holds: Vec<(Referent, BiPos)>,
+    ///Whether the object is declared with `var` or `let mut`, which makes the references it is a reference to mutable.
+    mutable: bool,
+    ///The properties that the object is a reference to, rather than one that only holds a reference to them, and where it takes each of them.
+    borrows: Vec<(usize, BiPos)>,
+    ///Whether the object is a property of a primitive type, whose value is copied wherever it is named without `ref`.
+    by_value: bool,
+}
+
+///A function or a lambda being counted.
+struct Frame{
+    ///The number of objects declared outside of it.
+    outside: usize,
+    ///What the values that it returns refer to, and where they are returned.
+    returns: Vec<(Referent, BiPos)>,
+    ///The objects that are the params of a function.
+    params: Vec<usize>,
+    ///The params of a function that it may keep a reference to after it returns, because they are held by what it was passed or by an object declared outside of it.
+    kept: Vec<usize>,
+}
+
+///What is known about a function of the module, for its calls.
+struct Summary{
+    ///What the value it returns refers to.
+    returns: Vec<Referent>,
+    ///The params it may keep a reference to after it returns.
+    kept: Vec<usize>,
+}
+
+///Counts the references to every property at compile time, which is how properties are freed without a runtime reference counter.
+///A reference to a property is taken wherever its value is stored in another object: a local or a property initialized or assigned with it,
+///an object constructed with it or with a field assigned to it, or a lambda that captures it. Passing it to a function only lends it for the call.
+///A property is dropped once the objects that hold a reference to it are, and a reference must never outlive the property it refers to.
+///A property declared in a function lives on the stack of the function, unless it is lent to a callee that may keep it or the function returns it.
+pub struct RefCounter<'m>{
+    memmy: &'m MemmyGenerator,
+    objects: Vec<Counted>,
+    ///The objects visible at the point being counted, by scope.
+    scopes: Vec<Vec<usize>>,
+    frames: Vec<Frame>,
+    ///What is known about each function of the module that has been counted, by its name.
+    summaries: HashMap<String, Summary>,
+    result: Result<(), ()>,
+}
+
+impl MemmyGenerator{
+    ///Count the references to every property of the module, returning the order that the properties of the module are dropped in at its end.
+    pub(crate) fn count_refs(&self, statements: &mut [Statement]) -> Result<Vec<String>, ()>{
+        let mut counter = RefCounter::new(self);
+        for statement in statements.iter_mut(){
+            statement.count(&mut counter);
+        }
+        let drops = statements.iter().rev().filter_map(|statement| match &statement.kind{
+            StatementKind::Property(property) => Some(property.ident.ident.clone()),
+            _ => None
+        }).collect::<Vec<_>>();
+        let drops = counter.order_drops(&drops);
+        counter.check_cycles();
+        counter.result.map(|_| drops)
+    }
+}
+
+impl<'m> RefCounter<'m>{
+    fn new(memmy: &'m MemmyGenerator) -> Self{
+        RefCounter{
+            memmy,
+            objects: vec![],
+            scopes: vec![vec![]],
+            frames: vec![],
+            summaries: HashMap::new(),
+            result: Ok(()),
+        }
+    }
+
+    ///Declare an object that holds references to `referents` from `pos` on, which is mutable if it is declared with `var` or `let mut`. Only a property has a lifetime, since only properties can be referenced.
+    ///A property of a function lives on its stack until it is found to escape, while a property of the module lives on the heap.
+    pub fn declare(&mut self, ident: &Identifier, lifetime: Option<Rc<RefCell<ObjectLifetime>>>, mutable: bool, referents: Vec<Referent>, pos: BiPos){
+        let object = self.objects.len();
+        if let Some(lifetime) = &lifetime{
+            lifetime.borrow_mut().residence = Some(match self.frames.is_empty(){
+                true => Residence::Heap(Escape::Module),
+                false => Residence::Stack,
+            });
+        }
+        let param = referents.iter().any(|referent| matches!(referent, Referent::Param(_)));
+        self.objects.push(Counted{
+            ident: ident.clone(),
+            lifetime,
+            holds: vec![],
+            mutable,
+            borrows: vec![],
+            by_value: false,
+        });
+        if let Some(scope) = self.scopes.last_mut(){
+            scope.push(object);
+        }
+        self.hold(object, referents, pos);
+        if let (true, Some(frame)) = (param, self.frames.last_mut()){
+            frame.params.push(object);
+        }
+    }
+
+    ///Record that the property `ident` is of a primitive type, so naming it copies its value instead of referring to it.
+    pub fn pass_by_value(&mut self, ident: &Identifier){
+        if let Some(object) = self.resolve(&ident.ident){
+            self.objects[object].by_value = true;
+        }
+    }
+
+    ///Whether naming `ident` copies its value, since it is a property of a primitive type.
+    pub fn is_by_value(&self, ident: &Identifier) -> bool{
+        self.resolve(&ident.ident).is_some_and(|object| self.objects[object].by_value)
+    }
+
+    fn resolve(&self, ident: &str) -> Option<usize>{
+        self.scopes.iter().rev().flat_map(|scope| scope.iter().rev()).find(|object| self.objects[**object].ident.ident == ident).copied()
+    }
+
+    ///What naming `ident` refers to. A property is referenced itself, while a local refers to whatever it holds references to.
+    pub fn refer(&self, ident: &Identifier) -> Vec<Referent>{
+        match self.resolve(&ident.ident){
+            Some(object) if self.objects[object].lifetime.is_some() => vec![Referent::Property(object)],
+            Some(object) => self.objects[object].holds.iter().map(|(referent, _)| *referent).collect(),
+            None => vec![]
+        }
+    }
+
+    ///What a copy of the object `ident` refers to. A copy of a property is a new object, so it only refers to what the property holds.
+    pub fn copy(&self, ident: &Identifier) -> Vec<Referent>{
+        match self.resolve(&ident.ident){
+            Some(object) => self.objects[object].holds.iter().map(|(referent, _)| *referent).collect(),
+            None => vec![]
+        }
+    }
+
+    ///Record that `holder` takes a reference to each of `referents` at `pos`.
+    fn hold(&mut self, holder: usize, referents: Vec<Referent>, pos: BiPos){
+        for referent in referents{
+            if self.objects[holder].holds.iter().any(|(held, _)| *held == referent){
+                continue
+            }
+            self.objects[holder].holds.push((referent, pos));
+            //A param that is held by another param or by an object declared outside of the function may be referred to after it returns
+            if let (Referent::Param(param), Some(frame)) = (referent, self.function_mut()){
+                if (holder < frame.outside || frame.params.contains(&holder)) && !frame.kept.contains(&param){
+                    frame.kept.push(param);
+                }
+            }
+            if let Referent::Property(property) = referent{
+                let ident = self.objects[holder].ident.ident.clone();
+                if let Some(lifetime) = &self.objects[property].lifetime{
+                    lifetime.borrow_mut().holders.push(Holder{
+                        ident,
+                        pos
+                    });
+                }
+            }
+        }
+    }
+
+    ///Record that the object `ident` is assigned a value that refers to `referents` at `pos`, returning whether the references it held until then are released.
+    ///It may hold either its old or its new references afterwards, depending on the path taken to get there, so it keeps both.
+    pub fn assign(&mut self, ident: &Identifier, referents: Vec<Referent>, pos: BiPos) -> bool{
+        match self.resolve(&ident.ident){
+            Some(object) => {
+                let releases = !self.objects[object].holds.is_empty();
+                self.hold(object, referents, pos);
+                releases
+            }
+            None => false
+        }
+    }
+
+    ///Record that a field of the object `ident` is assigned a value that refers to `referents` at `pos`, which the object then holds.
+    ///This mutates the properties that the object is a reference to, which it must be a mutable reference to.
+    pub fn assign_field(&mut self, ident: &Identifier, referents: Vec<Referent>, pos: BiPos){
+        let object = match self.resolve(&ident.ident){
+            Some(object) => object,
+            None => return
+        };
+        if !self.objects[object].mutable{
+            if let Some((property, taken)) = self.objects[object].borrows.first().copied(){
+                self.violation(Violation::ImmutableMutation{
+                    holder: ident.ident.clone(),
+                    property: self.objects[property].ident.ident.clone(),
+                    pos,
+                    taken
+                });
+            }
+        }
+        self.hold(object, referents, pos);
+    }
+
+    ///Record that the object `ident` becomes a reference to each property of `referents` at `pos`, as it is declared or assigned with the name of a property or with `ref`.
+    ///No two objects that are visible at once may be mutable references to the same property.
+    pub fn borrow(&mut self, ident: &Identifier, referents: &[Referent], pos: BiPos){
+        let object = match self.resolve(&ident.ident){
+            Some(object) => object,
+            None => return
+        };
+        for referent in referents.iter(){
+            let property = match referent{
+                Referent::Property(property) => *property,
+                Referent::Param(_) => continue
+            };
+            if self.objects[object].mutable{
+                let other = self.scopes.iter().flatten().filter(|other| **other != object && self.objects[**other].mutable).find_map(|other| self.objects[*other].borrows.iter().find(|(borrowed, _)| *borrowed == property).map(|(_, taken)| (*other, *taken)));
+                if let Some((other, taken)) = other{
+                    self.violation(Violation::MutableAlias{
+                        holder: ident.ident.clone(),
+                        other: self.objects[other].ident.ident.clone(),
+                        property: self.objects[property].ident.ident.clone(),
+                        pos,
+                        taken
+                    });
+                }
+            }
+            if !self.objects[object].borrows.iter().any(|(borrowed, _)| *borrowed == property){
+                self.objects[object].borrows.push((property, pos));
+            }
+        }
+    }
+
+    pub fn enter_scope(&mut self){
+        self.scopes.push(vec![]);
+    }
+
+    ///End the innermost scope. Every property declared in it is dropped, so nothing that outlives the scope may still hold a reference to it.
+    pub fn exit_scope(&mut self){
+        let scope = match self.scopes.pop(){
+            Some(scope) => scope,
+            None => return
+        };
+        let properties = scope.into_iter().filter(|object| self.objects[*object].lifetime.is_some()).collect::<Vec<_>>();
+        for property in properties{
+            let holders = self.scopes.iter().flatten().filter_map(|holder| self.objects[*holder].holds.iter().find(|(referent, _)| *referent == Referent::Property(property)).map(|(_, pos)| (*holder, *pos))).collect::<Vec<_>>();
+            for (holder, pos) in holders{
+                self.violation(Violation::Outlives{
+                    holder: self.objects[holder].ident.ident.clone(),
+                    property: self.objects[property].ident.ident.clone(),
+                    pos,
+                    decl: self.objects[property].ident.pos
+                });
+            }
+        }
+    }
+
+    ///Start counting a function or a lambda.
+    pub fn enter_frame(&mut self){
+        self.frames.push(Frame{
+            outside: self.objects.len(),
+            returns: vec![],
+            params: vec![],
+            kept: vec![],
+        });
+    }
+
+    ///The innermost function being counted that has params, which is the one that any param referred to belongs to. Lambdas never do, since their params refer to nothing.
+    fn function_mut(&mut self) -> Option<&mut Frame>{
+        self.frames.iter_mut().rev().find(|frame| !frame.params.is_empty())
+    }
+
+    ///Record that the innermost function or lambda returns a value that refers to `referents` with the `return` at `pos`, returning the names of the properties it declares that the value refers to.
+    ///Such a property outlives it, so it is allocated on the heap, and is left for whatever the value is returned to rather than dropped as it returns.
+    pub fn ret(&mut self, referents: Vec<Referent>, pos: BiPos) -> Vec<String>{
+        self.give_back(referents, pos, true)
+    }
+
+    ///Record that the innermost function or lambda returns a value that refers to `referents` at `pos`, either with a `return` or as the value its body ends with. See [RefCounter::ret].
+    fn give_back(&mut self, referents: Vec<Referent>, pos: BiPos, early: bool) -> Vec<String>{
+        let outside = match self.frames.last_mut(){
+            Some(frame) => {
+                frame.returns.extend(referents.iter().map(|referent| (*referent, pos)));
+                frame.outside
+            }
+            None => return vec![]
+        };
+        let mut returned = vec![];
+        for referent in referents{
+            let property = match referent{
+                Referent::Property(property) if property >= outside => property,
+                _ => continue
+            };
+            if let Some(lifetime) = &self.objects[property].lifetime{
+                let mut lifetime = lifetime.borrow_mut();
+                if let Some(Residence::Stack) = lifetime.residence{
+                    lifetime.residence = Some(Residence::Heap(Escape::Returned{
+                        pos
+                    }));
+                }
+                lifetime.drops.retain(|drop| match drop{
+                    DropPoint::EarlyExit(at) => !early || *at != pos,
+                    DropPoint::EndOfScope(_) => early,
+                });
+            }
+            returned.push(self.objects[property].ident.ident.clone());
+        }
+        returned
+    }
+
+    ///Finish counting the innermost function or lambda, whose body ends with a value that refers to `value` at `pos`, returning the names of the properties it declares that the value refers to. See [RefCounter::ret].
+    ///What the values it returns refer to otherwise is remembered for the calls to the function `name`, if it is a function of the module.
+    pub fn exit_frame(&mut self, name: Option<&str>, value: Vec<Referent>, pos: BiPos) -> Vec<String>{
+        let returned = self.give_back(value, pos, false);
+        let frame = match self.frames.pop(){
+            Some(frame) => frame,
+            None => return returned
+        };
+        let name = match name{
+            Some(name) => name,
+            None => return returned
+        };
+        let mut summary = vec![];
+        for (referent, _) in frame.returns{
+            match referent{
+                //The caller gets a property of its own, which nothing else refers to
+                Referent::Property(property) if property >= frame.outside => {}
+                _ if summary.contains(&referent) => {}
+                _ => summary.push(referent)
+            }
+        }
+        self.summaries.insert(name.to_owned(), Summary{
+            returns: summary,
+            kept: frame.kept,
+        });
+        returned
+    }
+
+    ///What the value that calling the function `name` at `pos` with `args` returns refers to, if it is a function of the module that has been counted.
+    ///What is passed for a param that the function may keep escapes, and so does anything passed to a function that has not been counted yet.
+    pub fn call(&mut self, name: &str, args: Vec<Vec<Referent>>, pos: BiPos) -> Vec<Referent>{
+        let summary = match self.summaries.get(name){
+            Some(summary) => summary,
+            None => {
+                self.lend(name, args, pos);
+                return vec![]
+            }
+        };
+        let returns = summary.returns.iter().flat_map(|referent| match referent{
+            Referent::Param(param) => args.get(*param).cloned().unwrap_or_default(),
+            Referent::Property(_) => vec![*referent],
+        }).collect();
+        let kept = summary.kept.iter().filter_map(|param| args.get(*param)).flatten().copied().collect::<Vec<_>>();
+        self.escape(&kept, name, pos);
+        returns
+    }
+
+    ///Record that `args` are passed at `pos` to `callee`, which is not a function of the module, such as a method or a lambda. It may keep any of them, so they all escape.
+    pub fn lend(&mut self, callee: &str, args: Vec<Vec<Referent>>, pos: BiPos){
+        let args = args.into_iter().flatten().collect::<Vec<_>>();
+        self.escape(&args, callee, pos);
+    }
+
+    ///Record that `referents` are passed at `pos` to `callee`, which may keep a reference to them after it returns.
+    ///A property that escapes this way has to be allocated on the heap, and a param of the function being counted is kept by it.
+    pub fn escape(&mut self, referents: &[Referent], callee: &str, pos: BiPos){
+        for referent in referents.iter(){
+            match referent{
+                Referent::Property(property) => if let Some(lifetime) = &self.objects[*property].lifetime{
+                    let mut lifetime = lifetime.borrow_mut();
+                    if let Some(Residence::Stack) = lifetime.residence{
+                        lifetime.residence = Some(Residence::Heap(Escape::Passed{
+                            callee: callee.to_owned(),
+                            pos
+                        }));
+                    }
+                },
+                Referent::Param(param) => if let Some(frame) = self.function_mut(){
+                    if !frame.kept.contains(param){
+                        frame.kept.push(*param);
+                    }
+                }
+            }
+        }
+    }
+
+    ///Order `drops`, which are the names of the objects dropped at a point in reverse declaration order, so that a property is only dropped once the objects that hold a reference to it are.
+    pub fn order_drops(&self, drops: &[String]) -> Vec<String>{
+        let mut remaining = drops.iter().map(|drop| (drop.clone(), self.resolve(drop))).collect::<Vec<_>>();
+        let mut ordered = vec![];
+        while !remaining.is_empty(){
+            let held = |object: usize| remaining.iter().any(|(_, holder)| match holder{
+                Some(holder) => *holder != object && self.objects[*holder].holds.iter().any(|(referent, _)| *referent == Referent::Property(object)),
+                None => false
+            });
+            //Properties that hold references to each other are reported as a cycle, and dropped in reverse declaration order
+            let next = remaining.iter().position(|(_, object)| object.is_none_or(|object| !held(object))).unwrap_or(0);
+            ordered.push(remaining.remove(next).0);
+        }
+        ordered
+    }
+
+    ///Report every cycle of properties that hold references to each other, since none of them could ever be dropped.
+    fn check_cycles(&mut self){
+        for start in 0..self.objects.len(){
+            if self.objects[start].lifetime.is_none(){
+                continue
+            }
+            let cycle = match self.cycle_from(start, start, &mut vec![]){
+                Some(cycle) => cycle,
+                None => continue
+            };
+            //Each cycle is reported once, from the first of its properties
+            if cycle.iter().any(|(property, _)| *property < start){
+                continue
+            }
+            let names = cycle.iter().map(|(property, _)| self.objects[*property].ident.ident.clone()).chain(std::iter::once(self.objects[start].ident.ident.clone())).collect::<Vec<_>>();
+            let pos = cycle[0].1;
+            self.error(pos, format!("The references between {} form a cycle, so none of them can ever be dropped", names.join(" -> ")), "Properties are only dropped once nothing refers to them. Break the cycle by not storing one of these references.".to_string());
+        }
+    }
+
+    ///Find a path of references from the property `from` back to `start`, as each property on it and where it takes its reference to the next.
+    fn cycle_from(&self, start: usize, from: usize, path: &mut Vec<(usize, BiPos)>) -> Option<Vec<(usize, BiPos)>>{
+        for (referent, pos) in self.objects[from].holds.iter(){
+            let next = match referent{
+                Referent::Property(next) => *next,
+                Referent::Param(_) => continue
+            };
+            path.push((from, *pos));
+            if next == start{
+                return Some(path.clone())
+            }
+            if !path.iter().any(|(property, _)| *property == next) && self.objects[next].lifetime.is_some(){
+                if let Some(cycle) = self.cycle_from(start, next, path){
+                    return Some(cycle)
+                }
+            }
+            path.pop();
+        }
+        None
+    }
+
+    fn violation(&mut self, violation: Violation){
+        self.result = Err(());
+        let _ = self.memmy.report(&violation);
+    }
+
+    fn error(&mut self, pos: BiPos, message: String, note: String){
+        self.result = Err(());
+        if let Ok(diagnosis) = self.memmy.error_source(pos, message){
+            self.memmy.emit_diagnostic(&[note], &[diagnosis]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(source: &str) -> Vec<String>{
+        MemmyGenerator::analyze_source(source).1
+    }
+
+    #[test]
+    fn properties_of_primitive_types_are_copied_where_they_are_named() {
+        assert_eq!(messages("fun first(): Int{\n    val p = 5\n    let x = p\n    let y = x\n    return y\n}\n"), Vec::<String>::new());
+        assert_eq!(messages("fun sum(): Int{\n    var total = 0\n    for (i in 0..10){\n        total = total + i\n    }\n    return total\n}\n"), Vec::<String>::new());
+        assert_eq!(messages("fun main(){\n    var h = 1\n    if (h > 0) {\n        var p = 2\n        h = p\n    }\n}\n"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn other_properties_and_captures_are_referred_to() {
+        assert_eq!(messages("struct Counter(var n: Int)\n\nfun main(){\n    var h = Counter(1)\n    if (h.n > 0) {\n        var p = Counter(2)\n        h = p\n    }\n}\n"), vec![
+            format!("h takes a reference to p here, but it outlives p"),
+            format!("p is declared here, and dropped at the end of its scope"),
+        ]);
+        assert_eq!(messages("fun main(){\n    var f = { -> 0 }\n    if (true) {\n        var p = 2\n        f = { -> p }\n    }\n}\n"), vec![
+            format!("f takes a reference to p here, but it outlives p"),
+            format!("p is declared here, and dropped at the end of its scope"),
+        ]);
+    }
+
+    #[test]
+    fn properties_that_refer_to_each_other_are_a_cycle() {
+        assert_eq!(messages("class Node(var n: Int)\n\nvar a = Node(1)\nvar b = a\n\nfun main(){\n    a = b\n}\n"), vec![
+            format!("The references between a -> b -> a form a cycle, so none of them can ever be dropped"),
+        ]);
+    }
+}
@@ -0,0 +1,190 @@
+use super::MemmyGenerator;
+
+use core::pos::BiPos;
+
+use notices::{
+    DiagnosticSource,
+    DiagnosticSourceBuilder,
+    DiagnosticLevel,
+};
+
+///A use of memory that memmy rejects, as found by the analyses of moves, of initialization and of references.
+///Each is reported with a primary label where it happens, and a secondary label at what it conflicts with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation{
+    ///`ident` is used at `pos` after it is moved at `moved`, which is `pos` itself when it is moved in an earlier iteration of a loop.
+    UseAfterMove{
+        ident: String,
+        pos: BiPos,
+        moved: BiPos,
+    },
+    ///`ident`, which is declared at `decl` with `= None`, is used at `pos` before it may have been assigned.
+    Uninitialized{
+        ident: String,
+        pos: BiPos,
+        decl: BiPos,
+    },
+    ///The immutable `ident` is assigned at `pos`, but it may have been initialized at `first` already.
+    Reassigned{
+        ident: String,
+        pos: BiPos,
+        first: BiPos,
+    },
+    ///`property` is mutated at `pos` through `holder`, which took an immutable reference to it at `taken`.
+    ImmutableMutation{
+        holder: String,
+        property: String,
+        pos: BiPos,
+        taken: BiPos,
+    },
+    ///`holder` takes a mutable reference to `property` at `pos`, while `other` still holds the one it took at `taken`.
+    MutableAlias{
+        holder: String,
+        other: String,
+        property: String,
+        pos: BiPos,
+        taken: BiPos,
+    },
+    ///`holder` takes a reference to `property` at `pos`, but outlives the scope of `property`, which is declared at `decl`.
+    Outlives{
+        holder: String,
+        property: String,
+        pos: BiPos,
+        decl: BiPos,
+    },
+}
+
+impl Violation{
+    ///Where the violation happens and what it is, followed by what it conflicts with.
+    fn labels(&self) -> ((BiPos, String), (BiPos, String)){
+        match self{
+            Violation::UseAfterMove{ ident, pos, moved } => (
+                (*pos, format!("{} is used here after it was moved", ident)),
+                (*moved, if moved == pos{
+                    format!("{} is moved here, in an earlier iteration of the loop", ident)
+                }else{
+                    format!("{} is moved here", ident)
+                })
+            ),
+            Violation::Uninitialized{ ident, pos, decl } => (
+                (*pos, format!("{} is used here before it is initialized", ident)),
+                (*decl, format!("{} is declared here without a value", ident))
+            ),
+            Violation::Reassigned{ ident, pos, first } => (
+                (*pos, format!("{} is assigned here, but it may already be initialized", ident)),
+                (*first, if first == pos{
+                    format!("{} is initialized here, in an earlier iteration of the loop", ident)
+                }else{
+                    format!("{} is initialized here", ident)
+                })
+            ),
+            Violation::ImmutableMutation{ holder, property, pos, taken } => (
+                (*pos, format!("{} is mutated here through {}, which holds an immutable reference to it", property, holder)),
+                (*taken, format!("{} takes an immutable reference to {} here", holder, property))
+            ),
+            Violation::MutableAlias{ holder, other, property, pos, taken } => (
+                (*pos, format!("{} takes a mutable reference to {} here, while {} still holds one", holder, property, other)),
+                (*taken, format!("{} takes a mutable reference to {} here", other, property))
+            ),
+            Violation::Outlives{ holder, property, pos, decl } => (
+                (*pos, format!("{} takes a reference to {} here, but it outlives {}", holder, property, property)),
+                (*decl, format!("{} is declared here, and dropped at the end of its scope", property))
+            ),
+        }
+    }
+
+    fn note(&self) -> String{
+        match self{
+            Violation::UseAfterMove{ ident, .. } => format!("A local cannot be used once it is moved, until it is assigned again. Use `copy {}` to move a copy of it instead.", ident),
+            Violation::Uninitialized{ .. } => "An object declared with `= None` has to be assigned on every path that leads to where it is used.".to_string(),
+            Violation::Reassigned{ ident, .. } => format!("An immutable object declared with `= None` can only be assigned once. Declare {} with `var` or `let mut` to assign it again.", ident),
+            Violation::ImmutableMutation{ holder, property, .. } => format!("A property can only be mutated through a reference held by an object declared with `var` or `let mut`. Declare {} with `var` to mutate {} through it.", holder, property),
+            Violation::MutableAlias{ property, .. } => format!("Only one mutable reference to a property can be held at a time. Declare one of them with `val` or `let` to hold an immutable reference to {} instead.", property),
+            Violation::Outlives{ holder, property, .. } => format!("A reference cannot outlive the property it refers to. Declare {} in the scope of {}, or in a scope inside of it.", holder, property),
+        }
+    }
+}
+
+impl MemmyGenerator{
+    ///Report `violation` as an error, with a primary label where it happens and a secondary label at what it conflicts with.
+    pub(crate) fn report(&self, violation: &Violation) -> Result<(), ()>{
+        let ((pos, message), (other, label)) = violation.labels();
+        let primary = self.error_source(pos, message)?;
+        let secondary = self.label_source(other, label)?;
+        self.emit_diagnostic(&[violation.note()], &[primary, secondary]);
+        Ok(())
+    }
+
+    ///Build a secondary label that points at `pos`, which is underlined with `-` rather than the `^` of the error it belongs to, and is not prefixed with its level.
+    pub(crate) fn label_source(&self, pos: BiPos, message: String) -> Result<DiagnosticSource, ()>{
+        let source = match self.request_source_snippet(pos){
+            Ok(source) => source,
+            Err(diag) => {
+                self.emit_diagnostic(&[], &[diag]);
+                return Err(())
+            }
+        };
+        Ok(DiagnosticSourceBuilder::new(self.module_name.clone(), pos.start.0)
+            .level(DiagnosticLevel::Warning)
+            .message(message)
+            .range(pos.col_range())
+            .source(source)
+            .build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///The line, the columns and the message of each label of the only diagnostic emitted for `source`.
+    fn labels(source: &str) -> Vec<(usize, (usize, usize), String)>{
+        let (mir, diagnostics, _) = MemmyGenerator::analyze_diagnosed(source);
+        assert!(mir.is_none());
+        assert_eq!(diagnostics.len(), 1);
+        diagnostics[0].sources.iter().map(|source| {
+            let slice = source.to_slice();
+            (slice.line_start, slice.annotations[0].range, source.message().to_owned())
+        }).collect()
+    }
+
+    #[test]
+    fn uses_after_moves_point_at_the_move() {
+        assert_eq!(labels("class Counter(var n: Int)\n\nfun take(c: Counter): Int = c.n\n\nfun main(){\n    let c = Counter(1)\n    let n = take(move c)\n    let m = c.n\n}\n"), vec![
+            (7, (13, 14), "c is used here after it was moved".to_string()),
+            (6, (18, 22), "c is moved here".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn uses_before_initialization_point_at_the_declaration() {
+        assert_eq!(labels("fun main(flag: Bool){\n    let x: Int = None\n    if(flag){\n        x = 1\n    }\n    let y = x\n}\n"), vec![
+            (5, (13, 14), "x is used here before it is initialized".to_string()),
+            (1, (9, 10), "x is declared here without a value".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn mutations_through_immutable_references_point_at_the_reference() {
+        assert_eq!(labels("struct Counter(var n: Int)\n\nfun main(){\n    var c = Counter(1)\n    val view = c\n    view.n = 2\n}\n"), vec![
+            (5, (10, 11), "c is mutated here through view, which holds an immutable reference to it".to_string()),
+            (4, (9, 13), "view takes an immutable reference to c here".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn mutable_aliases_point_at_the_other_reference() {
+        assert_eq!(labels("struct Counter(var n: Int)\n\nfun main(){\n    var c = Counter(1)\n    var a = c\n    var b = ref c\n    b.n = 3\n}\n"), vec![
+            (5, (9, 10), "b takes a mutable reference to c here, while a still holds one".to_string()),
+            (4, (9, 10), "a takes a mutable reference to c here".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn references_that_outlive_their_property_point_at_its_declaration() {
+        assert_eq!(labels("struct Counter(var n: Int)\n\nfun main(){\n    var h = Counter(1)\n    if (h.n > 0) {\n        var p = Counter(2)\n        h = p\n    }\n}\n"), vec![
+            (6, (9, 10), "h takes a reference to p here, but it outlives p".to_string()),
+            (5, (13, 14), "p is declared here, and dropped at the end of its scope".to_string()),
+        ]);
+    }
+}
@@ -1,16 +1,38 @@
 use super::{
     Load,
+    Unload,
     property::Property,
     fun::Fun,
     local::Local,
+    expr::Expression,
+    assign::Assign,
+    structs::Struct,
+    interfaces::Interface,
+    enums::Enum,
+    cfg::{
+        Build,
+        Builder,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
     MemmyGenerator
 };
 
-use ir::{ Chunk, hir::HIRInstruction };
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+    mir::MIRInstructions,
+};
 
 use core::pos::BiPos;
 
-use ir_traits::{ ReadInstruction };
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
 
 use notices::{
     DiagnosticSourceBuilder,
@@ -18,20 +40,31 @@ use notices::{
 };
 
 #[derive(Debug, Clone)]
-pub struct Statement<'a>{
-    pos: BiPos,
-    kind: StatementKind<'a>,
+pub struct Statement{
+    pub pos: BiPos,
+    pub kind: StatementKind,
 }
 
 #[derive(Debug, Clone)]
-pub enum StatementKind<'a>{
-    Property(Property<'a>),
-    Fun(Fun<'a>),
-    Local(Local)
+pub enum StatementKind{
+    Property(Property),
+    Fun(Fun),
+    Local(Local),
+    Expr(Expression),
+    ///Leaving a loop drops what is declared in it, in the order that it is dropped in.
+    Break(Option<Expression>, Vec<String>),
+    Continue(Vec<String>),
+    ///Returning drops what is declared in the function, including its params.
+    Return(Option<Expression>, Vec<String>),
+    Assign(Assign),
+    Struct(Struct),
+    ///An interface or a trait, which has nothing to lower since loading it registers it with the generator.
+    Interface,
+    Enum(Enum),
 }
 
-impl<'a> Load for Statement<'a>{
-    type Output = Statement<'a>;
+impl Load for Statement{
+    type Output = Statement;
 
     fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
         let ins = chunk.read_instruction();
@@ -99,6 +132,181 @@ impl<'a> Load for Statement<'a>{
                     pos
                 })
             }
+            Some(HIRInstruction::ExprStmt) => {
+                let expr = match Expression::load(chunk, memmy){
+                    Ok(expr) => expr,
+                    Err(diag) => return Err(diag)
+                };
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        memmy.emit_diagnostic(&[], &[diagnosis]);
+                        return Err(())
+                    }
+                };
+                Ok(Statement{
+                    kind: StatementKind::Expr(expr),
+                    pos
+                })
+            }
+            Some(HIRInstruction::Break) => {
+                let value = if chunk.read_bool(){
+                    match Expression::load(chunk, memmy){
+                        Ok(expr) => Some(expr),
+                        Err(diag) => return Err(diag)
+                    }
+                }else{
+                    None
+                };
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        memmy.emit_diagnostic(&[], &[diagnosis]);
+                        return Err(())
+                    }
+                };
+                Ok(Statement{
+                    kind: StatementKind::Break(value, memmy.env.loop_drops(pos)),
+                    pos
+                })
+            }
+            Some(HIRInstruction::Return) => {
+                let value = if chunk.read_bool(){
+                    match Expression::load(chunk, memmy){
+                        Ok(expr) => Some(expr),
+                        Err(diag) => return Err(diag)
+                    }
+                }else{
+                    None
+                };
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        memmy.emit_diagnostic(&[], &[diagnosis]);
+                        return Err(())
+                    }
+                };
+                Ok(Statement{
+                    kind: StatementKind::Return(value, memmy.env.frame_drops(pos)),
+                    pos
+                })
+            }
+            Some(HIRInstruction::Assign) => {
+                let assign = match Assign::load(chunk, memmy){
+                    Ok(assign) => assign,
+                    Err(diag) => return Err(diag)
+                };
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        memmy.emit_diagnostic(&[], &[diagnosis]);
+                        return Err(())
+                    }
+                };
+                Ok(Statement{
+                    kind: StatementKind::Assign(assign),
+                    pos
+                })
+            }
+            Some(HIRInstruction::Struct) | Some(HIRInstruction::Class) => {
+                let loaded = match ins{
+                    Some(HIRInstruction::Class) => Struct::load_class(chunk, memmy),
+                    _ => Struct::load(chunk, memmy)
+                };
+                let structure = match loaded{
+                    Ok(structure) => structure,
+                    Err(diag) => return Err(diag)
+                };
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        memmy.emit_diagnostic(&[], &[diagnosis]);
+                        return Err(())
+                    }
+                };
+                Ok(Statement{
+                    kind: StatementKind::Struct(structure),
+                    pos
+                })
+            }
+            Some(HIRInstruction::Interface) | Some(HIRInstruction::Trait) => {
+                if let Err(diag) = Interface::load(chunk, memmy, ins == Some(HIRInstruction::Trait)){
+                    return Err(diag)
+                }
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        memmy.emit_diagnostic(&[], &[diagnosis]);
+                        return Err(())
+                    }
+                };
+                Ok(Statement{
+                    kind: StatementKind::Interface,
+                    pos
+                })
+            }
+            Some(HIRInstruction::Enum) => {
+                let enumeration = match Enum::load(chunk, memmy){
+                    Ok(enumeration) => enumeration,
+                    Err(diag) => return Err(diag)
+                };
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        memmy.emit_diagnostic(&[], &[diagnosis]);
+                        return Err(())
+                    }
+                };
+                Ok(Statement{
+                    kind: StatementKind::Enum(enumeration),
+                    pos
+                })
+            }
+            Some(HIRInstruction::Continue) => {
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        memmy.emit_diagnostic(&[], &[diagnosis]);
+                        return Err(())
+                    }
+                };
+                Ok(Statement{
+                    kind: StatementKind::Continue(memmy.env.loop_drops(pos)),
+                    pos
+                })
+            }
             _ => {
                 let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
                         .message(format!("This feature is not yet implemented: {:?}", ins.clone().unwrap()))
@@ -110,4 +318,113 @@ impl<'a> Load for Statement<'a>{
         }
         
     }
-}
\ No newline at end of file
+}
+
+impl Unload for Statement{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        match &self.kind{
+            StatementKind::Property(property) => return property.unload(),
+            StatementKind::Fun(fun) => return fun.unload(),
+            StatementKind::Local(local) => return local.unload(),
+            StatementKind::Expr(expr) => return expr.unload(),
+            StatementKind::Assign(assign) => return assign.unload(),
+            StatementKind::Struct(structure) => return structure.unload(),
+            StatementKind::Enum(enumeration) => return enumeration.unload(),
+            StatementKind::Interface => {}
+            StatementKind::Break(value, drops) | StatementKind::Return(value, drops) => {
+                chunk.write_instruction(match &self.kind{
+                    StatementKind::Break(..) => MIRInstructions::Break,
+                    _ => MIRInstructions::Return,
+                });
+                chunk.write_pos(self.pos);
+                match value{
+                    Some(value) => {
+                        chunk.write_bool(true);
+                        chunk.write_chunk(value.unload()?);
+                    }
+                    None => chunk.write_bool(false)
+                }
+                chunk.write_chunk(unload_drops(drops, self.pos));
+            }
+            StatementKind::Continue(drops) => {
+                chunk.write_instruction(MIRInstructions::Continue);
+                chunk.write_pos(self.pos);
+                chunk.write_chunk(unload_drops(drops, self.pos));
+            }
+        }
+        Ok(chunk)
+    }
+}
+
+impl<'t> Build<'t> for Statement{
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        match &self.kind{
+            StatementKind::Property(property) => property.build(cfg),
+            StatementKind::Fun(fun) => fun.build(cfg),
+            StatementKind::Local(local) => local.build(cfg),
+            StatementKind::Expr(expr) => expr.build(cfg),
+            StatementKind::Assign(assign) => assign.build(cfg),
+            StatementKind::Struct(structure) => structure.build(cfg),
+            StatementKind::Interface | StatementKind::Enum(_) => {}
+            StatementKind::Break(value, _) | StatementKind::Return(value, _) => {
+                if let Some(value) = value{
+                    value.build(cfg);
+                }
+                match &self.kind{
+                    StatementKind::Break(..) => cfg.flow.jump_break(),
+                    _ => cfg.flow.jump_return(),
+                }
+            }
+            StatementKind::Continue(_) => cfg.flow.jump_continue(),
+        }
+    }
+}
+
+impl Count for Statement{
+    ///Only an expression statement has a value, which is the value of a block it ends.
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        match &mut self.kind{
+            StatementKind::Property(property) => property.count(counter),
+            StatementKind::Fun(fun) => fun.count(counter),
+            StatementKind::Local(local) => local.count(counter),
+            StatementKind::Expr(expr) => return expr.count(counter),
+            StatementKind::Assign(assign) => assign.count(counter),
+            StatementKind::Struct(structure) => structure.count(counter),
+            StatementKind::Interface | StatementKind::Enum(_) => vec![],
+            StatementKind::Break(value, drops) => {
+                if let Some(value) = value{
+                    value.count(counter);
+                }
+                *drops = counter.order_drops(drops);
+                vec![]
+            }
+            StatementKind::Return(value, drops) => {
+                if let Some(value) = value{
+                    let referents = value.count(counter);
+                    let returned = counter.ret(referents, self.pos);
+                    drops.retain(|drop| !returned.contains(drop));
+                }
+                *drops = counter.order_drops(drops);
+                vec![]
+            }
+            StatementKind::Continue(drops) => {
+                *drops = counter.order_drops(drops);
+                vec![]
+            }
+        };
+        vec![]
+    }
+}
+
+///Write the number of `drops`, followed by a `Drop` of each of them at `pos`.
+fn unload_drops(drops: &[String], pos: BiPos) -> Chunk{
+    let mut chunk = Chunk::new();
+    chunk.write_usize(drops.len());
+    for drop in drops.iter(){
+        chunk.write_instruction(MIRInstructions::Drop);
+        chunk.write_pos(pos);
+        chunk.write_str(drop);
+    }
+    chunk
+}
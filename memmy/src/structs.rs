@@ -0,0 +1,561 @@
+use super::{
+    Load,
+    Unload,
+    ident::Identifier,
+    expr::{
+        Expression,
+        Access,
+    },
+    fun::Fun,
+    layout::{
+        Layout,
+        StructLayout,
+        VTABLE_FIELD,
+        TRAITS_FIELD,
+        REF_SIZE,
+        layout_of,
+    },
+    interfaces::TraitMask,
+    vtable::{
+        VTable,
+        Dispatch,
+    },
+    glue::DropGlue,
+    Mutability,
+    MemmyGenerator,
+    capture::BindingKind,
+    cfg::{
+        Build,
+        Builder,
+        Site,
+    },
+    refcount::{
+        Count,
+        RefCounter,
+        Referent,
+    },
+};
+
+use core::pos::BiPos;
+
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+    mir::MIRInstructions,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+use notices::{
+    DiagnosticSourceBuilder,
+    DiagnosticLevel
+};
+
+use std::cell::Cell;
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct Struct{
+    ident: Identifier,
+    ///The full path of the struct, such as `main::Point`.
+    path: String,
+    fields: Vec<Field>,
+    methods: Vec<Fun>,
+    layout: StructLayout,
+    ///The vtable of a class, or `None` if this is a struct.
+    vtable: Option<VTable>,
+    ///The trait mask of a struct with traits.
+    trait_mask: Option<TraitMask>,
+    base: Option<Base>,
+    ///The `drop` methods that are called when an object of the struct is dropped.
+    drop_glue: DropGlue,
+    pos: BiPos,
+}
+
+///The base class of a class, and the args that its constructor is called with.
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct Base{
+    ident: Identifier,
+    path: String,
+    args: Vec<Expression>,
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct Field{
+    ident: Identifier,
+    ty: Ty,
+    mutable: Mutability,
+    init: Option<Expression>,
+    ///The size of the field, which is only known once the struct is laid out.
+    size: usize,
+    ///How the constructor passes the param of a field without an initializer to the field.
+    access: Cell<Access>,
+    pos: BiPos,
+}
+
+impl Load for Struct{
+    type Output = Struct;
+
+    ///The layout of the struct is registered before its methods are loaded, so that they can construct it.
+    ///A struct with traits starts with its trait mask.
+    fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
+        load_struct(chunk, memmy, false)
+    }
+}
+
+impl Struct{
+    ///Load a class. Its layout starts with a pointer to its vtable, followed by the fields it inherits and then the fields it declares.
+    ///Its vtable is registered along with its layout, with an itable for each interface it implements.
+    pub fn load_class(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self, ()>{
+        load_struct(chunk, memmy, true)
+    }
+}
+
+fn load_struct(chunk: &Chunk, memmy: &MemmyGenerator, is_class: bool) -> Result<Struct, ()>{
+    let pos = match chunk.read_pos(){
+        Ok(pos) => pos,
+        Err(msg) => {
+            let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                .level(DiagnosticLevel::Error)
+                .message(msg)
+                .build();
+            memmy.emit_diagnostic(&[], &[diagnosis]);
+            return Err(())
+        }
+    };
+    let ident = match Identifier::load(chunk, memmy){
+        Ok(ident) => ident,
+        Err(()) => return Err(())
+    };
+    //The modifier of the class itself is only needed by type checking
+    if is_class{
+        load_modifier(chunk, memmy)?;
+    }
+    //Field initializers and the args to the constructor of the base class may use the constructor params
+    let depth = memmy.env.depth();
+    let fields = load_fields(chunk, memmy).and_then(|fields| match is_class{
+        true => load_base(chunk, memmy).map(|base| (fields, base)),
+        false => Ok((fields, None))
+    });
+    memmy.env.end_scope(depth, ident.pos);
+    let (fields, base) = fields?;
+    let mut with = vec![];
+    while chunk.read_instruction() == Some(HIRInstruction::With){
+        with.push(chunk.read_string().to_owned());
+    }
+    chunk.dec_ins_ptr(1);
+    let mut fields = fields;
+    let mut layouts = vec![];
+    for field in fields.iter_mut(){
+        match memmy.layout(&field.ty){
+            Some(layout) => {
+                field.size = layout.size;
+                layouts.push((field.ident.ident.clone(), layout));
+            }
+            None => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), field.pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Cannot compute the size of the field {} of type {}", field.ident.ident, field.ty))
+                    .range(field.pos.col_range())
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
+            }
+        }
+    }
+    let path = vec![memmy.module_name.clone(), ident.ident.clone()];
+    let (layout, mut vtable) = if is_class{
+        let base_path = base.as_ref().map(|base| format!("{}::{}", memmy.module_name, base.ident.ident));
+        let inherited = match &base_path{
+            Some(base_path) => memmy.structs.borrow().get(base_path).cloned(),
+            None => Some(StructLayout::new(&[(VTABLE_FIELD.to_string(), Layout{ size: 8, align: 8 })]))
+        };
+        let base_vtable = base_path.and_then(|base_path| memmy.vtables.borrow().get(&base_path).cloned());
+        let mut dispatches = vec![];
+        while let Some(dispatch) = load_modifier(chunk, memmy)?{
+            dispatches.push((chunk.read_string().to_owned(), dispatch));
+        }
+        match (inherited, base_vtable.is_some() || base.is_none()){
+            (Some(inherited), true) => (inherited.extend(&layouts), Some(VTable::new(path.join("::"), base_vtable.as_ref(), &dispatches, ident.pos))),
+            _ => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), ident.pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("The base class of {} must be loaded before it", ident.ident))
+                    .range(ident.pos.col_range())
+                    .build();
+                memmy.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diagnosis]);
+                return Err(())
+            }
+        }
+    }else if with.is_empty(){
+        (StructLayout::new(&layouts), None)
+    }else{
+        (StructLayout::new(&[(TRAITS_FIELD.to_string(), Layout{ size: 8, align: 8 })]).extend(&layouts), None)
+    };
+    let trait_mask = match (&vtable, with.is_empty()){
+        (None, false) => Some(memmy.trait_mask(path.join("::"), &with, ident.pos)?),
+        _ => None
+    };
+    if let Some(vtable) = &mut vtable{
+        for interface in with.iter(){
+            let methods = memmy.interfaces.borrow().get(interface).map(|interface| interface.methods.clone()).unwrap_or_default();
+            if let Err(method) = vtable.implement(interface.clone(), &methods){
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), ident.pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("The method {} of {} has no slot in the vtable of {}", method, interface, ident.ident))
+                    .range(ident.pos.col_range())
+                    .build();
+                memmy.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diagnosis]);
+                return Err(())
+            }
+        }
+    }
+    memmy.structs.borrow_mut().insert(path.join("::"), layout.clone());
+    if let Some(vtable) = &vtable{
+        memmy.vtables.borrow_mut().insert(path.join("::"), vtable.clone());
+    }
+    if let Some(base) = &base{
+        memmy.bases.borrow_mut().insert(path.join("::"), base.path.clone());
+    }
+    let depth = memmy.env.depth();
+    //The object that a method is called on is passed to it by reference, like a property is
+    let this = Identifier{
+        ident: "this".to_string(),
+        pos: ident.pos
+    };
+    memmy.env.declare(&this, BindingKind::Property, &Ty::new(TyKind::Named(path.clone()), ident.pos));
+    let methods = load_methods(chunk, memmy, &path.join("::"));
+    memmy.env.end_scope(depth, ident.pos);
+    let methods = methods?;
+    let drop_glue = {
+        let glues = memmy.drop_glue.borrow();
+        let has_drop = methods.iter().any(|method| method.ident.ident == "drop");
+        let inherited = base.as_ref().and_then(|base| glues.get(&base.path));
+        let fields = fields.iter().map(|field| (field.ident.ident.as_str(), match field.ty.kind().as_ref(){
+            TyKind::Named(path) => glues.get(&path.join("::")),
+            _ => None
+        })).collect::<Vec<_>>();
+        DropGlue::new(path.join("::"), has_drop, inherited, &fields, ident.pos)
+    };
+    memmy.drop_glue.borrow_mut().insert(path.join("::"), drop_glue.clone());
+    //Copying an object of a class would copy it as the class of its type, which may not be its actual class
+    //Copying an object with drop glue would call its `drop` methods once for each copy
+    if !is_class && drop_glue.is_empty() && fields.iter().all(|field| memmy.is_copyable(&field.ty)){
+        memmy.copyable.borrow_mut().insert(path.join("::"));
+    }
+    Ok(Struct{
+        ident,
+        path: path.join("::"),
+        fields,
+        methods,
+        layout,
+        vtable,
+        trait_mask,
+        base,
+        drop_glue,
+        pos
+    })
+}
+
+///Load the modifier of a class or of one of its methods, if there is one at the current instruction.
+fn load_modifier(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Option<Dispatch>, ()>{
+    let dispatch = match chunk.read_instruction(){
+        Some(HIRInstruction::Open) => Dispatch::Open,
+        Some(HIRInstruction::Abstract) => Dispatch::Abstract,
+        Some(HIRInstruction::Override) => Dispatch::Override,
+        Some(HIRInstruction::Impl) => Dispatch::Impl,
+        _ => {
+            chunk.dec_ins_ptr(1);
+            return Ok(None)
+        }
+    };
+    if let Err(msg) = chunk.read_pos(){
+        let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+            .level(DiagnosticLevel::Error)
+            .message(msg)
+            .build();
+        memmy.emit_diagnostic(&[], &[diagnosis]);
+        return Err(())
+    }
+    Ok(Some(dispatch))
+}
+
+///Load the base class of a class, if it has one.
+fn load_base(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Option<Base>, ()>{
+    if chunk.read_instruction() != Some(HIRInstruction::Base){
+        chunk.dec_ins_ptr(1);
+        return Ok(None)
+    }
+    let ident = Identifier::load(chunk, memmy)?;
+    let mut args = vec![];
+    while chunk.read_bool(){
+        args.push(Expression::load(chunk, memmy)?);
+    }
+    Ok(Some(Base{
+        path: format!("{}::{}", memmy.module_name, ident.ident),
+        ident,
+        args
+    }))
+}
+
+fn load_fields(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Vec<Field>, ()>{
+    let mut fields = vec![];
+    loop{
+        match chunk.read_instruction(){
+            Some(HIRInstruction::Field) => {
+                let field = Field::load(chunk, memmy)?;
+                if field.init.is_none(){
+                    memmy.env.declare(&field.ident, BindingKind::Local, &field.ty);
+                }
+                fields.push(field);
+            }
+            Some(HIRInstruction::EndParams) => return Ok(fields),
+            ins => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected a field but instead got {:?}", ins))
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
+            }
+        }
+    }
+}
+
+///Load the methods of the struct at `path`, registering each of them once it is loaded.
+fn load_methods(chunk: &Chunk, memmy: &MemmyGenerator, path: &str) -> Result<Vec<Fun>, ()>{
+    let mut methods = vec![];
+    loop{
+        match chunk.read_instruction(){
+            Some(HIRInstruction::Fn) => {
+                let method = Fun::load(chunk, memmy)?;
+                memmy.methods.borrow_mut().entry(path.to_owned()).or_default().push(method.ident.ident.clone());
+                methods.push(method);
+            }
+            Some(HIRInstruction::EndStruct) => return Ok(methods),
+            ins => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected a method but instead got {:?}", ins))
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
+            }
+        }
+    }
+}
+
+impl Load for Field{
+    type Output = Field;
+
+    fn load(chunk: &Chunk, memmy: &MemmyGenerator) -> Result<Self::Output, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
+            }
+        };
+        let ident = Identifier::load(chunk, memmy)?;
+        let mut_pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
+            }
+        };
+        let mutable = chunk.read_bool();
+        let ty = Ty::load(chunk, memmy)?;
+        let init = if chunk.read_bool(){
+            Some(Expression::load(chunk, memmy)?)
+        }else{
+            None
+        };
+        Ok(Field{
+            ident,
+            ty,
+            mutable: Mutability{
+                mutable,
+                pos: mut_pos
+            },
+            init,
+            size: 0,
+            access: Cell::new(Access::Copy),
+            pos
+        })
+    }
+}
+
+impl MemmyGenerator{
+    ///The layout of a value of type `ty`, if every struct it contains has been loaded.
+    pub fn layout(&self, ty: &Ty) -> Option<Layout>{
+        layout_of(ty, &self.structs.borrow())
+    }
+}
+
+impl Unload for Struct{
+    ///Write the layout of the struct, followed by its vtable or its trait mask, its constructor, its methods and its drop glue if it has any.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(MIRInstructions::Struct);
+        chunk.write_pos(self.pos);
+        chunk.write_str(&self.path);
+        chunk.write_usize(self.layout.layout.size);
+        chunk.write_usize(self.layout.offsets.len());
+        for (field, offset) in self.layout.offsets.iter(){
+            chunk.write_str(field);
+            chunk.write_usize(*offset);
+        }
+        if let Some(vtable) = &self.vtable{
+            chunk.write_chunk(vtable.unload()?);
+        }
+        if let Some(trait_mask) = &self.trait_mask{
+            chunk.write_chunk(trait_mask.unload()?);
+        }
+        chunk.write_chunk(self.unload_constructor()?);
+        for method in self.methods.iter(){
+            //An abstract method has no body, so there is nothing to call for it
+            let is_abstract = self.vtable.as_ref().is_some_and(|vtable| vtable.slots.iter().any(|slot| slot.method == method.ident.ident && slot.target.is_none()));
+            if !is_abstract{
+                chunk.write_chunk(method.unload_as(&format!("{}::{}", self.path, method.ident.ident), true)?);
+            }
+        }
+        if !self.drop_glue.is_empty(){
+            chunk.write_chunk(self.drop_glue.unload()?);
+        }
+        chunk.write_instruction(MIRInstructions::EndStruct);
+        Ok(chunk)
+    }
+}
+
+impl Struct{
+    ///Write the constructor, which initializes the object it is passed as `this`.
+    ///The constructor of the base class of a class initializes the fields it inherits, then each field is initialized with its initializer or with the param of the same name.
+    fn unload_constructor(&self) -> Result<Chunk, ()>{
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(MIRInstructions::Fun);
+        chunk.write_pos(self.pos);
+        chunk.write_str(&self.path);
+        chunk.write_instruction(MIRInstructions::FunParam);
+        chunk.write_pos(self.ident.pos);
+        chunk.write_str("this");
+        chunk.write_usize(REF_SIZE);
+        let params = self.fields.iter().filter(|field| field.init.is_none()).collect::<Vec<_>>();
+        for param in params.iter(){
+            chunk.write_instruction(MIRInstructions::FunParam);
+            chunk.write_pos(param.pos);
+            chunk.write_str(&param.ident.ident);
+            chunk.write_usize(param.size);
+        }
+        chunk.write_instruction(MIRInstructions::Block);
+        chunk.write_pos(self.pos);
+        if let Some(base) = &self.base{
+            chunk.write_instruction(MIRInstructions::Call);
+            chunk.write_pos(base.ident.pos);
+            chunk.write_instruction(MIRInstructions::Function);
+            chunk.write_pos(base.ident.pos);
+            chunk.write_str(&base.path);
+            chunk.write_bool(true);
+            chunk.write_instruction(MIRInstructions::Ref);
+            chunk.write_pos(base.ident.pos);
+            chunk.write_str("this");
+            for arg in base.args.iter(){
+                chunk.write_bool(true);
+                chunk.write_chunk(arg.unload()?);
+            }
+            chunk.write_bool(false);
+        }
+        for field in self.fields.iter(){
+            chunk.write_instruction(MIRInstructions::ObjMut);
+            chunk.write_pos(field.pos);
+            chunk.write_string(format!("this.{}", field.ident.ident));
+            match &field.init{
+                Some(init) => chunk.write_chunk(init.unload()?),
+                None => {
+                    chunk.write_instruction(match field.access.get(){
+                        Access::Move => MIRInstructions::Move,
+                        _ => MIRInstructions::Copy,
+                    });
+                    chunk.write_pos(field.ident.pos);
+                    chunk.write_str(&field.ident.ident);
+                }
+            }
+        }
+        chunk.write_instruction(MIRInstructions::EndBlock);
+        for param in params.iter().rev(){
+            chunk.write_instruction(MIRInstructions::Drop);
+            chunk.write_pos(param.pos);
+            chunk.write_str(&param.ident.ident);
+        }
+        chunk.write_instruction(MIRInstructions::EndFun);
+        Ok(chunk)
+    }
+}
+
+impl<'t> Build<'t> for Struct{
+    ///The constructor is built as a graph of its own, which starts by declaring its params. Each param is used by the field it initializes.
+    fn build(&'t self, cfg: &mut Builder<'t>) {
+        let mut constructor = Builder::new();
+        for field in self.fields.iter().filter(|field| field.init.is_none()){
+            constructor.declare(&field.ident, &field.ty);
+        }
+        if let Some(base) = &self.base{
+            for arg in base.args.iter(){
+                arg.build(&mut constructor);
+            }
+        }
+        for field in self.fields.iter(){
+            match &field.init{
+                Some(init) => init.build(&mut constructor),
+                None => constructor.use_object(&field.ident, field.ident.pos, Site::Access(&field.access)),
+            }
+        }
+        cfg.nest(constructor.finish());
+        for method in self.methods.iter(){
+            method.build(cfg);
+        }
+    }
+}
+
+impl Count for Struct{
+    ///The constructor is counted like a function whose params are the fields without an initializer, followed by the methods.
+    fn count(&mut self, counter: &mut RefCounter) -> Vec<Referent> {
+        counter.enter_frame();
+        counter.enter_scope();
+        for field in self.fields.iter().filter(|field| field.init.is_none()){
+            counter.declare(&field.ident, None, false, vec![], field.ident.pos);
+        }
+        if let Some(base) = &mut self.base{
+            for arg in base.args.iter_mut(){
+                arg.count(counter);
+            }
+        }
+        for init in self.fields.iter_mut().filter_map(|field| field.init.as_mut()){
+            init.count(counter);
+        }
+        counter.exit_scope();
+        counter.exit_frame(None, vec![], self.pos);
+        for method in self.methods.iter_mut(){
+            method.count_method(counter);
+        }
+        vec![]
+    }
+}
@@ -8,7 +8,12 @@ use notices::{
     DiagnosticLevel
 };
 
-use ty::Ty;
+use ty::{
+    Ty,
+    TyCtx,
+    TyKind,
+    TyRef,
+};
 
 use ir::{
     Chunk,
@@ -32,13 +37,72 @@ impl Load for Ty{
                 return Err(())
             }
         };
-        let _ins = chunk.read_instruction() as Option<HIRInstruction>;
-        let ident = chunk.read_string().to_owned();
-        Ok(
-            Ty{
-                pos,
-                ident
+        let kind = match load_kind(chunk){
+            Some(kind) => kind,
+            None => {
+                let diagnosis = DiagnosticSourceBuilder::new(memmy.module_name.clone(), pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Attempted to read type information from typeck but found corrupt bytecode."))
+                    .build();
+                memmy.emit_diagnostic(&[], &[diagnosis]);
+                return Err(())
             }
-        )
+        };
+        Ok(Ty::new(kind, pos))
     }
-}
\ No newline at end of file
+}
+
+///Read the structure of a type, as written by typeck.
+fn load_kind(chunk: &Chunk) -> Option<TyKind>{
+    let kind = match chunk.read_instruction() as Option<HIRInstruction>{
+        Some(HIRInstruction::Unit) => TyKind::Unit,
+        Some(HIRInstruction::Bool) => TyKind::Bool,
+        Some(HIRInstruction::Integer) => TyKind::Int,
+        Some(HIRInstruction::Float) => TyKind::Float,
+        Some(HIRInstruction::String) => TyKind::String,
+        Some(HIRInstruction::Int8) => TyKind::Int8,
+        Some(HIRInstruction::Int16) => TyKind::Int16,
+        Some(HIRInstruction::Int64) => TyKind::Int64,
+        Some(HIRInstruction::UInt8) => TyKind::UInt8,
+        Some(HIRInstruction::UInt16) => TyKind::UInt16,
+        Some(HIRInstruction::UInt32) => TyKind::UInt32,
+        Some(HIRInstruction::UInt64) => TyKind::UInt64,
+        Some(HIRInstruction::Float64) => TyKind::Float64,
+        Some(HIRInstruction::Char) => TyKind::Char,
+        Some(HIRInstruction::Custom) => TyKind::Named(path(chunk.read_string())),
+        Some(HIRInstruction::TyApp) => {
+            let name = path(chunk.read_string());
+            TyKind::App(name, load_list(chunk)?)
+        }
+        Some(HIRInstruction::FnType) => {
+            let params = load_list(chunk)?;
+            TyKind::Function(params, intern(load_kind(chunk)?))
+        }
+        Some(HIRInstruction::Nullable) => TyKind::Option(intern(load_kind(chunk)?)),
+        Some(HIRInstruction::Reference) => TyKind::Ref(chunk.read_string().to_owned()),
+        //A type that typeck could not infer. Anything that depends on it has already been reported.
+        Some(HIRInstruction::Unknown) => return Some(TyCtx::with(|ctx| ctx.fresh_var()).as_ref().clone()),
+        _ => return None
+    };
+    Some(kind)
+}
+
+///Read types preceded by `FnParam` until `EndParams`.
+fn load_list(chunk: &Chunk) -> Option<Vec<TyRef>>{
+    let mut tys = vec![];
+    loop{
+        match chunk.read_instruction() as Option<HIRInstruction>{
+            Some(HIRInstruction::FnParam) => tys.push(intern(load_kind(chunk)?)),
+            Some(HIRInstruction::EndParams) => return Some(tys),
+            _ => return None
+        }
+    }
+}
+
+fn intern(kind: TyKind) -> TyRef{
+    TyCtx::with(|ctx| ctx.intern(kind))
+}
+
+fn path(path: &str) -> Vec<String>{
+    path.split("::").map(|segment| segment.to_owned()).collect()
+}
@@ -0,0 +1,184 @@
+use super::Unload;
+
+use core::pos::BiPos;
+
+use ir::{
+    Chunk,
+    mir::MIRInstructions,
+};
+
+use ir_traits::WriteInstruction;
+
+///How a method of a class that may be overridden is declared. Every other method is called directly, without the vtable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dispatch{
+    ///An open method, which gets a slot of its own.
+    Open,
+    ///An abstract method, which gets a slot that is left empty until a class that inherits it overrides it.
+    Abstract,
+    ///A method that overrides an inherited method, which fills the slot of the method it overrides.
+    Override,
+    ///A method that implements a method of an interface, which gets a slot of its own so that classes that inherit it may override it.
+    Impl,
+}
+
+///The methods of a class that may be overridden, in the order of their slots.
+///A class starts with the slots of its base class, so a slot means the same method in every class that inherits it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VTable{
+    ///The full path of the class, such as `main::Square`.
+    pub class: String,
+    pub slots: Vec<Slot>,
+    ///The interfaces that the class implements, including those that it inherits.
+    pub itables: Vec<ITable>,
+    pub pos: BiPos,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slot{
+    pub method: String,
+    ///The full path of the method that is called for this slot, such as `main::Square::area`, or `None` if it is abstract.
+    pub target: Option<String>,
+}
+
+///How the methods of an interface are found on an object of a class that implements it.
+///An object of an interface only knows that its class implements it, so the methods are found through the slots of the vtable of its class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ITable{
+    ///The full path of the interface, such as `main::Named`.
+    pub interface: String,
+    ///The slot of the vtable for each method of the interface, in the order the interface declares them.
+    pub slots: Vec<usize>,
+}
+
+impl VTable{
+    ///The vtable of the class at `class`, which inherits the slots of `base` and declares `methods`.
+    pub fn new(class: String, base: Option<&VTable>, methods: &[(String, Dispatch)], pos: BiPos) -> VTable{
+        let mut slots = base.map_or(vec![], |base| base.slots.clone());
+        for (method, dispatch) in methods.iter(){
+            let target = format!("{}::{}", class, method);
+            match dispatch{
+                Dispatch::Override => match slots.iter_mut().find(|slot| slot.method == *method){
+                    Some(slot) => slot.target = Some(target),
+                    None => slots.push(Slot{ method: method.clone(), target: Some(target) })
+                },
+                Dispatch::Open | Dispatch::Impl => slots.push(Slot{ method: method.clone(), target: Some(target) }),
+                Dispatch::Abstract => slots.push(Slot{ method: method.clone(), target: None }),
+            }
+        }
+        VTable{
+            class,
+            slots,
+            itables: base.map_or(vec![], |base| base.itables.clone()),
+            pos
+        }
+    }
+
+    ///Add the itable of `interface`, whose methods are `methods`. Each of them must have a slot, which is the slot of the method that implements it.
+    ///Returns the first method that has no slot if there is one.
+    pub fn implement(&mut self, interface: String, methods: &[String]) -> Result<(), String>{
+        let mut slots = vec![];
+        for method in methods.iter(){
+            match self.slot(method){
+                Some(slot) => slots.push(slot),
+                None => return Err(method.clone())
+            }
+        }
+        if !self.itables.iter().any(|itable| itable.interface == interface){
+            self.itables.push(ITable{ interface, slots });
+        }
+        Ok(())
+    }
+
+    ///The index of the slot of `method`, if it is called through the vtable.
+    pub fn slot(&self, method: &str) -> Option<usize>{
+        self.slots.iter().position(|slot| slot.method == method)
+    }
+}
+
+impl Unload for VTable{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(MIRInstructions::VTable);
+        chunk.write_pos(self.pos);
+        chunk.write_str(&self.class);
+        chunk.write_usize(self.slots.len());
+        for (index, slot) in self.slots.iter().enumerate(){
+            chunk.write_instruction(MIRInstructions::VTableSlot);
+            chunk.write_usize(index);
+            match &slot.target{
+                Some(target) => {
+                    chunk.write_bool(true);
+                    chunk.write_str(target);
+                }
+                None => chunk.write_bool(false)
+            }
+        }
+        for itable in self.itables.iter(){
+            chunk.write_instruction(MIRInstructions::ITable);
+            chunk.write_str(&itable.interface);
+            chunk.write_usize(itable.slots.len());
+            for (index, slot) in itable.slots.iter().enumerate(){
+                chunk.write_instruction(MIRInstructions::ITableSlot);
+                chunk.write_usize(index);
+                chunk.write_usize(*slot);
+            }
+        }
+        chunk.write_instruction(MIRInstructions::EndVTable);
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(vtable: &VTable, method: &str) -> Option<String>{
+        vtable.slots[vtable.slot(method).unwrap()].target.clone()
+    }
+
+    #[test]
+    fn overrides_keep_the_slot_of_the_method_they_override() {
+        let pos = BiPos::default();
+        let shape = VTable::new("main::Shape".to_string(), None, &[
+            ("area".to_string(), Dispatch::Abstract),
+            ("name".to_string(), Dispatch::Open),
+        ], pos);
+        assert_eq!(shape.slot("sides"), None);
+        assert_eq!(target(&shape, "area"), None);
+        let square = VTable::new("main::Square".to_string(), Some(&shape), &[
+            ("name".to_string(), Dispatch::Override),
+            ("area".to_string(), Dispatch::Override),
+            ("scale".to_string(), Dispatch::Open),
+        ], pos);
+        assert_eq!(square.slot("area"), Some(0));
+        assert_eq!(square.slot("name"), Some(1));
+        assert_eq!(square.slot("scale"), Some(2));
+        assert_eq!(target(&square, "area"), Some("main::Square::area".to_string()));
+        assert_eq!(target(&square, "name"), Some("main::Square::name".to_string()));
+        let cube = VTable::new("main::Cube".to_string(), Some(&square), &[], pos);
+        assert_eq!(cube.slots, square.slots);
+    }
+
+    fn interface_slot(vtable: &VTable, interface: &str, index: usize) -> Option<usize>{
+        vtable.itables.iter().find(|itable| itable.interface == interface)?.slots.get(index).copied()
+    }
+
+    #[test]
+    fn itables_point_at_the_slots_of_the_implementing_methods() {
+        let pos = BiPos::default();
+        let mut animal = VTable::new("main::Animal".to_string(), None, &[
+            ("age".to_string(), Dispatch::Open),
+            ("name".to_string(), Dispatch::Impl),
+        ], pos);
+        assert_eq!(animal.implement("main::Named".to_string(), &["name".to_string()]), Ok(()));
+        assert_eq!(animal.implement("main::Aged".to_string(), &["age".to_string(), "birthday".to_string()]), Err("birthday".to_string()));
+        assert_eq!(interface_slot(&animal, "main::Named", 0), Some(1));
+        let dog = VTable::new("main::Dog".to_string(), Some(&animal), &[
+            ("name".to_string(), Dispatch::Override),
+        ], pos);
+        assert_eq!(interface_slot(&dog, "main::Named", 0), Some(1));
+        assert_eq!(dog.slots[1].target, Some("main::Dog::name".to_string()));
+        assert_eq!(interface_slot(&dog, "main::Aged", 0), None);
+    }
+}
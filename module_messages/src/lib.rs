@@ -1,7 +1,34 @@
 use core::pos::BiPos;
 
+use std::{
+    sync::mpsc::{
+        Receiver,
+        Sender,
+    },
+    thread::{
+        self,
+        JoinHandle,
+    },
+};
+
 #[derive(Debug, Clone)]
 pub enum ModuleMessage{
     SourceRequest(BiPos),
     SourceResponse(String)
-}
\ No newline at end of file
+}
+
+///Answer every `SourceRequest` received on `requests` with the lines of `source` that it asks for, until every sender of requests is dropped.
+pub fn serve_source(source: String, requests: Receiver<ModuleMessage>, responses: Sender<ModuleMessage>) -> JoinHandle<()>{
+    thread::spawn(move ||{
+        while let Ok(ModuleMessage::SourceRequest(pos)) = requests.recv(){
+            let lines = source
+                .lines()
+                .skip(pos.line_region.0)
+                .take(pos.line_region.1 - pos.line_region.0)
+                .collect();
+            if responses.send(ModuleMessage::SourceResponse(lines)).is_err(){
+                return
+            }
+        }
+    })
+}
@@ -87,6 +87,10 @@ pub struct DiagnosticSource{
 }
 
 impl DiagnosticSource{
+    pub fn message(&self) -> &str{
+        &self.msg
+    }
+
     pub fn to_slice(&self) -> Slice{
         Slice{
             source: self.source.clone(),
@@ -0,0 +1,115 @@
+use super::{
+    Parser,
+    ParseRule,
+    ParseContext,
+    local_statements::LocalStatementParser,
+};
+
+use lexer::tokens::TokenType;
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+pub struct BlockParser;
+
+impl BlockParser{
+    ///Parse a block of local statements enclosed in curly braces. The current token must be the opening `{`, and upon success the current token is the closing `}`.
+    ///Every statement in the block is collected into the returned chunk, between a `Block` and an `EndBlock` instruction.
+    pub fn parse_block(parser: &mut Parser) -> Result<Chunk, ()>{
+        let pos = parser.current_token().pos;
+        if !parser.check(TokenType::LCurly){
+            let source = match parser.request_source_snippet(pos){
+                Ok(source) => source,
+                Err(diag) => {
+                    parser.emit_parse_diagnostic(&[], &[diag]);
+                    return Err(())
+                }
+            };
+            let diag_source = DiagnosticSourceBuilder::new(parser.name.clone(), pos.start.0)
+                .level(DiagnosticLevel::Error)
+                .message(format!("Expected '{{' to start a block but instead got {:?}", parser.current_token().type_))
+                .range(pos.col_range())
+                .source(source)
+                .build();
+            parser.emit_parse_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        Self::collect(parser, pos, |parser|{
+            if let Err(diag) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[diag]);
+                return Err(())
+            }
+            Self::parse_until_close(parser, pos)
+        })
+    }
+
+    ///Parse the statements of a block that was opened at `pos`, such as the body of a lambda after its params.
+    ///The current token must be the first token of the first statement, and upon success the current token is the closing `}`.
+    pub fn parse_statements(parser: &mut Parser, pos: core::pos::BiPos) -> Result<Chunk, ()>{
+        Self::collect(parser, pos, |parser| Self::parse_until_close(parser, pos))
+    }
+
+    ///Parse the body of a control flow expression, which is either a block or a single local statement.
+    ///Either way, the body is returned as a block chunk. Upon success the current token is the last token of the body.
+    pub fn parse_body(parser: &mut Parser) -> Result<Chunk, ()>{
+        if parser.check(TokenType::LCurly){
+            return Self::parse_block(parser)
+        }
+        let pos = parser.current_token().pos;
+        Self::collect(parser, pos, LocalStatementParser::parse)
+    }
+
+    ///Run `parse` while collecting every chunk it emits into a new block chunk.
+    fn collect<F>(parser: &mut Parser, pos: core::pos::BiPos, parse: F) -> Result<Chunk, ()>
+        where F: FnOnce(&mut Parser) -> Result<(), ()>{
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Block);
+        chunk.write_pos(pos);
+        let context = std::mem::replace(&mut parser.context, ParseContext::Local);
+        parser.blocks.push(chunk);
+        let result = parse(parser);
+        parser.context = context;
+        let mut chunk = parser.blocks.pop().expect("The block stack should never be empty while collecting a block.");
+        result?;
+        chunk.write_instruction(HIRInstruction::EndBlock);
+        chunk.write_pos(parser.current_token().pos);
+        Ok(chunk)
+    }
+
+    fn parse_until_close(parser: &mut Parser, pos: core::pos::BiPos) -> Result<(), ()>{
+        while !parser.check(TokenType::RCurly){
+            if parser.check(TokenType::Eof){
+                let source = match parser.request_source_snippet(pos){
+                    Ok(source) => source,
+                    Err(diag) => {
+                        parser.emit_parse_diagnostic(&[], &[diag]);
+                        return Err(())
+                    }
+                };
+                let diag_source = DiagnosticSourceBuilder::new(parser.name.clone(), pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message("This block is never closed. Expected '}' but instead reached the end of the file.".to_string())
+                    .range(pos.col_range())
+                    .source(source)
+                    .build();
+                parser.emit_parse_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+            LocalStatementParser::parse(parser)?;
+            if let Err(diag) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[diag]);
+                return Err(())
+            }
+        }
+        Ok(())
+    }
+}
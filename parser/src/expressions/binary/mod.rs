@@ -1,10 +1,8 @@
 use crate::{
     Parser,
     OwnedParse,
-    expressions::{
-        ExpressionParser,
-        literal::LiteralParser,
-    },
+    expressions::unary::UnaryParser,
+    type_::TypeParser,
 };
 
 use ir::{
@@ -14,124 +12,81 @@ use ir::{
 
 use ir_traits::WriteInstruction;
 
+use lexer::tokens::TokenType;
+
 use notices::{
     DiagnosticSource,
 };
 
-pub struct AddParser;
+///The precedence of the loosest binding binary operator.
+pub const LOWEST_PRECEDENCE: u8 = 1;
+///The precedence of `is` checks, which bind like comparisons.
+const IS_PRECEDENCE: u8 = 5;
+///The precedence of `as` casts, which bind tighter than every binary operator but looser than unary operators.
+const CAST_PRECEDENCE: u8 = 10;
 
-impl OwnedParse for AddParser{
-    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
-        let mut chunk = Chunk::new();
-        chunk.write_instruction(HIRInstruction::Add);
-        chunk.write_pos(parser.next_token().pos);
+pub struct BinaryParser;
 
-        match LiteralParser::owned_parse(parser){
-            Ok(left) => {
-                chunk.write_chunk(left);
-            }
-            Err(msg) => {
-                return Err(msg)
-            }
-        }
-        parser.advance().unwrap();
-        parser.advance().unwrap();
-        match ExpressionParser::owned_parse(parser){
-            Ok(right) => {
-                chunk.write_chunk(right);
-            }
-            Err(msg) => {
-                return Err(msg)
-            }
-        }
-        Ok(chunk)
-    }
-}
-
-pub struct SubParser;
-
-impl OwnedParse for SubParser{
-    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
-        let mut chunk = Chunk::new();
-        chunk.write_instruction(HIRInstruction::Sub);
-        chunk.write_pos(parser.next_token().pos);
-
-        match LiteralParser::owned_parse(parser){
-            Ok(left) => {
-                chunk.write_chunk(left);
-            }
-            Err(msg) => {
-                return Err(msg)
-            }
-        }
-        parser.advance().unwrap();
-        parser.advance().unwrap();
-        match ExpressionParser::owned_parse(parser){
-            Ok(right) => {
-                chunk.write_chunk(right);
-            }
-            Err(msg) => {
-                return Err(msg)
-            }
+impl BinaryParser{
+    ///Get the instruction and precedence of a binary operator. Operators with a higher precedence bind tighter.
+    fn operator(type_: &TokenType) -> Option<(HIRInstruction, u8)>{
+        match type_{
+            TokenType::PipePipe => Some((HIRInstruction::Or, 1)),
+            TokenType::AndAnd => Some((HIRInstruction::And, 2)),
+            TokenType::EqualEqual => Some((HIRInstruction::Eq, 3)),
+            TokenType::BangEqual => Some((HIRInstruction::NotEq, 3)),
+            TokenType::LAngle => Some((HIRInstruction::Less, 4)),
+            TokenType::RAngle => Some((HIRInstruction::Greater, 4)),
+            TokenType::LAngleEqual => Some((HIRInstruction::LessEq, 4)),
+            TokenType::RAngleEqual => Some((HIRInstruction::GreaterEq, 4)),
+            TokenType::QMarkColon => Some((HIRInstruction::Elvis, 6)),
+            TokenType::DotDot => Some((HIRInstruction::Range, 7)),
+            TokenType::Plus => Some((HIRInstruction::Add, 8)),
+            TokenType::Minus => Some((HIRInstruction::Sub, 8)),
+            TokenType::Star => Some((HIRInstruction::Mult, 9)),
+            TokenType::Slash => Some((HIRInstruction::Div, 9)),
+            _ => None
         }
-        Ok(chunk)
     }
-}
-
-pub struct MulParser;
-
-impl OwnedParse for MulParser{
-    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
-        let mut chunk = Chunk::new();
-        chunk.write_instruction(HIRInstruction::Sub);
 
-        match LiteralParser::owned_parse(parser){
-            Ok(left) => {
+    ///Parse a binary expression by precedence climbing. Operators that bind looser than `min_precedence` are left for the caller.
+    ///Binary operators are left associative. The chunk for each operation is its instruction and position followed by its left and right operands.
+    pub fn parse_precedence(parser: &mut Parser, min_precedence: u8) -> Result<Chunk, DiagnosticSource>{
+        let mut left = UnaryParser::owned_parse(parser)?;
+        loop{
+            //A cast such as `x as Int64` or a check such as `x is Int` is followed by a type rather than an expression
+            let type_operator = match parser.next_token().type_{
+                TokenType::KwAs if CAST_PRECEDENCE >= min_precedence => Some(HIRInstruction::Cast),
+                TokenType::KwIs if IS_PRECEDENCE >= min_precedence => Some(HIRInstruction::Is),
+                _ => None
+            };
+            if let Some(ins) = type_operator{
+                parser.advance()?;
+                let pos = parser.current_token().pos;
+                let ty = TypeParser::get_type(parser)?;
+                let mut chunk = Chunk::new();
+                chunk.write_instruction(ins);
+                chunk.write_pos(pos);
                 chunk.write_chunk(left);
-            }
-            Err(msg) => {
-                return Err(msg)
-            }
+                chunk.write_chunk(ty);
+                left = chunk;
+                continue
+            }
+            let (ins, precedence) = match Self::operator(&parser.next_token().type_){
+                Some((ins, precedence)) if precedence >= min_precedence => (ins, precedence),
+                _ => break
+            };
+            parser.advance()?;
+            let pos = parser.current_token().pos;
+            parser.advance()?;
+            let right = Self::parse_precedence(parser, precedence + 1)?;
+            let mut chunk = Chunk::new();
+            chunk.write_instruction(ins);
+            chunk.write_pos(pos);
+            chunk.write_chunk(left);
+            chunk.write_chunk(right);
+            left = chunk;
         }
-        parser.advance().unwrap();
-        parser.advance().unwrap();
-        match ExpressionParser::owned_parse(parser){
-            Ok(right) => {
-                chunk.write_chunk(right);
-            }
-            Err(msg) => {
-                return Err(msg)
-            }
-        }
-        Ok(chunk)
+        Ok(left)
     }
 }
-
-pub struct DivParser;
-
-impl OwnedParse for DivParser{
-    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
-        let mut chunk = Chunk::new();
-        chunk.write_instruction(HIRInstruction::Mult);
-
-        match LiteralParser::owned_parse(parser){
-            Ok(left) => {
-                chunk.write_chunk(left);
-            }
-            Err(msg) => {
-                return Err(msg)
-            }
-        }
-        parser.advance().unwrap();
-        parser.advance().unwrap();
-        match ExpressionParser::owned_parse(parser){
-            Ok(right) => {
-                chunk.write_chunk(right);
-            }
-            Err(msg) => {
-                return Err(msg)
-            }
-        }
-        Ok(chunk)
-    }
-}
\ No newline at end of file
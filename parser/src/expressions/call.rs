@@ -0,0 +1,278 @@
+use crate::{
+    Parser,
+    OwnedParse,
+    expressions::{
+        ExpressionParser,
+        lambda::LambdaParser,
+    },
+    type_::TypeParser,
+};
+
+use core::pos::BiPos;
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use lexer::tokens::{
+    TokenType,
+    TokenData,
+};
+
+use notices::{
+    DiagnosticSource,
+    DiagnosticSourceBuilder,
+    DiagnosticLevel,
+};
+
+///Parses the calls that follow a callee, such as `f(a, b)`, `f(a) { x -> x }`, `f { x -> x }` or `id<Int>(3)`, along with member accesses `.member` and the postfix `!!` and `?.` operators.
+pub struct CallParser;
+
+impl CallParser{
+    ///Parse every call, `!!`, member access `.member` and safe call `?.member` that follows `callee`, which starts at `pos`. The current token must be the last token of the callee.
+    ///Arguments must start on the same line as the end of the callee, so that a parenthesized expression or lambda on the next line is not mistaken for a call.
+    ///Upon success, the current token is the last token of the last call.
+    pub fn parse_calls(parser: &mut Parser, callee: Chunk, pos: BiPos) -> Result<Chunk, DiagnosticSource>{
+        let mut callee = callee;
+        loop{
+            if parser.check_next(TokenType::BangBang){
+                parser.advance()?;
+                let mut assert_pos = pos;
+                assert_pos.end = parser.current_token().pos.end;
+                let mut chunk = Chunk::new();
+                chunk.write_instruction(HIRInstruction::NotNull);
+                chunk.write_pos(assert_pos);
+                chunk.write_chunk(callee);
+                callee = chunk;
+                continue
+            }
+            if parser.check_next(TokenType::QMarkDot) || parser.check_next(TokenType::Dot){
+                let ins = if parser.check_next(TokenType::Dot){
+                    HIRInstruction::Member
+                }else{
+                    HIRInstruction::SafeAccess
+                };
+                parser.advance()?;
+                let op = parser.current_token().type_;
+                parser.advance()?;
+                let token = parser.current_token();
+                let member = match (&token.type_, &token.data){
+                    (TokenType::Identifier, TokenData::String(member)) => member.clone(),
+                    _ => {
+                        let op = if op == TokenType::Dot{ "." }else{ "?." };
+                        let source = parser.request_source_snippet(token.pos)?;
+                        return Err(DiagnosticSourceBuilder::new(parser.name.clone(), token.pos.start.0)
+                            .level(DiagnosticLevel::Error)
+                            .message(format!("Expected a member name after `{}` but instead got {:?}", op, token.type_))
+                            .range(token.pos.col_range())
+                            .source(source)
+                            .build())
+                    }
+                };
+                let mut chunk = Chunk::new();
+                chunk.write_instruction(ins);
+                chunk.write_pos(token.pos);
+                chunk.write_chunk(callee);
+                chunk.write_string(member);
+                callee = chunk;
+                continue
+            }
+            let ty_args = if Self::on_same_line(parser) && Self::starts_ty_args(parser)?{
+                Self::parse_ty_args(parser)?
+            }else{
+                Chunk::new()
+            };
+            let is_call = Self::on_same_line(parser) && (parser.check_next(TokenType::LParen) || parser.check_next(TokenType::LCurly));
+            if !is_call{
+                return Ok(callee)
+            }
+            let mut chunk = Chunk::new();
+            chunk.write_instruction(HIRInstruction::Call);
+            chunk.write_pos(pos);
+            chunk.write_chunk(callee);
+            chunk.write_chunk(ty_args);
+            if parser.check_next(TokenType::LParen){
+                parser.advance()?;
+                if !parser.check_consume_next(TokenType::RParen)?{
+                    loop{
+                        parser.advance()?;
+                        let arg = ExpressionParser::owned_parse(parser)?;
+                        chunk.write_chunk(arg);
+                        if parser.check_consume_next(TokenType::Comma)?{
+                            continue;
+                        }
+                        parser.consume(TokenType::RParen)?;
+                        break;
+                    }
+                }
+                if parser.check_next(TokenType::LCurly) && Self::on_same_line(parser){
+                    parser.advance()?;
+                    let lambda = LambdaParser::owned_parse(parser)?;
+                    chunk.write_chunk(lambda);
+                }
+            }else{
+                parser.advance()?;
+                let lambda = LambdaParser::owned_parse(parser)?;
+                chunk.write_chunk(lambda);
+            }
+            chunk.write_instruction(HIRInstruction::EndCall);
+            callee = chunk;
+        }
+    }
+
+    ///Whether the next token is a `<` that starts the type arguments of a call, such as the `<Int>` of `id<Int>(3)`, rather than a comparison.
+    ///It does when the tokens up to the matching `>` can all be part of a type, and the `>` is followed by the args of the call.
+    fn starts_ty_args(parser: &mut Parser) -> Result<bool, DiagnosticSource>{
+        if !parser.check_next(TokenType::LAngle){
+            return Ok(false)
+        }
+        let mut depth = 0;
+        let mut distance = 0;
+        loop{
+            match parser.peek(distance)?.type_{
+                TokenType::LAngle => depth += 1,
+                TokenType::RAngle => {
+                    depth -= 1;
+                    if depth == 0{
+                        return Ok(parser.peek(distance + 1)?.type_ == TokenType::LParen)
+                    }
+                }
+                TokenType::Identifier | TokenType::Comma | TokenType::QMark | TokenType::LParen | TokenType::RParen | TokenType::Arrow => {}
+                _ => return Ok(false)
+            }
+            distance += 1;
+        }
+    }
+
+    ///Parse the type arguments of a call, each preceded by `FnParam`. The next token must be the `<`, and upon success the current token is the closing `>`.
+    fn parse_ty_args(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let mut chunk = Chunk::new();
+        parser.consume(TokenType::LAngle)?;
+        loop{
+            let arg = TypeParser::get_type(parser)?;
+            chunk.write_instruction(HIRInstruction::FnParam);
+            chunk.write_chunk(arg);
+            if parser.check_consume_next(TokenType::Comma)?{
+                continue;
+            }
+            parser.consume(TokenType::RAngle)?;
+            break;
+        }
+        Ok(chunk)
+    }
+
+    fn on_same_line(parser: &Parser) -> bool{
+        parser.next_token().pos.start.0 == parser.current_token().pos.start.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ir_traits::ReadInstruction;
+
+    fn parse(source: &str) -> Chunk{
+        let (mut parser, _, _) = Parser::of_source(source);
+        ExpressionParser::owned_parse(&mut parser).unwrap()
+    }
+
+    fn reference(chunk: &Chunk) -> &str{
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Reference));
+        chunk.read_pos().unwrap();
+        chunk.read_string()
+    }
+
+    #[test]
+    fn calls_may_be_given_type_arguments() {
+        let chunk = parse("id<Box<Int>, Int?>(3)");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Call));
+        chunk.read_pos().unwrap();
+        assert_eq!(reference(&chunk), "id");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::FnParam));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::TyApp));
+        assert_eq!(chunk.read_string(), "Box");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::FnParam));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Integer));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::EndParams));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::FnParam));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Nullable));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Integer));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Integer));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_long(), 3);
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::EndCall));
+    }
+
+    ///Read the head of a call of a member, up to the expression that the member is accessed on.
+    fn member_call(chunk: &Chunk){
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Call));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Member));
+        chunk.read_pos().unwrap();
+    }
+
+    #[test]
+    fn members_of_literals_can_be_called() {
+        let chunk = parse("\"a\".shout()");
+        member_call(&chunk);
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::String));
+        chunk.read_pos().unwrap();
+        chunk.read_string();
+        assert_eq!(chunk.read_string(), "shout");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::EndCall));
+        let chunk = parse("3.double()");
+        member_call(&chunk);
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Integer));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_long(), 3);
+        assert_eq!(chunk.read_string(), "double");
+        let chunk = parse("1.5");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Float));
+    }
+
+    #[test]
+    fn members_of_grouped_expressions_can_be_called() {
+        let chunk = parse("(a + b).f()!!");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::NotNull));
+        chunk.read_pos().unwrap();
+        member_call(&chunk);
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Add));
+        chunk.read_pos().unwrap();
+        assert_eq!(reference(&chunk), "a");
+        assert_eq!(reference(&chunk), "b");
+        assert_eq!(chunk.read_string(), "f");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::EndCall));
+    }
+
+    #[test]
+    fn comparisons_are_not_type_arguments() {
+        let chunk = parse("a < b");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Less));
+        chunk.read_pos().unwrap();
+        assert_eq!(reference(&chunk), "a");
+        assert_eq!(reference(&chunk), "b");
+        let chunk = parse("a < b && c > (d)");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::And));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Less));
+    }
+
+    #[test]
+    fn trailing_lambdas_are_the_last_argument() {
+        let chunk = parse("apply(2){ n -> n }");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Call));
+        chunk.read_pos().unwrap();
+        assert_eq!(reference(&chunk), "apply");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Integer));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_long(), 2);
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Lambda));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    Parser,
+    OwnedParse,
+    expressions::{
+        ExpressionParser,
+        parse_body,
+    },
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use lexer::tokens::TokenType;
+
+use notices::DiagnosticSource;
+
+///Parses `if(cond) { ... } else { ... }`. The else branch is optional, and may itself be another `if`.
+pub struct IfParser;
+
+impl OwnedParse for IfParser{
+    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        parse_conditional(parser, false)
+    }
+}
+
+///Parses `unless(cond) { ... }`, which is sugar for `if(!cond) { ... }`.
+pub struct UnlessParser;
+
+impl OwnedParse for UnlessParser{
+    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        parse_conditional(parser, true)
+    }
+}
+
+fn parse_conditional(parser: &mut Parser, negate: bool) -> Result<Chunk, DiagnosticSource>{
+    let pos = parser.current_token().pos;
+    parser.consume(TokenType::LParen)?;
+    parser.advance()?;
+    let cond = ExpressionParser::owned_parse(parser)?;
+    parser.consume(TokenType::RParen)?;
+    parser.advance()?;
+    let then = parse_body(parser, pos)?;
+    let mut chunk = Chunk::new();
+    chunk.write_instruction(HIRInstruction::If);
+    chunk.write_pos(pos);
+    if negate{
+        chunk.write_instruction(HIRInstruction::Not);
+        chunk.write_pos(pos);
+    }
+    chunk.write_chunk(cond);
+    chunk.write_chunk(then);
+    if parser.check_consume_next(TokenType::KwElse)?{
+        let else_pos = parser.current_token().pos;
+        parser.advance()?;
+        let otherwise = parse_body(parser, else_pos)?;
+        chunk.write_bool(true);
+        chunk.write_chunk(otherwise);
+    }else{
+        chunk.write_bool(false);
+    }
+    Ok(chunk)
+}
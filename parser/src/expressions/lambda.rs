@@ -0,0 +1,129 @@
+use crate::{
+    Parser,
+    OwnedParse,
+    block::BlockParser,
+    type_::TypeParser,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use lexer::tokens::{
+    TokenType,
+    TokenData,
+};
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSource,
+    DiagnosticSourceBuilder,
+};
+
+///Parses a lambda literal such as `{ x, y -> x + y }` or `{ x: Int -> x }`. A lambda without params may leave out the arrow, as in `{ println(1) }`.
+///The types of params without annotations are inferred from the context the lambda is used in.
+pub struct LambdaParser;
+
+impl OwnedParse for LambdaParser{
+    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let pos = parser.current_token().pos;
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Lambda);
+        chunk.write_pos(pos);
+        parser.advance()?;
+        let has_params = parser.check(TokenType::Identifier) && matches!(parser.next_token().type_, TokenType::Arrow | TokenType::Comma | TokenType::Colon);
+        if has_params{
+            loop{
+                let param_pos = parser.current_token().pos;
+                let name = match &parser.current_token().data{
+                    TokenData::String(name) => name.clone(),
+                    data => {
+                        let message = format!("Expected to find identifier data but instead found: {:?}", data);
+                        return Err(DiagnosticSourceBuilder::new(parser.name.clone(), param_pos.start.0)
+                            .level(DiagnosticLevel::Error)
+                            .message(message)
+                            .build())
+                    }
+                };
+                chunk.write_instruction(HIRInstruction::FnParam);
+                chunk.write_pos(param_pos);
+                chunk.write_string(name);
+                if parser.check_consume_next(TokenType::Colon)?{
+                    let ty = TypeParser::get_type(parser)?;
+                    chunk.write_chunk(ty);
+                }else{
+                    chunk.write_pos(param_pos);
+                    chunk.write_instruction(HIRInstruction::Unknown);
+                }
+                if parser.check_consume_next(TokenType::Comma)?{
+                    parser.consume(TokenType::Identifier)?;
+                    continue;
+                }
+                parser.consume(TokenType::Arrow)?;
+                break;
+            }
+            parser.advance()?;
+        }else if parser.check(TokenType::Arrow){
+            parser.advance()?;
+        }
+        chunk.write_instruction(HIRInstruction::EndParams);
+        let body = match BlockParser::parse_statements(parser, pos){
+            Ok(body) => body,
+            Err(()) => {
+                let source = parser.request_source_snippet(pos)?;
+                return Err(DiagnosticSourceBuilder::new(parser.name.clone(), pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message("Failed to parse the body of this lambda.".to_string())
+                    .range(pos.col_range())
+                    .source(source)
+                    .build())
+            }
+        };
+        chunk.write_chunk(body);
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::ExpressionParser;
+    use ir_traits::ReadInstruction;
+
+    fn parse(source: &str) -> Chunk{
+        let (mut parser, _, _) = Parser::of_source(source);
+        ExpressionParser::owned_parse(&mut parser).unwrap()
+    }
+
+    ///Read a param, returning its name and the instruction of its type.
+    fn param(chunk: &Chunk) -> (String, Option<HIRInstruction>){
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::FnParam));
+        chunk.read_pos().unwrap();
+        let name = chunk.read_string().to_owned();
+        chunk.read_pos().unwrap();
+        (name, chunk.read_instruction())
+    }
+
+    #[test]
+    fn params_without_annotations_are_inferred_later() {
+        let chunk = parse("{ x, y: Int -> x }");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Lambda));
+        chunk.read_pos().unwrap();
+        assert_eq!(param(&chunk), ("x".to_owned(), Some(HIRInstruction::Unknown)));
+        assert_eq!(param(&chunk), ("y".to_owned(), Some(HIRInstruction::Integer)));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::EndParams));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Block));
+    }
+
+    #[test]
+    fn lambdas_without_params_leave_out_the_arrow() {
+        let chunk = parse("{ 1 }");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Lambda));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::EndParams));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Block));
+    }
+}
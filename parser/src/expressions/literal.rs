@@ -32,14 +32,21 @@ impl OwnedParse for LiteralParser{
                 if let TokenData::Float(f) = token.data{
                     chunk.write_instruction(HIRInstruction::Float);
                     chunk.write_pos(token.pos);
-                    chunk.write_float(f);
+                    chunk.write_double(f);
                 }
             }
             TokenType::Number => {
                 if let TokenData::Integer(i) = token.data{
                     chunk.write_instruction(HIRInstruction::Integer);
                     chunk.write_pos(token.pos);
-                    chunk.write_int(i);
+                    chunk.write_long(i);
+                }
+            }
+            TokenType::Char => {
+                if let TokenData::Char(c) = token.data{
+                    chunk.write_instruction(HIRInstruction::Char);
+                    chunk.write_pos(token.pos);
+                    chunk.write_char(c);
                 }
             }
             TokenType::String => {
@@ -49,7 +56,7 @@ impl OwnedParse for LiteralParser{
                     chunk.write_str(s);
                 }
             }
-            TokenType::KwNone => {
+            TokenType::KwNone | TokenType::KwNull => {
                 chunk.write_instruction(HIRInstruction::None);
                 chunk.write_pos(token.pos);
             }
@@ -0,0 +1,225 @@
+use crate::{
+    Parser,
+    OwnedParse,
+    expressions::{
+        ExpressionParser,
+        parse_body,
+    },
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use lexer::tokens::{
+    TokenType,
+    TokenData,
+};
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSource,
+    DiagnosticSourceBuilder,
+};
+
+///Parses `loop { ... }`, which loops until it is broken out of.
+pub struct LoopParser;
+
+impl OwnedParse for LoopParser{
+    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let pos = parser.current_token().pos;
+        parser.advance()?;
+        let body = parse_body(parser, pos)?;
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Loop);
+        chunk.write_pos(pos);
+        chunk.write_bool(false);
+        chunk.write_bool(false);
+        chunk.write_chunk(body);
+        Ok(chunk)
+    }
+}
+
+///Parses `while(cond) { ... }`, which loops for as long as `cond` is true.
+pub struct WhileParser;
+
+impl OwnedParse for WhileParser{
+    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let pos = parser.current_token().pos;
+        parser.consume(TokenType::LParen)?;
+        parser.advance()?;
+        let cond = ExpressionParser::owned_parse(parser)?;
+        parser.consume(TokenType::RParen)?;
+        parser.advance()?;
+        let body = parse_body(parser, pos)?;
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Loop);
+        chunk.write_pos(pos);
+        chunk.write_bool(true);
+        chunk.write_chunk(cond);
+        chunk.write_bool(false);
+        chunk.write_chunk(body);
+        Ok(chunk)
+    }
+}
+
+///Parses `for(x in iterable) { ... }`, which binds `x` to each element of `iterable` in turn.
+pub struct ForParser;
+
+impl OwnedParse for ForParser{
+    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let pos = parser.current_token().pos;
+        parser.consume(TokenType::LParen)?;
+        let name = match parser.consume(TokenType::Identifier)?{
+            TokenData::String(name) => name.clone(),
+            data => {
+                let message = format!("Expected to find identifier data but instead found: {:?}", data);
+                return Err(DiagnosticSourceBuilder::new(parser.name.clone(), parser.current_token().pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(message)
+                    .build())
+            }
+        };
+        let name_pos = parser.current_token().pos;
+        parser.consume(TokenType::KwIn)?;
+        parser.advance()?;
+        let iterable = ExpressionParser::owned_parse(parser)?;
+        parser.consume(TokenType::RParen)?;
+        parser.advance()?;
+        let body = parse_body(parser, pos)?;
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Loop);
+        chunk.write_pos(pos);
+        chunk.write_bool(false);
+        chunk.write_bool(true);
+        chunk.write_pos(name_pos);
+        chunk.write_string(name);
+        chunk.write_chunk(iterable);
+        chunk.write_chunk(body);
+        Ok(chunk)
+    }
+}
+
+///Parses `until(n) { ... }`, which runs its body `n` times with the current iteration bound to `it`.
+///This is sugar for `for(it in 0..n) { ... }`.
+pub struct UntilParser;
+
+impl OwnedParse for UntilParser{
+    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let pos = parser.current_token().pos;
+        parser.consume(TokenType::LParen)?;
+        parser.advance()?;
+        let count = ExpressionParser::owned_parse(parser)?;
+        parser.consume(TokenType::RParen)?;
+        parser.advance()?;
+        let body = parse_body(parser, pos)?;
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Loop);
+        chunk.write_pos(pos);
+        chunk.write_bool(false);
+        chunk.write_bool(true);
+        chunk.write_pos(pos);
+        chunk.write_str("it");
+        chunk.write_instruction(HIRInstruction::Range);
+        chunk.write_pos(pos);
+        chunk.write_instruction(HIRInstruction::Integer);
+        chunk.write_pos(pos);
+        chunk.write_long(0);
+        chunk.write_chunk(count);
+        chunk.write_chunk(body);
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ir_traits::ReadInstruction;
+
+    fn parse(source: &str) -> Chunk{
+        let (mut parser, _, _) = Parser::of_source(source);
+        ExpressionParser::owned_parse(&mut parser).unwrap()
+    }
+
+    ///Read the header of a loop, which is whether it has a condition and whether it binds each element of an iterable.
+    fn header(chunk: &Chunk) -> (bool, bool){
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Loop));
+        chunk.read_pos().unwrap();
+        let has_cond = chunk.read_bool();
+        if has_cond{
+            assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Reference));
+            chunk.read_pos().unwrap();
+            chunk.read_string();
+        }
+        (has_cond, chunk.read_bool())
+    }
+
+    #[test]
+    fn loops_have_neither_a_condition_nor_a_binding() {
+        let chunk = parse("loop{ break }");
+        assert_eq!(header(&chunk), (false, false));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Block));
+    }
+
+    #[test]
+    fn while_loops_have_a_condition() {
+        let chunk = parse("while(running){ }");
+        assert_eq!(header(&chunk), (true, false));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Block));
+    }
+
+    #[test]
+    fn for_loops_bind_each_element() {
+        let chunk = parse("for(x in xs){ }");
+        assert_eq!(header(&chunk), (false, true));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "x");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Reference));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "xs");
+    }
+
+    #[test]
+    fn until_binds_it_to_a_range_from_zero() {
+        let chunk = parse("until(n){ }");
+        assert_eq!(header(&chunk), (false, true));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "it");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Range));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Integer));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_long(), 0);
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Reference));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "n");
+    }
+
+    #[test]
+    fn until_is_an_identifier_without_parens() {
+        let chunk = parse("until");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Reference));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "until");
+    }
+
+    #[test]
+    fn unless_negates_its_condition() {
+        let chunk = parse("unless(done){ }");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::If));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Not));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Reference));
+    }
+
+    #[test]
+    fn for_loops_without_in_are_rejected() {
+        let (mut parser, _, _) = Parser::of_source("for(x xs){ }");
+        let diag = ExpressionParser::owned_parse(&mut parser).unwrap_err();
+        assert!(diag.message().contains("KwIn"), "{}", diag.message());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{
+    Parser,
+    OwnedParse,
+    expressions::{
+        ExpressionParser,
+        parse_body,
+        literal::LiteralParser,
+        unary::UnaryParser,
+    },
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use lexer::tokens::{
+    TokenType,
+    TokenData,
+};
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSource,
+    DiagnosticSourceBuilder,
+};
+
+///Parses a match expression such as
+///```norust
+///match(shape){
+///    Circle(r) => r * r * 3,
+///    Rect(w, h) => w * h,
+///    Empty => 0
+///}
+///```
+///Each arm is a pattern followed by `=>` and its body, which is either a block or a single local statement. Arms may be separated by commas.
+pub struct MatchParser;
+
+impl OwnedParse for MatchParser{
+    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let pos = parser.current_token().pos;
+        parser.consume(TokenType::LParen)?;
+        parser.advance()?;
+        let scrutinee = ExpressionParser::owned_parse(parser)?;
+        parser.consume(TokenType::RParen)?;
+        parser.consume(TokenType::LCurly)?;
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Match);
+        chunk.write_pos(pos);
+        chunk.write_chunk(scrutinee);
+        parser.advance()?;
+        while !parser.check(TokenType::RCurly){
+            if parser.check(TokenType::Eof){
+                let source = parser.request_source_snippet(pos)?;
+                return Err(DiagnosticSourceBuilder::new(parser.name.clone(), pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message("This match is never closed. Expected '}' but instead reached the end of the file.".to_string())
+                    .range(pos.col_range())
+                    .source(source)
+                    .build())
+            }
+            let pattern = Self::parse_pattern(parser)?;
+            parser.consume(TokenType::FatArrow)?;
+            let arrow_pos = parser.current_token().pos;
+            parser.advance()?;
+            let body = parse_body(parser, arrow_pos)?;
+            chunk.write_chunk(pattern);
+            chunk.write_chunk(body);
+            parser.check_consume_next(TokenType::Comma)?;
+            parser.advance()?;
+        }
+        chunk.write_instruction(HIRInstruction::EndMatch);
+        Ok(chunk)
+    }
+}
+
+impl MatchParser{
+    ///Parse a pattern such as `_`, `x`, `3`, `Rect(w, _)` or `None`. The current token must be the first token of the pattern, and upon success the current token is its last token.
+    fn parse_pattern(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let token = parser.current_token().clone();
+        let mut chunk = Chunk::new();
+        match (&token.type_, &token.data){
+            (TokenType::Underscore, _) => {
+                chunk.write_instruction(HIRInstruction::PatternWildcard);
+                chunk.write_pos(token.pos);
+            }
+            (TokenType::Identifier, TokenData::String(name)) => {
+                if !parser.check_next(TokenType::LParen){
+                    chunk.write_instruction(HIRInstruction::PatternBinding);
+                    chunk.write_pos(token.pos);
+                    chunk.write_string(name.clone());
+                    return Ok(chunk)
+                }
+                chunk.write_instruction(HIRInstruction::PatternVariant);
+                chunk.write_pos(token.pos);
+                chunk.write_string(name.clone());
+                parser.advance()?;
+                if !parser.check_consume_next(TokenType::RParen)?{
+                    loop{
+                        parser.advance()?;
+                        let field = Self::parse_pattern(parser)?;
+                        chunk.write_chunk(field);
+                        if parser.check_consume_next(TokenType::Comma)?{
+                            continue;
+                        }
+                        parser.consume(TokenType::RParen)?;
+                        break;
+                    }
+                }
+                chunk.write_instruction(HIRInstruction::EndParams);
+            }
+            //`None` is a keyword, but it is matched like a variant without fields
+            (TokenType::KwNone, _) => {
+                chunk.write_instruction(HIRInstruction::PatternVariant);
+                chunk.write_pos(token.pos);
+                chunk.write_string("None".to_string());
+                chunk.write_instruction(HIRInstruction::EndParams);
+            }
+            (TokenType::Number, _) | (TokenType::Decimal, _) | (TokenType::Char, _) | (TokenType::String, _) | (TokenType::KwTrue, _) | (TokenType::KwFalse, _) => {
+                chunk.write_instruction(HIRInstruction::PatternLiteral);
+                chunk.write_chunk(LiteralParser::owned_parse(parser)?);
+            }
+            //A negative number such as `-1`
+            (TokenType::Minus, _) if parser.check_next(TokenType::Number) || parser.check_next(TokenType::Decimal) => {
+                chunk.write_instruction(HIRInstruction::PatternLiteral);
+                chunk.write_chunk(UnaryParser::owned_parse(parser)?);
+            }
+            _ => {
+                let source = parser.request_source_snippet(token.pos)?;
+                return Err(DiagnosticSourceBuilder::new(parser.name.clone(), token.pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected a pattern but instead got {:?}", token.type_))
+                    .range(token.pos.col_range())
+                    .source(source)
+                    .build())
+            }
+        }
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ir_traits::ReadInstruction;
+
+    fn pattern(source: &str) -> Chunk{
+        let (mut parser, _, _) = Parser::of_source(source);
+        MatchParser::parse_pattern(&mut parser).unwrap()
+    }
+
+    #[test]
+    fn none_is_a_variant_without_fields() {
+        let chunk = pattern("None");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::PatternVariant));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "None");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::EndParams));
+        let chunk = pattern("Some(v)");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::PatternVariant));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "Some");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::PatternBinding));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "v");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::EndParams));
+    }
+}
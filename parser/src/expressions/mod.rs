@@ -1,8 +1,11 @@
 use super::{
     Parser,
     OwnedParse,
+    block::BlockParser,
 };
 
+use core::pos::BiPos;
+
 use ir::{
     Chunk,
     hir::HIRInstruction,
@@ -23,80 +26,97 @@ use ir_traits::WriteInstruction;
 
 mod binary;
 mod literal;
+mod unary;
+mod loops;
+mod conditional;
+mod lambda;
+mod call;
+mod match_;
 
 pub struct ExpressionParser;
 
 impl OwnedParse for ExpressionParser{
+    ///Parse an expression starting at the current token. Upon success, the current token is the last token of the expression.
     fn owned_parse(parser: &mut Parser) -> Result<Chunk,DiagnosticSource>{
-        let mut chunk = Chunk::new();
-        let next = parser.next_token();
-        match &next.type_{
-            TokenType::Plus => {
-                match binary::AddParser::owned_parse(parser){
-                    Ok(expr) => {
-                        chunk.write_chunk(expr);
-                    }
-                    Err(msg) => {
-                        return Err(msg)
-                    }
-                }
-            }
-            TokenType::Minus => {
-                match binary::SubParser::owned_parse(parser){
-                    Ok(expr) => {
-                        chunk.write_chunk(expr);
-                    }
-                    Err(msg) => {
-                        return Err(msg)
-                    }
-                }
-            }
-            TokenType::Star => {
-                match binary::MulParser::owned_parse(parser){
-                    Ok(expr) => {
-                        chunk.write_chunk(expr);
-                    }
-                    Err(msg) => {
-                        return Err(msg)
-                    }
-                }
+        binary::BinaryParser::parse_precedence(parser, binary::LOWEST_PRECEDENCE)
+    }
+}
+
+///Parses the operands of unary and binary expressions: literals, references and calls, grouped expressions, lambdas and control flow expressions.
+pub struct PrimaryParser;
+
+impl OwnedParse for PrimaryParser{
+    fn owned_parse(parser: &mut Parser) -> Result<Chunk,DiagnosticSource>{
+        let token = parser.current_token().clone();
+        match &token.type_{
+            TokenType::Number | TokenType::Decimal | TokenType::Char | TokenType::String | TokenType::KwTrue | TokenType::KwFalse | TokenType::KwNone | TokenType::KwNull => {
+                let literal = literal::LiteralParser::owned_parse(parser)?;
+                call::CallParser::parse_calls(parser, literal, token.pos)
             }
-            TokenType::Slash => {
-                match binary::DivParser::owned_parse(parser){
-                    Ok(expr) => {
-                        chunk.write_chunk(expr);
-                    }
-                    Err(msg) => {
-                        return Err(msg)
-                    }
-                }
+            TokenType::KwLoop => loops::LoopParser::owned_parse(parser),
+            TokenType::KwWhile => loops::WhileParser::owned_parse(parser),
+            TokenType::KwFor => loops::ForParser::owned_parse(parser),
+            TokenType::KwIf => conditional::IfParser::owned_parse(parser),
+            TokenType::KwMatch => match_::MatchParser::owned_parse(parser),
+            TokenType::LCurly => lambda::LambdaParser::owned_parse(parser),
+            TokenType::LParen => {
+                parser.advance()?;
+                let expr = ExpressionParser::owned_parse(parser)?;
+                parser.consume(TokenType::RParen)?;
+                call::CallParser::parse_calls(parser, expr, token.pos)
             }
-            TokenType::Number | TokenType::Decimal | TokenType::String => match literal::LiteralParser::owned_parse(parser){
-                Ok(literal) => chunk.write_chunk(literal),
-                Err(msg) => {
-                    return Err(msg)
-                }
-            },
             TokenType::Identifier => {
-                let ident = match &next.data{
-                    TokenData::String(ident) => ident,
+                let ident = match &token.data{
+                    TokenData::String(ident) => ident.clone(),
                     _ => {
                         return Err(
-                            DiagnosticSourceBuilder::new(parser.name.clone(), next.pos.start.0)
-                                .message(format!("Expected to find identifier data but instead found: {:?}", next.data))
+                            DiagnosticSourceBuilder::new(parser.name.clone(), token.pos.start.0)
+                                .message(format!("Expected to find identifier data but instead found: {:?}", token.data))
                                 .level(DiagnosticLevel::Error)
                                 .build()
                         )
                     }
                 };
+                //`until` and `unless` are not keywords, they only have special meaning when followed by a parenthesized expression
+                if parser.check_next(TokenType::LParen){
+                    match ident.as_str(){
+                        "until" => return loops::UntilParser::owned_parse(parser),
+                        "unless" => return conditional::UnlessParser::owned_parse(parser),
+                        _ => {}
+                    }
+                }
                 let mut chunk = Chunk::new();
                 chunk.write_instruction(HIRInstruction::Reference);
-                chunk.write_pos(next.pos);
-                chunk.write_string(ident.clone());
+                chunk.write_pos(token.pos);
+                chunk.write_string(ident);
+                call::CallParser::parse_calls(parser, chunk, token.pos)
+            }
+            _ => {
+                let source = parser.request_source_snippet(token.pos)?;
+                Err(DiagnosticSourceBuilder::new(parser.name.clone(), token.pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected an expression but instead got {:?}", token.type_))
+                    .range(token.pos.col_range())
+                    .source(source)
+                    .build())
             }
-            _ => unimplemented!("Working on it! :)")
-            
         }
-        Ok(chunk)
     }
-}
\ No newline at end of file
+}
+
+///Parse the body of a control flow expression that starts at `owner_pos`.
+///Errors inside the body have already been reported by the time this returns, so the returned diagnostic only points at the owning expression.
+fn parse_body(parser: &mut Parser, owner_pos: BiPos) -> Result<Chunk, DiagnosticSource>{
+    match BlockParser::parse_body(parser){
+        Ok(chunk) => Ok(chunk),
+        Err(()) => {
+            let source = parser.request_source_snippet(owner_pos)?;
+            Err(DiagnosticSourceBuilder::new(parser.name.clone(), owner_pos.start.0)
+                .level(DiagnosticLevel::Error)
+                .message(format!("Failed to parse the body of this expression."))
+                .range(owner_pos.col_range())
+                .source(source)
+                .build())
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    Parser,
+    OwnedParse,
+    expressions::PrimaryParser,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use lexer::tokens::{
+    TokenType,
+    TokenData,
+};
+
+use notices::DiagnosticSource;
+
+pub struct UnaryParser;
+
+impl OwnedParse for UnaryParser{
+    fn owned_parse(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let ins = match parser.current_token().type_{
+            TokenType::Bang => HIRInstruction::Not,
+            TokenType::Minus => HIRInstruction::Neg,
+            TokenType::KwRef => HIRInstruction::Ref,
+            TokenType::KwMove => HIRInstruction::Move,
+            TokenType::KwCopy => HIRInstruction::Copy,
+            //`!!x` is lexed as one token, but as a prefix it is just `!(!x)`
+            TokenType::BangBang => {
+                let pos = parser.current_token().pos;
+                parser.advance()?;
+                let operand = Self::owned_parse(parser)?;
+                let mut chunk = Chunk::new();
+                for _ in 0..2{
+                    chunk.write_instruction(HIRInstruction::Not);
+                    chunk.write_pos(pos);
+                }
+                chunk.write_chunk(operand);
+                return Ok(chunk)
+            }
+            _ => return PrimaryParser::owned_parse(parser)
+        };
+        let pos = parser.current_token().pos;
+        parser.advance()?;
+        //A negative number is a literal of its own, so that the smallest value of a signed integer type can be written
+        if let (HIRInstruction::Neg, TokenData::Integer(int)) = (&ins, &parser.current_token().data){
+            let mut literal_pos = pos;
+            literal_pos.end = parser.current_token().pos.end;
+            let mut chunk = Chunk::new();
+            chunk.write_instruction(HIRInstruction::Integer);
+            chunk.write_pos(literal_pos);
+            chunk.write_long(-int);
+            return Ok(chunk)
+        }
+        let operand = Self::owned_parse(parser)?;
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(ins);
+        chunk.write_pos(pos);
+        chunk.write_chunk(operand);
+        Ok(chunk)
+    }
+}
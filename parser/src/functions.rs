@@ -1,9 +1,10 @@
 use super::{
     Parser,
     ParseRule,
-    local_statements::LocalStatementParser,
+    OwnedParse,
+    block::BlockParser,
+    expressions::ExpressionParser,
     type_::TypeParser,
-    ParseContext,
 };
 
 use lexer::tokens::{
@@ -24,6 +25,17 @@ pub struct FunctionParser;
 
 impl ParseRule for FunctionParser{
     fn parse(parser: &mut Parser) -> Result<(),()>{
+        Self::parse_fun(parser, true)
+    }
+}
+
+impl FunctionParser{
+    ///Parse an abstract method such as `abstract fun area(): Int`, which has no body. It is written with an empty body.
+    pub fn parse_abstract(parser: &mut Parser) -> Result<(), ()>{
+        Self::parse_fun(parser, false)
+    }
+
+    fn parse_fun(parser: &mut Parser, has_body: bool) -> Result<(),()>{
         let mut chunk = Chunk::new();
         let lpos = parser.current_token().pos;
         if let Err(_) = parser.check_consume(TokenType::KwFun) {
@@ -54,6 +66,38 @@ impl ParseRule for FunctionParser{
         }
         chunk.write_instruction(HIRInstruction::Fn);
         chunk.write_pos(lpos.clone());
+        //An extension function such as `fun String.shout()` or `fun Point?.describe()`
+        if parser.check(TokenType::Identifier) && (parser.check_next(TokenType::Dot) || parser.check_next(TokenType::QMarkDot)){
+            let receiver = TypeParser::get_current_type(parser).and_then(|receiver| {
+                //The lexer reads the `?.` of `Point?.describe` as a single token
+                if parser.check_next(TokenType::QMarkDot){
+                    let mut pos = parser.current_token().pos;
+                    parser.advance()?;
+                    pos.end = parser.current_token().pos.start;
+                    let mut nullable = Chunk::new();
+                    nullable.write_pos(pos);
+                    nullable.write_instruction(HIRInstruction::Nullable);
+                    nullable.write_chunk(receiver);
+                    parser.advance()?;
+                    return Ok(nullable)
+                }
+                parser.consume(TokenType::Dot)?;
+                parser.advance()?;
+                Ok(receiver)
+            });
+            match receiver{
+                Ok(receiver) => {
+                    chunk.write_instruction(HIRInstruction::Receiver);
+                    chunk.write_chunk(receiver);
+                }
+                Err(source) => {
+                    parser.emit_parse_diagnostic(&[
+                        format!("An extension function is declared with the type it extends before its name, like `fun String.shout()`.")
+                    ], &[source]);
+                    return Err(())
+                }
+            }
+        }
         if !parser.check(TokenType::Identifier) {
             let message = format!(
                 "Expected an identifier token, but instead got {}",
@@ -107,9 +151,30 @@ impl ParseRule for FunctionParser{
         };
         chunk.write_pos(parser.current_token().pos);
         chunk.write_string(name);
-        match parser.advance(){
-            Ok(()) => {},
-            Err(source) => {
+        //A generic function such as `fun id<T>(x: T): T`
+        if parser.check_next(TokenType::LAngle){
+            match TypeParser::get_type_params(parser){
+                Ok(ty_params) => chunk.write_chunk(ty_params),
+                Err(source) => {
+                    parser.emit_parse_diagnostic(&[], &[source]);
+                    return Err(())
+                }
+            }
+        }
+        //Functions without any params may leave out the parens altogether, like `fun main{}`
+        if parser.check_next(TokenType::LParen){
+            match parser.advance(){
+                Ok(()) => {},
+                Err(source) => {
+                    let diag = DiagnosticBuilder::new(DiagnosticLevel::Error)
+                        .add_source(source)
+                        .message(format!("An error occurred while parsing a function."))
+                        .build();
+                    parser.notice_tx.send(Some(diag)).unwrap();
+                    return Err(())
+                }
+            }
+            if let Err(source) = parser.check_consume_next(TokenType::RParen){
                 let diag = DiagnosticBuilder::new(DiagnosticLevel::Error)
                     .add_source(source)
                     .message(format!("An error occurred while parsing a function."))
@@ -117,8 +182,6 @@ impl ParseRule for FunctionParser{
                 parser.notice_tx.send(Some(diag)).unwrap();
                 return Err(())
             }
-        }
-        if parser.check(TokenType::LParen){
             loop{
                 if parser.check(TokenType::RParen){
                     chunk.write_instruction(HIRInstruction::EndParams);
@@ -178,6 +241,8 @@ impl ParseRule for FunctionParser{
                     }
                 }
             }
+        }else{
+            chunk.write_instruction(HIRInstruction::EndParams);
         }
 
         match parser.check_consume_next(TokenType::Colon){
@@ -191,6 +256,11 @@ impl ParseRule for FunctionParser{
                 };
                 chunk.write_chunk(retype_chunk);
             }
+            //The return type of an expression bodied function is inferred from its expression
+            Ok(false) if parser.check_next(TokenType::Equal) => {
+                chunk.write_pos(parser.current_token().pos);
+                chunk.write_instruction(HIRInstruction::Unknown);
+            }
             Ok(false) => {
                 chunk.write_pos(parser.current_token().pos);
                 chunk.write_instruction(HIRInstruction::Unit);
@@ -205,41 +275,150 @@ impl ParseRule for FunctionParser{
             }
         }
 
+        let body_chunk = if !has_body{
+            let pos = parser.current_token().pos;
+            let mut chunk = Chunk::new();
+            chunk.write_instruction(HIRInstruction::Block);
+            chunk.write_pos(pos);
+            chunk.write_instruction(HIRInstruction::EndBlock);
+            chunk.write_pos(pos);
+            chunk
+        }else if parser.check_next(TokenType::Equal){
+            Self::parse_expression_body(parser)?
+        }else{
+            if let Err(source) = parser.consume(TokenType::LCurly){
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(());
+            }
+            BlockParser::parse_block(parser)?
+        };
+        chunk.write_chunk(body_chunk);
+        while has_body && parser.check_next(TokenType::KwWhere){
+            let guard_chunk = Self::parse_guard(parser)?;
+            chunk.write_chunk(guard_chunk);
+        }
+        chunk.write_instruction(HIRInstruction::EndFn);
+
         parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+}
 
+impl FunctionParser{
+    ///Parse the `drop` block of a struct or a class, such as `drop{ print("dropped") }`. The current token must be the `drop`.
+    ///It is written as a method named `drop` without any params, which returns `Unit`.
+    pub fn parse_drop(parser: &mut Parser) -> Result<(), ()>{
+        let pos = parser.current_token().pos;
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Fn);
+        chunk.write_pos(pos);
+        chunk.write_pos(pos);
+        chunk.write_string(format!("drop"));
+        chunk.write_instruction(HIRInstruction::EndParams);
+        chunk.write_pos(pos);
+        chunk.write_instruction(HIRInstruction::Unit);
         if let Err(source) = parser.consume(TokenType::LCurly){
+            parser.emit_parse_diagnostic(&[format!("A drop block has no params, like `drop{{ print(\"dropped\") }}`.")], &[source]);
+            return Err(())
+        }
+        chunk.write_chunk(BlockParser::parse_block(parser)?);
+        chunk.write_instruction(HIRInstruction::EndFn);
+        parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+
+    ///Parse the body of an expression bodied function such as `fun f(a: Int) = a + 1`. The current token must be the one before the `=`.
+    ///The body is returned as a block whose only statement is the expression, which is then returned implicitly.
+    fn parse_expression_body(parser: &mut Parser) -> Result<Chunk, ()>{
+        if let Err(source) = parser.advance(){
             parser.emit_parse_diagnostic(&[], &[source]);
-            return Err(());
+            return Err(())
         }
+        let pos = parser.current_token().pos;
+        if let Err(source) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[source]);
+            return Err(())
+        }
+        let expr = match ExpressionParser::owned_parse(parser){
+            Ok(expr) => expr,
+            Err(source) => {
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            }
+        };
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Block);
+        chunk.write_pos(pos);
+        chunk.write_instruction(HIRInstruction::ExprStmt);
+        chunk.write_chunk(expr);
+        chunk.write_instruction(HIRInstruction::EndBlock);
+        chunk.write_pos(parser.current_token().pos);
+        Ok(chunk)
+    }
 
-        parser.context = ParseContext::Local;
-        
-        let mut body_chunk = Chunk::new();
-        body_chunk.write_instruction(HIRInstruction::Block);
-        body_chunk.write_pos(parser.current_token().pos);
-        parser.emit_ir_whole(body_chunk);
-        while let Ok(false) = parser.check_consume(TokenType::RCurly){
-            match parser.advance(){
-                Ok(()) => {},
+    ///Parse a guard such as `where a = 'a' else "Expected 'a'"`. The current token must be the one before the `where`.
+    ///Within the condition, a top level `=` compares for equality.
+    fn parse_guard(parser: &mut Parser) -> Result<Chunk, ()>{
+        if let Err(source) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[source]);
+            return Err(())
+        }
+        let pos = parser.current_token().pos;
+        if let Err(source) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[source]);
+            return Err(())
+        }
+        let mut cond = match ExpressionParser::owned_parse(parser){
+            Ok(expr) => expr,
+            Err(source) => {
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            }
+        };
+        if parser.check_next(TokenType::Equal){
+            if let Err(source) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            }
+            let eq_pos = parser.current_token().pos;
+            if let Err(source) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            }
+            let right = match ExpressionParser::owned_parse(parser){
+                Ok(expr) => expr,
                 Err(source) => {
-                    let diag = DiagnosticBuilder::new(DiagnosticLevel::Error)
-                        .add_source(source)
-                        .message(format!("An error occurred while parsing a function."))
-                        .build();
-                    parser.notice_tx.send(Some(diag)).unwrap();
+                    parser.emit_parse_diagnostic(&[], &[source]);
                     return Err(())
                 }
-            }
-            if let Err(()) = LocalStatementParser::parse(parser){
+            };
+            let mut eq = Chunk::new();
+            eq.write_instruction(HIRInstruction::Eq);
+            eq.write_pos(eq_pos);
+            eq.write_chunk(cond);
+            eq.write_chunk(right);
+            cond = eq;
+        }
+        if let Err(source) = parser.consume(TokenType::KwElse){
+            parser.emit_parse_diagnostic(&[format!("A where guard must say what went wrong with an else, like `where a > 0 else \"a must be positive\"`")], &[source]);
+            return Err(())
+        }
+        if let Err(source) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[source]);
+            return Err(())
+        }
+        let message = match ExpressionParser::owned_parse(parser){
+            Ok(expr) => expr,
+            Err(source) => {
+                parser.emit_parse_diagnostic(&[], &[source]);
                 return Err(())
             }
-        }
-        let mut end_chunk = Chunk::new();
-        end_chunk.write_instruction(HIRInstruction::EndBlock);
-        end_chunk.write_pos(parser.prev_token().pos);
-        end_chunk.write_instruction(HIRInstruction::EndFn);
-
-        parser.emit_ir_whole(end_chunk);
-        Ok(())
+        };
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Guard);
+        chunk.write_pos(pos);
+        chunk.write_chunk(cond);
+        chunk.write_chunk(message);
+        Ok(chunk)
     }
-}
\ No newline at end of file
+}
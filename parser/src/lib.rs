@@ -5,6 +5,8 @@ mod functions;
 mod expressions;
 mod type_;
 mod local_statements;
+mod block;
+mod structs;
 
 use lexer::tokens::{LexerToken, TokenData, TokenType};
 
@@ -16,6 +18,8 @@ use ir::{
 
 use std::sync::{Arc, Mutex};
 
+use std::collections::VecDeque;
+
 use notices::{
     DiagnosticLevel, DiagnosticBuilder, Diagnostic, DiagnosticSource, DiagnosticSourceBuilder
 };
@@ -72,6 +76,10 @@ pub struct Parser {
     pub context: ParseContext,
 
     active_tokens: [LexerToken; 3],
+    ///The tokens after the next token that have been peeked at, in order, see [Parser::peek].
+    lookahead: VecDeque<LexerToken>,
+    ///The blocks currently being parsed, innermost last. While this is not empty, emitted IR is written into the innermost block instead of being sent out.
+    blocks: Vec<Chunk>,
     master_tx: Sender<ModuleMessage>,
     master_rx: Arc<Mutex<Receiver<ModuleMessage>>>,
 }
@@ -96,6 +104,8 @@ impl Parser {
                 LexerToken::default(),
                 LexerToken::default(),
             ],
+            lookahead: VecDeque::new(),
+            blocks: vec![],
             master_tx,
             master_rx
         }
@@ -152,22 +162,45 @@ impl Parser {
 
     #[inline]
     pub fn advance(&mut self) -> Result<(), DiagnosticSource> {
+        let next = match self.lookahead.pop_front(){
+            Some(token) => token,
+            None => self.receive()?
+        };
         self.active_tokens[PREV_TOKEN] = self.active_tokens[CURRENT_TOKEN].clone();
         self.active_tokens[CURRENT_TOKEN] = self.active_tokens[NEXT_TOKEN].clone();
-        self.active_tokens[NEXT_TOKEN] = match self
+        self.active_tokens[NEXT_TOKEN] = next;
+        Ok(())
+    }
+
+    ///The token `distance` tokens after the next token, without advancing. A distance of 0 is the next token itself.
+    pub fn peek(&mut self, distance: usize) -> Result<&LexerToken, DiagnosticSource> {
+        while self.lookahead.len() < distance{
+            let token = self.receive()?;
+            self.lookahead.push_back(token);
+        }
+        match distance{
+            0 => Ok(self.next_token()),
+            _ => Ok(&self.lookahead[distance - 1])
+        }
+    }
+
+    ///Receive the token after the last one received.
+    fn receive(&self) -> Result<LexerToken, DiagnosticSource> {
+        let last = self.lookahead.back().unwrap_or(&self.active_tokens[NEXT_TOKEN]);
+        match self
             .token_rx
             .recv_timeout(std::time::Duration::from_secs(1))
         {
+            //The lexer hangs up after sending Eof, so just keep reporting Eof from here on out.
+            Err(_) if last.type_ == TokenType::Eof => Ok(last.clone()),
             Err(_) =>{ 
                 let diag_source = DiagnosticSourceBuilder::new(self.name.clone(), self.current_token().pos.start.0)
                     .message(format!("Failed to receive token from tokenizer: token channel closed prematurely.\nReport this to the author:\n\tAlex Couch: alcouch65@gmail.com\n\tGithub Issues: https://github.com/beaglelang/beagle-lang/issues\n\tTuring Tarpit: https://discord.gg/RmgjcES"))
                     .build();
-                return Err(diag_source)
+                Err(diag_source)
             },
-            Ok(t) => t,
-        };
-
-        Ok(())
+            Ok(t) => Ok(t),
+        }
     }
 
     #[inline]
@@ -182,6 +215,10 @@ impl Parser {
 
     #[inline]
     pub fn emit_ir_whole(&mut self, hir: Chunk){
+        if let Some(block) = self.blocks.last_mut(){
+            block.write_chunk(hir);
+            return
+        }
         match self.ir_tx
             .lock()
             .expect("Failed to acquire lock on ir_tx sender.")
@@ -277,6 +314,11 @@ impl Parser {
                 }
                 return Err(())
             }
+            //Statements end on their last token, so move on to the next statement
+            if let Err(diag) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[diag]);
+                return Err(())
+            };
         }
         if parser.ir_tx.lock().unwrap().send(None).is_err(){
             return Ok(())
@@ -285,3 +327,28 @@ impl Parser {
     }
 }
 
+
+#[cfg(test)]
+impl Parser{
+    ///A parser of `source` as the module `test`, whose current token is the first token of `source`.
+    ///It comes with the receivers of the HIR and of the diagnostics it emits.
+    pub(crate) fn of_source(source: &str) -> (Parser, Receiver<Option<Chunk>>, Receiver<Option<Diagnostic>>){
+        use std::sync::mpsc::channel;
+        let (token_tx, token_rx) = channel();
+        let (hir_tx, hir_rx) = channel();
+        let (diagnostics_tx, diagnostics_rx) = channel();
+        let (request_tx, request_rx) = channel();
+        let (response_tx, response_rx) = channel();
+        let response_rx = Arc::new(Mutex::new(response_rx));
+        module_messages::serve_source(source.to_owned(), request_rx, response_tx);
+        let mut lexer = lexer::Lexer::new(format!("test"), source.to_owned(), token_tx, request_tx.clone(), response_rx.clone());
+        if let Err(diag) = lexer.start_tokenizing(){
+            diagnostics_tx.send(Some(diag)).unwrap();
+        }
+        drop(lexer);
+        let mut parser = Parser::new(format!("test"), hir_tx, token_rx, diagnostics_tx, request_tx, response_rx);
+        parser.advance().unwrap();
+        parser.advance().unwrap();
+        (parser, hir_rx, diagnostics_rx)
+    }
+}
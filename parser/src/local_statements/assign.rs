@@ -0,0 +1,140 @@
+use crate::{
+    Parser,
+    ParseRule,
+    OwnedParse,
+    expressions::ExpressionParser,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use lexer::tokens::{
+    TokenType,
+    TokenData,
+};
+
+use notices::{
+    DiagnosticSourceBuilder,
+    DiagnosticLevel,
+};
+
+///Parses `a = b`, the compound assignments `a += b`, `a -= b`, `a *= b`, `a /= b`, and the increments `a++` and `a--`.
+///Compound assignments and increments are written as a plain assignment, so `a += b` becomes `a = a + b` and `a++` becomes `a = a + 1`.
+///Assignments to fields, such as `a.b = c`, are parsed the same way once their target has been parsed as an expression.
+pub struct AssignParser;
+
+impl AssignParser{
+    ///Whether `type_` is an assignment operator that may follow the target of an assignment.
+    pub fn is_assign_op(type_: TokenType) -> bool{
+        matches!(type_, TokenType::Equal
+            | TokenType::PlusEqual
+            | TokenType::MinusEqual
+            | TokenType::StarEqual
+            | TokenType::SlashEqual
+            | TokenType::PlusPlus
+            | TokenType::MinusMinus)
+    }
+}
+
+impl ParseRule for AssignParser{
+    fn parse(parser: &mut Parser) -> Result<(),()>{
+        let target = parser.current_token().clone();
+        let ident = match &target.data{
+            TokenData::String(ident) => ident.clone(),
+            _ => {
+                let diag_source = DiagnosticSourceBuilder::new(parser.name.clone(), target.pos.start.0)
+                    .message(format!("Expected to find identifier data but instead found: {:?}", target.data))
+                    .level(DiagnosticLevel::Error)
+                    .build();
+                parser.emit_parse_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        if let Err(diag) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[diag]);
+            return Err(())
+        }
+        let op = parser.current_token().clone();
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Assign);
+        chunk.write_pos(op.pos);
+        chunk.write_pos(target.pos);
+        chunk.write_string(ident.clone());
+        let mut reference = Chunk::new();
+        reference.write_instruction(HIRInstruction::Reference);
+        reference.write_pos(target.pos);
+        reference.write_string(ident);
+        let value = Self::parse_value(parser, reference)?;
+        chunk.write_chunk(value);
+        parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+}
+
+impl AssignParser{
+    ///Parse an assignment to a field such as `this.count += 1`, whose target has already been parsed as a member access.
+    ///The current token must be the last token of the target.
+    pub fn parse_member(parser: &mut Parser, target: Chunk) -> Result<(), ()>{
+        if let Err(diag) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[diag]);
+            return Err(())
+        }
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::AssignMember);
+        chunk.write_pos(parser.current_token().pos);
+        chunk.write_chunk(target.clone());
+        let value = Self::parse_value(parser, target)?;
+        chunk.write_chunk(value);
+        parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+
+    ///Parse the value assigned by the assignment operator at the current token. For a compound assignment or an increment, this is the operation applied to `target`.
+    fn parse_value(parser: &mut Parser, target: Chunk) -> Result<Chunk, ()>{
+        let op = parser.current_token().clone();
+        let binary = match op.type_{
+            TokenType::PlusEqual | TokenType::PlusPlus => Some(HIRInstruction::Add),
+            TokenType::MinusEqual | TokenType::MinusMinus => Some(HIRInstruction::Sub),
+            TokenType::StarEqual => Some(HIRInstruction::Mult),
+            TokenType::SlashEqual => Some(HIRInstruction::Div),
+            _ => None
+        };
+        let value = match op.type_{
+            TokenType::PlusPlus | TokenType::MinusMinus => {
+                let mut one = Chunk::new();
+                one.write_instruction(HIRInstruction::Integer);
+                one.write_pos(op.pos);
+                one.write_long(1);
+                one
+            }
+            _ => {
+                if let Err(diag) = parser.advance(){
+                    parser.emit_parse_diagnostic(&[], &[diag]);
+                    return Err(())
+                }
+                match ExpressionParser::owned_parse(parser){
+                    Ok(value) => value,
+                    Err(diag) => {
+                        parser.emit_parse_diagnostic(&[], &[diag]);
+                        return Err(())
+                    }
+                }
+            }
+        };
+        match binary{
+            Some(ins) => {
+                let mut chunk = Chunk::new();
+                chunk.write_instruction(ins);
+                chunk.write_pos(op.pos);
+                chunk.write_chunk(target);
+                chunk.write_chunk(value);
+                Ok(chunk)
+            }
+            None => Ok(value)
+        }
+    }
+}
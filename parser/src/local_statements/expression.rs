@@ -0,0 +1,46 @@
+use crate::{
+    Parser,
+    ParseRule,
+    OwnedParse,
+    expressions::ExpressionParser,
+};
+
+use super::assign::AssignParser;
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+///A statement consisting of a single expression, such as a loop or a trailing value in a block.
+pub struct ExpressionStatementParser;
+
+impl ParseRule for ExpressionStatementParser{
+    fn parse(parser: &mut Parser) -> Result<(),()>{
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::ExprStmt);
+        let expr = match ExpressionParser::owned_parse(parser){
+            Ok(expr) => expr,
+            Err(diag) => {
+                parser.emit_parse_diagnostic(&[], &[diag]);
+                return Err(())
+            }
+        };
+        //An assignment to a field, such as `this.count += 1`, starts out looking like a member access
+        if AssignParser::is_assign_op(parser.next_token().type_){
+            let first: Option<HIRInstruction> = expr.read_instruction();
+            expr.jump_to(0).unwrap();
+            if let Some(HIRInstruction::Member) = first{
+                return AssignParser::parse_member(parser, expr)
+            }
+        }
+        chunk.write_chunk(expr);
+        parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+}
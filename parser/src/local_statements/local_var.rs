@@ -74,10 +74,10 @@ impl ParseRule for LocalVarParser{
         chunk.write_instruction(HIRInstruction::LocalVar);
         let pos = parser.current_token().pos;
         chunk.write_pos(pos);
-        match parser.check_consume_next(TokenType::KwMut){
+        match parser.check_consume(TokenType::KwMut){
             Ok(true) => {
                 chunk.write_bool(true);
-                chunk.write_pos(parser.current_token().pos);
+                chunk.write_pos(parser.prev_token().pos);
             }
             Ok(false) => {
                 chunk.write_bool(false);
@@ -134,6 +134,10 @@ impl ParseRule for LocalVarParser{
         chunk.write_pos(parser.current_token().pos);
         chunk.write_string(name.clone());
         if parser.next_token().type_ == TokenType::Colon {
+            if let Err(source) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            };
             match TypeParser::get_type(parser){
                 Ok(t) =>chunk.write_chunk(t),
                 Err(diag) => {
@@ -157,6 +161,10 @@ impl ParseRule for LocalVarParser{
                     return Err(());
                 }
             }
+            if let Err(source) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            };
         } else {
             if let Err(source) = parser.advance(){
                 parser.emit_parse_diagnostic(&[], &[source]);
@@ -165,7 +173,6 @@ impl ParseRule for LocalVarParser{
             chunk.write_pos(parser.current_token().pos);
             chunk.write_instruction(HIRInstruction::Unknown);
         }
-        parser.emit_ir_whole(chunk);
 
         if let Ok(false) = parser.check_consume(TokenType::Equal) {
             let found_token = parser.current_token();
@@ -204,12 +211,13 @@ impl ParseRule for LocalVarParser{
         }
 
         match ExpressionParser::owned_parse(parser) {
-            Ok(expr) => parser.emit_ir_whole(expr),
+            Ok(expr) => chunk.write_chunk(expr),
             Err(cause) => {
                 parser.emit_parse_diagnostic(&[], &[cause]);
                 return Err(())
             }
         }
+        parser.emit_ir_whole(chunk);
         Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,57 @@
+use crate::{
+    Parser,
+    ParseRule,
+    OwnedParse,
+    expressions::ExpressionParser,
+};
+
+use super::value_follows;
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+///Parses `break`, optionally followed by a value on the same line.
+pub struct BreakParser;
+
+impl ParseRule for BreakParser{
+    fn parse(parser: &mut Parser) -> Result<(),()>{
+        let mut chunk = Chunk::new();
+        let pos = parser.current_token().pos;
+        chunk.write_instruction(HIRInstruction::Break);
+        chunk.write_pos(pos);
+        let has_value = value_follows(parser);
+        chunk.write_bool(has_value);
+        if has_value{
+            if let Err(diag) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[diag]);
+                return Err(())
+            }
+            match ExpressionParser::owned_parse(parser){
+                Ok(value) => chunk.write_chunk(value),
+                Err(diag) => {
+                    parser.emit_parse_diagnostic(&[], &[diag]);
+                    return Err(())
+                }
+            }
+        }
+        parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+}
+
+///Parses `continue`.
+pub struct ContinueParser;
+
+impl ParseRule for ContinueParser{
+    fn parse(parser: &mut Parser) -> Result<(),()>{
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Continue);
+        chunk.write_pos(parser.current_token().pos);
+        parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+}
@@ -11,6 +11,21 @@ use lexer::tokens::{
 mod local_var;
 use local_var::LocalVarParser;
 
+mod loop_control;
+use loop_control::{
+    BreakParser,
+    ContinueParser,
+};
+
+mod expression;
+use expression::ExpressionStatementParser;
+
+mod return_;
+use return_::ReturnParser;
+
+mod assign;
+use assign::AssignParser;
+
 pub struct LocalStatementParser;
 
 impl ParseRule for LocalStatementParser{
@@ -18,15 +33,22 @@ impl ParseRule for LocalStatementParser{
         match parser.current_token().type_{
             TokenType::RCurly => return Ok(()),
             TokenType::KwLet => LocalVarParser::parse(parser)?,
-            TokenType::Identifier => {
-                match parser.next_token().type_{
-                    TokenType::Equal => unimplemented!(),
-                    _ => unimplemented!()
-                };
-            },
-            _ => StatementParser::parse(parser)?
+            TokenType::KwBreak => BreakParser::parse(parser)?,
+            TokenType::KwContinue => ContinueParser::parse(parser)?,
+            TokenType::KwReturn => ReturnParser::parse(parser)?,
+            TokenType::KwVal | TokenType::KwVar | TokenType::KwFun | TokenType::KwMod | TokenType::KwStruct | TokenType::KwClass | TokenType::KwOpen | TokenType::KwAbstract | TokenType::KwInterface | TokenType::KwTrait | TokenType::KwEnum => StatementParser::parse(parser)?,
+            TokenType::Identifier if AssignParser::is_assign_op(parser.next_token().type_) => AssignParser::parse(parser)?,
+            _ => ExpressionStatementParser::parse(parser)?
         };
-        // p.advance().unwrap();
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+///Whether the keyword at the current token, such as `break` or `return`, is followed by a value. The value must start on the same line as the keyword.
+fn value_follows(parser: &Parser) -> bool{
+    let next = parser.next_token();
+    next.pos.start.0 == parser.current_token().pos.start.0 && match next.type_{
+        TokenType::RCurly | TokenType::Semicolon | TokenType::Comma | TokenType::Eof => false,
+        _ => true
+    }
+}
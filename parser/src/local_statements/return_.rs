@@ -0,0 +1,43 @@
+use crate::{
+    Parser,
+    ParseRule,
+    OwnedParse,
+    expressions::ExpressionParser,
+};
+
+use super::value_follows;
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+///Parses `return`, optionally followed by a value on the same line.
+pub struct ReturnParser;
+
+impl ParseRule for ReturnParser{
+    fn parse(parser: &mut Parser) -> Result<(),()>{
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Return);
+        chunk.write_pos(parser.current_token().pos);
+        let has_value = value_follows(parser);
+        chunk.write_bool(has_value);
+        if has_value{
+            if let Err(diag) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[diag]);
+                return Err(())
+            }
+            match ExpressionParser::owned_parse(parser){
+                Ok(value) => chunk.write_chunk(value),
+                Err(diag) => {
+                    parser.emit_parse_diagnostic(&[], &[diag]);
+                    return Err(())
+                }
+            }
+        }
+        parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+}
@@ -45,10 +45,22 @@ impl ParseRule for ModuleParser{
             return Err(())
         };
         parser.emit_ir_whole(chunk);
+        if let Err(diag_source) = parser.consume(TokenType::LCurly){
+            parser.emit_parse_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        if let Err(diag_source) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
         while !parser.check(TokenType::RCurly) {
             if let Err(()) = StatementParser::parse(parser) {
                 return Err(())
             }
+            if let Err(diag_source) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
         }
         let mut end_chunk = Chunk::new();
         end_chunk.write_instruction(HIRInstruction::EndModule);
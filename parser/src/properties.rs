@@ -142,6 +142,7 @@ impl ParseRule for PropertyParser{
                     parser.emit_parse_diagnostic(&[], &[diag_source]);
                     return Err(());
                 };
+                chunk.write_pos(parser.current_token().pos);
                 chunk.write_instruction(HIRInstruction::Unknown);
             }
             Err(diag) => {
@@ -186,18 +187,16 @@ impl ParseRule for PropertyParser{
             return Err(());
         }
 
-        parser.emit_ir_whole(chunk);
-        
         match ExpressionParser::owned_parse(parser){
             Ok(expr) => {
-                parser.emit_ir_whole(expr);
+                chunk.write_chunk(expr);
             }
             Err(msg) => {
                 parser.emit_parse_diagnostic(&[], &[msg]);
                 return Err(());
             }
         }
-        parser.advance().unwrap();
+        parser.emit_ir_whole(chunk);
         Ok(())
     }
 }
\ No newline at end of file
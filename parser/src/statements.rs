@@ -4,6 +4,12 @@ use crate::{
     properties::PropertyParser,
     functions::FunctionParser,
     modules::ModuleParser,
+    structs::{
+        StructParser,
+        ClassParser,
+        InterfaceParser,
+        EnumParser,
+    },
 };
 
 use lexer::tokens::{
@@ -25,6 +31,10 @@ impl ParseRule for StatementParser{
             TokenType::KwVal => PropertyParser::parse(parser)?,
             TokenType::KwVar => PropertyParser::parse(parser)?,
             TokenType::KwFun => FunctionParser::parse(parser)?,
+            TokenType::KwStruct => StructParser::parse(parser)?,
+            TokenType::KwClass | TokenType::KwOpen | TokenType::KwAbstract => ClassParser::parse(parser)?,
+            TokenType::KwInterface | TokenType::KwTrait => InterfaceParser::parse(parser)?,
+            TokenType::KwEnum => EnumParser::parse(parser)?,
             _ => {
                 let source = match parser.request_source_snippet(token.pos){
                     Ok(source) => source,
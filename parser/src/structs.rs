@@ -0,0 +1,629 @@
+use super::{
+    Parser,
+    ParseRule,
+    properties::PropertyParser,
+    functions::FunctionParser,
+    type_::TypeParser,
+    OwnedParse,
+    expressions::ExpressionParser,
+};
+
+use lexer::tokens::{
+    TokenData,
+    TokenType,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use notices::{
+    DiagnosticSource,
+    DiagnosticSourceBuilder,
+    DiagnosticLevel,
+};
+
+///Parses a struct declaration such as
+///```norust
+///struct Point(val x: Int, var y: Int){
+///    val origin = false
+///    fun sum(): Int = this.x + this.y
+///    drop{ print("dropped") }
+///}
+///```
+///The params of the constructor are the fields that the struct is constructed with. The body may declare more fields, which must be initialized, and methods.
+///Any field may be declared `private`, like `private val elements: [T]`, so that only the methods of the struct can use it.
+///A `drop` block is parsed as a method named `drop`, which is called on an object right before it is dropped.
+pub struct StructParser;
+
+impl ParseRule for StructParser{
+    fn parse(parser: &mut Parser) -> Result<(), ()>{
+        let mut chunk = match Self::parse_header(parser){
+            Ok(chunk) => chunk,
+            Err(source) => {
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            }
+        };
+        if parser.check_next(TokenType::LCurly){
+            let body = Self::parse_body(parser, false)?;
+            chunk.write_chunk(body);
+        }
+        chunk.write_instruction(HIRInstruction::EndStruct);
+        parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+}
+
+impl StructParser{
+    ///Parse everything up to the body of a struct: its name, its type params, its constructor and its traits. The current token must be the `struct` keyword.
+    ///Upon success, the current token is the last token of the header.
+    fn parse_header(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Struct);
+        chunk.write_pos(parser.current_token().pos);
+        let name = Self::consume_name(parser)?;
+        chunk.write_pos(parser.current_token().pos);
+        chunk.write_string(name);
+        //A generic struct such as `struct Box<T>(val value: T)`
+        if parser.check_next(TokenType::LAngle){
+            let ty_params = TypeParser::get_type_params(parser)?;
+            chunk.write_chunk(ty_params);
+        }
+        Self::parse_fields(parser, &mut chunk)?;
+        Self::parse_with(parser, &mut chunk)?;
+        Ok(chunk)
+    }
+
+    ///Parse the `with` clause of a struct or a class, such as `with Comparable, Collection<T>`, if it has one, and write each interface or trait to `chunk` as a `With`.
+    ///Upon success, the current token is the last token of the last type.
+    fn parse_with(parser: &mut Parser, chunk: &mut Chunk) -> Result<(), DiagnosticSource>{
+        if !parser.check_consume_next(TokenType::KwWith)?{
+            return Ok(())
+        }
+        loop{
+            let ty = TypeParser::get_type(parser)?;
+            chunk.write_instruction(HIRInstruction::With);
+            chunk.write_chunk(ty);
+            if !parser.check_consume_next(TokenType::Comma)?{
+                return Ok(())
+            }
+        }
+    }
+
+    ///Parse the constructor of a struct or a class, if it has one, and write its fields to `chunk` followed by `EndParams`. The current token must be the name.
+    fn parse_fields(parser: &mut Parser, chunk: &mut Chunk) -> Result<(), DiagnosticSource>{
+        if parser.check_consume_next(TokenType::LParen)? && !parser.check_consume_next(TokenType::RParen)?{
+            loop{
+                parser.advance()?;
+                let field = Self::parse_field(parser)?;
+                chunk.write_chunk(field);
+                if parser.check_consume_next(TokenType::Comma)?{
+                    continue;
+                }
+                parser.consume(TokenType::RParen)?;
+                break;
+            }
+        }
+        chunk.write_instruction(HIRInstruction::EndParams);
+        Ok(())
+    }
+
+    ///Parse a constructor param such as `val x: Int` or `private val x: Int`. The current token must be the `private`, `val` or `var` keyword, and upon success the current token is the last token of the type.
+    fn parse_field(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let mut chunk = Chunk::new();
+        if parser.check(TokenType::KwPrivate){
+            chunk.write_instruction(HIRInstruction::Private);
+            chunk.write_pos(parser.current_token().pos);
+            parser.advance()?;
+        }
+        let token = parser.current_token().clone();
+        let mutable = match token.type_{
+            TokenType::KwVal => false,
+            TokenType::KwVar => true,
+            _ => {
+                let source = parser.request_source_snippet(token.pos)?;
+                return Err(DiagnosticSourceBuilder::new(parser.name.clone(), token.pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected a field declared with val or var but instead got {:?}", token.type_))
+                    .range(token.pos.col_range())
+                    .source(source)
+                    .build())
+            }
+        };
+        chunk.write_instruction(HIRInstruction::Field);
+        chunk.write_pos(token.pos);
+        chunk.write_bool(mutable);
+        chunk.write_pos(token.pos);
+        let name = Self::consume_name(parser)?;
+        chunk.write_pos(parser.current_token().pos);
+        chunk.write_string(name);
+        parser.consume(TokenType::Colon)?;
+        let ty = TypeParser::get_type(parser)?;
+        chunk.write_chunk(ty);
+        Ok(chunk)
+    }
+
+    ///Parse the body of a struct or a class, which may only declare fields and methods. The current token must be the one before the `{`, and upon success the current token is the closing `}`.
+    ///The chunks of the fields and methods are collected like the statements of a block are.
+    fn parse_body(parser: &mut Parser, is_class: bool) -> Result<Chunk, ()>{
+        if let Err(source) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[source]);
+            return Err(())
+        }
+        let pos = parser.current_token().pos;
+        parser.blocks.push(Chunk::new());
+        let result = Self::parse_members(parser, pos, is_class);
+        let chunk = parser.blocks.pop().expect("The block stack should never be empty while collecting the body of a struct.");
+        result?;
+        Ok(chunk)
+    }
+
+    fn parse_members(parser: &mut Parser, pos: core::pos::BiPos, is_class: bool) -> Result<(), ()>{
+        let kind = if is_class{ "class" }else{ "struct" };
+        if let Err(source) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[source]);
+            return Err(())
+        }
+        while !parser.check(TokenType::RCurly){
+            let token = parser.current_token().clone();
+            match token.type_{
+                TokenType::KwVal | TokenType::KwVar => PropertyParser::parse(parser)?,
+                TokenType::KwFun => FunctionParser::parse(parser)?,
+                TokenType::KwOpen | TokenType::KwAbstract | TokenType::KwOverride if is_class => Self::parse_method(parser)?,
+                TokenType::KwImpl => Self::parse_method(parser)?,
+                TokenType::KwPrivate => Self::parse_private(parser)?,
+                TokenType::KwDrop => FunctionParser::parse_drop(parser)?,
+                _ => {
+                    let (message, at) = match token.type_{
+                        TokenType::Eof => (format!("This {} body is never closed. Expected '}}' but instead reached the end of the file.", kind), pos),
+                        _ => (format!("Expected a field, a method or a drop block in the body of a {} but instead got {:?}", kind, token.type_), token.pos)
+                    };
+                    let source = match parser.request_source_snippet(at){
+                        Ok(source) => source,
+                        Err(diag) => {
+                            parser.emit_parse_diagnostic(&[], &[diag]);
+                            return Err(())
+                        }
+                    };
+                    let diag_source = DiagnosticSourceBuilder::new(parser.name.clone(), at.start.0)
+                        .level(DiagnosticLevel::Error)
+                        .message(message)
+                        .range(at.col_range())
+                        .source(source)
+                        .build();
+                    parser.emit_parse_diagnostic(&[], &[diag_source]);
+                    return Err(())
+                }
+            }
+            if let Err(source) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            }
+        }
+        Ok(())
+    }
+
+    ///Parse a method with a modifier, such as `override fun area(): Int = 4` or `impl fun compare(other: Point): Int`. The current token must be the modifier.
+    ///The modifier is collected on its own, right before the method.
+    fn parse_method(parser: &mut Parser) -> Result<(), ()>{
+        let token = parser.current_token().clone();
+        let mut chunk = Chunk::new();
+        match token.type_{
+            TokenType::KwOpen => chunk.write_instruction(HIRInstruction::Open),
+            TokenType::KwAbstract => chunk.write_instruction(HIRInstruction::Abstract),
+            TokenType::KwImpl => chunk.write_instruction(HIRInstruction::Impl),
+            _ => chunk.write_instruction(HIRInstruction::Override),
+        }
+        chunk.write_pos(token.pos);
+        parser.emit_ir_whole(chunk);
+        if let Err(source) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[source]);
+            return Err(())
+        }
+        match token.type_{
+            TokenType::KwAbstract => FunctionParser::parse_abstract(parser),
+            _ => FunctionParser::parse(parser)
+        }
+    }
+
+    ///Parse a private field in the body of a struct, such as `private var count = 0`. The current token must be the `private` keyword.
+    ///Like the modifier of a method, `Private` is collected on its own, right before the field.
+    fn parse_private(parser: &mut Parser) -> Result<(), ()>{
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Private);
+        chunk.write_pos(parser.current_token().pos);
+        parser.emit_ir_whole(chunk);
+        if let Err(source) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[source]);
+            return Err(())
+        }
+        let token = parser.current_token().clone();
+        match token.type_{
+            TokenType::KwVal | TokenType::KwVar => PropertyParser::parse(parser),
+            _ => {
+                let source = match parser.request_source_snippet(token.pos){
+                    Ok(source) => source,
+                    Err(diag) => {
+                        parser.emit_parse_diagnostic(&[], &[diag]);
+                        return Err(())
+                    }
+                };
+                let diag_source = DiagnosticSourceBuilder::new(parser.name.clone(), token.pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected a field declared with val or var after private but instead got {:?}", token.type_))
+                    .range(token.pos.col_range())
+                    .source(source)
+                    .build();
+                parser.emit_parse_diagnostic(&[
+                    "Only fields can be private.".to_string()
+                ], &[diag_source]);
+                Err(())
+            }
+        }
+    }
+
+    fn consume_name(parser: &mut Parser) -> Result<String, DiagnosticSource>{
+        match parser.consume(TokenType::Identifier)?{
+            TokenData::String(name) => Ok(name.clone()),
+            data => {
+                let data = format!("{:?}", data);
+                let source = parser.request_source_snippet(parser.current_token().pos)?;
+                Err(DiagnosticSourceBuilder::new(parser.name.clone(), parser.current_token().pos.start.0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected to find identifier data but instead found: {}", data))
+                    .range(parser.current_token().pos.col_range())
+                    .source(source)
+                    .build())
+            }
+        }
+    }
+}
+
+///Parses a class declaration such as
+///```norust
+///abstract class Shape(val sides: Int){
+///    abstract fun area(): Int
+///    open fun name(): String = "shape"
+///}
+///
+///class Square(val side: Int) : Shape(4){
+///    override fun area(): Int = this.side * this.side
+///}
+///```
+///A class is declared like a struct, except that it may inherit from a single open or abstract class, and that its methods may be `open`, `abstract` or `override`.
+pub struct ClassParser;
+
+impl ParseRule for ClassParser{
+    fn parse(parser: &mut Parser) -> Result<(), ()>{
+        let mut chunk = match Self::parse_header(parser){
+            Ok(chunk) => chunk,
+            Err(source) => {
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            }
+        };
+        if parser.check_next(TokenType::LCurly){
+            let body = StructParser::parse_body(parser, true)?;
+            chunk.write_chunk(body);
+        }
+        chunk.write_instruction(HIRInstruction::EndStruct);
+        parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+}
+
+impl ClassParser{
+    ///Parse everything up to the body of a class: its modifier, its name, its constructor, its base class and its interfaces. The current token must be the `open`, `abstract` or `class` keyword.
+    ///Upon success, the current token is the last token of the header.
+    fn parse_header(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let modifier = parser.current_token().clone();
+        if modifier.type_ != TokenType::KwClass{
+            parser.consume(TokenType::KwClass)?;
+        }
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Class);
+        chunk.write_pos(parser.current_token().pos);
+        let name = StructParser::consume_name(parser)?;
+        chunk.write_pos(parser.current_token().pos);
+        chunk.write_string(name);
+        match modifier.type_{
+            TokenType::KwOpen => chunk.write_instruction(HIRInstruction::Open),
+            TokenType::KwAbstract => chunk.write_instruction(HIRInstruction::Abstract),
+            _ => {}
+        }
+        if modifier.type_ != TokenType::KwClass{
+            chunk.write_pos(modifier.pos);
+        }
+        if parser.check_next(TokenType::LAngle){
+            parser.advance()?;
+            let pos = parser.current_token().pos;
+            let source = parser.request_source_snippet(pos)?;
+            return Err(DiagnosticSourceBuilder::new(parser.name.clone(), pos.start.0)
+                .level(DiagnosticLevel::Error)
+                .message("Classes cannot have type params, only structs can be generic".to_string())
+                .range(pos.col_range())
+                .source(source)
+                .build())
+        }
+        StructParser::parse_fields(parser, &mut chunk)?;
+        if parser.check_consume_next(TokenType::Colon)?{
+            let base = Self::parse_base(parser)?;
+            chunk.write_chunk(base);
+        }
+        StructParser::parse_with(parser, &mut chunk)?;
+        Ok(chunk)
+    }
+
+    ///Parse the base class of a class such as `Shape(4)`, whose constructor args may be left out along with the parens when it has none. The current token must be the `:`.
+    fn parse_base(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let name = StructParser::consume_name(parser)?;
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Base);
+        chunk.write_pos(parser.current_token().pos);
+        chunk.write_string(name);
+        if parser.check_consume_next(TokenType::LParen)? && !parser.check_consume_next(TokenType::RParen)?{
+            loop{
+                parser.advance()?;
+                let arg = ExpressionParser::owned_parse(parser)?;
+                chunk.write_chunk(arg);
+                if parser.check_consume_next(TokenType::Comma)?{
+                    continue;
+                }
+                parser.consume(TokenType::RParen)?;
+                break;
+            }
+        }
+        chunk.write_instruction(HIRInstruction::EndCall);
+        Ok(chunk)
+    }
+}
+
+///Parses an interface or a trait declaration such as
+///```norust
+///interface Named{
+///    fun name(): String
+///}
+///
+///trait Collection<T>{
+///    fun push(elem: T)
+///}
+///```
+///Only classes can implement interfaces, and only structs can implement traits. Their methods have no body, since they only tell what implementing them requires.
+pub struct InterfaceParser;
+
+impl ParseRule for InterfaceParser{
+    fn parse(parser: &mut Parser) -> Result<(), ()>{
+        let mut chunk = match Self::parse_header(parser){
+            Ok(chunk) => chunk,
+            Err(source) => {
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            }
+        };
+        if parser.check_next(TokenType::LCurly){
+            if let Err(source) = parser.advance(){
+                parser.emit_parse_diagnostic(&[], &[source]);
+                return Err(())
+            }
+            let pos = parser.current_token().pos;
+            parser.blocks.push(Chunk::new());
+            let result = Self::parse_methods(parser, pos);
+            let body = parser.blocks.pop().expect("The block stack should never be empty while collecting the body of an interface.");
+            result?;
+            chunk.write_chunk(body);
+        }
+        chunk.write_instruction(HIRInstruction::EndStruct);
+        parser.emit_ir_whole(chunk);
+        Ok(())
+    }
+}
+
+impl InterfaceParser{
+    ///Parse the name and the type params of an interface or a trait. The current token must be the `interface` or `trait` keyword.
+    fn parse_header(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let mut chunk = Chunk::new();
+        match parser.current_token().type_{
+            TokenType::KwTrait => chunk.write_instruction(HIRInstruction::Trait),
+            _ => chunk.write_instruction(HIRInstruction::Interface),
+        }
+        chunk.write_pos(parser.current_token().pos);
+        let name = StructParser::consume_name(parser)?;
+        chunk.write_pos(parser.current_token().pos);
+        chunk.write_string(name);
+        if parser.check_next(TokenType::LAngle){
+            let ty_params = TypeParser::get_type_params(parser)?;
+            chunk.write_chunk(ty_params);
+        }
+        Ok(chunk)
+    }
+
+    ///Parse the methods of an interface or a trait. The current token must be the `{`, and upon success the current token is the closing `}`.
+    fn parse_methods(parser: &mut Parser, pos: core::pos::BiPos) -> Result<(), ()>{
+        if let Err(source) = parser.advance(){
+            parser.emit_parse_diagnostic(&[], &[source]);
+            return Err(())
+        }
+        while !parser.check(TokenType::RCurly){
+            let token = parser.current_token().clone();
+            let (message, at) = match token.type_{
+                TokenType::KwFun => {
+                    FunctionParser::parse_abstract(parser)?;
+                    match parser.next_token().type_{
+                        TokenType::LCurly | TokenType::Equal => ("The methods of an interface or a trait cannot have a body".to_string(), parser.next_token().pos),
+                        _ => {
+                            if let Err(source) = parser.advance(){
+                                parser.emit_parse_diagnostic(&[], &[source]);
+                                return Err(())
+                            }
+                            continue
+                        }
+                    }
+                }
+                TokenType::Eof => ("This body is never closed. Expected '}' but instead reached the end of the file.".to_string(), pos),
+                _ => (format!("Expected a method in the body of an interface or a trait but instead got {:?}", token.type_), token.pos)
+            };
+            let source = match parser.request_source_snippet(at){
+                Ok(source) => source,
+                Err(diag) => {
+                    parser.emit_parse_diagnostic(&[], &[diag]);
+                    return Err(())
+                }
+            };
+            let diag_source = DiagnosticSourceBuilder::new(parser.name.clone(), at.start.0)
+                .level(DiagnosticLevel::Error)
+                .message(message)
+                .range(at.col_range())
+                .source(source)
+                .build();
+            parser.emit_parse_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        Ok(())
+    }
+}
+
+///Parses an enum declaration such as
+///```norust
+///enum Shape{
+///    Circle(Float),
+///    Rect(Int, Int),
+///    Empty
+///}
+///```
+///Each variant may carry fields, which are only given their types. Variants may be separated by commas or newlines.
+pub struct EnumParser;
+
+impl ParseRule for EnumParser{
+    fn parse(parser: &mut Parser) -> Result<(), ()>{
+        match Self::parse_enum(parser){
+            Ok(chunk) => {
+                parser.emit_ir_whole(chunk);
+                Ok(())
+            }
+            Err(source) => {
+                parser.emit_parse_diagnostic(&[], &[source]);
+                Err(())
+            }
+        }
+    }
+}
+
+impl EnumParser{
+    ///Parse a whole enum. The current token must be the `enum` keyword, and upon success the current token is the closing `}`.
+    fn parse_enum(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let pos = parser.current_token().pos;
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Enum);
+        chunk.write_pos(pos);
+        let name = StructParser::consume_name(parser)?;
+        chunk.write_pos(parser.current_token().pos);
+        chunk.write_string(name);
+        if parser.check_next(TokenType::LAngle){
+            parser.advance()?;
+            let pos = parser.current_token().pos;
+            let source = parser.request_source_snippet(pos)?;
+            return Err(DiagnosticSourceBuilder::new(parser.name.clone(), pos.start.0)
+                .level(DiagnosticLevel::Error)
+                .message("Enums cannot have type params, only structs can be generic".to_string())
+                .range(pos.col_range())
+                .source(source)
+                .build())
+        }
+        parser.consume(TokenType::LCurly)?;
+        parser.advance()?;
+        while !parser.check(TokenType::RCurly){
+            let token = parser.current_token().clone();
+            let name = match (&token.type_, &token.data){
+                (TokenType::Identifier, TokenData::String(name)) => name.clone(),
+                _ => {
+                    let (message, at) = match token.type_{
+                        TokenType::Eof => ("This enum body is never closed. Expected '}' but instead reached the end of the file.".to_string(), pos),
+                        _ => (format!("Expected a variant in the body of an enum but instead got {:?}", token.type_), token.pos)
+                    };
+                    let source = parser.request_source_snippet(at)?;
+                    return Err(DiagnosticSourceBuilder::new(parser.name.clone(), at.start.0)
+                        .level(DiagnosticLevel::Error)
+                        .message(message)
+                        .range(at.col_range())
+                        .source(source)
+                        .build())
+                }
+            };
+            chunk.write_instruction(HIRInstruction::Variant);
+            chunk.write_pos(token.pos);
+            chunk.write_string(name);
+            if parser.check_consume_next(TokenType::LParen)? && !parser.check_consume_next(TokenType::RParen)?{
+                loop{
+                    let ty = TypeParser::get_type(parser)?;
+                    chunk.write_instruction(HIRInstruction::FnParam);
+                    chunk.write_chunk(ty);
+                    if parser.check_consume_next(TokenType::Comma)?{
+                        continue;
+                    }
+                    parser.consume(TokenType::RParen)?;
+                    break;
+                }
+            }
+            chunk.write_instruction(HIRInstruction::EndParams);
+            parser.check_consume_next(TokenType::Comma)?;
+            parser.advance()?;
+        }
+        chunk.write_instruction(HIRInstruction::EndStruct);
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ir_traits::ReadInstruction;
+
+    fn parse(source: &str) -> Chunk{
+        let (mut parser, hir_rx, _) = Parser::of_source(source);
+        StructParser::parse(&mut parser).unwrap();
+        hir_rx.try_recv().unwrap().unwrap()
+    }
+
+    #[test]
+    fn private_comes_right_before_the_field() {
+        let chunk = parse("struct Counter(private val start: Int, var step: Int){\n    private var count = 0\n}");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Struct));
+        chunk.read_pos().unwrap();
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "Counter");
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Private));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Field));
+        chunk.read_pos().unwrap();
+        assert!(!chunk.read_bool());
+        chunk.read_pos().unwrap();
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "start");
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Integer));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Field));
+        chunk.read_pos().unwrap();
+        assert!(chunk.read_bool());
+        chunk.read_pos().unwrap();
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_string(), "step");
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Integer));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::EndParams));
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Private));
+        chunk.read_pos().unwrap();
+        assert_eq!(chunk.read_instruction(), Some(HIRInstruction::Property));
+    }
+
+    #[test]
+    fn only_fields_can_be_private() {
+        let (mut parser, _, diagnostics_rx) = Parser::of_source("struct Counter(val start: Int){\n    private fun next(): Int = 1\n}");
+        assert!(StructParser::parse(&mut parser).is_err());
+        let diagnostic = diagnostics_rx.try_recv().unwrap().unwrap();
+        assert_eq!(diagnostic.sources[0].message(), "Expected a field declared with val or var after private but instead got KwFun");
+    }
+}
@@ -19,28 +19,50 @@ use lexer::tokens::{
 pub struct TypeParser;
 
 impl TypeParser{
+    ///Parse the type that starts at the token after the current one. Upon success, the current token is the last token of the type.
     pub fn get_type(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
-        let mut chunk = Chunk::new();
         if let Err(notice) = parser.advance(){
             return Err(notice)
         }
-        let current_token = parser.current_token();
+        Self::get_current_type(parser)
+    }
+
+    ///Parse the type that starts at the current token. Upon success, the current token is the last token of the type.
+    pub fn get_current_type(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let mut chunk = Chunk::new();
+        let current_token = parser.current_token().clone();
+        let mut pos = current_token.pos;
         let ret = match (&current_token.type_, &current_token.data) {
             (TokenType::Identifier, TokenData::String(s)) => {
                 chunk.write_pos(current_token.pos);
-                let str = s.as_str();
-                match str{
-                    "Int" => chunk.write_instruction(HIRInstruction::Integer),
-                    "Float" => chunk.write_instruction(HIRInstruction::Float),
-                    "String" => chunk.write_instruction(HIRInstruction::String),
-                    "Bool" => chunk.write_instruction(HIRInstruction::Bool),
-                    _ => {
+                match (s.as_str(), HIRInstruction::from_string(s.clone())){
+                    ("Unit", _) => chunk.write_instruction(HIRInstruction::Unit),
+                    (_, (ins, None)) => chunk.write_instruction(ins),
+                    //A generic type applied to type arguments, such as `Result<Int, String>`
+                    (_, (_, Some(name))) if parser.check_next(TokenType::LAngle) => {
+                        chunk.write_instruction(HIRInstruction::TyApp);
+                        chunk.write_string(name);
+                        parser.advance()?;
+                        loop{
+                            let arg = Self::get_type(parser)?;
+                            chunk.write_instruction(HIRInstruction::FnParam);
+                            chunk.write_chunk(arg);
+                            if parser.check_consume_next(TokenType::Comma)?{
+                                continue;
+                            }
+                            parser.consume(TokenType::RAngle)?;
+                            break;
+                        }
+                        chunk.write_instruction(HIRInstruction::EndParams);
+                    }
+                    (_, (_, Some(name))) => {
                         chunk.write_instruction(HIRInstruction::Custom);
-                        chunk.write_string(s.clone());
+                        chunk.write_string(name);
                     }
                 }
                 chunk
             }
+            (TokenType::LParen, _) => Self::get_function_type(parser)?,
             _ => {
                 let source = match parser.request_source_snippet(current_token.pos){
                     Ok(source) => source,
@@ -56,7 +78,83 @@ impl TypeParser{
                 return Err(diag_source)
             },
         };
-        
+        //A nullable type such as `Int?`
+        if parser.check_next(TokenType::QMark){
+            parser.advance()?;
+            pos.end = parser.current_token().pos.end;
+            let mut nullable = Chunk::new();
+            nullable.write_pos(pos);
+            nullable.write_instruction(HIRInstruction::Nullable);
+            nullable.write_chunk(ret);
+            return Ok(nullable)
+        }
         Ok(ret)
     }
-}
\ No newline at end of file
+
+    ///Parse the type params of a generic declaration, such as the `<T, U: Comparable>` of `fun f<T, U: Comparable>`. The current token must be the one before the `<`.
+    ///Each param is written as `TyParam`, its position and name, then each of its bounds preceded by `FnParam`, then `EndParams`. A param may have several bounds, like `T: A + B`.
+    ///Upon success, the current token is the closing `>`.
+    pub fn get_type_params(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let mut chunk = Chunk::new();
+        parser.consume(TokenType::LAngle)?;
+        loop{
+            let name = match parser.consume(TokenType::Identifier)?{
+                TokenData::String(name) => name.clone(),
+                data => {
+                    let data = format!("{:?}", data);
+                    let source = parser.request_source_snippet(parser.current_token().pos)?;
+                    return Err(DiagnosticSourceBuilder::new(parser.name.clone(), parser.current_token().pos.start.0)
+                        .level(DiagnosticLevel::Error)
+                        .message(format!("Expected to find identifier data but instead found: {}", data))
+                        .range(parser.current_token().pos.col_range())
+                        .source(source)
+                        .build())
+                }
+            };
+            chunk.write_instruction(HIRInstruction::TyParam);
+            chunk.write_pos(parser.current_token().pos);
+            chunk.write_string(name);
+            if parser.check_consume_next(TokenType::Colon)?{
+                loop{
+                    let bound = Self::get_type(parser)?;
+                    chunk.write_instruction(HIRInstruction::FnParam);
+                    chunk.write_chunk(bound);
+                    if !parser.check_consume_next(TokenType::Plus)?{
+                        break;
+                    }
+                }
+            }
+            chunk.write_instruction(HIRInstruction::EndParams);
+            if parser.check_consume_next(TokenType::Comma)?{
+                continue;
+            }
+            parser.consume(TokenType::RAngle)?;
+            break;
+        }
+        Ok(chunk)
+    }
+
+    ///Parse a function type such as `(Int, Int) -> Int`. The current token must be the opening paren of the param types.
+    fn get_function_type(parser: &mut Parser) -> Result<Chunk, DiagnosticSource>{
+        let mut chunk = Chunk::new();
+        chunk.write_pos(parser.current_token().pos);
+        chunk.write_instruction(HIRInstruction::FnType);
+        if !parser.check_consume_next(TokenType::RParen)?{
+            loop{
+                let param = Self::get_type(parser)?;
+                chunk.write_instruction(HIRInstruction::FnParam);
+                chunk.write_chunk(param);
+                if parser.check_consume_next(TokenType::Comma)?{
+                    continue;
+                }
+                parser.consume(TokenType::RParen)?;
+                break;
+            }
+        }
+        chunk.write_instruction(HIRInstruction::EndParams);
+        parser.consume(TokenType::Arrow)?;
+        let ret = Self::get_type(parser)?;
+        chunk.write_chunk(ret);
+        Ok(chunk)
+    }
+}
@@ -1,9 +1,16 @@
 // #![feature(async_closure)]
 
-use frontend::Driver;
+use frontend::{
+    Driver,
+    MemmyFlags,
+};
 
 fn main() -> std::io::Result<()> {
-    let driver = Driver::new();
+    let memmy_flags = MemmyFlags{
+        lifetimes: std::env::args().any(|arg| arg == "--memmy-lifetimes"),
+        report: std::env::args().any(|arg| arg == "--memmy-report"),
+    };
+    let driver = Driver::new(memmy_flags);
     let _ = futures::executor::block_on(driver.parse_module("test.txt".to_string()));
     Ok(())
 }
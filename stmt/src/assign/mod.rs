@@ -0,0 +1,14 @@
+use ident::Identifier;
+use core::pos::BiPos;
+use super::expr::Expr;
+
+///An assignment of `value` to an existing property, local or field. Compound assignments have already been expanded by the parser.
+#[derive(Debug, Clone)]
+pub struct Assign{
+    ///The name of what is assigned to. For a field, this is the name of the field.
+    pub target: Identifier,
+    ///The object whose field `target` is assigned to, for an assignment `a.b = c`.
+    pub receiver: Option<Expr>,
+    pub value: Expr,
+    pub pos: BiPos,
+}
@@ -0,0 +1,10 @@
+use core::pos::BiPos;
+
+use super::Statement;
+
+///A sequence of statements enclosed in curly braces. Every block introduces a new scope, so locals declared within it are only visible until the end of the block.
+#[derive(Debug, Clone)]
+pub struct Block{
+    pub statements: Vec<Statement>,
+    pub pos: BiPos,
+}
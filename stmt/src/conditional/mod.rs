@@ -0,0 +1,21 @@
+use core::pos::BiPos;
+
+use ty::Ty;
+
+use super::{
+    block::Block,
+    expr::Expr,
+};
+
+use std::cell::RefCell;
+
+///An if-else. `unless(x)` is desugared by the parser into `if(!x)`.
+#[derive(Debug, Clone)]
+pub struct If{
+    pub cond: Expr,
+    pub then: Block,
+    pub otherwise: Option<Block>,
+    ///The type the if-else evaluates to. This is `Unit` unless both branches end in an expression of the same type.
+    pub ty: RefCell<Ty>,
+    pub pos: BiPos,
+}
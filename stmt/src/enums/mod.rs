@@ -0,0 +1,34 @@
+use ident::Identifier;
+use ty::Ty;
+use core::pos::BiPos;
+
+///An `enum` declaration, whose values are each one of its variants, such as
+///```norust
+///enum Shape{
+///    Circle(Float),
+///    Rect(Int, Int),
+///    Empty
+///}
+///```
+///Each variant is constructed by calling it like a function, or by naming it if it has no fields.
+#[derive(Debug, Clone)]
+pub struct Enum{
+    pub ident: Identifier,
+    pub variants: Vec<Variant>,
+    pub pos: BiPos,
+}
+
+///A variant of an enum, along with the types of the fields it carries.
+#[derive(Debug, Clone)]
+pub struct Variant{
+    pub ident: Identifier,
+    pub fields: Vec<Ty>,
+    pub pos: BiPos,
+}
+
+impl Enum{
+    ///The variant named `name`, if this enum has one.
+    pub fn variant(&self, name: &str) -> Option<&Variant>{
+        self.variants.iter().find(|variant| variant.ident.ident == name)
+    }
+}
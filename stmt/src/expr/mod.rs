@@ -0,0 +1,87 @@
+use core::pos::BiPos;
+
+use ty::{ Ty, TyValue };
+
+use super::{
+    loops::Loop,
+    conditional::If,
+    lambda::Lambda,
+    pattern::Match,
+};
+
+use std::cell::RefCell;
+
+#[derive(Debug, Clone)]
+pub struct Expr{
+    pub kind: Box<ExprElement>,
+    ///The type of the expression. Literals know their type upon creation, whereas references and control flow are given their type during checking.
+    pub ty: RefCell<Ty>,
+    pub pos: BiPos,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum ExprElement{
+    Grouped(Expr),
+    Value(TyValue),
+    UnaryOp(OpKind, Expr),
+    Binary(OpKind, Expr, Expr),
+    Loop(Loop),
+    If(If),
+    ///The success value of a `Result`, produced when desugaring a guarded function.
+    ResultOk(Expr),
+    ///The failure value of a `Result`, produced when desugaring a guarded function.
+    ResultErr(Expr),
+    Lambda(Lambda),
+    Call(Call),
+    ///A conversion `x as Int64` of the expression to the type.
+    Cast(Expr, Ty),
+    ///An access `x.member` of a field or a method of a struct.
+    Member(Expr, String),
+    ///A safe call `x?.member` of the member of a nullable expression, which is `None` when the expression is.
+    SafeAccess(Expr, String),
+    ///An assertion `x!!` that a nullable expression is not `None`.
+    NotNull(Expr),
+    ///A check `x is Int` of whether the expression has the type.
+    Is(Expr, Ty),
+    Match(Match),
+}
+
+///A call such as `f(a, b)`. A trailing lambda is the last of the args.
+#[derive(Debug, Clone)]
+pub struct Call{
+    pub callee: Expr,
+    ///The type arguments given to a generic callee, such as the `Int` of `id<Int>(3)`. Usually there are none, and they are inferred from the args instead.
+    pub ty_args: Vec<Ty>,
+    pub args: Vec<Expr>,
+    ///The name of the extension function that a call such as `s.shout()` turned out to call, which is given its receiver as its first arg.
+    pub extension: RefCell<Option<String>>,
+    pub pos: BiPos,
+}
+
+#[derive(Debug, Clone)]
+pub enum OpKind{
+    Add,
+    Min,
+    Mul,
+    Div,
+    Lt,
+    Gt,
+    LtEq,
+    GtEq,
+    Eq,
+    NotEq,
+    And,
+    Or,
+    Not,
+    Neg,
+    Range,
+    ///`a ?: b`, which is `b` when `a` is `None`.
+    Elvis,
+    ///`ref x`, an explicit reference to the object `x`.
+    Ref,
+    ///`move x`, an explicit move of the local `x`.
+    Move,
+    ///`copy x`, an explicit copy of the object `x`.
+    Copy,
+}
@@ -2,15 +2,31 @@ use ident::Identifier;
 
 use ty::Ty;
 use core::pos::BiPos;
-use super::Statement;
+use super::{
+    block::Block,
+    expr::Expr,
+    generic::TyParam,
+    structs::Modifier,
+};
+
+use std::cell::RefCell;
 
 
 #[derive(Debug, Clone)]
 pub struct Fun{
     pub ident: Identifier,
-    pub ty: Ty,
+    ///The type that an extension function is declared on, such as the `String` of `fun String.shout()`. Within its body, `this` is a value of this type.
+    pub receiver: Option<Ty>,
+    ///The type params of a generic function. A generic function is checked once, and then copied for each combination of type arguments it is used with.
+    pub ty_params: Vec<TyParam>,
+    ///The return type. For an expression bodied function without an annotation, this is inferred from its expression during checking.
+    pub ty: RefCell<Ty>,
     pub params: Vec<FunParam>,
-    pub body: Vec<Statement>,
+    pub body: Block,
+    ///The `where` guards that must hold before the body runs. A guarded function returns a `Result<T, String>` whose failure is the message of the first guard that did not hold.
+    pub guards: Vec<Guard>,
+    ///Whether a method of a class may be, or must be, overridden. Every other function is final.
+    pub modifier: Modifier,
     pub pos: BiPos,
 }
 
@@ -19,4 +35,19 @@ pub struct FunParam{
     pub ident: Identifier,
     pub ty: Ty,
     pub pos: BiPos
-}
\ No newline at end of file
+}
+
+///A `return` from the enclosing function, optionally with a value.
+#[derive(Debug, Clone)]
+pub struct Return{
+    pub value: Option<Expr>,
+    pub pos: BiPos,
+}
+
+///A `where cond else message` guard on a function.
+#[derive(Debug, Clone)]
+pub struct Guard{
+    pub cond: Expr,
+    pub message: Expr,
+    pub pos: BiPos,
+}
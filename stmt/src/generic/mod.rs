@@ -0,0 +1,10 @@
+use ident::Identifier;
+use ty::Ty;
+
+///A type param of a generic declaration, such as the `T: Comparable` of `fun max<T: Comparable>(a: T, b: T): T`.
+#[derive(Debug, Clone)]
+pub struct TyParam{
+    pub ident: Identifier,
+    ///The interfaces that every type argument for this param must implement.
+    pub bounds: Vec<Ty>,
+}
@@ -0,0 +1,27 @@
+use ident::Identifier;
+
+use ty::Ty;
+use core::pos::BiPos;
+use super::block::Block;
+
+use std::cell::RefCell;
+
+///A lambda literal such as `{ x -> x + 1 }`. It evaluates to the trailing expression of its body.
+#[derive(Debug, Clone)]
+pub struct Lambda{
+    pub params: Vec<LambdaParam>,
+    pub body: Block,
+    ///The function type that the context expects this lambda to have, if any. Params without a type annotation take their types from it.
+    pub expected: RefCell<Option<Ty>>,
+    ///The function type of the lambda, known once it has been checked.
+    pub ty: RefCell<Ty>,
+    pub pos: BiPos,
+}
+
+#[derive(Debug, Clone)]
+pub struct LambdaParam{
+    pub ident: Identifier,
+    ///`Unknown` until inferred, unless the param was annotated.
+    pub ty: RefCell<Ty>,
+    pub pos: BiPos,
+}
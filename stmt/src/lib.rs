@@ -1,11 +1,32 @@
 pub mod fun;
-use fun::Fun;
+use fun::{
+    Fun,
+    Return,
+};
 pub mod local;
 use local::Local;
 pub mod property;
 use property::Property;
 pub mod modules;
 // use modules::Module;
+pub mod expr;
+use expr::Expr;
+pub mod block;
+pub mod loops;
+use loops::Break;
+pub mod conditional;
+pub mod lambda;
+pub mod assign;
+use assign::Assign;
+pub mod generic;
+pub mod structs;
+use structs::{
+    Struct,
+    Interface,
+};
+pub mod enums;
+use enums::Enum;
+pub mod pattern;
 
 use core::pos::BiPos;
 
@@ -20,4 +41,12 @@ pub enum StatementKind{
     Property(Property),
     Fun(Fun),
     Local(Local),
+    Expr(Expr),
+    Break(Break),
+    Continue,
+    Return(Return),
+    Assign(Assign),
+    Struct(Struct),
+    Interface(Interface),
+    Enum(Enum),
 }
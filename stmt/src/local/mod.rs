@@ -2,7 +2,7 @@ use ident::Identifier;
 use mutable::Mutability;
 use ty::Ty;
 use core::pos::BiPos;
-use expr::Expr;
+use super::expr::Expr;
 
 use std::cell::RefCell;
 
@@ -12,5 +12,7 @@ pub struct Local{
     pub ty: RefCell<Ty>,
     pub expr: Expr,
     pub pos: BiPos,
-    pub mutable: Mutability
+    pub mutable: Mutability,
+    ///Whether it is declared with `= None` even though its type is not nullable, which leaves it to be initialized by its first assignment.
+    pub lateinit: bool,
 }
@@ -0,0 +1,47 @@
+use core::pos::BiPos;
+
+use ident::Identifier;
+use ty::Ty;
+
+use super::{
+    block::Block,
+    expr::Expr,
+};
+
+use std::cell::RefCell;
+
+///A loop. Every loop form is desugared by the parser into this one representation:
+/// * `loop{}` has neither a condition nor a binding.
+/// * `while(cond){}` has a condition which is checked before every iteration.
+/// * `for(x in iterable){}` binds each element of `iterable` to `x`.
+/// * `until(n){}` is sugar for `for(it in 0..n){}`.
+#[derive(Debug, Clone)]
+pub struct Loop{
+    pub cond: Option<Expr>,
+    pub binding: Option<LoopBinding>,
+    pub body: Block,
+    ///The type the loop evaluates to. This is `Unit` unless an unconditional `loop` breaks with a value.
+    pub ty: RefCell<Ty>,
+    pub pos: BiPos,
+}
+
+impl Loop{
+    ///Only an unconditional `loop` can break with a value, since every other loop form can finish without ever reaching a `break`.
+    pub fn can_break_with_value(&self) -> bool{
+        self.cond.is_none() && self.binding.is_none()
+    }
+}
+
+///The `x in iterable` part of a `for` loop.
+#[derive(Debug, Clone)]
+pub struct LoopBinding{
+    pub ident: Identifier,
+    pub iterable: Expr,
+}
+
+///A `break` out of the innermost loop, optionally with the value the loop evaluates to.
+#[derive(Debug, Clone)]
+pub struct Break{
+    pub value: Option<Expr>,
+    pub pos: BiPos,
+}
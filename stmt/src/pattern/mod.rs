@@ -0,0 +1,50 @@
+use core::pos::BiPos;
+
+use ident::Identifier;
+use ty::Ty;
+
+use super::{
+    block::Block,
+    expr::Expr,
+};
+
+use std::cell::RefCell;
+
+///A `match(x){ ... }`, which evaluates the body of the first arm whose pattern matches `x`.
+///Its arms must match every value that `x` may have, and each arm must match something that the arms before it do not.
+#[derive(Debug, Clone)]
+pub struct Match{
+    pub scrutinee: Expr,
+    pub arms: Vec<Arm>,
+    ///The type the match evaluates to. This is `Unit` unless every arm that finishes ends in an expression of the same type.
+    pub ty: RefCell<Ty>,
+    pub pos: BiPos,
+}
+
+///An arm `pattern => body` of a match. The bindings of the pattern are in scope in the body.
+#[derive(Debug, Clone)]
+pub struct Arm{
+    pub pattern: Pattern,
+    pub body: Block,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pattern{
+    ///A name is only known to be a binding rather than a variant without fields once the type it is matched against is known.
+    pub kind: RefCell<PatternKind>,
+    ///The type of the values that the pattern is matched against. This is given during checking.
+    pub ty: RefCell<Ty>,
+    pub pos: BiPos,
+}
+
+#[derive(Debug, Clone)]
+pub enum PatternKind{
+    ///`_`, which matches anything.
+    Wildcard,
+    ///A name, which matches anything and binds it to the name.
+    Binding(Identifier),
+    ///A literal such as `1` or `"yes"`, which matches values equal to it.
+    Literal(Expr),
+    ///A variant such as `Circle(r)`, which matches values of the variant whose fields match the patterns of its fields.
+    Variant(Identifier, Vec<Pattern>),
+}
@@ -3,7 +3,7 @@ use core::pos::BiPos;
 use ident::Identifier;
 use mutable::Mutability;
 use ty::Ty;
-use expr::Expr;
+use super::expr::Expr;
 
 use std::cell::RefCell;
 
@@ -14,5 +14,7 @@ pub struct Property{
     pub expr: Expr,
     pub pos: BiPos,
     pub mutable: Mutability,
+    ///Whether it is declared with `= None` even though its type is not nullable, which leaves it to be initialized by its first assignment.
+    pub lateinit: bool,
 }
 
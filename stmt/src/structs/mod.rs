@@ -0,0 +1,104 @@
+use ident::Identifier;
+use mutable::Mutability;
+use ty::Ty;
+use core::pos::BiPos;
+use super::{
+    expr::Expr,
+    fun::Fun,
+    generic::TyParam,
+};
+
+use std::cell::RefCell;
+
+///A `struct` declaration. Its constructor takes the fields without an initializer, in the order they are declared in.
+#[derive(Debug, Clone)]
+pub struct Struct{
+    pub ident: Identifier,
+    pub ty_params: Vec<TyParam>,
+    pub fields: Vec<Field>,
+    ///The methods, within which `this` is the struct they are called on.
+    pub methods: Vec<Fun>,
+    ///What a class declares beyond what a struct does, or `None` if this is a struct.
+    pub class: Option<Class>,
+    ///The interfaces of a class, or the traits of a struct, from its `with` clause.
+    pub with: Vec<Ty>,
+    pub pos: BiPos,
+}
+
+///An `interface`, which only classes can implement, or a `trait`, which only structs can implement.
+///Each of its methods has an empty body, since it only tells what a method implementing it must look like.
+#[derive(Debug, Clone)]
+pub struct Interface{
+    pub ident: Identifier,
+    pub ty_params: Vec<TyParam>,
+    pub methods: Vec<Fun>,
+    pub is_trait: bool,
+    pub pos: BiPos,
+}
+
+impl Interface{
+    ///`interface` or `trait`, for diagnostics.
+    pub fn kind(&self) -> &'static str{
+        if self.is_trait{ "trait" }else{ "interface" }
+    }
+}
+
+///A `class`, which is a struct that may inherit the fields and methods of a single base class.
+///Its methods that may be overridden are called through the vtable of the class of the object they are called on.
+#[derive(Debug, Clone)]
+pub struct Class{
+    ///Whether the class may be inherited from, and whether it may be constructed. An abstract class is also open.
+    pub modifier: Modifier,
+    pub base: Option<Base>,
+}
+
+///The base class of a class, along with the args that its constructor is called with.
+///The args may use the constructor params of the class that inherits it.
+#[derive(Debug, Clone)]
+pub struct Base{
+    pub ident: Identifier,
+    pub args: Vec<Expr>,
+    pub pos: BiPos,
+}
+
+///How a class may be inherited from, or how a method may be overridden.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Modifier{
+    ///Neither inherited from nor overridden, which is what a declaration without a modifier is.
+    Final,
+    Open,
+    ///A class that cannot be constructed, or a method without a body.
+    Abstract,
+    ///A method that overrides the method of its base class with the same name. It may be overridden in turn.
+    Override,
+    ///A method that implements the method of an interface or a trait with the same name. The method of a class may be overridden in turn.
+    Impl,
+}
+
+impl Modifier{
+    ///Whether a class with this modifier may be inherited from, or a method with it overridden.
+    pub fn is_open(&self) -> bool{
+        *self != Modifier::Final
+    }
+}
+
+///A field of a struct, declared either as a constructor param or in the body of the struct with an initializer.
+#[derive(Debug, Clone)]
+pub struct Field{
+    pub ident: Identifier,
+    ///The type of the field. For an unannotated field in the body, this is inferred from its initializer during checking.
+    pub ty: RefCell<Ty>,
+    pub mutable: Mutability,
+    ///The initial value of a field declared in the body. It may use the constructor params, but not `this`.
+    pub init: Option<Expr>,
+    ///Whether only the methods of the struct itself can use the field.
+    pub private: bool,
+    pub pos: BiPos,
+}
+
+impl Struct{
+    ///The fields that are passed to the constructor.
+    pub fn params(&self) -> impl Iterator<Item = &Field>{
+        self.fields.iter().filter(|field| field.init.is_none())
+    }
+}
@@ -1,4 +1,18 @@
 use core::pos::BiPos;
+
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    fmt,
+    rc::Rc,
+};
+
 ///A type, the meat of the sandwhich.
 ///Ty represents a type which is used to represent a specific type. Type info is generated or inferred by context.
 ///A Ty can be inferred or generated depending upon building blocks or sister componenets. Smart casting using the given context to ensure that while within a conditional block that checks for a type's instance, that we safely cast an object's type to the checked type.
@@ -10,8 +24,8 @@ use core::pos::BiPos;
 ///```
 #[derive(Debug, Clone)]
 pub struct Ty{
-    ///The name of the type. This is used for comparison.
-    pub ident: String,
+    ///The structure of the type. This is interned in the [TyCtx], so two types are equal exactly when they share the same `kind`.
+    kind: TyRef,
     ///The location in source code of the type. This can be one of the following:
     /// * A type annotation
     ///     * Function return type
@@ -24,6 +38,102 @@ pub struct Ty{
     pub pos: BiPos
 }
 
+///An interned [TyKind]. Only ever created by [TyCtx::intern].
+pub type TyRef = Rc<TyKind>;
+
+///The structure of a type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TyKind{
+    Unit,
+    Bool,
+    ///A 32 bit signed integer, also written `Int32`.
+    Int,
+    ///A 32 bit float, also written `Float32`.
+    Float,
+    String,
+    Int8,
+    Int16,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Float64,
+    Char,
+    ///A nominal type such as a struct or class, named by its canonical path like `main::Person`.
+    Named(Vec<String>),
+    ///A generic type applied to type arguments, such as `Result<Int, String>` or `Range<Int>`.
+    App(Vec<String>, Vec<TyRef>),
+    ///The type of a function or lambda, such as `(Int, Int) -> Int`.
+    Function(Vec<TyRef>, TyRef),
+    ///A nullable type `T?`.
+    Option(TyRef),
+    ///A reference `r(a)` that binds to the term `a`. See [TyValueElement::Ref].
+    Ref(String),
+    ///A type param of a generic function, such as the `T` of `fun id<T>(x: T): T`.
+    ///Within the function it only equals itself. Wherever the function is used, it is substituted with a type argument.
+    Param(String),
+    ///A type that is not known yet, such as the type of an unannotated local before its value has been checked.
+    Var(usize),
+}
+
+///The type context. Every type is interned here so that each distinct type exists exactly once per thread,
+///which makes comparing types a pointer comparison.
+///It also holds what each type variable has been unified with, see [Ty::unify].
+#[derive(Debug, Default)]
+pub struct TyCtx{
+    interned: RefCell<HashSet<TyRef>>,
+    next_var: Cell<usize>,
+    ///The type that each bound type variable stands for, found where it was inferred.
+    bindings: RefCell<HashMap<usize, Ty>>,
+}
+
+thread_local!{
+    static TY_CTX: TyCtx = TyCtx::default();
+}
+
+impl TyCtx{
+    ///Run `f` with the type context of the current thread.
+    pub fn with<R>(f: impl FnOnce(&TyCtx) -> R) -> R{
+        TY_CTX.with(f)
+    }
+
+    ///Get the one shared instance of `kind`.
+    pub fn intern(&self, kind: TyKind) -> TyRef{
+        if let Some(interned) = self.interned.borrow().get(&kind){
+            return interned.clone()
+        }
+        let interned = Rc::new(kind);
+        self.interned.borrow_mut().insert(interned.clone());
+        interned
+    }
+
+    ///A type variable that has never been handed out before.
+    pub fn fresh_var(&self) -> TyRef{
+        let var = self.next_var.get();
+        self.next_var.set(var + 1);
+        self.intern(TyKind::Var(var))
+    }
+
+    fn binding(&self, var: usize) -> Option<Ty>{
+        self.bindings.borrow().get(&var).cloned()
+    }
+
+    fn bind(&self, var: usize, ty: Ty){
+        self.bindings.borrow_mut().insert(var, ty);
+    }
+}
+
+///Two types that could not be unified, each found where it originates from.
+///For an inferred type, that is where it was inferred rather than where the type variable was introduced.
+#[derive(Debug, Clone)]
+pub struct Mismatch{
+    pub expected: Ty,
+    pub found: Ty,
+    ///Whether unifying would have made a type contain itself, such as `'0 = ('0) -> Int`.
+    pub infinite: bool,
+}
+
 ///A value from input and it's type.
 ///This can include primitive data such as literals, Unit values (aka, void or nothing), or custom types when parsing Constructors.
 ///See [TyValueElement] for more information.
@@ -43,10 +153,15 @@ pub struct TyValue{
 ///```
 #[derive(Debug, Clone, PartialEq)]
 pub enum TyValueElement{
-    Integer(i32),
-    Float(f32),
+    ///An integer literal. It is wide enough for any integer type that it may be inferred as.
+    Integer(i64),
+    ///A float literal. It is wide enough for any float type that it may be inferred as.
+    Float(f64),
+    Char(char),
     String(String),
     Bool(bool),
+    ///The absence of a value, written `None` or `null`.
+    None,
     Custom(String),
     ///In Beagle, references are integral dependent types, thus they deserve their own spot in TyValueElement
     ///The String value is a term to which the reference binds to.
@@ -82,7 +197,447 @@ pub enum TyValueElement{
 }
 
 impl PartialEq<Ty> for Ty{
+    ///Types are equal when they are the same once every inferred type variable has been substituted.
     fn eq(&self, other: &Ty) -> bool {
-        self.ident == other.ident
+        Rc::ptr_eq(&self.resolve().kind, &other.resolve().kind)
+    }
+}
+
+impl Ty{
+    pub fn new(kind: TyKind, pos: BiPos) -> Ty{
+        Ty{
+            kind: TyCtx::with(|ctx| ctx.intern(kind)),
+            pos
+        }
+    }
+
+    ///A fresh type variable, for a type that has yet to be inferred.
+    pub fn var(pos: BiPos) -> Ty{
+        Ty{
+            kind: TyCtx::with(|ctx| ctx.fresh_var()),
+            pos
+        }
+    }
+
+    ///The outermost structure of the type. If this is a type variable that has been inferred, this is the structure of what it was inferred as.
+    pub fn kind(&self) -> TyRef{
+        self.shallow().kind
+    }
+
+    ///This same type, found at `pos`.
+    pub fn at(&self, pos: BiPos) -> Ty{
+        Ty{
+            kind: self.kind.clone(),
+            pos
+        }
+    }
+
+    ///The type of a function or lambda that takes `params` and returns `ret`.
+    pub fn function(params: &[Ty], ret: &Ty, pos: BiPos) -> Ty{
+        let params = params.iter().map(|param| param.kind.clone()).collect();
+        Ty::new(TyKind::Function(params, ret.kind.clone()), pos)
+    }
+
+    ///A generic type such as `Result` applied to `args`.
+    pub fn app(name: &str, args: &[Ty], pos: BiPos) -> Ty{
+        let args = args.iter().map(|arg| arg.kind.clone()).collect();
+        Ty::new(TyKind::App(vec![name.to_owned()], args), pos)
+    }
+
+    ///A type declared in source such as the struct `main::Point`, applied to `args` if it is generic like `main::Box<Int>`.
+    pub fn named(path: Vec<String>, args: &[Ty], pos: BiPos) -> Ty{
+        if args.is_empty(){
+            return Ty::new(TyKind::Named(path), pos)
+        }
+        let args = args.iter().map(|arg| arg.kind.clone()).collect();
+        Ty::new(TyKind::App(path, args), pos)
+    }
+
+    ///The nullable type `inner?`.
+    pub fn option(inner: &Ty, pos: BiPos) -> Ty{
+        Ty::new(TyKind::Option(inner.kind.clone()), pos)
+    }
+
+    ///The type inside a nullable type, or `None` if this is not a nullable type.
+    pub fn option_inner(&self) -> Option<Ty>{
+        match self.kind().as_ref(){
+            TyKind::Option(inner) => Some(self.child(inner)),
+            _ => None
+        }
+    }
+
+    ///The param types and return type of a function type, or `None` if this is not a function type.
+    ///They are all found at the position of this type.
+    pub fn function_signature(&self) -> Option<(Vec<Ty>, Ty)>{
+        match self.kind().as_ref(){
+            TyKind::Function(params, ret) => Some((
+                params.iter().map(|param| self.child(param)).collect(),
+                self.child(ret)
+            )),
+            _ => None
+        }
+    }
+
+    ///The type arguments of the generic type `name`, or `None` if this is some other type.
+    pub fn app_args(&self, name: &str) -> Option<Vec<Ty>>{
+        match self.kind().as_ref(){
+            TyKind::App(path, args) if path.len() == 1 && path[0] == name => Some(args.iter().map(|arg| self.child(arg)).collect()),
+            _ => None
+        }
+    }
+
+    ///The path and type arguments of a named type, which are empty unless it is generic, or `None` if this is not a named type.
+    pub fn named_args(&self) -> Option<(Vec<String>, Vec<Ty>)>{
+        match self.kind().as_ref(){
+            TyKind::Named(path) => Some((path.clone(), vec![])),
+            TyKind::App(path, args) => Some((path.clone(), args.iter().map(|arg| self.child(arg)).collect())),
+            _ => None
+        }
+    }
+
+    ///Whether this is a type variable that has not been inferred yet.
+    pub fn is_var(&self) -> bool{
+        match self.kind().as_ref(){
+            TyKind::Var(_) => true,
+            _ => false
+        }
+    }
+
+    ///Whether any part of this type has not been inferred yet.
+    pub fn has_vars(&self) -> bool{
+        has_vars(&self.resolve().kind)
+    }
+
+    pub fn is_numeric(&self) -> bool{
+        self.is_integral() || self.is_float()
+    }
+
+    pub fn is_integral(&self) -> bool{
+        self.int_bounds().is_some()
+    }
+
+    pub fn is_float(&self) -> bool{
+        matches!(self.kind().as_ref(), TyKind::Float | TyKind::Float64)
     }
-}
\ No newline at end of file
+
+    ///The smallest and largest values of an integer type, or `None` if this is not an integer type.
+    pub fn int_bounds(&self) -> Option<(i128, i128)>{
+        match self.kind().as_ref(){
+            TyKind::Int8 => Some((i8::MIN as i128, i8::MAX as i128)),
+            TyKind::Int16 => Some((i16::MIN as i128, i16::MAX as i128)),
+            TyKind::Int => Some((i32::MIN as i128, i32::MAX as i128)),
+            TyKind::Int64 => Some((i64::MIN as i128, i64::MAX as i128)),
+            TyKind::UInt8 => Some((0, u8::MAX as i128)),
+            TyKind::UInt16 => Some((0, u16::MAX as i128)),
+            TyKind::UInt32 => Some((0, u32::MAX as i128)),
+            TyKind::UInt64 => Some((0, u64::MAX as i128)),
+            _ => None
+        }
+    }
+
+    ///A type that is part of this one, such as the inner type of an `Option`, at the same position as this one.
+    pub fn child(&self, kind: &TyRef) -> Ty{
+        Ty{
+            kind: kind.clone(),
+            pos: self.pos
+        }
+    }
+
+    ///Follow the type variable this may be until reaching something that is not an inferred type variable.
+    fn shallow(&self) -> Ty{
+        let mut ty = self.clone();
+        while let TyKind::Var(var) = ty.kind.as_ref(){
+            match TyCtx::with(|ctx| ctx.binding(*var)){
+                Some(bound) => ty = bound,
+                None => break
+            }
+        }
+        ty
+    }
+
+    ///This type with every inferred type variable in it substituted, found where its outermost structure originates from.
+    pub fn resolve(&self) -> Ty{
+        let shallow = self.shallow();
+        Ty{
+            kind: resolve_kind(&shallow.kind),
+            pos: shallow.pos
+        }
+    }
+
+    ///This type with each type param in `args` substituted with its type argument.
+    pub fn substitute(&self, args: &[(String, Ty)]) -> Ty{
+        let resolved = self.resolve();
+        Ty{
+            kind: substitute_kind(&resolved.kind, args),
+            pos: resolved.pos
+        }
+    }
+
+    ///This type with each part of it replaced by what `f` returns for it, or kept when `f` returns `None`.
+    ///The parts inside of a type are replaced before the type itself, so `f` sees them replaced already.
+    pub fn map(&self, f: &mut dyn FnMut(&Ty) -> Option<Ty>) -> Ty{
+        let resolved = self.resolve();
+        let kind = match resolved.kind.as_ref(){
+            TyKind::App(path, args) => TyKind::App(path.clone(), args.iter().map(|arg| resolved.child(arg).map(f).kind).collect()),
+            TyKind::Function(params, ret) => TyKind::Function(
+                params.iter().map(|param| resolved.child(param).map(f).kind).collect(),
+                resolved.child(ret).map(f).kind
+            ),
+            TyKind::Option(inner) => TyKind::Option(resolved.child(inner).map(f).kind),
+            kind => kind.clone()
+        };
+        let mapped = Ty::new(kind, resolved.pos);
+        f(&mapped).unwrap_or(mapped)
+    }
+
+    ///Make this type and `found` the same type by inferring the type variables in them.
+    ///When they cannot be made the same, some of the type variables may already have been inferred.
+    pub fn unify(&self, found: &Ty) -> Result<(), Mismatch>{
+        match unify(self, found){
+            Ok(()) => Ok(()),
+            Err(infinite) => Err(Mismatch{
+                expected: self.resolve(),
+                found: found.resolve(),
+                infinite
+            })
+        }
+    }
+
+    ///Like [Ty::unify], but for a value of type `found` that flows into a place of this type.
+    ///A value that is not nullable may flow into a nullable place, in which case it is unified with the type inside it.
+    pub fn coerce(&self, found: &Ty) -> Result<(), Mismatch>{
+        match self.option_inner(){
+            Some(inner) if found.option_inner().is_none() => match unify(&inner, found){
+                Ok(()) => Ok(()),
+                Err(infinite) => Err(Mismatch{
+                    expected: self.resolve(),
+                    found: found.resolve(),
+                    infinite
+                })
+            },
+            _ => self.unify(found)
+        }
+    }
+}
+
+///Unify `expected` with `found`, failing with whether the failure is because a type would contain itself.
+fn unify(expected: &Ty, found: &Ty) -> Result<(), bool>{
+    let expected = expected.shallow();
+    let found = found.shallow();
+    if Rc::ptr_eq(&expected.kind, &found.kind){
+        return Ok(())
+    }
+    match (expected.kind.as_ref(), found.kind.as_ref()){
+        (TyKind::Var(var), _) => bind(*var, &found),
+        (_, TyKind::Var(var)) => bind(*var, &expected),
+        (TyKind::Function(expected_params, expected_ret), TyKind::Function(found_params, found_ret)) if expected_params.len() == found_params.len() => {
+            for (expected_param, found_param) in expected_params.iter().zip(found_params.iter()){
+                unify(&expected.child(expected_param), &found.child(found_param))?;
+            }
+            unify(&expected.child(expected_ret), &found.child(found_ret))
+        }
+        (TyKind::App(expected_path, expected_args), TyKind::App(found_path, found_args)) if expected_path == found_path && expected_args.len() == found_args.len() => {
+            for (expected_arg, found_arg) in expected_args.iter().zip(found_args.iter()){
+                unify(&expected.child(expected_arg), &found.child(found_arg))?;
+            }
+            Ok(())
+        }
+        (TyKind::Option(expected_inner), TyKind::Option(found_inner)) => unify(&expected.child(expected_inner), &found.child(found_inner)),
+        _ => Err(false)
+    }
+}
+
+fn bind(var: usize, ty: &Ty) -> Result<(), bool>{
+    if occurs(var, &ty.resolve().kind){
+        return Err(true)
+    }
+    TyCtx::with(|ctx| ctx.bind(var, ty.clone()));
+    Ok(())
+}
+
+///Whether the type variable `var` occurs anywhere in `kind`.
+fn occurs(var: usize, kind: &TyKind) -> bool{
+    match kind{
+        TyKind::Var(other) => *other == var,
+        TyKind::App(_, args) => args.iter().any(|arg| occurs(var, arg)),
+        TyKind::Function(params, ret) => params.iter().any(|param| occurs(var, param)) || occurs(var, ret),
+        TyKind::Option(inner) => occurs(var, inner),
+        _ => false
+    }
+}
+
+fn has_vars(kind: &TyKind) -> bool{
+    match kind{
+        TyKind::Var(_) => true,
+        TyKind::App(_, args) => args.iter().any(|arg| has_vars(arg)),
+        TyKind::Function(params, ret) => params.iter().any(|param| has_vars(param)) || has_vars(ret),
+        TyKind::Option(inner) => has_vars(inner),
+        _ => false
+    }
+}
+
+///Substitute every inferred type variable in `kind`.
+fn resolve_kind(kind: &TyRef) -> TyRef{
+    let resolved = match kind.as_ref(){
+        TyKind::Var(var) => match TyCtx::with(|ctx| ctx.binding(*var)){
+            Some(bound) => return resolve_kind(&bound.kind),
+            None => return kind.clone()
+        },
+        TyKind::App(path, args) => TyKind::App(path.clone(), args.iter().map(resolve_kind).collect()),
+        TyKind::Function(params, ret) => TyKind::Function(params.iter().map(resolve_kind).collect(), resolve_kind(ret)),
+        TyKind::Option(inner) => TyKind::Option(resolve_kind(inner)),
+        _ => return kind.clone()
+    };
+    TyCtx::with(|ctx| ctx.intern(resolved))
+}
+
+fn substitute_kind(kind: &TyRef, args: &[(String, Ty)]) -> TyRef{
+    let substituted = match kind.as_ref(){
+        TyKind::Param(name) => return match args.iter().find(|(param, _)| param == name){
+            Some((_, arg)) => arg.kind.clone(),
+            None => kind.clone()
+        },
+        TyKind::App(path, tys) => TyKind::App(path.clone(), tys.iter().map(|ty| substitute_kind(ty, args)).collect()),
+        TyKind::Function(params, ret) => TyKind::Function(params.iter().map(|param| substitute_kind(param, args)).collect(), substitute_kind(ret, args)),
+        TyKind::Option(inner) => TyKind::Option(substitute_kind(inner, args)),
+        _ => return kind.clone()
+    };
+    TyCtx::with(|ctx| ctx.intern(substituted))
+}
+
+impl fmt::Display for Ty{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.resolve().kind)
+    }
+}
+
+impl fmt::Display for TyKind{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |tys: &[TyRef]| tys.iter().map(|ty| ty.to_string()).collect::<Vec<String>>().join(", ");
+        match self{
+            TyKind::Unit => write!(f, "Unit"),
+            TyKind::Bool => write!(f, "Bool"),
+            TyKind::Int => write!(f, "Int"),
+            TyKind::Float => write!(f, "Float"),
+            TyKind::String => write!(f, "String"),
+            TyKind::Int8 => write!(f, "Int8"),
+            TyKind::Int16 => write!(f, "Int16"),
+            TyKind::Int64 => write!(f, "Int64"),
+            TyKind::UInt8 => write!(f, "UInt8"),
+            TyKind::UInt16 => write!(f, "UInt16"),
+            TyKind::UInt32 => write!(f, "UInt32"),
+            TyKind::UInt64 => write!(f, "UInt64"),
+            TyKind::Float64 => write!(f, "Float64"),
+            TyKind::Char => write!(f, "Char"),
+            TyKind::Named(path) => write!(f, "{}", path.join("::")),
+            TyKind::App(path, args) => write!(f, "{}<{}>", path.join("::"), list(args)),
+            TyKind::Function(params, ret) => write!(f, "({}) -> {}", list(params), ret),
+            TyKind::Option(inner) => match inner.as_ref(){
+                TyKind::Function(..) => write!(f, "({})?", inner),
+                _ => write!(f, "{}?", inner)
+            },
+            TyKind::Ref(term) => write!(f, "r({})", term),
+            TyKind::Param(name) => write!(f, "{}", name),
+            TyKind::Var(var) => write!(f, "'{}", var),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ty(kind: TyKind) -> Ty{
+        Ty::new(kind, BiPos::default())
+    }
+
+    #[test]
+    fn structurally_equal_types_are_interned_once() {
+        let callback = Ty::function(&[ty(TyKind::Int)], &Ty::app("Result", &[ty(TyKind::Int), ty(TyKind::String)], BiPos::default()), BiPos::default());
+        let fun = Ty::function(&[callback.clone(), ty(TyKind::Bool)], &ty(TyKind::Unit), callback.pos);
+        assert_eq!(fun.to_string(), "((Int) -> Result<Int, String>, Bool) -> Unit");
+        let (params, ret) = fun.function_signature().unwrap();
+        assert_eq!(params, vec![callback, ty(TyKind::Bool)]);
+        assert_eq!(ret, ty(TyKind::Unit));
+        assert_eq!(fun, Ty::function(&params, &ret, BiPos::default()));
+        assert!(ty(TyKind::Int).function_signature().is_none());
+        assert_ne!(Ty::var(BiPos::default()), Ty::var(BiPos::default()));
+        assert_eq!(ty(TyKind::Option(params[0].kind.clone())).to_string(), "((Int) -> Result<Int, String>)?");
+    }
+
+    #[test]
+    fn unification_infers_type_variables() {
+        let param = Ty::var(BiPos::default());
+        let ret = Ty::var(BiPos::default());
+        let lambda = Ty::function(&[param.clone()], &ret, BiPos::default());
+        assert_eq!(lambda.to_string(), format!("({}) -> {}", param, ret));
+        assert!(lambda.has_vars());
+        let int_pos = BiPos{ start: core::pos::Position(2, 4), end: core::pos::Position(2, 5), ..BiPos::default() };
+        param.unify(&Ty::new(TyKind::Int, int_pos)).unwrap();
+        ret.unify(&param).unwrap();
+        assert_eq!(lambda.to_string(), "(Int) -> Int");
+        assert!(!lambda.has_vars());
+        assert_eq!(ret.resolve().pos, int_pos);
+
+        let mismatch = lambda.unify(&Ty::function(&[ty(TyKind::Int)], &ty(TyKind::Bool), BiPos::default())).unwrap_err();
+        assert!(!mismatch.infinite);
+        assert_eq!(mismatch.expected.to_string(), "(Int) -> Int");
+        assert_eq!(mismatch.found.to_string(), "(Int) -> Bool");
+    }
+
+    #[test]
+    fn integer_types_know_their_bounds() {
+        assert_eq!(ty(TyKind::Int8).int_bounds(), Some((-128, 127)));
+        assert_eq!(ty(TyKind::UInt64).int_bounds(), Some((0, u64::MAX as i128)));
+        assert!(ty(TyKind::Float64).is_numeric() && !ty(TyKind::Float64).is_integral());
+        assert!(!ty(TyKind::Char).is_numeric());
+    }
+
+    #[test]
+    fn unification_fails_occurs_check() {
+        let var = Ty::var(BiPos::default());
+        let fun = Ty::function(std::slice::from_ref(&var), &ty(TyKind::Int), BiPos::default());
+        let mismatch = var.unify(&fun).unwrap_err();
+        assert!(mismatch.infinite);
+        assert!(var.is_var());
+        var.unify(&var).unwrap();
+    }
+
+    #[test]
+    fn values_coerce_into_nullable_places() {
+        let nullable = Ty::option(&ty(TyKind::Int), BiPos::default());
+        let literal = Ty::var(BiPos::default());
+        nullable.coerce(&literal).unwrap();
+        assert_eq!(literal, ty(TyKind::Int));
+        nullable.coerce(&Ty::option(&Ty::var(BiPos::default()), BiPos::default())).unwrap();
+        assert_eq!(nullable.option_inner(), Some(ty(TyKind::Int)));
+        assert!(ty(TyKind::Int).coerce(&nullable).is_err());
+        assert_eq!(nullable.coerce(&ty(TyKind::Bool)).unwrap_err().expected.to_string(), "Int?");
+    }
+
+    #[test]
+    fn type_params_are_substituted_everywhere() {
+        let param = ty(TyKind::Param("T".to_owned()));
+        let fun = Ty::function(&[param.clone(), Ty::option(&param, BiPos::default())], &param, BiPos::default());
+        let substituted = fun.substitute(&[("T".to_owned(), ty(TyKind::Int))]);
+        assert_eq!(substituted.to_string(), "(Int, Int?) -> Int");
+        assert_eq!(fun.substitute(&[]), fun);
+    }
+
+    #[test]
+    fn named_types_are_mapped_inside_out() {
+        let point = Ty::named(vec!["main".to_owned(), "Point".to_owned()], &[], BiPos::default());
+        let boxed = Ty::named(vec!["main".to_owned(), "Box".to_owned()], std::slice::from_ref(&point), BiPos::default());
+        let nullable = Ty::option(&boxed, BiPos::default());
+        assert_eq!(nullable.to_string(), "main::Box<main::Point>?");
+        let mut seen = vec![];
+        let mapped = nullable.map(&mut |ty| {
+            seen.push(ty.to_string());
+            let (path, args) = ty.named_args().filter(|(_, args)| !args.is_empty())?;
+            let name = format!("{}[{}]", path[1], args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>().join(", "));
+            Some(Ty::named(vec![path[0].clone(), name], &[], ty.pos))
+        });
+        assert_eq!(seen, vec!["main::Point", "main::Box<main::Point>", "main::Box[main::Point]?"]);
+        assert_eq!(mapped.to_string(), "main::Box[main::Point]?");
+    }
+}
@@ -0,0 +1,248 @@
+use super::{
+    Typeck,
+    Load,
+    Unload,
+    Check,
+    ty::GetTy,
+    lambda::expect,
+};
+
+use stmt::{
+    assign::Assign,
+    expr::{
+        Expr,
+        ExprElement,
+    },
+};
+
+use ident::Identifier;
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+impl Load for Assign{
+    type Output = Assign;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let target = match Identifier::load(chunk, typeck){
+            Ok(Some(target)) => target,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let value = match Expr::load(chunk, typeck){
+            Ok(Some(value)) => value,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        Ok(Some(Assign{
+            target,
+            receiver: None,
+            value,
+            pos
+        }))
+    }
+}
+
+///Load an assignment `a.b = c` to a field, whose target is a member access.
+pub fn load_assign_member(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Assign>, ()>{
+    let pos = match chunk.read_pos(){
+        Ok(pos) => pos,
+        Err(msg) => {
+            let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                .level(DiagnosticLevel::Error)
+                .message(msg)
+                .build();
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+    };
+    let target = match Expr::load(chunk, typeck){
+        Ok(Some(target)) => target,
+        Ok(None) => return Ok(None),
+        Err(notice) => return Err(notice)
+    };
+    let (receiver, ident) = match *target.kind{
+        ExprElement::Member(receiver, ident) => (receiver, ident),
+        _ => {
+            let diag_source = typeck.error_source(target.pos, "Expected a field to assign to".to_string())?;
+            typeck.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diag_source]);
+            return Err(())
+        }
+    };
+    let value = match Expr::load(chunk, typeck){
+        Ok(Some(value)) => value,
+        Ok(None) => return Ok(None),
+        Err(notice) => return Err(notice)
+    };
+    Ok(Some(Assign{
+        target: Identifier{
+            ident,
+            pos: target.pos
+        },
+        receiver: Some(receiver),
+        value,
+        pos
+    }))
+}
+
+///Check an assignment to a field, which must have been declared with `var`.
+fn check_field(assign: &Assign, receiver: &Expr, typeck: &Typeck) -> Result<(), ()>{
+    receiver.check(typeck)?;
+    let member = typeck.member(&receiver.get_ty(), &assign.target.ident, assign.target.pos)?;
+    let mutable = match &member.mutable{
+        Some(mutable) => mutable.mutable,
+        None => {
+            let diag_source = typeck.error_source(assign.target.pos, format!("Cannot assign to {} because it is a method", assign.target.ident))?;
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+    };
+    if !mutable{
+        let assign_source = typeck.error_source(assign.target.pos, format!("Cannot assign to {} because it is declared with val", assign.target.ident))?;
+        let decl_source = typeck.error_source(member.ident.pos, format!("{} is declared here", member.ident.ident))?;
+        typeck.emit_diagnostic(&[
+            "Only fields declared with `var` can be assigned to.".to_string()
+        ], &[assign_source, decl_source]);
+        return Err(())
+    }
+    expect(&assign.value, &member.ty);
+    assign.value.check(typeck)?;
+    match member.ty.coerce(&typeck.upcast(&member.ty, &assign.value.get_ty())){
+        Ok(()) => Ok(()),
+        Err(mismatch) => {
+            let found_message = format!("This value has type {}", mismatch.found);
+            let expected_message = format!("But {} is declared as {} because of this", member.ident.ident, mismatch.expected);
+            typeck.emit_mismatch(&mismatch, found_message, expected_message)
+        }
+    }
+}
+
+impl<'a> Check<'a> for Assign{
+    ///The target must be a `var` property, a `let mut` local, an object that is initialized late or a `var` field, and the value must have the type it was declared with.
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        if let Some(receiver) = &self.receiver{
+            return check_field(self, receiver, typeck)
+        }
+        let symbol = match typeck.scopes.lookup(&self.target.ident){
+            Some(symbol) => symbol,
+            None => {
+                let diag_source = typeck.error_source(self.target.pos, format!("Unresolved reference: {}", self.target.ident))?;
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        if !symbol.mutable.mutable && !symbol.lateinit{
+            let assign_source = typeck.error_source(self.target.pos, format!("Cannot assign to {} because it is immutable", self.target.ident))?;
+            let decl_source = typeck.error_source(symbol.ident.pos, format!("{} is declared here", symbol.ident.ident))?;
+            typeck.emit_diagnostic(&[
+                "Only locals declared with `let mut` and properties declared with `var` can be assigned to.".to_string()
+            ], &[assign_source, decl_source]);
+            return Err(())
+        }
+        expect(&self.value, &symbol.ty);
+        self.value.check(typeck)?;
+        match symbol.ty.coerce(&typeck.upcast(&symbol.ty, &self.value.get_ty())){
+            Ok(()) => Ok(()),
+            Err(mismatch) => {
+                let found_message = format!("This value has type {}", mismatch.found);
+                let expected_message = format!("But {} is declared as {} because of this", symbol.ident.ident, mismatch.expected);
+                typeck.emit_mismatch(&mismatch, found_message, expected_message)
+            }
+        }
+    }
+}
+
+impl Unload for Assign{
+    ///The target is followed by whether it is a field, and then the object it is a field of.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Assign);
+        chunk.write_pos(self.pos);
+        match self.target.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        match &self.receiver{
+            Some(receiver) => {
+                chunk.write_bool(true);
+                match receiver.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+            }
+            None => chunk.write_bool(false)
+        }
+        match self.value.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    #[test]
+    fn mutable_targets_can_be_assigned_to() {
+        let errors = errors("struct Counter(var n: Int)\nvar total: Int = 0\nfun f(){\n    let mut n: Int = 1\n    n = 2\n    n += 3\n    n++\n    total = n\n    total -= 1\n    val c = Counter(1)\n    c.n = n\n}");
+        assert_eq!(errors, Vec::<String>::new());
+    }
+
+    #[test]
+    fn immutable_locals_and_properties_cannot_be_assigned_to() {
+        assert_eq!(errors("fun f(){\n    let n: Int = 1\n    n = 2\n}"), vec![
+            format!("Cannot assign to n because it is immutable"),
+            format!("n is declared here"),
+        ]);
+        assert_eq!(errors("val total: Int = 0\nfun f(){\n    total += 1\n}"), vec![
+            format!("Cannot assign to total because it is immutable"),
+            format!("total is declared here"),
+        ]);
+        assert_eq!(errors("fun f(n: Int){\n    n++\n}"), vec![
+            format!("Cannot assign to n because it is immutable"),
+            format!("n is declared here"),
+        ]);
+    }
+
+    #[test]
+    fn fields_declared_with_val_cannot_be_assigned_to() {
+        assert_eq!(errors("struct Point(val x: Int)\nfun f(){\n    var p = Point(1)\n    p.x = 2\n}"), vec![
+            format!("Cannot assign to x because it is declared with val"),
+            format!("x is declared here"),
+        ]);
+    }
+
+    #[test]
+    fn assigned_values_must_have_the_declared_type() {
+        assert_eq!(errors("fun f(){\n    let mut n: Int = 1\n    n = \"two\"\n}"), vec![
+            format!("This value has type String"),
+            format!("But n is declared as Int because of this"),
+        ]);
+    }
+}
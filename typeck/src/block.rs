@@ -0,0 +1,128 @@
+use super::{
+    Typeck,
+    Load,
+    Unload,
+    Check,
+    ty::GetTy,
+    nullable::narrow_guarded,
+};
+
+use stmt::{
+    Statement,
+    StatementKind,
+    block::Block,
+};
+
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+impl Load for Block{
+    type Output = Block;
+
+    ///Load a block, starting at its `Block` instruction and ending after its `EndBlock` instruction.
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let ins = chunk.read_instruction() as Option<HIRInstruction>;
+        if ins != Some(HIRInstruction::Block){
+            let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                .level(DiagnosticLevel::Error)
+                .message(format!("Expected a block but instead got {:?}", ins))
+                .build();
+            typeck.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diag_source]);
+            return Err(())
+        }
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let mut statements = vec![];
+        loop{
+            if let Some(HIRInstruction::EndBlock) = chunk.read_instruction() as Option<HIRInstruction>{
+                if let Err(msg) = chunk.read_pos(){
+                    let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                        .level(DiagnosticLevel::Error)
+                        .message(msg)
+                        .build();
+                    typeck.emit_diagnostic(&[], &[diag_source]);
+                    return Err(())
+                }
+                break;
+            }
+            chunk.dec_ins_ptr(1);
+            let statement = match Statement::load(chunk, typeck){
+                Ok(Some(statement)) => statement,
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            };
+            statements.push(statement);
+        }
+        Ok(Some(Block{
+            statements,
+            pos
+        }))
+    }
+}
+
+impl<'a> Check<'a> for Block{
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        typeck.scopes.push();
+        for statement in self.statements.iter(){
+            narrow_guarded(statement.pos, typeck);
+            if let Err(()) = statement.check(typeck).and_then(|_| typeck.check_inferred_literals()){
+                typeck.scopes.pop();
+                return Err(())
+            }
+        }
+        typeck.scopes.pop();
+        Ok(())
+    }
+}
+
+impl GetTy for Block{
+    ///A block evaluates to its trailing expression statement, or `Unit` if it doesn't end in one.
+    fn get_ty(&self) -> Ty {
+        match self.statements.last(){
+            Some(Statement{ kind: StatementKind::Expr(expr), .. }) => expr.get_ty(),
+            _ => Ty::new(TyKind::Unit, self.pos)
+        }
+    }
+}
+
+impl Unload for Block{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Block);
+        chunk.write_pos(self.pos);
+        for statement in self.statements.iter(){
+            match statement.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+        }
+        chunk.write_instruction(HIRInstruction::EndBlock);
+        Ok(chunk)
+    }
+}
@@ -0,0 +1,220 @@
+use super::{
+    Typeck,
+    Load,
+    Unload,
+    Check,
+    ty::GetTy,
+    lambda::expect,
+};
+
+use stmt::expr::{
+    Call,
+    Expr,
+    ExprElement,
+};
+
+use ty::{
+    Ty,
+    TyValue,
+    TyValueElement,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::{ReadInstruction, WriteInstruction};
+
+use std::cell::RefCell;
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+impl Load for Call{
+    type Output = Call;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let callee = match Expr::load(chunk, typeck){
+            Ok(Some(callee)) => callee,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let mut ty_args = vec![];
+        while let Some(HIRInstruction::FnParam) = chunk.read_instruction() as Option<HIRInstruction>{
+            match Ty::load(chunk, typeck){
+                Ok(Some(ty)) => ty_args.push(ty),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            }
+        }
+        chunk.dec_ins_ptr(1);
+        let mut args = vec![];
+        loop{
+            if let Some(HIRInstruction::EndCall) = chunk.read_instruction() as Option<HIRInstruction>{
+                break;
+            }
+            chunk.dec_ins_ptr(1);
+            match Expr::load(chunk, typeck){
+                Ok(Some(arg)) => args.push(arg),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            }
+        }
+        Ok(Some(Call{
+            callee,
+            ty_args,
+            args,
+            extension: RefCell::new(None),
+            pos
+        }))
+    }
+}
+
+impl<'a> Check<'a> for Call{
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        //A member may turn out to be an extension function, which can only be called
+        match self.callee.kind.as_ref(){
+            ExprElement::Member(receiver, member) => {
+                receiver.check(typeck)?;
+                let member = typeck.member(&receiver.get_ty(), member, self.callee.pos)?;
+                self.callee.ty.replace(member.ty.at(self.callee.pos));
+                self.extension.replace(member.extension);
+            }
+            _ => self.callee.check(typeck)?
+        }
+        if let ExprElement::Value(TyValue{ elem: TyValueElement::Ref(name), .. }) = self.callee.kind.as_ref(){
+            typeck.check_constructible(name, self.callee.pos)?;
+        }
+        if !self.ty_args.is_empty(){
+            typeck.apply_ty_args(&self.callee, &self.ty_args)?;
+        }
+        let callee_ty = callee_ty(self);
+        //A callee that is not inferred yet, such as a lambda param, is inferred to be a function that takes these args
+        if callee_ty.is_var(){
+            let params = self.args.iter().map(|arg| Ty::var(arg.pos)).collect::<Vec<Ty>>();
+            let fun_ty = Ty::function(&params, &Ty::var(self.pos), self.pos);
+            typeck.unify(&callee_ty, &fun_ty)?;
+        }
+        let (params, _) = match callee_ty.function_signature(){
+            Some(signature) => signature,
+            None => {
+                let diag_source = typeck.error_source(self.callee.pos, format!("This is not a function, it has type {}", callee_ty))?;
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        if params.len() != self.args.len(){
+            let call_source = typeck.error_source(self.pos, format!("This call passes {} arguments", self.args.len()))?;
+            let callee_source = typeck.error_source(self.callee.pos, format!("But {} takes {}", callee_ty, params.len()))?;
+            typeck.emit_diagnostic(&[], &[call_source, callee_source]);
+            return Err(())
+        }
+        for (arg, param) in self.args.iter().zip(params.iter()){
+            expect(arg, param);
+            arg.check(typeck)?;
+            if let Err(mismatch) = param.coerce(&typeck.upcast(param, &arg.get_ty())){
+                let found_message = format!("This argument has type {}", mismatch.found);
+                let expected_message = format!("But {} expects {} here", callee_ty, mismatch.expected);
+                return typeck.emit_mismatch(&mismatch, found_message, expected_message)
+            }
+        }
+        Ok(())
+    }
+}
+
+impl GetTy for Call{
+    ///The return type of the callee. Only meaningful once the call has been checked.
+    ///A safe call `x?.f()` is None when `x` is, so it returns a nullable type.
+    fn get_ty(&self) -> Ty {
+        let ret = match callee_ty(self).function_signature(){
+            Some((_, ret)) => ret.at(self.pos),
+            None => return Ty::var(self.pos)
+        };
+        match (self.callee.kind.as_ref(), ret.option_inner()){
+            (ExprElement::SafeAccess(..), None) => Ty::option(&ret, self.pos),
+            _ => ret
+        }
+    }
+}
+
+///The type of the function that is called. The callee of a safe call `x?.f()` is nullable, but it is only called when it is not None.
+fn callee_ty(call: &Call) -> Ty{
+    let ty = call.callee.get_ty();
+    match (call.callee.kind.as_ref(), ty.option_inner()){
+        (ExprElement::SafeAccess(..), Some(inner)) => inner,
+        _ => ty
+    }
+}
+
+impl Unload for Call{
+    ///TIR expressions start with their position rather than an instruction, so each arg is preceded by `true` and the args end with `false` instead of `EndCall`.
+    ///A call of an extension function is written as a call of the function it names, with the receiver as the first arg.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Call);
+        let receiver = match (&*self.extension.borrow(), self.callee.kind.as_ref()){
+            (Some(extension), ExprElement::Member(receiver, _)) => {
+                chunk.write_pos(self.callee.pos);
+                chunk.write_instruction(HIRInstruction::Reference);
+                chunk.write_string(extension.clone());
+                Some(receiver)
+            }
+            _ => {
+                match self.callee.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                None
+            }
+        };
+        for arg in receiver.into_iter().chain(self.args.iter()){
+            chunk.write_bool(true);
+            match arg.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+        }
+        chunk.write_bool(false);
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    #[test]
+    fn trailing_lambdas_are_the_last_argument() {
+        assert_eq!(errors("fun apply(x: Int, f: (Int) -> Int): Int = f(x)\nfun g(): Int = apply(2){ n -> n * 2 }"), Vec::<String>::new());
+        assert_eq!(errors("fun apply(x: Int, f: (Int) -> Int): Int = f(x)\nfun g(): Int = apply(2){ a, b -> a + b }"), vec![
+            format!("This lambda takes 2 params"),
+            format!("But a function of type (Int) -> Int is expected here"),
+        ]);
+    }
+
+    #[test]
+    fn calls_pass_as_many_arguments_as_the_callee_takes() {
+        assert_eq!(errors("fun g(){\n    val f = { n: Int -> n + 1 }\n    val x: Int = f(1, 2)\n}"), vec![
+            format!("This call passes 2 arguments"),
+            format!("But (Int) -> Int takes 1"),
+        ]);
+    }
+}
@@ -0,0 +1,138 @@
+use super::{
+    Typeck,
+    Load,
+    Unload,
+    Check,
+    ty::GetTy,
+    nullable::{
+        narrow,
+        check_narrowed,
+    },
+};
+
+use stmt::{
+    block::Block,
+    conditional::If,
+    expr::Expr,
+};
+
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+use std::cell::RefCell;
+
+impl Load for If{
+    type Output = If;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let cond = match Expr::load(chunk, typeck){
+            Ok(Some(cond)) => cond,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let then = match Block::load(chunk, typeck){
+            Ok(Some(block)) => block,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let otherwise = if chunk.read_bool(){
+            match Block::load(chunk, typeck){
+                Ok(Some(block)) => Some(block),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            }
+        }else{
+            None
+        };
+        Ok(Some(If{
+            cond,
+            then,
+            otherwise,
+            ty: RefCell::new(Ty::new(TyKind::Unit, pos)),
+            pos
+        }))
+    }
+}
+
+impl<'a> Check<'a> for If{
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        self.cond.check(typeck)?;
+        let cond_ty = self.cond.get_ty();
+        if Ty::new(TyKind::Bool, self.cond.pos).unify(&cond_ty).is_err(){
+            let diag_source = typeck.error_source(self.cond.pos, format!("The condition of an if must be Bool but instead found {}", cond_ty))?;
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        //A symbol that the condition narrows has the narrower type in the branch that the condition guards
+        let (when_true, when_false) = narrow(&self.cond, typeck);
+        check_narrowed(typeck, when_true, || self.then.check(typeck))?;
+        if let Some(otherwise) = &self.otherwise{
+            check_narrowed(typeck, when_false, || otherwise.check(typeck))?;
+            let then_ty = self.then.get_ty();
+            let otherwise_ty = otherwise.get_ty();
+            //When only one branch is nullable, the other is coerced into it
+            let (joined, other) = match (then_ty.option_inner(), otherwise_ty.option_inner()){
+                (None, Some(_)) => (otherwise_ty, then_ty),
+                _ => (then_ty, otherwise_ty)
+            };
+            //Objects of different classes join as the nearest class they have in common
+            let joined = typeck.common_class(&joined, &other).unwrap_or(joined);
+            //An if that is only used as a statement may have branches of different types
+            if joined.coerce(&typeck.upcast(&joined, &other)).is_ok(){
+                self.ty.replace(joined.at(self.pos));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Unload for If{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::If);
+        match self.cond.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        match self.then.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        match &self.otherwise{
+            Some(otherwise) => {
+                chunk.write_bool(true);
+                match otherwise.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+            }
+            None => chunk.write_bool(false)
+        }
+        Ok(chunk)
+    }
+}
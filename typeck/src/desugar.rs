@@ -0,0 +1,332 @@
+use super::{
+    modules::Module,
+    fun::result_ty,
+};
+
+use ty::{
+    Ty,
+    TyKind,
+    TyValue,
+    TyValueElement,
+};
+
+use stmt::{
+    Statement,
+    StatementKind,
+    block::Block,
+    conditional::If,
+    expr::{
+        Expr,
+        ExprElement,
+    },
+    fun::Fun,
+    structs::Class,
+};
+
+use core::pos::BiPos;
+
+use std::cell::RefCell;
+
+///Rewrites checked IR into the simpler forms that later stages understand. This runs after the check phase and before unloading.
+///
+///A guarded function such as
+///```norust
+///fun A(a: Int) = a + 1 where a > 0 else "a must be positive"
+///```
+///is rewritten into
+///```norust
+///fun A(a: Int): Result<Int, String>{
+///    if(a > 0){
+///        Ok(a + 1)
+///    }else{
+///        Err("a must be positive")
+///    }
+///}
+///```
+pub trait Desugar{
+    fn desugar(&mut self);
+}
+
+impl Desugar for Module{
+    fn desugar(&mut self) {
+        for statement in self.statements.iter_mut(){
+            statement.desugar();
+        }
+    }
+}
+
+impl Desugar for Statement{
+    fn desugar(&mut self) {
+        match &mut self.kind{
+            StatementKind::Fun(fun) => fun.desugar(),
+            StatementKind::Struct(structure) => {
+                for method in structure.methods.iter_mut(){
+                    method.desugar();
+                }
+                if let Some(Class{ base: Some(base), .. }) = &mut structure.class{
+                    for arg in base.args.iter_mut(){
+                        arg.desugar();
+                    }
+                }
+            }
+            StatementKind::Expr(expr) => expr.desugar(),
+            _ => {}
+        }
+    }
+}
+
+impl Desugar for Block{
+    fn desugar(&mut self) {
+        for statement in self.statements.iter_mut(){
+            statement.desugar();
+        }
+    }
+}
+
+impl Desugar for Expr{
+    fn desugar(&mut self) {
+        match self.kind.as_mut(){
+            ExprElement::Loop(loop_) => loop_.body.desugar(),
+            ExprElement::If(if_) => {
+                if_.then.desugar();
+                if let Some(otherwise) = if_.otherwise.as_mut(){
+                    otherwise.desugar();
+                }
+            }
+            ExprElement::Match(match_) => {
+                match_.scrutinee.desugar();
+                for arm in match_.arms.iter_mut(){
+                    arm.body.desugar();
+                }
+            }
+            ExprElement::Lambda(lambda) => lambda.body.desugar(),
+            ExprElement::Call(call) => {
+                call.callee.desugar();
+                for arg in call.args.iter_mut(){
+                    arg.desugar();
+                }
+            }
+            ExprElement::Cast(expr, _) | ExprElement::Is(expr, _) | ExprElement::NotNull(expr) | ExprElement::SafeAccess(expr, _)
+                | ExprElement::Member(expr, _) => expr.desugar(),
+            _ => {}
+        }
+    }
+}
+
+impl Desugar for Fun{
+    ///Nest the body in one if-else per guard, outermost first, so that the first guard that does not hold decides the failure message.
+    fn desugar(&mut self) {
+        self.body.desugar();
+        if self.guards.is_empty(){
+            return
+        }
+        let result = result_ty(&self.ty.borrow());
+        self.ty.replace(result.clone());
+        let mut body = self.body.clone();
+        wrap_ok(&mut body, &result);
+        if result == result_ty(&unit_ty(body.pos)) && !ends_in_value(&body){
+            body.statements.push(Statement{
+                pos: body.pos,
+                kind: StatementKind::Expr(ok(unit(body.pos), &result)),
+            });
+        }
+        for guard in self.guards.drain(..).rev(){
+            let err = Expr{
+                pos: guard.message.pos,
+                ty: RefCell::new(result.clone()),
+                kind: Box::new(ExprElement::ResultErr(guard.message)),
+            };
+            let if_ = If{
+                cond: guard.cond,
+                then: body,
+                otherwise: Some(Block{
+                    statements: vec![Statement{
+                        pos: guard.pos,
+                        kind: StatementKind::Expr(err),
+                    }],
+                    pos: guard.pos,
+                }),
+                ty: RefCell::new(result.clone()),
+                pos: guard.pos,
+            };
+            body = Block{
+                statements: vec![Statement{
+                    pos: guard.pos,
+                    kind: StatementKind::Expr(Expr{
+                        pos: guard.pos,
+                        ty: RefCell::new(result.clone()),
+                        kind: Box::new(ExprElement::If(if_)),
+                    }),
+                }],
+                pos: guard.pos,
+            };
+        }
+        self.body = body;
+    }
+}
+
+fn unit_ty(pos: BiPos) -> Ty{
+    Ty::new(TyKind::Unit, pos)
+}
+
+fn unit(pos: BiPos) -> Expr{
+    Expr{
+        kind: Box::new(ExprElement::Value(TyValue{
+            ty: unit_ty(pos),
+            elem: TyValueElement::Unit
+        })),
+        ty: RefCell::new(unit_ty(pos)),
+        pos
+    }
+}
+
+fn ok(value: Expr, result: &Ty) -> Expr{
+    Expr{
+        pos: value.pos,
+        ty: RefCell::new(result.clone()),
+        kind: Box::new(ExprElement::ResultOk(value)),
+    }
+}
+
+///Whether `block` ends in an expression that it evaluates to.
+fn ends_in_value(block: &Block) -> bool{
+    if let Some(Statement{ kind: StatementKind::Expr(expr), .. }) = block.statements.last(){
+        if let ExprElement::ResultOk(_) = expr.kind.as_ref(){
+            return true
+        }
+    }
+    false
+}
+
+///Wrap the value of every `return` in `block` in a `ResultOk`, along with the expression the block evaluates to if it is the function's value.
+///Nested functions are left alone, since their returns are their own.
+fn wrap_ok(block: &mut Block, result: &Ty){
+    if let Some(Statement{ kind: StatementKind::Expr(expr), .. }) = block.statements.last_mut(){
+        if result_ty(&expr.ty.borrow()) == *result{
+            let value = expr.clone();
+            *expr = ok(value, result);
+        }
+    }
+    wrap_returns(block, result);
+}
+
+fn wrap_returns(block: &mut Block, result: &Ty){
+    for statement in block.statements.iter_mut(){
+        match &mut statement.kind{
+            StatementKind::Return(ret) => {
+                let value = ret.value.take().unwrap_or_else(|| unit(ret.pos));
+                ret.value = Some(ok(value, result));
+            }
+            StatementKind::Expr(expr) => wrap_expr_returns(expr, result),
+            _ => {}
+        }
+    }
+}
+
+fn wrap_expr_returns(expr: &mut Expr, result: &Ty){
+    match expr.kind.as_mut(){
+        ExprElement::Loop(loop_) => wrap_returns(&mut loop_.body, result),
+        ExprElement::If(if_) => {
+            wrap_returns(&mut if_.then, result);
+            if let Some(otherwise) = if_.otherwise.as_mut(){
+                wrap_returns(otherwise, result);
+            }
+        }
+        ExprElement::Match(match_) => {
+            for arm in match_.arms.iter_mut(){
+                wrap_returns(&mut arm.body, result);
+            }
+        }
+        ExprElement::ResultOk(value) => wrap_expr_returns(value, result),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Typeck;
+
+    fn desugared(source: &str, name: &str) -> Fun{
+        let (module, errors) = Typeck::check_source(source);
+        assert_eq!(errors, Vec::<String>::new());
+        module.unwrap().statements.into_iter().find_map(|statement| match statement.kind{
+            StatementKind::Fun(fun) if fun.ident.ident == name => Some(fun),
+            _ => None
+        }).unwrap()
+    }
+
+    ///The expression that `block` evaluates to.
+    fn value(block: &Block) -> &ExprElement{
+        match block.statements.last().map(|statement| &statement.kind){
+            Some(StatementKind::Expr(expr)) => expr.kind.as_ref(),
+            _ => panic!("{:?} does not end in a value", block)
+        }
+    }
+
+    ///Split the if-else that `block` evaluates to into the block it takes when its guard holds, and the message it fails with otherwise.
+    fn guard(block: &Block) -> (&Block, &str){
+        let if_ = match value(block){
+            ExprElement::If(if_) => if_,
+            value => panic!("Expected a guard but found {:?}", value)
+        };
+        let message = match value(if_.otherwise.as_ref().unwrap()){
+            ExprElement::ResultErr(Expr{ kind, .. }) => match kind.as_ref(){
+                ExprElement::Value(TyValue{ elem: TyValueElement::String(message), .. }) => message.as_str(),
+                kind => panic!("Expected a message but found {:?}", kind)
+            },
+            value => panic!("Expected a failure but found {:?}", value)
+        };
+        (&if_.then, message)
+    }
+
+    #[test]
+    fn guards_nest_in_order_around_the_body() {
+        let fun = desugared("fun half(n: Int): Int{\n    n / 2\n} where n > 0 else \"negative\" where n < 100 else \"too big\"", "half");
+        assert!(fun.guards.is_empty());
+        assert_eq!(fun.ty.borrow().to_string(), "Result<Int, String>");
+        let (then, message) = guard(&fun.body);
+        assert_eq!(message, "\"negative\"");
+        let (then, message) = guard(then);
+        assert_eq!(message, "\"too big\"");
+        assert!(matches!(value(then), ExprElement::ResultOk(_)));
+    }
+
+    #[test]
+    fn returns_of_guarded_functions_succeed() {
+        let fun = desugared("fun clamp(n: Int): Int{\n    if(n > 10){ return 10 }\n    return n\n} where n > 0 else \"negative\"", "clamp");
+        let (then, _) = guard(&fun.body);
+        let returns = then.statements.iter().filter_map(|statement| match &statement.kind{
+            StatementKind::Return(ret) => ret.value.as_ref(),
+            _ => None
+        }).collect::<Vec<_>>();
+        assert_eq!(returns.len(), 1);
+        assert!(returns.iter().all(|value| matches!(value.kind.as_ref(), ExprElement::ResultOk(_))));
+        match &then.statements[0].kind{
+            StatementKind::Expr(Expr{ kind, .. }) => match kind.as_ref(){
+                ExprElement::If(if_) => assert!(matches!(&if_.then.statements[0].kind, StatementKind::Return(ret) if matches!(ret.value.as_ref().unwrap().kind.as_ref(), ExprElement::ResultOk(_)))),
+                kind => panic!("Expected an if but found {:?}", kind)
+            },
+            kind => panic!("Expected an if but found {:?}", kind)
+        }
+    }
+
+    #[test]
+    fn guarded_functions_without_a_value_succeed_with_unit() {
+        let fun = desugared("fun check(n: Int){\n    val m = n\n} where n > 0 else \"negative\"", "check");
+        assert_eq!(fun.ty.borrow().to_string(), "Result<Unit, String>");
+        let (then, _) = guard(&fun.body);
+        match value(then){
+            ExprElement::ResultOk(Expr{ kind, .. }) => assert!(matches!(kind.as_ref(), ExprElement::Value(TyValue{ elem: TyValueElement::Unit, .. }))),
+            value => panic!("Expected a success but found {:?}", value)
+        }
+    }
+
+    #[test]
+    fn functions_without_guards_are_left_alone() {
+        let fun = desugared("fun half(n: Int): Int = n / 2", "half");
+        assert_eq!(fun.ty.borrow().to_string(), "Int");
+        assert!(!matches!(value(&fun.body), ExprElement::ResultOk(_) | ExprElement::If(_)));
+    }
+}
@@ -0,0 +1,199 @@
+use super::{
+    Typeck,
+    Load,
+    Unload,
+    Check,
+    scope::Symbol,
+};
+
+use ident::Identifier;
+
+use ty::Ty;
+
+use stmt::enums::{
+    Enum,
+    Variant,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+use core::pos::BiPos;
+
+use std::collections::HashMap;
+
+impl Load for Enum{
+    type Output = Enum;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let ident = match Identifier::load(chunk, typeck){
+            Ok(Some(ident)) => ident,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let mut variants = vec![];
+        loop{
+            match chunk.read_instruction() as Option<HIRInstruction>{
+                Some(HIRInstruction::Variant) => match Variant::load(chunk, typeck){
+                    Ok(Some(variant)) => variants.push(variant),
+                    Ok(None) => return Ok(None),
+                    Err(notice) => return Err(notice)
+                },
+                Some(HIRInstruction::EndStruct) => break,
+                ins => {
+                    let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                        .level(DiagnosticLevel::Error)
+                        .message(format!("Expected a variant of an enum but instead got {:?}", ins))
+                        .build();
+                    typeck.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diag_source]);
+                    return Err(())
+                }
+            }
+        }
+        Ok(Some(Enum{
+            ident,
+            variants,
+            pos
+        }))
+    }
+}
+
+impl Load for Variant{
+    type Output = Variant;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let ident = match Identifier::load(chunk, typeck){
+            Ok(Some(ident)) => ident,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let mut fields = vec![];
+        while let Some(HIRInstruction::FnParam) = chunk.read_instruction() as Option<HIRInstruction>{
+            match Ty::load(chunk, typeck){
+                Ok(Some(field)) => fields.push(field),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            }
+        }
+        Ok(Some(Variant{
+            pos: ident.pos,
+            ident,
+            fields,
+        }))
+    }
+}
+
+impl<'a> Check<'a> for Enum{
+    ///The variants are declared along with the enum, so only where it is declared and the names of its variants are checked.
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        if !typeck.return_tys.borrow().is_empty(){
+            let diag_source = typeck.error_source(self.pos, "Enums can only be declared at the top level of a module".to_string())?;
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        let mut declared = HashMap::<&str, BiPos>::new();
+        for variant in self.variants.iter(){
+            if let Some(first) = declared.insert(&variant.ident.ident, variant.pos){
+                let duplicate_source = typeck.error_source(variant.pos, format!("{} already has a variant named {}", self.ident.ident, variant.ident.ident))?;
+                let first_source = typeck.error_source(first, format!("{} is first declared here", variant.ident.ident))?;
+                typeck.emit_diagnostic(&[], &[duplicate_source, first_source]);
+                return Err(())
+            }
+        }
+        Ok(())
+    }
+}
+
+///Register `enumeration` so that it can be matched on, and declare each of its variants in the innermost scope.
+///A variant with fields is a function that constructs it, and a variant without fields is a value of the enum.
+pub(crate) fn declare_enum(enumeration: &Enum, typeck: &Typeck){
+    typeck.enums.borrow_mut().insert(enumeration.ident.ident.clone(), enumeration.clone());
+    let enum_ty = Ty::named(vec![typeck.module_name.clone(), enumeration.ident.ident.clone()], &[], enumeration.ident.pos);
+    for variant in enumeration.variants.iter(){
+        let ty = match variant.fields.is_empty(){
+            true => enum_ty.clone(),
+            false => Ty::function(&variant.fields, &enum_ty, variant.pos),
+        };
+        typeck.scopes.declare(Symbol::immutable(variant.ident.clone(), ty));
+    }
+}
+
+impl Typeck{
+    ///The variants of `ty` along with the types of their fields, if it is an enum of this module, a `Result` or nullable.
+    ///A nullable value is matched as if it were an enum with the variants `None` and `Some`.
+    pub fn variants(&self, ty: &Ty) -> Option<Vec<(String, Vec<Ty>)>>{
+        let ty = ty.resolve();
+        if let Some(inner) = ty.option_inner(){
+            return Some(vec![
+                ("None".to_string(), vec![]),
+                ("Some".to_string(), vec![inner]),
+            ])
+        }
+        if let Some(args) = ty.app_args("Result"){
+            return Some(vec![
+                ("Ok".to_string(), vec![args[0].clone()]),
+                ("Err".to_string(), vec![args[1].clone()]),
+            ])
+        }
+        let (path, _) = ty.named_args()?;
+        match path.as_slice(){
+            [module, name] if *module == self.module_name => self.enums.borrow().get(name).map(|enumeration| {
+                enumeration.variants.iter().map(|variant| (variant.ident.ident.clone(), variant.fields.clone())).collect()
+            }),
+            _ => None
+        }
+    }
+}
+
+impl Unload for Enum{
+    ///Each variant is written with the types of its fields, since that is all that laying it out needs.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Enum);
+        chunk.write_pos(self.pos);
+        match self.ident.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        for variant in self.variants.iter(){
+            chunk.write_instruction(HIRInstruction::Variant);
+            match variant.ident.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+            for field in variant.fields.iter(){
+                chunk.write_instruction(HIRInstruction::FnParam);
+                match field.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+            }
+            chunk.write_instruction(HIRInstruction::EndParams);
+        }
+        chunk.write_instruction(HIRInstruction::EndStruct);
+        Ok(chunk)
+    }
+}
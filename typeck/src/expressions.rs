@@ -1,13 +1,30 @@
 use super::{
     ty::GetTy,
+    fun::result_ty,
+    unify::Obligation,
+    nullable::{
+        check_safe_access,
+        check_not_null,
+        check_elvis,
+        check_is,
+        narrow,
+        check_narrowed,
+    },
     Typeck,
     Load,
     Unload,
+    Check,
 };
 
-use ty::{ Ty, TyValue, TyValueElement };
+use ty::{ Ty, TyKind, TyValue, TyValueElement };
 
-use expr::{ Expr, ExprElement, OpKind };
+use stmt::{
+    expr::{ Expr, ExprElement, OpKind, Call },
+    lambda::Lambda,
+    loops::Loop,
+    conditional::If,
+    pattern::Match,
+};
 
 use ir::{
     Chunk,
@@ -24,10 +41,13 @@ use notices::{
     DiagnosticSourceBuilder,
 };
 
+use core::pos::BiPos;
+
+use std::cell::RefCell;
 
 impl GetTy for Expr{
-    fn get_ty(&self) -> &Ty {
-        self.kind.get_ty()
+    fn get_ty(&self) -> Ty {
+        self.ty.borrow().clone()
     }
 }
 
@@ -39,7 +59,7 @@ impl Unload for Expr{
             Ok(ch) => chunk.write_chunk(ch),
             Err(notice) => return Err(notice)
         }
-        
+
         Ok(chunk)
     }
 }
@@ -82,12 +102,80 @@ impl Unload for ExprElement{
                 chunk.write_chunk(right_chunk);
                 Ok(chunk)
             }
+            ExprElement::Loop(loop_) => loop_.unload(),
+            ExprElement::If(if_) => if_.unload(),
+            ExprElement::Match(match_) => match_.unload(),
+            ExprElement::Lambda(lambda) => lambda.unload(),
+            ExprElement::Call(call) => call.unload(),
+            ExprElement::Cast(expr, ty) => {
+                let mut chunk = Chunk::new();
+                chunk.write_instruction(HIRInstruction::Cast);
+                match expr.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                match ty.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                Ok(chunk)
+            }
+            //The type of the receiver follows the member, so that the class whose vtable a method is dispatched through is known
+            ExprElement::SafeAccess(receiver, member) | ExprElement::Member(receiver, member) => {
+                let mut chunk = Chunk::new();
+                match self{
+                    ExprElement::Member(..) => chunk.write_instruction(HIRInstruction::Member),
+                    _ => chunk.write_instruction(HIRInstruction::SafeAccess),
+                }
+                match receiver.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                chunk.write_string(member.clone());
+                match receiver.get_ty().unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                Ok(chunk)
+            }
+            ExprElement::NotNull(operand) => {
+                let mut chunk = Chunk::new();
+                chunk.write_instruction(HIRInstruction::NotNull);
+                match operand.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                Ok(chunk)
+            }
+            ExprElement::Is(expr, ty) => {
+                let mut chunk = Chunk::new();
+                chunk.write_instruction(HIRInstruction::Is);
+                match expr.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                match ty.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                Ok(chunk)
+            }
+            ExprElement::ResultOk(value) | ExprElement::ResultErr(value) => {
+                let mut chunk = Chunk::new();
+                match self{
+                    ExprElement::ResultOk(_) => chunk.write_instruction(HIRInstruction::ResultOk),
+                    _ => chunk.write_instruction(HIRInstruction::ResultErr),
+                }
+                match value.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                Ok(chunk)
+            }
         }
     }
 }
 
-
-
 impl Unload for OpKind{
     fn unload(&self) -> Result<Chunk, ()> {
         let mut chunk = Chunk::new();
@@ -95,23 +183,282 @@ impl Unload for OpKind{
             OpKind::Add => chunk.write_instruction(HIRInstruction::Add),
             OpKind::Min => chunk.write_instruction(HIRInstruction::Sub),
             OpKind::Div => chunk.write_instruction(HIRInstruction::Div),
-            OpKind::Mul => chunk.write_instruction(HIRInstruction::Mult)
+            OpKind::Mul => chunk.write_instruction(HIRInstruction::Mult),
+            OpKind::Lt => chunk.write_instruction(HIRInstruction::Less),
+            OpKind::Gt => chunk.write_instruction(HIRInstruction::Greater),
+            OpKind::LtEq => chunk.write_instruction(HIRInstruction::LessEq),
+            OpKind::GtEq => chunk.write_instruction(HIRInstruction::GreaterEq),
+            OpKind::Eq => chunk.write_instruction(HIRInstruction::Eq),
+            OpKind::NotEq => chunk.write_instruction(HIRInstruction::NotEq),
+            OpKind::And => chunk.write_instruction(HIRInstruction::And),
+            OpKind::Or => chunk.write_instruction(HIRInstruction::Or),
+            OpKind::Not => chunk.write_instruction(HIRInstruction::Not),
+            OpKind::Neg => chunk.write_instruction(HIRInstruction::Neg),
+            OpKind::Range => chunk.write_instruction(HIRInstruction::Range),
+            OpKind::Elvis => chunk.write_instruction(HIRInstruction::Elvis),
+            OpKind::Ref => chunk.write_instruction(HIRInstruction::Ref),
+            OpKind::Move => chunk.write_instruction(HIRInstruction::Move),
+            OpKind::Copy => chunk.write_instruction(HIRInstruction::Copy),
         }
         Ok(chunk)
     }
 }
 
-impl GetTy for ExprElement{
-    fn get_ty(&self) -> &Ty{
-        match self{
-            Self::Grouped(expr) => {
+impl<'a> Check<'a> for Expr{
+    ///Check the expression and its subexpressions bottom up, giving each of them their type.
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        let ty = match self.kind.as_ref(){
+            ExprElement::Grouped(expr) => {
+                expr.check(typeck)?;
                 expr.get_ty()
             }
-            Self::Value(t) => &t.ty,
-            Self::UnaryOp(_, left) => &left.ty,
-            Self::Binary(_, left, _) => &left.ty
+            ExprElement::Value(TyValue{ elem: TyValueElement::Ref(name), .. }) => match typeck.scopes.lookup(name){
+                Some(symbol) => typeck.instantiate(&symbol, self.pos),
+                None => {
+                    let diag_source = typeck.error_source(self.pos, format!("Unresolved reference: {}", name))?;
+                    typeck.emit_diagnostic(&[], &[diag_source]);
+                    return Err(())
+                }
+            },
+            ExprElement::Value(value) => {
+                //Number literals take the width that their context expects, which is only known once inference is done
+                if let TyValueElement::Integer(_) | TyValueElement::Float(_) = value.elem{
+                    typeck.defer(Obligation::Literal{
+                        value: value.elem.clone(),
+                        ty: value.ty.clone(),
+                        pos: self.pos
+                    });
+                }
+                value.ty.clone()
+            }
+            ExprElement::UnaryOp(op, operand) => {
+                operand.check(typeck)?;
+                check_unary(op, operand, self.pos, typeck)?
+            }
+            ExprElement::Binary(op, left, right) => {
+                left.check(typeck)?;
+                //The right side of `&&` is only evaluated when the left side is true, and the right side of `||` when it is false
+                let narrowed = match op{
+                    OpKind::And => narrow(left, typeck).0,
+                    OpKind::Or => narrow(left, typeck).1,
+                    _ => vec![]
+                };
+                check_narrowed(typeck, narrowed, || right.check(typeck))?;
+                check_binary(op, left, right, self.pos, typeck)?
+            }
+            ExprElement::Loop(loop_) => {
+                loop_.check(typeck)?;
+                loop_.ty.borrow().clone()
+            }
+            ExprElement::If(if_) => {
+                if_.check(typeck)?;
+                if_.ty.borrow().clone()
+            }
+            ExprElement::Match(match_) => {
+                match_.check(typeck)?;
+                match_.ty.borrow().clone()
+            }
+            ExprElement::Lambda(lambda) => {
+                lambda.check(typeck)?;
+                lambda.ty.borrow().clone()
+            }
+            ExprElement::Call(call) => {
+                call.check(typeck)?;
+                call.get_ty()
+            }
+            ExprElement::Cast(expr, ty) => {
+                expr.check(typeck)?;
+                let from = expr.get_ty();
+                //A literal is only known to be a number until inference is done
+                if from.is_var(){
+                    typeck.defer(Obligation::Cast{
+                        from,
+                        to: ty.clone(),
+                        pos: self.pos
+                    });
+                }else{
+                    typeck.check_cast(&from, ty, self.pos)?;
+                }
+                ty.at(self.pos)
+            }
+            ExprElement::SafeAccess(receiver, member) => {
+                receiver.check(typeck)?;
+                check_safe_access(receiver, member, self.pos, typeck)?
+            }
+            ExprElement::Member(receiver, member) => {
+                receiver.check(typeck)?;
+                typeck.member_ty(&receiver.get_ty(), member, self.pos)?
+            }
+            ExprElement::NotNull(operand) => {
+                operand.check(typeck)?;
+                check_not_null(operand, self.pos, typeck)?
+            }
+            ExprElement::Is(expr, ty) => {
+                expr.check(typeck)?;
+                check_is(expr, ty, self.pos, typeck)?
+            }
+            ExprElement::ResultOk(value) => {
+                value.check(typeck)?;
+                result_ty(&value.get_ty())
+            }
+            //The success type of a failure cannot be known from the message, so it keeps the type it was given when desugared
+            ExprElement::ResultErr(message) => {
+                message.check(typeck)?;
+                self.get_ty()
+            }
+        };
+        self.ty.replace(ty);
+        Ok(())
+    }
+}
+
+fn op_symbol(op: &OpKind) -> &'static str{
+    match op{
+        OpKind::Add => "+",
+        OpKind::Min | OpKind::Neg => "-",
+        OpKind::Mul => "*",
+        OpKind::Div => "/",
+        OpKind::Lt => "<",
+        OpKind::Gt => ">",
+        OpKind::LtEq => "<=",
+        OpKind::GtEq => ">=",
+        OpKind::Eq => "==",
+        OpKind::NotEq => "!=",
+        OpKind::And => "&&",
+        OpKind::Or => "||",
+        OpKind::Not => "!",
+        OpKind::Range => "..",
+        OpKind::Elvis => "?:",
+        OpKind::Ref => "ref",
+        OpKind::Move => "move",
+        OpKind::Copy => "copy",
+    }
+}
+
+fn check_unary(op: &OpKind, operand: &Expr, pos: BiPos, typeck: &Typeck) -> Result<Ty, ()>{
+    let operand_ty = operand.get_ty();
+    let valid = match op{
+        OpKind::Ref | OpKind::Move | OpKind::Copy => return check_explicit(op, operand, pos, typeck),
+        OpKind::Not => return typeck.unify(&Ty::new(TyKind::Bool, pos), &operand_ty).map(|_| operand_ty.at(pos)),
+        //The operand may still be inferred to be numeric later on
+        _ if operand_ty.is_var() => {
+            typeck.defer(Obligation::Operand{
+                op: op_symbol(op),
+                ty: operand_ty.clone(),
+                allows_string: false,
+                pos
+            });
+            true
         }
+        //Unsigned integers cannot be negated
+        _ => operand_ty.is_numeric() && operand_ty.int_bounds().is_none_or(|(min, _)| min < 0),
+    };
+    if !valid{
+        let op_source = typeck.error_source(pos, format!("Operator '{}' cannot be applied to type {}", op_symbol(op), operand_ty))?;
+        let operand_source = typeck.error_source(operand.pos, format!("This has type {}", operand_ty))?;
+        let notes = match operand_ty.option_inner(){
+            Some(_) => vec![nullable_note()],
+            None => vec![]
+        };
+        typeck.emit_diagnostic(&notes, &[op_source, operand_source]);
+        return Err(())
     }
+    Ok(operand_ty.at(pos))
+}
+
+///Check `ref x`, `move x` or `copy x`, which can only be applied to the name of an object.
+///A reference to `x` has the type `r(x)`, which binds to `x`, while a move or a copy of `x` has the type of `x`.
+fn check_explicit(op: &OpKind, operand: &Expr, pos: BiPos, typeck: &Typeck) -> Result<Ty, ()>{
+    let name = match operand.kind.as_ref(){
+        ExprElement::Value(TyValue{ elem: TyValueElement::Ref(name), .. }) => name,
+        _ => {
+            let op_source = typeck.error_source(pos, format!("Operator '{}' can only be applied to the name of an object", op_symbol(op)))?;
+            let operand_source = typeck.error_source(operand.pos, format!("This is not the name of an object"))?;
+            typeck.emit_diagnostic(&[
+                format!("Store the value in a property or a local first, then apply '{}' to its name.", op_symbol(op))
+            ], &[op_source, operand_source]);
+            return Err(())
+        }
+    };
+    match op{
+        OpKind::Ref => Ok(Ty::new(TyKind::Ref(name.clone()), pos)),
+        _ => Ok(operand.get_ty().at(pos))
+    }
+}
+
+fn check_binary(op: &OpKind, left: &Expr, right: &Expr, pos: BiPos, typeck: &Typeck) -> Result<Ty, ()>{
+    let left_ty = left.get_ty();
+    let right_ty = right.get_ty();
+    match op{
+        OpKind::And | OpKind::Or => {
+            typeck.unify(&Ty::new(TyKind::Bool, pos), &left_ty)?;
+            typeck.unify(&Ty::new(TyKind::Bool, pos), &right_ty)?;
+            return Ok(Ty::new(TyKind::Bool, pos))
+        }
+        OpKind::Range => {
+            typeck.unify(&Ty::new(TyKind::Int, pos), &left_ty)?;
+            typeck.unify(&Ty::new(TyKind::Int, pos), &right_ty)?;
+            return Ok(Ty::app("Range", &[left_ty], pos))
+        }
+        OpKind::Elvis => return check_elvis(left, right, pos, typeck),
+        _ => {}
+    }
+    //A literal only takes the type of the other operand if that is a number, so that `n + 1` with a nullable `n` is blamed on `n`
+    if !right_ty.is_var() && !right_ty.is_numeric(){
+        typeck.default_literal(&left_ty)?;
+    }
+    if !left_ty.is_var() && !left_ty.is_numeric(){
+        typeck.default_literal(&right_ty)?;
+    }
+    if let Err(mismatch) = left_ty.unify(&right_ty){
+        if mismatch.infinite{
+            return typeck.emit_mismatch(&mismatch, String::new(), String::new()).map(|_| left_ty)
+        }
+        let op_source = typeck.error_source(pos, format!("Operator '{}' cannot be applied to types {} and {}", op_symbol(op), mismatch.expected, mismatch.found))?;
+        let left_source = typeck.error_source(mismatch.expected.pos, format!("This has type {}", mismatch.expected))?;
+        let right_source = typeck.error_source(mismatch.found.pos, format!("This has type {}", mismatch.found))?;
+        let notes = if mismatch.expected.is_numeric() && mismatch.found.is_numeric(){
+            vec![format!("Numbers are never converted implicitly. Convert one of them with `as`, like `x as {}`.", mismatch.expected)]
+        }else if mismatch.expected.option_inner().is_some() || mismatch.found.option_inner().is_some(){
+            vec![nullable_note()]
+        }else{
+            vec![]
+        };
+        typeck.emit_diagnostic(&notes, &[op_source, left_source, right_source]);
+        return Err(())
+    }
+    let allows_string = matches!(op, OpKind::Add);
+    let result = match op{
+        OpKind::Eq | OpKind::NotEq => return Ok(Ty::new(TyKind::Bool, pos)),
+        OpKind::Add | OpKind::Min | OpKind::Mul | OpKind::Div => left_ty.at(pos),
+        _ => Ty::new(TyKind::Bool, pos),
+    };
+    //The operands may still be inferred to be numeric later on
+    if left_ty.is_var(){
+        typeck.defer(Obligation::Operand{
+            op: op_symbol(op),
+            ty: left_ty,
+            allows_string,
+            pos
+        });
+        return Ok(result)
+    }
+    if left_ty.is_numeric() || (allows_string && *left_ty.kind() == TyKind::String){
+        return Ok(result)
+    }
+    let op_source = typeck.error_source(pos, format!("Operator '{}' cannot be applied to types {} and {}", op_symbol(op), left_ty, right_ty))?;
+    let left_source = typeck.error_source(left.pos, format!("This has type {}", left_ty))?;
+    let right_source = typeck.error_source(right.pos, format!("This has type {}", right_ty))?;
+    let notes = match left_ty.option_inner(){
+        Some(_) => vec![nullable_note()],
+        None => vec![]
+    };
+    typeck.emit_diagnostic(&notes, &[op_source, left_source, right_source]);
+    Err(())
+}
+
+fn nullable_note() -> String{
+    format!("A nullable value has to be checked for None before it can be used, with `if (x != None)`, `x ?: default` or `x!!`.")
 }
 
 impl Load for Expr{
@@ -119,183 +466,202 @@ impl Load for Expr{
 
     fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
         let ins: Option<HIRInstruction> = chunk.read_instruction();
-        let pos = match chunk.read_pos(){
-            Ok(pos) => pos,
-            Err(msg) => {
-                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
-                    .message(msg)
-                    .level(DiagnosticLevel::Error)
-                    .build();
-                typeck.emit_diagnostic(&[], &[diag_source]);
-                return Err(())
-            }
-        };
-        match &ins {
-            Some(HIRInstruction::Bool) => {
-                let value = chunk.read_bool();
-                let ty = Ty{
-                    ident: "Bool".to_owned(),
-                    pos
+        match &ins{
+            Some(HIRInstruction::Loop) => {
+                let loop_ = match Loop::load(chunk, typeck){
+                    Ok(Some(loop_)) => loop_,
+                    Ok(None) => return Ok(None),
+                    Err(notice) => return Err(notice)
                 };
-                let kind = ExprElement::Value(TyValue{
-                    ty: ty.clone(),
-                    elem: TyValueElement::Bool(value),
-                });
+                let ty = loop_.ty.clone();
                 return Ok(Some(Expr{
-                    kind: Box::new(kind),
+                    pos: loop_.pos,
                     ty,
-                    pos
-                }));
+                    kind: Box::new(ExprElement::Loop(loop_)),
+                }))
             }
-            Some(HIRInstruction::Integer) => {
-                let value = chunk.read_int();
-                let ty = Ty{
-                    ident: "Integer".to_owned(),
-                    pos
+            Some(HIRInstruction::If) => {
+                let if_ = match If::load(chunk, typeck){
+                    Ok(Some(if_)) => if_,
+                    Ok(None) => return Ok(None),
+                    Err(notice) => return Err(notice)
                 };
-                let kind = ExprElement::Value(TyValue{
-                    elem: TyValueElement::Integer(value),
-                    ty: ty.clone(),
-                });
+                let ty = if_.ty.clone();
                 return Ok(Some(Expr{
-                    kind: Box::new(kind),
+                    pos: if_.pos,
                     ty,
-                    pos
-                }));
+                    kind: Box::new(ExprElement::If(if_)),
+                }))
             }
-            Some(HIRInstruction::Float) => {
-                let value = chunk.read_float();
-                let ty = Ty{
-                    ident: "Float".to_owned(),
-                    pos
+            Some(HIRInstruction::Match) => {
+                let match_ = match Match::load(chunk, typeck){
+                    Ok(Some(match_)) => match_,
+                    Ok(None) => return Ok(None),
+                    Err(notice) => return Err(notice)
                 };
-                let kind = ExprElement::Value(TyValue{
-                    elem: TyValueElement::Float(value),
-                    ty: ty.clone(),
-                });
+                let ty = match_.ty.clone();
                 return Ok(Some(Expr{
-                    kind: Box::new(kind),
+                    pos: match_.pos,
                     ty,
-                    pos
-                }));
+                    kind: Box::new(ExprElement::Match(match_)),
+                }))
             }
-            Some(HIRInstruction::String) => {
-                let value = chunk.read_string().to_owned();
-                let ty = Ty{
-                    ident: "String".to_owned(),
-                    pos
+            Some(HIRInstruction::Lambda) => {
+                let lambda = match Lambda::load(chunk, typeck){
+                    Ok(Some(lambda)) => lambda,
+                    Ok(None) => return Ok(None),
+                    Err(notice) => return Err(notice)
                 };
-                let kind = ExprElement::Value(TyValue{
-                    elem: TyValueElement::String(value),
-                    ty: ty.clone(),
-                });
+                let ty = lambda.ty.clone();
                 return Ok(Some(Expr{
-                    kind: Box::new(kind),
+                    pos: lambda.pos,
                     ty,
-                    pos
-                }));
+                    kind: Box::new(ExprElement::Lambda(lambda)),
+                }))
             }
-            Some(HIRInstruction::Add) => {
-                let left = match Expr::load(&chunk, typeck){
-                    Ok(Some(expr)) => {
-                        expr
-                    }
-                    Ok(None) => return Ok(None),
-                    Err(notice) => return Err(notice)
-                };
-                let right = match Expr::load(&chunk, typeck){
-                    Ok(Some(expr)) => {
-                        expr
-                    }
+            Some(HIRInstruction::Call) => {
+                let call = match Call::load(chunk, typeck){
+                    Ok(Some(call)) => call,
                     Ok(None) => return Ok(None),
                     Err(notice) => return Err(notice)
                 };
                 return Ok(Some(Expr{
-                    kind: Box::new(ExprElement::Binary(
-                        OpKind::Add,
-                        left.clone(),
-                        right,
-                    )),
-                    ty: left.ty,
-                    pos
+                    pos: call.pos,
+                    ty: RefCell::new(Ty::var(call.pos)),
+                    kind: Box::new(ExprElement::Call(call)),
                 }))
-            },
-            Some(HIRInstruction::Sub) => {
-                let left = match Expr::load(&chunk, typeck){
-                    Ok(Some(expr)) => {
-                        expr
-                    }
+            }
+            _ => {}
+        }
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .message(msg)
+                    .level(DiagnosticLevel::Error)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let binary_op = match &ins{
+            Some(HIRInstruction::Add) => Some(OpKind::Add),
+            Some(HIRInstruction::Sub) => Some(OpKind::Min),
+            Some(HIRInstruction::Mult) => Some(OpKind::Mul),
+            Some(HIRInstruction::Div) => Some(OpKind::Div),
+            Some(HIRInstruction::Less) => Some(OpKind::Lt),
+            Some(HIRInstruction::Greater) => Some(OpKind::Gt),
+            Some(HIRInstruction::LessEq) => Some(OpKind::LtEq),
+            Some(HIRInstruction::GreaterEq) => Some(OpKind::GtEq),
+            Some(HIRInstruction::Eq) => Some(OpKind::Eq),
+            Some(HIRInstruction::NotEq) => Some(OpKind::NotEq),
+            Some(HIRInstruction::And) => Some(OpKind::And),
+            Some(HIRInstruction::Or) => Some(OpKind::Or),
+            Some(HIRInstruction::Range) => Some(OpKind::Range),
+            Some(HIRInstruction::Elvis) => Some(OpKind::Elvis),
+            _ => None
+        };
+        if let Some(op) = binary_op{
+            let left = match Expr::load(chunk, typeck){
+                Ok(Some(expr)) => expr,
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            };
+            let right = match Expr::load(chunk, typeck){
+                Ok(Some(expr)) => expr,
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            };
+            return Ok(Some(Expr{
+                kind: Box::new(ExprElement::Binary(
+                    op,
+                    left,
+                    right,
+                )),
+                ty: RefCell::new(Ty::var(pos)),
+                pos
+            }))
+        }
+        let (elem, ty) = match &ins {
+            Some(HIRInstruction::Bool) => (TyValueElement::Bool(chunk.read_bool()), Ty::new(TyKind::Bool, pos)),
+            Some(HIRInstruction::Integer) => (TyValueElement::Integer(chunk.read_long()), Ty::var(pos)),
+            Some(HIRInstruction::Float) => (TyValueElement::Float(chunk.read_double()), Ty::var(pos)),
+            Some(HIRInstruction::Char) => (TyValueElement::Char(chunk.read_char()), Ty::new(TyKind::Char, pos)),
+            Some(HIRInstruction::String) => (TyValueElement::String(chunk.read_string().to_owned()), Ty::new(TyKind::String, pos)),
+            Some(HIRInstruction::Reference) => {
+                let term = chunk.read_string().to_owned();
+                (TyValueElement::Ref(term.clone()), Ty::new(TyKind::Ref(term), pos))
+            }
+            Some(HIRInstruction::None) => (TyValueElement::None, Ty::option(&Ty::var(pos), pos)),
+            Some(HIRInstruction::Cast) | Some(HIRInstruction::Is) => {
+                let expr = match Expr::load(chunk, typeck){
+                    Ok(Some(expr)) => expr,
                     Ok(None) => return Ok(None),
                     Err(notice) => return Err(notice)
                 };
-                let right = match Expr::load(&chunk, typeck){
-                    Ok(Some(expr)) => {
-                        expr
-                    }
+                let ty = match Ty::load(chunk, typeck){
+                    Ok(Some(ty)) => ty,
                     Ok(None) => return Ok(None),
                     Err(notice) => return Err(notice)
                 };
+                let kind = match ins{
+                    Some(HIRInstruction::Is) => ExprElement::Is(expr, ty),
+                    _ => ExprElement::Cast(expr, ty)
+                };
                 return Ok(Some(Expr{
-                    kind: Box::new(ExprElement::Binary(
-                        OpKind::Min,
-                        left.clone(),
-                        right,
-                    )),
-                    ty: left.ty,
+                    kind: Box::new(kind),
+                    ty: RefCell::new(Ty::var(pos)),
                     pos
                 }))
             }
-            Some(HIRInstruction::Mult) => {
-                let left = match Expr::load(&chunk, typeck){
-                    Ok(Some(expr)) => {
-                        expr
-                    }
+            Some(HIRInstruction::NotNull) => {
+                let operand = match Expr::load(chunk, typeck){
+                    Ok(Some(expr)) => expr,
                     Ok(None) => return Ok(None),
                     Err(notice) => return Err(notice)
                 };
-                let right = match Expr::load(&chunk, typeck){
-                    Ok(Some(expr)) => {
-                        expr
-                    }
+                return Ok(Some(Expr{
+                    kind: Box::new(ExprElement::NotNull(operand)),
+                    ty: RefCell::new(Ty::var(pos)),
+                    pos
+                }))
+            }
+            Some(HIRInstruction::SafeAccess) | Some(HIRInstruction::Member) => {
+                let receiver = match Expr::load(chunk, typeck){
+                    Ok(Some(expr)) => expr,
                     Ok(None) => return Ok(None),
                     Err(notice) => return Err(notice)
                 };
+                let member = chunk.read_string().to_owned();
+                let kind = match ins{
+                    Some(HIRInstruction::Member) => ExprElement::Member(receiver, member),
+                    _ => ExprElement::SafeAccess(receiver, member)
+                };
                 return Ok(Some(Expr{
-                    kind: Box::new(ExprElement::Binary(
-                        OpKind::Mul,
-                        left.clone(),
-                        right,
-                    )),
-                    ty: left.ty,
+                    kind: Box::new(kind),
+                    ty: RefCell::new(Ty::var(pos)),
                     pos
                 }))
             }
-            Some(HIRInstruction::Div) => {
-                let left = match Expr::load(&chunk, typeck){
-                    Ok(Some(expr)) => {
-                        expr
-                    }
-                    Ok(None) => return Ok(None),
-                    Err(notice) => return Err(notice)
+            Some(HIRInstruction::Not) | Some(HIRInstruction::Neg) | Some(HIRInstruction::Ref) | Some(HIRInstruction::Move) | Some(HIRInstruction::Copy) => {
+                let op = match ins{
+                    Some(HIRInstruction::Not) => OpKind::Not,
+                    Some(HIRInstruction::Neg) => OpKind::Neg,
+                    Some(HIRInstruction::Ref) => OpKind::Ref,
+                    Some(HIRInstruction::Move) => OpKind::Move,
+                    _ => OpKind::Copy
                 };
-                let right = match Expr::load(&chunk, typeck){
-                    Ok(Some(expr)) => {
-                        expr
-                    }
+                let operand = match Expr::load(chunk, typeck){
+                    Ok(Some(expr)) => expr,
                     Ok(None) => return Ok(None),
                     Err(notice) => return Err(notice)
                 };
                 return Ok(Some(Expr{
-                    kind: Box::new(ExprElement::Binary(
-                        OpKind::Div,
-                        left.clone(),
-                        right,
-                    )),
-                    ty: left.ty,
+                    kind: Box::new(ExprElement::UnaryOp(op, operand)),
+                    ty: RefCell::new(Ty::var(pos)),
                     pos
                 }))
             }
-            
             _ => {
                 let source = match typeck.request_source_snippet(pos){
                     Ok(source) => source,
@@ -305,7 +671,7 @@ impl Load for Expr{
                     }
                 };
                 let report = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
-                        .message(format!("Expected an expression but instead got instruction {:?}", ins.unwrap()))
+                        .message(format!("Expected an expression but instead got instruction {:?}", ins))
                         .level(DiagnosticLevel::Error)
                         .source(source)
                         .range(pos.col_range())
@@ -313,7 +679,14 @@ impl Load for Expr{
                 typeck.emit_diagnostic(&[], &[report]);
                 return Err(())
             }
-        }
+        };
+        Ok(Some(Expr{
+            kind: Box::new(ExprElement::Value(TyValue{
+                ty: ty.clone(),
+                elem,
+            })),
+            ty: RefCell::new(ty),
+            pos
+        }))
     }
 }
-
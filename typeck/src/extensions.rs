@@ -0,0 +1,158 @@
+use super::{
+    Typeck,
+    scope::{
+        Symbol,
+        Extension,
+    },
+    fun::fun_ty,
+    structs::Member,
+};
+
+use ident::Identifier;
+
+use ty::Ty;
+
+use stmt::fun::{
+    Fun,
+    FunParam,
+};
+
+use core::pos::BiPos;
+
+///The name that the extension function `name` of `receiver` is unloaded as, such as `String.shout`, so that the extensions of different types do not clash.
+pub(crate) fn extension_name(receiver: &Ty, name: &str) -> String{
+    format!("{}.{}", receiver, name)
+}
+
+///The param that the receiver of an extension function is passed as, which is how `this` is found in its body.
+pub(crate) fn this_param(receiver: &Ty, pos: BiPos) -> FunParam{
+    FunParam{
+        ident: Identifier{
+            ident: "this".to_string(),
+            pos
+        },
+        ty: receiver.clone(),
+        pos
+    }
+}
+
+///Declare the extension function `fun` in the innermost scope, so that it can be called on the values of its receiver type.
+pub(crate) fn declare_extension(fun: &Fun, receiver: &Ty, typeck: &Typeck){
+    typeck.scopes.declare_extension(Extension{
+        ident: fun.ident.clone(),
+        receiver: receiver.clone(),
+        ty: fun_ty(fun),
+    });
+}
+
+///Check what is particular to an extension function, which is that it has no type params and is not a method.
+///Within its body, `this` is its receiver.
+pub(crate) fn check_receiver(fun: &Fun, receiver: &Ty, typeck: &Typeck) -> Result<(), ()>{
+    if let Some(param) = fun.ty_params.first(){
+        let diag_source = typeck.error_source(param.ident.pos, "Extension functions cannot have type params".to_string())?;
+        typeck.emit_diagnostic(&[
+            format!("Declare a generic function that takes the {} as a param instead.", receiver)
+        ], &[diag_source]);
+        return Err(())
+    }
+    typeck.scopes.declare(Symbol::immutable(Identifier{
+        ident: "this".to_string(),
+        pos: receiver.pos
+    }, receiver.clone()));
+    Ok(())
+}
+
+///Check that `method` of a struct, class, interface or trait is not an extension function.
+pub(crate) fn check_method(method: &Fun, typeck: &Typeck) -> Result<(), ()>{
+    let receiver = match &method.receiver{
+        Some(receiver) => receiver,
+        None => return Ok(())
+    };
+    let diag_source = typeck.error_source(receiver.pos, "Methods cannot be extension functions".to_string())?;
+    typeck.emit_diagnostic(&[
+        format!("Declare it outside, like `fun {}.{}()`.", receiver, method.ident.ident)
+    ], &[diag_source]);
+    Err(())
+}
+
+impl Typeck{
+    ///The extension function `name` that is called on a value of type `ty` at `pos`, if one is in scope.
+    ///The innermost scope that has an extension for `ty` decides, and within it an extension of `ty` itself is preferred over one of a class it inherits from,
+    ///an interface it implements, or its nullable type. Two extensions that are equally preferred are ambiguous.
+    pub(crate) fn extension_member(&self, ty: &Ty, name: &str, pos: BiPos) -> Result<Option<Member>, ()>{
+        for extensions in self.scopes.extensions(name){
+            let (exact, inexact): (Vec<Extension>, Vec<Extension>) = extensions.into_iter().filter(|extension| self.receives(&extension.receiver, ty)).partition(|extension| extension.receiver == *ty);
+            let candidates = match exact.is_empty(){
+                true => inexact,
+                false => exact
+            };
+            match candidates.as_slice(){
+                [] => continue,
+                [extension] => return Ok(Some(Member{
+                    ident: extension.ident.clone(),
+                    ty: extension.ty.clone(),
+                    mutable: None,
+                    extension: Some(extension_name(&extension.receiver, name)),
+                })),
+                _ => {
+                    let mut sources = vec![self.error_source(pos, format!("This call of {} on {} is ambiguous", name, ty))?];
+                    for extension in candidates.iter(){
+                        sources.push(self.error_source(extension.ident.pos, format!("It could call the extension of {} declared here", extension.receiver))?);
+                    }
+                    self.emit_diagnostic(&[
+                        "Remove all but one of these extensions, or declare one of them in a scope closer to the call.".to_string()
+                    ], &sources);
+                    return Err(())
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    ///Whether a value of type `ty` may be the receiver of an extension of `receiver`.
+    fn receives(&self, receiver: &Ty, ty: &Ty) -> bool{
+        *receiver == *ty || receiver.option_inner().is_some_and(|inner| inner == *ty) || self.upcast(receiver, ty) == *receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    #[test]
+    fn extensions_are_called_on_their_receivers() {
+        assert_eq!(errors("fun String.shout(): String = this + \"!\"\nfun Int.double(): Int = this * 2\nfun Int?.orZero(): Int = this ?: 0\nfun f(a: Int): String{\n    val n: Int = 3.double() + (a + 1).double() + a.orZero()\n    return \"a\".shout()\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun String.shout(): String = this\nfun f(){\n    val n = 3.shout()\n}"), vec![
+            format!("Int has no member named shout"),
+        ]);
+    }
+
+    #[test]
+    fn members_of_the_type_come_before_its_extensions() {
+        assert_eq!(errors("struct Point(val x: Int){\n    fun sum(): Int = this.x\n}\nfun Point.sum(): String = \"sum\"\nfun f(p: Point): Int = p.sum()"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn extensions_of_the_type_itself_come_before_inherited_ones() {
+        assert_eq!(errors("open class Shape(val sides: Int)\nclass Square(val side: Int) : Shape(4)\nfun Shape.name(): String = \"shape\"\nfun Square.name(): Int = 4\nfun f(s: Square): Int = s.name()"), Vec::<String>::new());
+        assert_eq!(errors("fun Int?.name(): String = \"nullable\"\nfun Int.name(): Int = 4\nfun f(n: Int): Int = n.name()"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn extensions_in_closer_scopes_come_first() {
+        assert_eq!(errors("fun Int.twice(): String = \"twice\"\nfun f(n: Int): Int{\n    fun Int.twice(): Int = this * 2\n    return n.twice()\n}"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn equally_preferred_extensions_are_ambiguous() {
+        assert_eq!(errors("open class Shape(val sides: Int)\nclass Square(val side: Int) : Shape(4)\nfun Shape.corners(): Int = 1\nfun Int.corners(): Int = 0\nfun Shape.corners(): Int = 2\nfun f(s: Square): Int = s.corners()"), vec![
+            format!("This call of corners on test::Square is ambiguous"),
+            format!("It could call the extension of test::Shape declared here"),
+            format!("It could call the extension of test::Shape declared here"),
+        ]);
+    }
+}
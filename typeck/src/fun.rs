@@ -1,17 +1,40 @@
 use super::{
     Check,
+    scope::Symbol,
+    ty::GetTy,
+    lambda::expect,
+    extensions::{
+        declare_extension,
+        check_receiver,
+        extension_name,
+        this_param,
+    },
 };
 
 use ident::Identifier;
-use ty::Ty;
+use ty::{
+    Ty,
+    TyKind,
+};
 use stmt::{
     Statement,
+    StatementKind,
+    block::Block,
+    expr::Expr,
     fun::{
         Fun,
-        FunParam
+        FunParam,
+        Guard,
+        Return,
     },
+    generic::TyParam,
+    structs::Modifier,
 };
 
+use dataflow::tir::Flow;
+
+use std::cell::RefCell;
+
 use ir::{
     Chunk,
 };
@@ -49,15 +72,157 @@ impl Unload for FunParam{
 
 impl<'a> Check<'a> for Fun{
     fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
-        for statement in self.body.iter(){
-            if let Err(notice) = statement.check(typeck){
-                return Err(notice)
-            }
+        typeck.check_ty_params(self)?;
+        typeck.with_ty_params(&self.ty_params, || check_fun(self, typeck))
+    }
+}
+
+///Check `fun` with its type params in scope.
+fn check_fun(fun: &Fun, typeck: &Typeck) -> Result<(), ()>{
+    //Declared up front so that the function can call itself
+    declare_fun(fun, typeck);
+    typeck.scopes.push();
+    if let Some(receiver) = &fun.receiver{
+        if let Err(()) = check_receiver(fun, receiver, typeck){
+            typeck.scopes.pop();
+            return Err(())
+        }
+    }
+    for param in fun.params.iter(){
+        typeck.scopes.declare(Symbol::immutable(param.ident.clone(), param.ty.clone()));
+    }
+    for guard in fun.guards.iter(){
+        if let Err(()) = guard.check(typeck){
+            typeck.scopes.pop();
+            return Err(())
+        }
+    }
+    let loops = typeck.loops.suspend();
+    typeck.return_tys.borrow_mut().push(fun.ty.borrow().clone());
+    typeck.smart_casts.enter(&fun.body);
+    let result = fun.body.check(typeck);
+    typeck.smart_casts.exit();
+    typeck.return_tys.borrow_mut().pop();
+    typeck.loops.resume(loops);
+    typeck.scopes.pop();
+    result?;
+    check_return_ty(fun, typeck)?;
+    //Declared again now that an inferred return type is known
+    declare_fun(fun, typeck);
+    Ok(())
+}
+
+///Check that the body of `fun` agrees with its return type, or infer the return type from the body if it has none.
+fn check_return_ty(fun: &Fun, typeck: &Typeck) -> Result<(), ()>{
+    let ret_ty = fun.ty.borrow().clone();
+    let body_ty = fun.body.get_ty();
+    if ret_ty.is_var(){
+        return typeck.unify(&ret_ty, &body_ty)
+    }
+    if *ret_ty.kind() == TyKind::Unit || always_returns(&fun.body){
+        return Ok(())
+    }
+    let mismatch = match ret_ty.coerce(&typeck.upcast(&ret_ty, &body_ty)){
+        Ok(()) => return Ok(()),
+        Err(mismatch) => mismatch
+    };
+    //A trailing expression of the wrong type was most likely meant to be the return value
+    if let Some(Statement{ kind: StatementKind::Expr(_), .. }) = fun.body.statements.last(){
+        if *body_ty.kind() != TyKind::Unit{
+            let found_message = format!("The function body evaluates to a value of type {}", mismatch.found);
+            let expected_message = format!("But the function is expected to return {}", mismatch.expected);
+            return typeck.emit_mismatch(&mismatch, found_message, expected_message)
+        }
+    }
+    let fun_source = typeck.error_source(fun.pos, format!("Function {} does not return a value on every path", fun.ident.ident))?;
+    let ty_source = typeck.error_source(ret_ty.pos, format!("Expected a return value of type {}", ret_ty))?;
+    typeck.emit_diagnostic(&[], &[fun_source, ty_source]);
+    Err(())
+}
+
+///Declare `fun` in the innermost scope so that it can be called. An extension function is declared as an extension of its receiver instead.
+pub(crate) fn declare_fun(fun: &Fun, typeck: &Typeck){
+    if let Some(receiver) = &fun.receiver{
+        return declare_extension(fun, receiver, typeck)
+    }
+    typeck.scopes.declare(Symbol{
+        ty_params: fun.ty_params.iter().map(|param| param.ident.ident.clone()).collect(),
+        ..Symbol::immutable(fun.ident.clone(), fun_ty(fun))
+    });
+}
+
+///The function type of `fun`. Guarded functions return a `Result` of their return type.
+pub(crate) fn fun_ty(fun: &Fun) -> Ty{
+    let params = fun.params.iter().map(|param| param.ty.clone()).collect::<Vec<Ty>>();
+    let ret = if fun.guards.is_empty(){
+        fun.ty.borrow().clone()
+    }else{
+        result_ty(&fun.ty.borrow())
+    };
+    Ty::function(&params, &ret, fun.pos)
+}
+
+impl<'a> Check<'a> for Guard{
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        self.cond.check(typeck)?;
+        self.message.check(typeck)?;
+        let cond_ty = self.cond.get_ty();
+        if Ty::new(TyKind::Bool, self.cond.pos).unify(&cond_ty).is_err(){
+            let diag_source = typeck.error_source(self.cond.pos, format!("The condition of a where guard must be a Bool, but this is {}", cond_ty))?;
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        let message_ty = self.message.get_ty();
+        if Ty::new(TyKind::String, self.message.pos).unify(&message_ty).is_err(){
+            let guard_source = typeck.error_source(self.pos, format!("This guard fails with a value of type {} instead of a message", message_ty))?;
+            let message_source = typeck.error_source(self.message.pos, format!("Expected a String but instead got {}", message_ty))?;
+            typeck.emit_diagnostic(&[], &[guard_source, message_source]);
+            return Err(())
         }
         Ok(())
     }
 }
 
+///The type of a guarded function that returns `ok` when all of its guards hold.
+pub(crate) fn result_ty(ok: &Ty) -> Ty{
+    Ty::app("Result", &[ok.clone(), Ty::new(TyKind::String, ok.pos)], ok.pos)
+}
+
+///Whether every path through `block` ends in a `return`, or never finishes at all.
+pub(crate) fn always_returns(block: &Block) -> bool{
+    !Flow::new(block).finishes()
+}
+
+impl<'a> Check<'a> for Return{
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        let expected = match typeck.return_tys.borrow().last(){
+            Some(ty) => ty.clone(),
+            None => {
+                let diag_source = typeck.error_source(self.pos, format!("'return' can only be used inside of a function"))?;
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let ty = match &self.value{
+            Some(value) => {
+                expect(value, &expected);
+                value.check(typeck)?;
+                value.get_ty()
+            }
+            None => Ty::new(TyKind::Unit, self.pos)
+        };
+        //The return type of an expression bodied function is inferred from its returns and its body
+        match expected.coerce(&typeck.upcast(&expected, &ty)){
+            Ok(()) => Ok(()),
+            Err(mismatch) => {
+                let found_message = format!("This returns a value of type {}", mismatch.found);
+                let expected_message = format!("But the function is expected to return {}", mismatch.expected);
+                typeck.emit_mismatch(&mismatch, found_message, expected_message)
+            }
+        }
+    }
+}
+
 impl Load for Fun{
     type Output = Fun;
 
@@ -73,11 +238,34 @@ impl Load for Fun{
                 return Err(())
             }
         };
+        let receiver = match chunk.read_instruction() as Option<HIRInstruction>{
+            Some(HIRInstruction::Receiver) => match Ty::load(chunk, typeck){
+                Ok(Some(receiver)) => Some(receiver),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            },
+            _ => {
+                chunk.dec_ins_ptr(1);
+                None
+            }
+        };
         let ident = match Identifier::load(chunk, typeck){
             Ok(Some(ident)) => ident,
             Ok(None) => return Ok(None),
             Err(msg) => return Err(msg)
         };
+        let mut ty_params = vec![];
+        while let Some(HIRInstruction::TyParam) = chunk.read_instruction() as Option<HIRInstruction>{
+            match TyParam::load(chunk, typeck){
+                Ok(Some(param)) => ty_params.push(param),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            }
+        }
+        chunk.dec_ins_ptr(1);
+        //The type params are only visible within the function, along with those of the struct it is a method of
+        let outer_ty_params = typeck.ty_params.borrow().clone();
+        typeck.ty_params.borrow_mut().extend(ty_params.iter().map(|param| param.ident.ident.clone()));
         let mut params = vec![];
         while let Some(ins) = chunk.read_instruction() as Option<HIRInstruction>{
             if ins == HIRInstruction::EndParams{
@@ -134,84 +322,40 @@ impl Load for Fun{
             Err(notice) => return Err(notice)
         };
 
-        let block_chunk = match typeck.chunk_rx.recv(){
-            Ok(Some(chunk)) => {
-                chunk
-            }
-            Ok(None) => {
-                let report = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
-                    .message(format!("Failed to get chunk from chunk channel."))
-                    .level(DiagnosticLevel::Error)
-                    .build();
-                typeck.emit_diagnostic(&[
-                    format!("The previous error should only have occurred during development. If you are a user then please notify the author.")
-                    ], 
-                    &[report]
-                );
-                return Err(())
-            }
-            Err(_) =>{
-                let report = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
-                    .message(format!("Failed to get chunk from chunk channel."))
-                    .level(DiagnosticLevel::Error)
-                    .build();
-                typeck.emit_diagnostic(&[
-                    format!("The previous error should only have occurred during development. If you are a user then please notify the author.")
-                    ], 
-                    &[report]
-                );
-                return Err(())
-            }
+        let block = match Block::load(chunk, typeck){
+            Ok(Some(block)) => block,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
         };
-        let block = block_chunk.read_instruction();
-        let mut block: Vec<Statement> = if let Some(HIRInstruction::Block) = block{
-            vec![]
-        }else{
-            let pos = match chunk.read_pos(){
-                Ok(pos) => pos,
-                Err(msg) => {
+        let mut guards = vec![];
+        loop{
+            match chunk.read_instruction() as Option<HIRInstruction>{
+                Some(HIRInstruction::EndFn) => break,
+                Some(HIRInstruction::Guard) => match Guard::load(chunk, typeck){
+                    Ok(Some(guard)) => guards.push(guard),
+                    Ok(None) => return Ok(None),
+                    Err(notice) => return Err(notice)
+                },
+                _ => {
                     let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
-                        .message(msg)
+                        .message(format!("Expected the end of the function {} after its body.", ident.ident))
                         .level(DiagnosticLevel::Error)
                         .build();
-                    typeck.emit_diagnostic(&[], &[diag_source]);
-                    return Err(())
-                }
-            };
-            let source = match typeck.request_source_snippet(pos){
-                Ok(source) => source,
-                Err(diag) => {
-                    typeck.emit_diagnostic(&[], &[diag]);
+                    typeck.emit_diagnostic(&[format!("This is a bug in the compiler.")], &[diag_source]);
                     return Err(())
                 }
-            };
-            let report = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
-                    .message(format!("Expected a function body but instead got {:?}.", block))
-                    .level(DiagnosticLevel::Error)
-                    .source(source)
-                    .range(pos.col_range())
-                    .build();
-            typeck.emit_diagnostic(&[], &[report]);
-            return Err(())
-        };
-        loop{
-            let next_chunk = typeck.chunk_rx.recv().unwrap().unwrap();
-            if let Some(HIRInstruction::EndBlock) = next_chunk.read_instruction(){
-                break;
             }
-            next_chunk.jump_to(0).unwrap();
-            let statement = match Statement::load(&next_chunk, typeck){
-                Ok(Some(statement)) => statement,
-                Ok(None) => return Ok(None),
-                Err(notice) => return Err(notice)
-            };
-            block.push(statement);
         }
+        typeck.ty_params.replace(outer_ty_params);
         let fun = Fun{
             ident,
-            ty: return_type,
+            receiver,
+            ty_params,
+            ty: RefCell::new(return_type),
             body: block,
             params,
+            guards,
+            modifier: Modifier::Final,
             pos
         };
         Ok(Some(fun))
@@ -219,19 +363,59 @@ impl Load for Fun{
 
 }
 
+impl Load for Guard{
+    type Output = Guard;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let cond = match Expr::load(chunk, typeck){
+            Ok(Some(cond)) => cond,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let message = match Expr::load(chunk, typeck){
+            Ok(Some(message)) => message,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        Ok(Some(Guard{
+            cond,
+            message,
+            pos
+        }))
+    }
+}
+
 impl Unload for Fun{
     fn unload(&self) -> Result<Chunk, ()> {
         let mut chunk = Chunk::new();
         chunk.write_instruction(HIRInstruction::Fn);
         chunk.write_pos(self.pos);
-        //Write the identifier
-        match self.ident.unload(){
+        //Write the identifier. An extension function is written as a function that takes its receiver as its first param
+        let (ident, receiver) = match &self.receiver{
+            Some(receiver) => (Identifier{
+                ident: extension_name(receiver, &self.ident.ident),
+                pos: self.ident.pos
+            }, Some(this_param(receiver, receiver.pos))),
+            None => (self.ident.clone(), None)
+        };
+        match ident.unload(){
             Ok(ch) => chunk.write_chunk(ch),
             Err(notice) => return Err(notice)
         }
 
         //Write the params information
-        for param in self.params.iter(){
+        for param in receiver.iter().chain(self.params.iter()){
             match param.unload(){
                 Ok(ch) => chunk.write_chunk(ch),
                 Err(notice) => return Err(notice)
@@ -239,20 +423,59 @@ impl Unload for Fun{
         }
         chunk.write_instruction(HIRInstruction::EndParams);
         //Write the return type information
-        match self.ty.unload(){
+        match self.ty.borrow().unload(){
             Ok(ch) => chunk.write_chunk(ch),
             Err(notice) => return Err(notice)
         }
         //Write the body
-        for statement in self.body.iter(){
-            match statement.unload(){
-                Ok(ch) => chunk.write_chunk(ch),
-                Err(notice) => return Err(notice)
-            }
+        match self.body.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
         }
 
         chunk.write_instruction(HIRInstruction::EndFn);
         
         Ok(chunk)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    #[test]
+    fn bodies_that_evaluate_to_the_wrong_type_are_mismatches() {
+        assert_eq!(errors("fun c(): Int = \"x\""), vec![
+            format!("The function body evaluates to a value of type String"),
+            format!("But the function is expected to return Int"),
+        ]);
+        assert_eq!(errors("fun c(): Int{\n    \"x\"\n}"), vec![
+            format!("The function body evaluates to a value of type String"),
+            format!("But the function is expected to return Int"),
+        ]);
+    }
+
+    #[test]
+    fn bodies_that_fall_through_do_not_return_a_value() {
+        assert_eq!(errors("fun c(b: Bool): Int{\n    if(b){ return 1 }\n}"), vec![
+            format!("Function c does not return a value on every path"),
+            format!("Expected a return value of type Int"),
+        ]);
+        assert_eq!(errors("fun c(b: Bool): Int{\n    if(b){ return 1 }else{ return 2 }\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun c(): Int = 1"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn bodies_that_return_on_every_path_of_their_control_flow_return() {
+        assert_eq!(errors("fun c(b: Bool): Int{\n    let x = if(b){ return 1 }else{ return 2 }\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun c(b: Bool): Int{\n    loop{\n        loop{ break }\n    }\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun c(b: Bool): Int{\n    while(b){ return 1 }\n}"), vec![
+            format!("Function c does not return a value on every path"),
+            format!("Expected a return value of type Int"),
+        ]);
+    }
+}
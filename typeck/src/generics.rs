@@ -0,0 +1,517 @@
+use super::{
+    Typeck,
+    Load,
+    scope::Symbol,
+};
+
+use ident::Identifier;
+
+use ty::{
+    Ty,
+    TyValue,
+    TyValueElement,
+};
+
+use stmt::{
+    Statement,
+    StatementKind,
+    block::Block,
+    expr::{
+        Expr,
+        ExprElement,
+    },
+    fun::Fun,
+    generic::TyParam,
+    structs::{
+        Struct,
+        Class,
+    },
+    pattern::{
+        Pattern,
+        PatternKind,
+    },
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::ReadInstruction;
+
+use core::pos::BiPos;
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+};
+
+///How many copies of one generic function may be made before it is assumed to instantiate itself with ever larger types.
+const MAX_SPECIALIZATIONS: usize = 64;
+
+///A reference to a generic function or to the constructor of a generic struct, with the type arguments that are inferred for it.
+#[derive(Debug, Clone)]
+pub struct Instance{
+    pub fun: String,
+    pub args: Vec<Ty>,
+    pub pos: BiPos,
+}
+
+impl Load for TyParam{
+    type Output = TyParam;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let ident = match Identifier::load(chunk, typeck){
+            Ok(Some(ident)) => ident,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let mut bounds = vec![];
+        while let Some(HIRInstruction::FnParam) = chunk.read_instruction() as Option<HIRInstruction>{
+            match Ty::load(chunk, typeck){
+                Ok(Some(bound)) => bounds.push(bound),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            }
+        }
+        Ok(Some(TyParam{
+            ident,
+            bounds,
+        }))
+    }
+}
+
+impl Typeck{
+    ///The type of `symbol` referenced at `pos`. A generic function gets a fresh type variable for each of its type params, which is recorded for [monomorphize].
+    pub fn instantiate(&self, symbol: &Symbol, pos: BiPos) -> Ty{
+        if symbol.ty_params.is_empty(){
+            return symbol.ty.at(pos)
+        }
+        let args = symbol.ty_params.iter().map(|param| (param.clone(), Ty::var(pos))).collect::<Vec<(String, Ty)>>();
+        self.instances.borrow_mut().push(Instance{
+            fun: symbol.ident.ident.clone(),
+            args: args.iter().map(|(_, arg)| arg.clone()).collect(),
+            pos
+        });
+        symbol.ty.substitute(&args).at(pos)
+    }
+
+    ///Infer the type params of the generic function that `callee` refers to as the type arguments `args` it is given, as in `id<Int>(3)`.
+    pub(crate) fn apply_ty_args(&self, callee: &Expr, args: &[Ty]) -> Result<(), ()>{
+        let symbol = match callee.kind.as_ref(){
+            ExprElement::Value(TyValue{ elem: TyValueElement::Ref(name), .. }) => self.scopes.lookup(name),
+            _ => None
+        };
+        let symbol = match symbol{
+            Some(symbol) if !symbol.ty_params.is_empty() => symbol,
+            _ => {
+                let diag_source = self.error_source(callee.pos, "This is not a generic function, so it cannot be given type arguments".to_string())?;
+                self.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        if symbol.ty_params.len() != args.len(){
+            let call_source = self.error_source(callee.pos, format!("This is given {} type arguments", args.len()))?;
+            let decl_source = self.error_source(symbol.ident.pos, format!("But {} takes {}", symbol.ident.ident, symbol.ty_params.len()))?;
+            self.emit_diagnostic(&[], &[call_source, decl_source]);
+            return Err(())
+        }
+        let instance = self.instances.borrow().iter().rev().find(|instance| instance.pos == callee.pos).cloned();
+        if let Some(instance) = instance{
+            for (param, arg) in instance.args.iter().zip(args.iter()){
+                self.unify(param, arg)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///Check that a generic function is declared at the top level of a module, and that the bounds of its type params name interfaces or traits.
+    pub(crate) fn check_ty_params(&self, fun: &Fun) -> Result<(), ()>{
+        if fun.ty_params.is_empty(){
+            return Ok(())
+        }
+        if !self.return_tys.borrow().is_empty(){
+            let diag_source = self.error_source(fun.pos, "Generic functions can only be declared at the top level of a module".to_string())?;
+            self.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        self.check_bounds(&fun.ty_params)
+    }
+
+    ///Check that the bounds of every one of `ty_params` name interfaces or traits.
+    pub(crate) fn check_bounds(&self, ty_params: &[TyParam]) -> Result<(), ()>{
+        for bound in ty_params.iter().flat_map(|param| param.bounds.iter()){
+            self.check_bound(bound)?;
+        }
+        Ok(())
+    }
+
+    ///Check that `bound` names an interface or a trait, with as many type arguments as it takes.
+    fn check_bound(&self, bound: &Ty) -> Result<(), ()>{
+        let (interface, args) = match self.interface_named(bound){
+            Some(found) => found,
+            None => {
+                let diag_source = self.error_source(bound.pos, format!("There is no interface or trait named {}", bound))?;
+                self.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        if args.len() != interface.ty_params.len(){
+            let bound_source = self.error_source(bound.pos, format!("This gives {} type arguments to {}", args.len(), interface.ident.ident))?;
+            let decl_source = self.error_source(interface.ident.pos, format!("But it takes {}", interface.ty_params.len()))?;
+            self.emit_diagnostic(&[], &[bound_source, decl_source]);
+            return Err(())
+        }
+        Ok(())
+    }
+}
+
+///A generic function or struct, which copies are made of.
+enum Template<'a>{
+    Fun(&'a Fun),
+    Struct(&'a Struct),
+}
+
+impl<'a> Template<'a>{
+    fn ty_params(&self) -> &'a [TyParam]{
+        match self{
+            Template::Fun(fun) => &fun.ty_params,
+            Template::Struct(structure) => &structure.ty_params,
+        }
+    }
+}
+
+///The generic function or struct that `statement` declares, if it declares one.
+fn template(statement: &Statement) -> Option<(String, Template<'_>)>{
+    match &statement.kind{
+        StatementKind::Fun(fun) if !fun.ty_params.is_empty() => Some((fun.ident.ident.clone(), Template::Fun(fun))),
+        StatementKind::Struct(structure) if !structure.ty_params.is_empty() => Some((structure.ident.ident.clone(), Template::Struct(structure))),
+        _ => None
+    }
+}
+
+///The name of what `statement` declares, if it declares a function or a struct.
+fn declared_name(statement: &Statement) -> Option<&str>{
+    match &statement.kind{
+        StatementKind::Fun(fun) => Some(&fun.ident.ident),
+        StatementKind::Struct(structure) => Some(&structure.ident.ident),
+        _ => None
+    }
+}
+
+///Replace every generic function and struct in the checked module with a copy of it for each combination of type arguments it is used with,
+///so that nothing after the typeck ever sees a type param. A generic function or struct that is never used is left out entirely.
+///Each copy is named after its type arguments, like `id<Int>` or `Box<Int>`, and every reference to it is renamed to the copy it uses.
+///The type of a value of a generic struct, such as `main::Box<Int>`, becomes the type of its copy, `main::Box<Int>` without any type arguments.
+pub fn monomorphize(typeck: &Typeck) -> Result<Vec<Statement>, ()>{
+    let instances = typeck.instances.replace(vec![]);
+    let templates = typeck.module_ir.statements.iter().filter_map(template).collect::<HashMap<String, Template>>();
+    let mut specializer = Specializer{
+        typeck,
+        instances,
+        templates: &templates,
+        args: vec![],
+        requests: vec![],
+    };
+    let mut statements = vec![];
+    for statement in typeck.module_ir.statements.iter(){
+        match template(statement){
+            Some(_) => statements.push(None),
+            None => {
+                let mut statement = statement.clone();
+                specializer.statement(&mut statement)?;
+                statements.push(Some(statement));
+            }
+        }
+    }
+    //Copies of generic functions and structs may use other generic functions and structs in turn
+    let mut specialized = HashMap::<String, Vec<Statement>>::new();
+    while let Some((name, args, pos)) = specializer.requests.pop(){
+        let template = &templates[&name];
+        let copies = specialized.entry(name.clone()).or_default();
+        let ident = mangle(&name, &args);
+        if copies.iter().any(|copy| declared_name(copy) == Some(&ident)){
+            continue
+        }
+        if copies.len() == MAX_SPECIALIZATIONS{
+            let diag_source = typeck.error_source(pos, format!("{} is used with too many different type arguments", name))?;
+            typeck.emit_diagnostic(&[
+                "This happens when a generic function calls itself with ever larger type arguments, such as a `T?` for its `T`.".to_string()
+            ], &[diag_source]);
+            return Err(())
+        }
+        let copy = match template{
+            Template::Fun(fun) => {
+                let mut fun = (*fun).clone();
+                fun.ident.ident = ident;
+                specializer.args = fun.ty_params.drain(..).map(|param| param.ident.ident).zip(args).collect();
+                specializer.fun(&mut fun)?;
+                Statement{
+                    pos: fun.pos,
+                    kind: StatementKind::Fun(fun),
+                }
+            }
+            Template::Struct(structure) => {
+                let mut structure = (*structure).clone();
+                structure.ident.ident = ident;
+                specializer.args = structure.ty_params.drain(..).map(|param| param.ident.ident).zip(args).collect();
+                specializer.structure(&mut structure)?;
+                Statement{
+                    pos: structure.pos,
+                    kind: StatementKind::Struct(structure),
+                }
+            }
+        };
+        copies.push(copy);
+    }
+    Ok(typeck.module_ir.statements.iter().zip(statements).flat_map(|(template, statement)| match statement{
+        Some(statement) => vec![statement],
+        None => declared_name(template).and_then(|name| specialized.remove(name)).unwrap_or_default(),
+    }).collect())
+}
+
+///The name of the copy of the generic function or struct `fun` for `args`.
+fn mangle(fun: &str, args: &[Ty]) -> String{
+    format!("{}<{}>", fun, args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>().join(", "))
+}
+
+///Substitutes the type arguments of one copy of a generic function or struct throughout it, and renames the references to generic functions and structs in it.
+struct Specializer<'a>{
+    typeck: &'a Typeck,
+    instances: Vec<Instance>,
+    templates: &'a HashMap<String, Template<'a>>,
+    ///The type argument for each type param of the function or struct being copied. Empty outside of generic ones.
+    args: Vec<(String, Ty)>,
+    ///The generic functions and structs that copies are needed of, with their type arguments and where they are first used.
+    requests: Vec<(String, Vec<Ty>, BiPos)>,
+}
+
+impl<'a> Specializer<'a>{
+    ///`ty` with the type arguments substituted, and with every generic struct it mentions replaced by the copy of it for its type arguments.
+    fn ty(&mut self, ty: &Ty) -> Result<Ty, ()>{
+        let templates = self.templates;
+        let module_name = &self.typeck.module_name;
+        let mut uses = vec![];
+        let specialized = ty.substitute(&self.args).map(&mut |ty| {
+            let (path, args) = ty.named_args()?;
+            match (path.as_slice(), templates.get(path.last()?)){
+                ([module, name], Some(Template::Struct(_))) if module == module_name && !args.is_empty() => {
+                    uses.push((name.clone(), args.clone(), ty.pos));
+                    Some(Ty::named(vec![module.clone(), mangle(name, &args)], &[], ty.pos))
+                }
+                _ => None
+            }
+        });
+        for (name, args, pos) in uses{
+            if args.iter().any(|arg| arg.has_vars()){
+                let diag_source = self.typeck.error_source(pos, format!("Cannot infer the type arguments of {}", name))?;
+                self.typeck.emit_diagnostic(&[
+                    format!("Declare the type where it is used, like `val x: {}<Int> = ...`.", name)
+                ], &[diag_source]);
+                return Err(())
+            }
+            self.requests.push((name, args, pos));
+        }
+        Ok(specialized)
+    }
+
+    fn ty_cell(&mut self, ty: &RefCell<Ty>) -> Result<(), ()>{
+        let specialized = self.ty(&ty.borrow())?;
+        ty.replace(specialized);
+        Ok(())
+    }
+
+    fn structure(&mut self, structure: &mut Struct) -> Result<(), ()>{
+        for field in structure.fields.iter_mut(){
+            self.ty_cell(&field.ty)?;
+            self.exprs(field.init.iter_mut())?;
+        }
+        for method in structure.methods.iter_mut(){
+            self.fun(method)?;
+        }
+        if let Some(Class{ base: Some(base), .. }) = &mut structure.class{
+            self.exprs(base.args.iter_mut())?;
+        }
+        Ok(())
+    }
+
+    fn fun(&mut self, fun: &mut Fun) -> Result<(), ()>{
+        if let Some(receiver) = fun.receiver.as_mut(){
+            *receiver = self.ty(receiver)?;
+        }
+        for param in fun.params.iter_mut(){
+            param.ty = self.ty(&param.ty)?;
+        }
+        self.ty_cell(&fun.ty)?;
+        self.block(&mut fun.body)?;
+        for guard in fun.guards.iter_mut(){
+            self.expr(&mut guard.cond)?;
+            self.expr(&mut guard.message)?;
+        }
+        Ok(())
+    }
+
+    fn block(&mut self, block: &mut Block) -> Result<(), ()>{
+        for statement in block.statements.iter_mut(){
+            self.statement(statement)?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: &mut Statement) -> Result<(), ()>{
+        match &mut statement.kind{
+            StatementKind::Property(property) => {
+                self.ty_cell(&property.ty)?;
+                self.expr(&mut property.expr)
+            }
+            StatementKind::Local(local) => {
+                self.ty_cell(&local.ty)?;
+                self.expr(&mut local.expr)
+            }
+            StatementKind::Fun(fun) => self.fun(fun),
+            StatementKind::Struct(structure) => self.structure(structure),
+            StatementKind::Expr(expr) => self.expr(expr),
+            StatementKind::Assign(assign) => {
+                self.exprs(assign.receiver.iter_mut())?;
+                self.expr(&mut assign.value)
+            }
+            StatementKind::Break(break_) => self.exprs(break_.value.iter_mut()),
+            StatementKind::Return(return_) => self.exprs(return_.value.iter_mut()),
+            StatementKind::Continue | StatementKind::Interface(_) | StatementKind::Enum(_) => Ok(())
+        }
+    }
+
+    fn exprs<'e>(&mut self, exprs: impl Iterator<Item = &'e mut Expr>) -> Result<(), ()>{
+        for expr in exprs{
+            self.expr(expr)?;
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &mut Expr) -> Result<(), ()>{
+        self.ty_cell(&expr.ty)?;
+        let pos = expr.pos;
+        match expr.kind.as_mut(){
+            ExprElement::Value(TyValue{ elem: TyValueElement::Ref(name), .. }) => {
+                if let Some(instance) = self.instances.iter().find(|instance| instance.pos == pos).cloned(){
+                    *name = self.request(&instance)?;
+                }
+                Ok(())
+            }
+            ExprElement::Value(_) => Ok(()),
+            ExprElement::Grouped(inner) | ExprElement::UnaryOp(_, inner) | ExprElement::ResultOk(inner) | ExprElement::ResultErr(inner)
+                | ExprElement::NotNull(inner) | ExprElement::SafeAccess(inner, _) | ExprElement::Member(inner, _) => self.expr(inner),
+            ExprElement::Cast(inner, ty) | ExprElement::Is(inner, ty) => {
+                *ty = self.ty(ty)?;
+                self.expr(inner)
+            }
+            ExprElement::Binary(_, left, right) => {
+                self.expr(left)?;
+                self.expr(right)
+            }
+            ExprElement::Loop(loop_) => {
+                self.ty_cell(&loop_.ty)?;
+                self.exprs(loop_.cond.iter_mut())?;
+                self.exprs(loop_.binding.iter_mut().map(|binding| &mut binding.iterable))?;
+                self.block(&mut loop_.body)
+            }
+            ExprElement::If(if_) => {
+                self.ty_cell(&if_.ty)?;
+                self.expr(&mut if_.cond)?;
+                self.block(&mut if_.then)?;
+                match if_.otherwise.as_mut(){
+                    Some(otherwise) => self.block(otherwise),
+                    None => Ok(())
+                }
+            }
+            ExprElement::Match(match_) => {
+                self.ty_cell(&match_.ty)?;
+                self.expr(&mut match_.scrutinee)?;
+                for arm in match_.arms.iter_mut(){
+                    self.pattern(&arm.pattern)?;
+                    self.block(&mut arm.body)?;
+                }
+                Ok(())
+            }
+            ExprElement::Lambda(lambda) => {
+                for param in lambda.params.iter(){
+                    self.ty_cell(&param.ty)?;
+                }
+                self.ty_cell(&lambda.ty)?;
+                self.block(&mut lambda.body)
+            }
+            ExprElement::Call(call) => {
+                self.expr(&mut call.callee)?;
+                self.exprs(call.args.iter_mut())
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) -> Result<(), ()>{
+        self.ty_cell(&pattern.ty)?;
+        match &mut *pattern.kind.borrow_mut(){
+            PatternKind::Literal(literal) => self.expr(literal),
+            PatternKind::Variant(_, fields) => fields.iter().try_for_each(|field| self.pattern(field)),
+            PatternKind::Wildcard | PatternKind::Binding(_) => Ok(())
+        }
+    }
+
+    ///The name of the copy that `instance` uses, which is requested if it does not exist yet.
+    fn request(&mut self, instance: &Instance) -> Result<String, ()>{
+        let args = instance.args.iter().map(|arg| self.ty(arg)).collect::<Result<Vec<Ty>, ()>>()?;
+        let params = self.templates[&instance.fun].ty_params();
+        for (arg, param) in args.iter().zip(params.iter()){
+            if arg.has_vars(){
+                let diag_source = self.typeck.error_source(instance.pos, format!("Cannot infer the type argument {} of {}", param.ident.ident, instance.fun))?;
+                self.typeck.emit_diagnostic(&[
+                    format!("Use the result where its type is known, like `let x: Int = {}(...)`.", instance.fun)
+                ], &[diag_source]);
+                return Err(())
+            }
+        }
+        //Only now are the type arguments known well enough to tell whether they satisfy the bounds of the type params
+        let substitution = params.iter().map(|param| param.ident.ident.clone()).zip(args.iter().cloned()).collect::<Vec<(String, Ty)>>();
+        for (arg, param) in args.iter().zip(params.iter()){
+            for bound in param.bounds.iter().map(|bound| bound.substitute(&substitution)){
+                if !self.typeck.satisfies(arg, &bound){
+                    let diag_source = self.typeck.error_source(instance.pos, format!("{} does not implement {}, which {} of {} requires", arg, bound, param.ident.ident, instance.fun))?;
+                    let bound_source = self.typeck.error_source(param.ident.pos, format!("{} is bounded by {} here", param.ident.ident, bound))?;
+                    self.typeck.emit_diagnostic(&[], &[diag_source, bound_source]);
+                    return Err(())
+                }
+            }
+        }
+        let name = mangle(&instance.fun, &args);
+        self.requests.push((instance.fun.clone(), args, instance.pos));
+        Ok(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    #[test]
+    fn type_arguments_are_the_type_params_of_the_call() {
+        assert_eq!(errors("fun id<T>(x: T): T = x\nstruct Box<T>(val v: T)\nfun f(){\n    val n = id<Int8>(3)\n    val b = id<Box<Int>>(Box(3))\n    val none = id<Int?>(None)\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun id<T>(x: T): T = x\nfun f(){\n    val s = id<String>(3)\n}"), vec![
+            format!("This integer literal cannot have type String"),
+            format!("String is expected because of this"),
+        ]);
+    }
+
+    #[test]
+    fn type_arguments_must_match_the_type_params() {
+        assert_eq!(errors("fun id<T>(x: T): T = x\nfun f(){\n    val n = id<Int, Int>(3)\n}"), vec![
+            format!("This is given 2 type arguments"),
+            format!("But id takes 1"),
+        ]);
+        assert_eq!(errors("fun one(x: Int): Int = x\nfun f(){\n    val n = one<Int>(3)\n}"), vec![
+            format!("This is not a generic function, so it cannot be given type arguments"),
+        ]);
+    }
+}
@@ -0,0 +1,413 @@
+use super::{
+    Typeck,
+    Load,
+    Unload,
+    Check,
+    fun::fun_ty,
+    structs::Member,
+    extensions::check_method,
+};
+
+use ident::Identifier;
+
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use stmt::{
+    fun::Fun,
+    generic::TyParam,
+    structs::{
+        Struct,
+        Interface,
+        Modifier,
+    },
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+use core::pos::BiPos;
+
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+///Load an interface, or a trait if `is_trait`.
+pub fn load_interface(chunk: &Chunk, typeck: &Typeck, is_trait: bool) -> Result<Option<Interface>, ()>{
+    let pos = match chunk.read_pos(){
+        Ok(pos) => pos,
+        Err(msg) => {
+            let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                .level(DiagnosticLevel::Error)
+                .message(msg)
+                .build();
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+    };
+    let ident = match Identifier::load(chunk, typeck){
+        Ok(Some(ident)) => ident,
+        Ok(None) => return Ok(None),
+        Err(notice) => return Err(notice)
+    };
+    let mut ty_params = vec![];
+    while let Some(HIRInstruction::TyParam) = chunk.read_instruction() as Option<HIRInstruction>{
+        match TyParam::load(chunk, typeck){
+            Ok(Some(param)) => ty_params.push(param),
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        }
+    }
+    chunk.dec_ins_ptr(1);
+    let outer_ty_params = typeck.ty_params.borrow().clone();
+    typeck.ty_params.borrow_mut().extend(ty_params.iter().map(|param| param.ident.ident.clone()));
+    let methods = load_methods(chunk, typeck);
+    typeck.ty_params.replace(outer_ty_params);
+    let methods = match methods{
+        Ok(Some(methods)) => methods,
+        Ok(None) => return Ok(None),
+        Err(notice) => return Err(notice)
+    };
+    Ok(Some(Interface{
+        ident,
+        ty_params,
+        methods,
+        is_trait,
+        pos
+    }))
+}
+
+///Load the methods of an interface. A method without a return type returns Unit, since there is no body to infer it from.
+fn load_methods(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Vec<Fun>>, ()>{
+    let mut methods = vec![];
+    loop{
+        match chunk.read_instruction() as Option<HIRInstruction>{
+            Some(HIRInstruction::Fn) => match Fun::load(chunk, typeck){
+                Ok(Some(method)) => {
+                    if method.ty.borrow().is_var(){
+                        method.ty.replace(Ty::new(TyKind::Unit, method.pos));
+                    }
+                    methods.push(Fun{
+                        modifier: Modifier::Abstract,
+                        ..method
+                    })
+                }
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            },
+            Some(HIRInstruction::EndStruct) => return Ok(Some(methods)),
+            ins => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected a method of an interface but instead got {:?}", ins))
+                    .build();
+                typeck.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diag_source]);
+                return Err(())
+            }
+        }
+    }
+}
+
+impl<'a> Check<'a> for Interface{
+    ///The methods of an interface have no bodies, so only where it is declared and the names of its methods are checked.
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        if !typeck.return_tys.borrow().is_empty(){
+            let kind = if self.is_trait{ "Traits" }else{ "Interfaces" };
+            let diag_source = typeck.error_source(self.pos, format!("{} can only be declared at the top level of a module", kind))?;
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        typeck.check_bounds(&self.ty_params)?;
+        let mut declared = HashMap::<&str, BiPos>::new();
+        for method in self.methods.iter(){
+            if let Some(first) = declared.insert(&method.ident.ident, method.ident.pos){
+                let duplicate_source = typeck.error_source(method.ident.pos, format!("{} already has a method named {}", self.ident.ident, method.ident.ident))?;
+                let first_source = typeck.error_source(first, format!("{} is first declared here", method.ident.ident))?;
+                typeck.emit_diagnostic(&[], &[duplicate_source, first_source]);
+                return Err(())
+            }
+            check_method(method, typeck)?;
+            if let Some(param) = method.ty_params.first(){
+                let diag_source = typeck.error_source(param.ident.pos, "Methods cannot have type params of their own".to_string())?;
+                typeck.emit_diagnostic(&[
+                    format!("Declare {} on the {} instead, like `{} {}<{}>`.", param.ident.ident, self.kind(), self.kind(), self.ident.ident, param.ident.ident)
+                ], &[diag_source]);
+                return Err(())
+            }
+        }
+        Ok(())
+    }
+}
+
+///Register `interface` so that what implements it can be checked against it.
+pub(crate) fn declare_interface(interface: &Interface, typeck: &Typeck){
+    typeck.interfaces.borrow_mut().insert(interface.ident.ident.clone(), interface.clone());
+}
+
+///Check that `structure` has every method of each interface or trait in its `with` clause, with the types they are declared with.
+///A class only implements interfaces and a struct only traits. The methods it declares to implement them must be marked `impl`, and no others may be.
+pub(crate) fn check_with(structure: &Struct, typeck: &Typeck) -> Result<(), ()>{
+    let name = &structure.ident.ident;
+    let is_class = structure.class.is_some();
+    let ancestors = typeck.ancestors(name);
+    let mut implemented = HashSet::new();
+    for with in structure.with.iter(){
+        let (interface, args) = match typeck.interface_named(with){
+            Some(found) => found,
+            None => {
+                let diag_source = typeck.error_source(with.pos, format!("There is no interface or trait named {}", with))?;
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        if interface.is_trait == is_class{
+            let (kind, note) = match is_class{
+                true => ("class", "Only structs can implement traits, and only classes can implement interfaces."),
+                false => ("struct", "Only classes can implement interfaces, and only structs can implement traits."),
+            };
+            let with_source = typeck.error_source(with.pos, format!("{} is a {}, so it cannot implement the {} {}", name, kind, interface.kind(), interface.ident.ident))?;
+            typeck.emit_diagnostic(&[note.to_string()], &[with_source]);
+            return Err(())
+        }
+        if args.len() != interface.ty_params.len(){
+            let with_source = typeck.error_source(with.pos, format!("This gives {} type arguments to {}", args.len(), interface.ident.ident))?;
+            let decl_source = typeck.error_source(interface.ident.pos, format!("But it takes {}", interface.ty_params.len()))?;
+            typeck.emit_diagnostic(&[], &[with_source, decl_source]);
+            return Err(())
+        }
+        let args = interface.ty_params.iter().map(|param| param.ident.ident.clone()).zip(args).collect::<Vec<(String, Ty)>>();
+        for required in interface.methods.iter(){
+            let method_name = &required.ident.ident;
+            //A class may implement a method with one that it inherits
+            let found = std::iter::once(structure).chain(ancestors.iter()).find_map(|owner| {
+                owner.methods.iter().find(|method| method.ident.ident == *method_name).map(|method| (owner, method))
+            });
+            let (owner, method) = match found{
+                Some(found) => found,
+                None => {
+                    let struct_source = typeck.error_source(structure.ident.pos, format!("{} must implement the method {} of {}", name, method_name, interface.ident.ident))?;
+                    let method_source = typeck.error_source(required.ident.pos, format!("{} is declared here", method_name))?;
+                    typeck.emit_diagnostic(&[
+                        format!("Implement it with `impl fun {}`.", method_name)
+                    ], &[struct_source, method_source]);
+                    return Err(())
+                }
+            };
+            if owner.ident.ident == *name && method.modifier != Modifier::Impl && method.modifier != Modifier::Override{
+                let method_source = typeck.error_source(method.ident.pos, format!("{} implements the method {} of {}, but it is not declared as an implementation", method_name, method_name, interface.ident.ident))?;
+                typeck.emit_diagnostic(&[
+                    format!("Declare it as `impl fun {}`.", method_name)
+                ], &[method_source]);
+                return Err(())
+            }
+            //The methods of an interface are called through the vtable of the class of the object, so an inherited implementation must have a slot in it
+            if is_class && !method.modifier.is_open(){
+                let struct_source = typeck.error_source(structure.ident.pos, format!("{} implements the method {} of {} with the method of {}, which is not open", name, method_name, interface.ident.ident, owner.ident.ident))?;
+                let method_source = typeck.error_source(method.ident.pos, format!("{} is declared here", method_name))?;
+                typeck.emit_diagnostic(&[
+                    format!("Declare it as `open fun {}`.", method_name)
+                ], &[struct_source, method_source]);
+                return Err(())
+            }
+            //An implementation without a return type annotation infers the return type of the method it implements
+            if let Err(mismatch) = fun_ty(required).substitute(&args).unify(&fun_ty(method)){
+                let found_message = format!("{} has type {}", method_name, mismatch.found);
+                let expected_message = format!("But it implements {} of {}, which has type {}", method_name, interface.ident.ident, mismatch.expected);
+                return typeck.emit_mismatch(&mismatch, found_message, expected_message)
+            }
+            implemented.insert(method_name.clone());
+        }
+    }
+    for method in structure.methods.iter(){
+        if method.modifier == Modifier::Impl && !implemented.contains(&method.ident.ident){
+            let method_source = typeck.error_source(method.ident.pos, format!("{} implements nothing, since {} has no interface or trait with a method named {}", method.ident.ident, name, method.ident.ident))?;
+            typeck.emit_diagnostic(&[
+                format!("Add what it implements to the `with` clause of {}, or remove `impl`.", name)
+            ], &[method_source]);
+            return Err(())
+        }
+    }
+    Ok(())
+}
+
+impl Typeck{
+    ///The interface or trait of this module that `ty` is, along with its type arguments.
+    pub fn interface_named(&self, ty: &Ty) -> Option<(Interface, Vec<Ty>)>{
+        let (path, args) = ty.resolve().named_args()?;
+        match path.as_slice(){
+            [module, name] if *module == self.module_name => self.interfaces.borrow().get(name).cloned().map(|interface| (interface, args)),
+            _ => None
+        }
+    }
+
+    ///Whether `ty` is a trait, which is never the type of a value since structs are stored in place.
+    pub fn is_trait(&self, ty: &Ty) -> bool{
+        self.interface_named(ty).is_some_and(|(interface, _)| interface.is_trait)
+    }
+
+    ///The interface or trait named `interface` as the class or struct `structure` implements it, with the type arguments of its `with` clause.
+    ///A class also implements what the classes it inherits from implement.
+    pub fn implemented(&self, structure: &str, interface: &str) -> Option<Ty>{
+        let own = self.structs.borrow().get(structure).cloned()?;
+        std::iter::once(own).chain(self.ancestors(structure)).find_map(|owner| {
+            owner.with.iter().find(|with| self.interface_named(with).is_some_and(|(found, _)| found.ident.ident == interface)).cloned()
+        })
+    }
+
+    ///Whether a value of type `ty` implements `bound`, which names an interface or a trait along with its type arguments.
+    pub fn satisfies(&self, ty: &Ty, bound: &Ty) -> bool{
+        let interface = match self.interface_named(bound){
+            Some((interface, _)) => interface,
+            None => return false
+        };
+        if let Some((found, _)) = self.interface_named(ty){
+            return found.ident.ident == interface.ident.ident && ty.unify(bound).is_ok()
+        }
+        match ty.resolve().named_args(){
+            Some((path, _)) if path.len() == 2 && path[0] == self.module_name => match self.implemented(&path[1], &interface.ident.ident){
+                Some(with) => with.unify(bound).is_ok(),
+                None => false
+            },
+            _ => false
+        }
+    }
+
+    ///The method `name` of the interface or trait `ty`. For a type param, this is the method of one of the interfaces and traits that bound it.
+    pub(crate) fn interface_member(&self, ty: &Ty, name: &str) -> Option<Member>{
+        let bounds = match ty.kind().as_ref(){
+            TyKind::Param(param) => self.bounds.borrow().iter().rev().find(|bounded| bounded.ident.ident == *param)?.bounds.clone(),
+            _ => vec![ty.clone()]
+        };
+        bounds.iter().find_map(|bound| {
+            let (interface, args) = self.interface_named(bound)?;
+            let args = interface.ty_params.iter().map(|param| param.ident.ident.clone()).zip(args).collect::<Vec<(String, Ty)>>();
+            interface.methods.iter().find(|method| method.ident.ident == name).map(|method| Member{
+                ident: method.ident.clone(),
+                ty: fun_ty(method).substitute(&args),
+                mutable: None,
+                extension: None
+            })
+        })
+    }
+
+    ///Whether a value of type `from` may turn out to implement the interface or trait `to`, or may turn out to be the class `to` when it is an interface.
+    ///A type param may be anything, and an open class may be inherited by a class that implements it.
+    pub(crate) fn may_implement(&self, from: &Ty, to: &Ty) -> bool{
+        let may_be = |class: &Ty, interface: &Interface| match class.resolve().named_args(){
+            Some((path, _)) if path.len() == 2 && path[0] == self.module_name => {
+                let is_open = self.structs.borrow().get(&path[1]).and_then(|structure| structure.class.as_ref().map(|class| class.modifier.is_open())).unwrap_or(false);
+                is_open || self.implemented(&path[1], &interface.ident.ident).is_some()
+            }
+            _ => false
+        };
+        match (self.interface_named(from), self.interface_named(to)){
+            (Some((from, _)), Some((to, _))) => !from.is_trait && !to.is_trait,
+            (None, Some((interface, _))) => match from.resolve().kind().as_ref(){
+                TyKind::Param(_) => true,
+                _ => may_be(from, &interface)
+            },
+            (Some((interface, _)), None) => !interface.is_trait && may_be(to, &interface),
+            (None, None) => false
+        }
+    }
+
+    ///Run `check` with `ty_params` in scope, so that members can be found on values of their types through their bounds.
+    pub(crate) fn with_ty_params<T>(&self, ty_params: &[TyParam], check: impl FnOnce() -> Result<T, ()>) -> Result<T, ()>{
+        let depth = self.bounds.borrow().len();
+        self.bounds.borrow_mut().extend(ty_params.iter().cloned());
+        let result = check();
+        self.bounds.borrow_mut().truncate(depth);
+        result
+    }
+}
+
+impl Unload for Interface{
+    ///Only the names of the methods are written, in the order they are declared in, since that is all that dispatching through an interface needs.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        match self.is_trait{
+            true => chunk.write_instruction(HIRInstruction::Trait),
+            false => chunk.write_instruction(HIRInstruction::Interface),
+        }
+        chunk.write_pos(self.pos);
+        match self.ident.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        for method in self.methods.iter(){
+            chunk.write_bool(true);
+            chunk.write_string(method.ident.ident.clone());
+        }
+        chunk.write_bool(false);
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    const SHAPE: &str = "interface Shape{\n    fun area(): Int\n}\n";
+
+    #[test]
+    fn classes_implement_every_method_of_their_interfaces() {
+        assert_eq!(errors(&format!("{}class Square(val side: Int) with Shape{{\n    impl fun area(): Int = this.side * this.side\n}}\nfun f(s: Square): Shape = s", SHAPE)), Vec::<String>::new());
+        assert_eq!(errors(&format!("{}class Square(val side: Int) with Shape", SHAPE)), vec![
+            format!("Square must implement the method area of Shape"),
+            format!("area is declared here"),
+        ]);
+        assert_eq!(errors(&format!("{}class Square(val side: Int) with Shape{{\n    impl fun area(): String = \"big\"\n}}", SHAPE)), vec![
+            format!("area has type () -> String"),
+            format!("But it implements area of Shape, which has type () -> Int"),
+        ]);
+    }
+
+    #[test]
+    fn implementations_are_declared_with_impl() {
+        assert_eq!(errors(&format!("{}class Square(val side: Int) with Shape{{\n    fun area(): Int = 4\n}}", SHAPE)), vec![
+            format!("area implements the method area of Shape, but it is not declared as an implementation"),
+        ]);
+        assert_eq!(errors("class Square(val side: Int){\n    impl fun area(): Int = 4\n}"), vec![
+            format!("area implements nothing, since Square has no interface or trait with a method named area"),
+        ]);
+    }
+
+    #[test]
+    fn interfaces_are_for_classes_and_traits_are_for_structs() {
+        assert_eq!(errors(&format!("{}struct Square(val side: Int) with Shape{{\n    impl fun area(): Int = 4\n}}", SHAPE)), vec![
+            format!("Square is a struct, so it cannot implement the interface Shape"),
+        ]);
+        assert_eq!(errors("trait Sized{\n    fun size(): Int\n}\nclass Square(val side: Int) with Sized{\n    impl fun size(): Int = 4\n}"), vec![
+            format!("Square is a class, so it cannot implement the trait Sized"),
+        ]);
+    }
+
+    #[test]
+    fn structs_can_never_be_traits_they_do_not_implement() {
+        let sized = "trait Sized{\n    fun size(): Int\n}\nstruct Box(val value: Int) with Sized{\n    impl fun size(): Int = 1\n}\nstruct Bag(val value: Int)\n";
+        assert_eq!(errors(&format!("{}fun f(b: Box): Bool = b is Sized", sized)), Vec::<String>::new());
+        assert_eq!(errors(&format!("{}fun f(b: Bag): Bool = b is Sized", sized)), vec![
+            format!("A value of type test::Bag can never be test::Sized"),
+            format!("This has type test::Bag"),
+        ]);
+    }
+}
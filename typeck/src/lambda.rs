@@ -0,0 +1,238 @@
+use super::{
+    Typeck,
+    Load,
+    Unload,
+    Check,
+    ty::GetTy,
+    scope::Symbol,
+    fun::always_returns,
+    unify::Obligation,
+};
+
+use stmt::{
+    block::Block,
+    expr::{
+        Expr,
+        ExprElement,
+    },
+    lambda::{
+        Lambda,
+        LambdaParam,
+    },
+};
+
+use ident::Identifier;
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::{ReadInstruction, WriteInstruction};
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+use std::cell::RefCell;
+
+///Tell `expr` the type that its context expects it to have. Only lambdas make use of this, to infer the types of their params.
+pub fn expect(expr: &Expr, ty: &Ty){
+    match expr.kind.as_ref(){
+        ExprElement::Grouped(inner) => expect(inner, ty),
+        ExprElement::Lambda(lambda) => {
+            lambda.expected.replace(Some(ty.clone()));
+        }
+        _ => {}
+    }
+}
+
+impl Load for Lambda{
+    type Output = Lambda;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let mut params = vec![];
+        loop{
+            match chunk.read_instruction() as Option<HIRInstruction>{
+                Some(HIRInstruction::EndParams) => break,
+                Some(HIRInstruction::FnParam) => {}
+                ins => {
+                    let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), pos.start.0)
+                        .message(format!("Expected a lambda param but instead got {:?}", ins))
+                        .level(DiagnosticLevel::Error)
+                        .build();
+                    typeck.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diag_source]);
+                    return Err(())
+                }
+            }
+            let ident = match Identifier::load(chunk, typeck){
+                Ok(Some(ident)) => ident,
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            };
+            let ty = match Ty::load(chunk, typeck){
+                Ok(Some(ty)) => ty,
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            };
+            params.push(LambdaParam{
+                pos: ident.pos,
+                ident,
+                ty: RefCell::new(ty),
+            });
+        }
+        let body = match Block::load(chunk, typeck){
+            Ok(Some(body)) => body,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        Ok(Some(Lambda{
+            params,
+            body,
+            expected: RefCell::new(None),
+            ty: RefCell::new(Ty::var(pos)),
+            pos
+        }))
+    }
+}
+
+impl<'a> Check<'a> for Lambda{
+    ///Infer the types of params without annotations from the expected function type, then check the body.
+    ///A `return` inside of the body returns from the lambda, not from the function that encloses it.
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        let expected = self.expected.borrow().clone();
+        let signature = expected.as_ref().and_then(|ty| ty.function_signature());
+        if let (Some(expected), Some((params, _))) = (&expected, &signature){
+            if params.len() != self.params.len(){
+                let lambda_source = typeck.error_source(self.pos, format!("This lambda takes {} params", self.params.len()))?;
+                let expected_source = typeck.error_source(expected.pos, format!("But a function of type {} is expected here", expected))?;
+                typeck.emit_diagnostic(&[], &[lambda_source, expected_source]);
+                return Err(())
+            }
+        }
+        for (idx, param) in self.params.iter().enumerate(){
+            let annotated = param.ty.borrow().clone();
+            match signature.as_ref().map(|(params, _)| params[idx].clone()){
+                Some(expected_param) => {
+                    if let Err(mismatch) = expected_param.unify(&annotated){
+                        let found_message = format!("This param is declared as {}", mismatch.found);
+                        let expected_message = format!("But it is expected to be {}", mismatch.expected);
+                        return typeck.emit_mismatch(&mismatch, found_message, expected_message)
+                    }
+                }
+                //Inferred from how the param is used in the body instead
+                None if annotated.is_var() => typeck.defer(Obligation::Inferred{
+                    ident: param.ident.ident.clone(),
+                    ty: annotated,
+                }),
+                None => {}
+            }
+        }
+
+        typeck.scopes.push();
+        for param in self.params.iter(){
+            typeck.scopes.declare(Symbol::immutable(param.ident.clone(), param.ty.borrow().clone()));
+        }
+        let ret = signature.as_ref().map(|(_, ret)| ret.clone()).unwrap_or(Ty::var(self.pos));
+        let loops = typeck.loops.suspend();
+        typeck.return_tys.borrow_mut().push(ret.clone());
+        typeck.smart_casts.enter(&self.body);
+        let result = self.body.check(typeck);
+        typeck.smart_casts.exit();
+        typeck.return_tys.borrow_mut().pop();
+        typeck.loops.resume(loops);
+        typeck.scopes.pop();
+        result?;
+
+        let body_ty = self.body.get_ty();
+        if *ret.kind() != TyKind::Unit && !always_returns(&self.body){
+            if let Err(mismatch) = ret.coerce(&typeck.upcast(&ret, &body_ty)){
+                let found_message = format!("This lambda evaluates to {}", mismatch.found);
+                let expected_message = format!("But it is expected to return {}", mismatch.expected);
+                return typeck.emit_mismatch(&mismatch, found_message, expected_message)
+            }
+        }
+        let params = self.params.iter().map(|param| param.ty.borrow().clone()).collect::<Vec<Ty>>();
+        self.ty.replace(Ty::function(&params, &ret, self.pos));
+        Ok(())
+    }
+}
+
+impl Unload for Lambda{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Lambda);
+        for param in self.params.iter(){
+            chunk.write_instruction(HIRInstruction::FnParam);
+            chunk.write_pos(param.pos);
+            match param.ident.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+            match param.ty.borrow().unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+        }
+        chunk.write_instruction(HIRInstruction::EndParams);
+        match self.body.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    #[test]
+    fn params_are_inferred_from_the_expected_function_type() {
+        assert_eq!(errors("fun apply(f: (Int) -> Int, x: Int): Int = f(x)\nfun g(): Int = apply({ n -> n + 1 }, 2)"), Vec::<String>::new());
+        assert_eq!(errors("fun apply(f: (Int) -> Int, x: Int): Int = f(x)\nfun g(): Int = apply({ n -> n + \"a\" }, 2)"), vec![
+            format!("Operator '+' cannot be applied to types Int and String"),
+            format!("This has type Int"),
+            format!("This has type String"),
+        ]);
+    }
+
+    #[test]
+    fn function_types_must_agree() {
+        assert_eq!(errors("fun g(){\n    val f: (Int, Int) -> Int = { n: Int -> n }\n}"), vec![
+            format!("This lambda takes 1 params"),
+            format!("But a function of type (Int, Int) -> Int is expected here"),
+        ]);
+        assert_eq!(errors("fun g(){\n    val f: (Int) -> Int = { n -> n }\n    val h: (Int, Int) -> Int = f\n}"), vec![
+            format!("Expected an assignment of type (Int, Int) -> Int"),
+            format!("But instead found an assignment of type (Int) -> Int"),
+        ]);
+    }
+
+    #[test]
+    fn lambdas_cannot_break_out_of_enclosing_loops() {
+        assert_eq!(errors("fun g(){\n    loop{\n        val f = { break }\n        break\n    }\n}"), vec![
+            format!("'break' can only be used inside of a loop"),
+        ]);
+    }
+}
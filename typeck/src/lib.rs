@@ -23,6 +23,8 @@ use module_messages::ModuleMessage;
 
 use core::pos::BiPos;
 
+use std::cell::RefCell;
+
 mod expressions;
 
 mod statement;
@@ -34,6 +36,23 @@ mod modules;
 mod ty;
 mod ident;
 mod mutable;
+mod scope;
+mod block;
+mod loops;
+mod conditional;
+mod lambda;
+mod call;
+mod assign;
+mod unify;
+mod nullable;
+mod generics;
+mod structs;
+mod interfaces;
+mod enums;
+mod patterns;
+mod extensions;
+mod desugar;
+use desugar::Desugar;
 
 ///This trait provides an associated function for loading typeck IR into the current typeck instance.
 ///Output is what type is being returned upon success. Due to the fact that traits don't have known sizes at compiletime, an associated type will do.
@@ -135,6 +154,31 @@ pub struct Typeck{
 
     diagnostic_tx: Sender<Option<Diagnostic>>,
 
+    ///The scopes visible at the point currently being checked.
+    scopes: scope::ScopeStack,
+    ///The loops enclosing the point currently being checked, innermost last.
+    loops: loops::LoopStack,
+    ///What narrows symbols after the code that checked them, see [nullable::SmartCasts].
+    smart_casts: nullable::SmartCasts,
+    ///The return types of the functions enclosing the point currently being checked, innermost last.
+    return_tys: RefCell<Vec<::ty::Ty>>,
+    ///Checks on types that are still being inferred, see [unify::Obligation].
+    obligations: RefCell<Vec<unify::Obligation>>,
+    ///The names of the type params of the generic function being loaded.
+    ty_params: RefCell<Vec<String>>,
+    ///Every use of a generic function, see [generics::monomorphize].
+    instances: RefCell<Vec<generics::Instance>>,
+    ///The structs declared in the module by name, so that their members can be found.
+    structs: RefCell<std::collections::HashMap<String, stmt::structs::Struct>>,
+    ///The interfaces and traits declared in the module by name, so that what implements them can be checked.
+    interfaces: RefCell<std::collections::HashMap<String, stmt::structs::Interface>>,
+    ///The enums declared in the module by name, so that they can be matched on.
+    enums: RefCell<std::collections::HashMap<String, stmt::enums::Enum>>,
+    ///The type params in scope at the point currently being checked, so that members can be found through their bounds.
+    bounds: RefCell<Vec<stmt::generic::TyParam>>,
+    ///The struct whose members are being checked, which is the only one whose private fields can be used.
+    enclosing_struct: RefCell<Option<String>>,
+
     master_tx: Sender<ModuleMessage>,
     master_rx: Arc<Mutex<Receiver<ModuleMessage>>>
 }
@@ -176,6 +220,24 @@ impl<'a> Typeck{
         };
     }
 
+    ///Build an error diagnostic source that points at `pos`, with the source snippet for `pos` attached.
+    ///If the snippet could not be retrieved, that failure is emitted instead and `Err(())` is returned.
+    pub fn error_source(&self, pos: BiPos, message: String) -> Result<DiagnosticSource, ()>{
+        let source = match self.request_source_snippet(pos){
+            Ok(source) => source,
+            Err(diag) => {
+                self.emit_diagnostic(&[], &[diag]);
+                return Err(())
+            }
+        };
+        Ok(DiagnosticSourceBuilder::new(self.module_name.clone(), pos.start.0)
+            .level(DiagnosticLevel::Error)
+            .message(message)
+            .range(pos.col_range())
+            .source(source)
+            .build())
+    }
+
     pub fn emit_diagnostic(&self, notes: &[String], diag_sources: &[DiagnosticSource]){
         let diagnostic = DiagnosticBuilder::new(DiagnosticLevel::Error)
                     .message(format!("An error occurred during type checking."))
@@ -223,7 +285,30 @@ impl<'a> Typeck{
                         master_rx: Arc<Mutex<Receiver<ModuleMessage>>>,
                         diagnostic_tx: Sender<Option<Diagnostic>>
                     ) -> Result<(), ()>{
-        let mut typeck = Self{
+        let mut typeck = Self::new(module_name, ir_rx, typeck_tx, master_tx, master_rx, diagnostic_tx);
+
+        if let Err(()) = typeck.check_module(){
+            return Err(())
+        }
+
+        if let Err(()) = typeck.unload(){
+            return Err(())
+        }
+
+        typeck.typeck_tx.send(None).unwrap();
+        
+        Ok(())
+    }
+
+    fn new(
+        module_name: String, 
+        ir_rx: Receiver<Option<Chunk>>, 
+        typeck_tx: Sender<Option<Chunk>>, 
+        master_tx: Sender<ModuleMessage>, 
+        master_rx: Arc<Mutex<Receiver<ModuleMessage>>>,
+        diagnostic_tx: Sender<Option<Diagnostic>>
+    ) -> Self{
+        Self{
             module_name: module_name.clone(),
             typeck_tx,
             chunk_rx: ir_rx,
@@ -233,23 +318,54 @@ impl<'a> Typeck{
             },
             master_tx,
             master_rx,
-            diagnostic_tx
-        };
-
-        if let Err(()) = typeck.load(){
-            return Err(())
-        }
-        
-        if let Err(()) = typeck.module_ir.check(&typeck){
-            return Err(())
-        }
-
-        if let Err(()) = typeck.unload(){
-            return Err(())
+            diagnostic_tx,
+            scopes: scope::ScopeStack::default(),
+            loops: loops::LoopStack::default(),
+            smart_casts: nullable::SmartCasts::default(),
+            return_tys: RefCell::new(vec![]),
+            obligations: RefCell::new(vec![]),
+            ty_params: RefCell::new(vec![]),
+            instances: RefCell::new(vec![]),
+            structs: RefCell::new(std::collections::HashMap::new()),
+            interfaces: RefCell::new(std::collections::HashMap::new()),
+            enums: RefCell::new(std::collections::HashMap::new()),
+            bounds: RefCell::new(vec![]),
+            enclosing_struct: RefCell::new(None),
         }
+    }
 
-        typeck.typeck_tx.send(None).unwrap();
-        
+    ///Load, check and desugar the module, leaving it ready to be unloaded.
+    fn check_module(&mut self) -> Result<(), ()>{
+        self.load()?;
+        self.module_ir.check(self)?;
+        self.module_ir.statements = generics::monomorphize(self).and_then(|statements| structs::order_structs(statements, self))?;
+        self.module_ir.desugar();
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+impl Typeck{
+    ///Parse and check `source` as the module `test`, returning the module if it checks along with the message of every label of the diagnostics emitted on the way, in order.
+    pub(crate) fn check_source(source: &str) -> (Option<modules::Module>, Vec<String>){
+        use std::sync::mpsc::channel;
+        let (token_tx, token_rx) = channel();
+        let (hir_tx, hir_rx) = channel();
+        let (typeck_tx, _typeck_rx) = channel();
+        let (diagnostics_tx, diagnostics_rx) = channel();
+        let (request_tx, request_rx) = channel();
+        let (response_tx, response_rx) = channel();
+        let response_rx = Arc::new(Mutex::new(response_rx));
+        module_messages::serve_source(source.to_owned(), request_rx, response_tx);
+        let mut lexer = lexer::Lexer::new(format!("test"), source.to_owned(), token_tx, request_tx.clone(), response_rx.clone());
+        if let Err(diag) = lexer.start_tokenizing(){
+            diagnostics_tx.send(Some(diag)).unwrap();
+        }
+        drop(lexer);
+        let _ = parser::Parser::parse(format!("test"), hir_tx, token_rx, diagnostics_tx.clone(), request_tx.clone(), response_rx.clone());
+        let mut typeck = Typeck::new(format!("test"), hir_rx, typeck_tx, request_tx, response_rx, diagnostics_tx);
+        let module = typeck.check_module().ok().map(|()| typeck.module_ir);
+        let messages = diagnostics_rx.try_iter().flatten().flat_map(|diag| diag.sources.iter().map(|source| source.message().to_owned()).collect::<Vec<_>>()).collect();
+        (module, messages)
+    }
+}
@@ -0,0 +1,311 @@
+use super::{
+    Typeck,
+    Load,
+    Unload,
+    Check,
+    ty::GetTy,
+    scope::Symbol,
+};
+
+use stmt::{
+    block::Block,
+    expr::Expr,
+    loops::{
+        Loop,
+        LoopBinding,
+        Break,
+    },
+};
+
+use ident::Identifier;
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use core::pos::BiPos;
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::WriteInstruction;
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+use std::cell::RefCell;
+
+///What is known about a loop that encloses the code being checked.
+#[derive(Debug, Clone)]
+pub struct LoopContext{
+    ///Whether a `break` out of this loop may carry a value.
+    pub can_break_with_value: bool,
+    ///The type of the first `break` out of this loop, along with where that break is. Every other break must agree with it.
+    pub break_ty: Option<(Ty, BiPos)>,
+}
+
+///The loops enclosing the code being checked, innermost last.
+#[derive(Debug, Default)]
+pub struct LoopStack{
+    loops: RefCell<Vec<LoopContext>>,
+}
+
+impl LoopStack{
+    pub fn push(&self, context: LoopContext){
+        self.loops.borrow_mut().push(context);
+    }
+
+    pub fn pop(&self) -> Option<LoopContext>{
+        self.loops.borrow_mut().pop()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.loops.borrow().is_empty()
+    }
+
+    ///Hide every enclosing loop while checking a function or lambda body, which cannot break out of them. Hand the result back to `resume` afterwards.
+    pub fn suspend(&self) -> Vec<LoopContext>{
+        std::mem::take(&mut *self.loops.borrow_mut())
+    }
+
+    pub fn resume(&self, loops: Vec<LoopContext>){
+        *self.loops.borrow_mut() = loops;
+    }
+}
+
+impl Load for Loop{
+    type Output = Loop;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let cond = if chunk.read_bool(){
+            match Expr::load(chunk, typeck){
+                Ok(Some(cond)) => Some(cond),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            }
+        }else{
+            None
+        };
+        let binding = if chunk.read_bool(){
+            let ident = match Identifier::load(chunk, typeck){
+                Ok(Some(ident)) => ident,
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            };
+            let iterable = match Expr::load(chunk, typeck){
+                Ok(Some(iterable)) => iterable,
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            };
+            Some(LoopBinding{
+                ident,
+                iterable
+            })
+        }else{
+            None
+        };
+        let body = match Block::load(chunk, typeck){
+            Ok(Some(body)) => body,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        Ok(Some(Loop{
+            cond,
+            binding,
+            body,
+            ty: RefCell::new(Ty::new(TyKind::Unit, pos)),
+            pos
+        }))
+    }
+}
+
+impl<'a> Check<'a> for Loop{
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        if let Some(cond) = &self.cond{
+            cond.check(typeck)?;
+            let cond_ty = cond.get_ty();
+            if Ty::new(TyKind::Bool, cond.pos).unify(&cond_ty).is_err(){
+                let diag_source = typeck.error_source(cond.pos, format!("The condition of a while loop must be Bool but instead found {}", cond_ty))?;
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        }
+        typeck.scopes.push();
+        if let Some(binding) = &self.binding{
+            if let Err(()) = binding.iterable.check(typeck){
+                typeck.scopes.pop();
+                return Err(())
+            }
+            let iterable_ty = binding.iterable.get_ty();
+            //Ranges are the only iterables for now
+            let element_ty = Ty::var(binding.ident.pos);
+            match Ty::app("Range", std::slice::from_ref(&element_ty), binding.iterable.pos).unify(&iterable_ty){
+                Ok(()) => {}
+                Err(_) => {
+                    typeck.scopes.pop();
+                    let diag_source = typeck.error_source(binding.iterable.pos, format!("Expected something to iterate over but instead found {}", iterable_ty))?;
+                    typeck.emit_diagnostic(&[], &[diag_source]);
+                    return Err(())
+                }
+            }
+            typeck.scopes.declare(Symbol::immutable(binding.ident.clone(), element_ty));
+        }
+        typeck.loops.push(LoopContext{
+            can_break_with_value: self.can_break_with_value(),
+            break_ty: None,
+        });
+        let result = self.body.check(typeck);
+        let context = typeck.loops.pop();
+        typeck.scopes.pop();
+        result?;
+        if let Some(LoopContext{ break_ty: Some((ty, _)), .. }) = context{
+            self.ty.replace(ty.at(self.pos));
+        }
+        Ok(())
+    }
+}
+
+impl Unload for Loop{
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Loop);
+        match &self.cond{
+            Some(cond) => {
+                chunk.write_bool(true);
+                match cond.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+            }
+            None => chunk.write_bool(false)
+        }
+        match &self.binding{
+            Some(binding) => {
+                chunk.write_bool(true);
+                match binding.ident.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                match binding.iterable.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+            }
+            None => chunk.write_bool(false)
+        }
+        match self.body.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        Ok(chunk)
+    }
+}
+
+impl<'a> Check<'a> for Break{
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        if typeck.loops.is_empty(){
+            let diag_source = typeck.error_source(self.pos, "'break' can only be used inside of a loop".to_string())?;
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        let ty = match &self.value{
+            Some(value) => {
+                value.check(typeck)?;
+                value.get_ty()
+            }
+            None => Ty::new(TyKind::Unit, self.pos)
+        };
+        let mut loops = typeck.loops.loops.borrow_mut();
+        let context = loops.last_mut().expect("The loop stack was checked to not be empty.");
+        if self.value.is_some() && !context.can_break_with_value{
+            drop(loops);
+            let diag_source = typeck.error_source(self.pos, "Only 'loop' can break with a value. Loops with a condition or a binding always evaluate to Unit.".to_string())?;
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        match context.break_ty.clone(){
+            Some((break_ty, break_pos)) if break_ty.unify(&ty).is_err() => {
+                drop(loops);
+                let diag_source = typeck.error_source(self.pos, format!("This breaks with a value of type {}", ty))?;
+                let first_source = typeck.error_source(break_pos, format!("But the loop already breaks with a value of type {} here", break_ty))?;
+                typeck.emit_diagnostic(&[], &[diag_source, first_source]);
+                return Err(())
+            }
+            Some(_) => {}
+            None => context.break_ty = Some((ty, self.pos))
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn check_continue(pos: BiPos, typeck: &Typeck) -> Result<(), ()>{
+    if typeck.loops.is_empty(){
+        let diag_source = typeck.error_source(pos, "'continue' can only be used inside of a loop".to_string())?;
+        typeck.emit_diagnostic(&[], &[diag_source]);
+        return Err(())
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    #[test]
+    fn loops_check_without_errors() {
+        let errors = errors("fun f(xs: Int): Int{\n    var n = 0\n    while(n < 10){ n = n + 1 }\n    until(xs){ n = n + it }\n    for(x in 0..xs){ if(x == 5){ continue } }\n    val found = loop{ break n }\n    return found\n}");
+        assert_eq!(errors, Vec::<String>::new());
+    }
+
+    #[test]
+    fn while_conditions_must_be_bool() {
+        let errors = errors("fun f(){\n    while(\"yes\"){ }\n}");
+        assert_eq!(errors, vec![format!("The condition of a while loop must be Bool but instead found String")]);
+    }
+
+    #[test]
+    fn for_loops_need_something_to_iterate_over() {
+        let errors = errors("fun f(){\n    for(x in true){ }\n}");
+        assert_eq!(errors, vec![format!("Expected something to iterate over but instead found Bool")]);
+    }
+
+    #[test]
+    fn break_and_continue_must_be_inside_of_a_loop() {
+        assert_eq!(errors("fun f(){\n    break\n}"), vec![format!("'break' can only be used inside of a loop")]);
+        assert_eq!(errors("fun f(){\n    continue\n}"), vec![format!("'continue' can only be used inside of a loop")]);
+    }
+
+    #[test]
+    fn only_loop_breaks_with_a_value() {
+        let errors = errors("fun f(){\n    while(true){ break 1 }\n}");
+        assert_eq!(errors, vec![format!("Only 'loop' can break with a value. Loops with a condition or a binding always evaluate to Unit.")]);
+    }
+
+    #[test]
+    fn loops_break_with_one_type() {
+        let errors = errors("fun f(){\n    val x = loop{\n        if(true){ break true }\n        break \"one\"\n    }\n}");
+        assert_eq!(errors, vec![
+            format!("This breaks with a value of type String"),
+            format!("But the loop already breaks with a value of type Bool here"),
+        ]);
+    }
+}
@@ -1,8 +1,15 @@
 use super::{
-    Typeck
+    Typeck,
+    fun::declare_fun,
+    structs::declare_struct,
+    interfaces::declare_interface,
+    enums::declare_enum,
 };
 
-use stmt::Statement;
+use stmt::{
+    Statement,
+    StatementKind,
+};
 
 #[derive(Debug, Clone)]
 pub struct Module{
@@ -12,11 +19,24 @@ pub struct Module{
 
 impl<'a> super::Check<'a> for Module{
     fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        typeck.scopes.push();
+        //Functions, structs, interfaces and enums can be used before they are declared
+        for statement in self.statements.iter(){
+            match &statement.kind{
+                StatementKind::Fun(fun) => declare_fun(fun, typeck),
+                StatementKind::Struct(structure) => declare_struct(structure, typeck),
+                StatementKind::Interface(interface) => declare_interface(interface, typeck),
+                StatementKind::Enum(enumeration) => declare_enum(enumeration, typeck),
+                _ => {}
+            }
+        }
         for statement in self.statements.iter(){
-            if let Err(()) = statement.check(typeck){
+            if let Err(()) = statement.check(typeck).and_then(|_| typeck.discharge()){
+                typeck.scopes.pop();
                 return Err(())
             }
         }
+        typeck.scopes.pop();
         Ok(())
     }
 }
\ No newline at end of file
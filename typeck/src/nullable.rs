@@ -0,0 +1,397 @@
+use super::{
+    Typeck,
+    ty::GetTy,
+    scope::Symbol,
+};
+
+use stmt::{
+    block::Block,
+    expr::{
+        Expr,
+        ExprElement,
+        OpKind,
+    },
+    pattern::{
+        Pattern,
+        PatternKind,
+    },
+};
+
+use ty::{
+    Ty,
+    TyKind,
+    TyValue,
+    TyValueElement,
+};
+
+use core::pos::BiPos;
+
+use dataflow::{
+    Analysis,
+    Direction,
+    tir::{
+        Flow,
+        Item,
+    },
+};
+
+use std::cell::RefCell;
+
+impl Typeck{
+    ///The type of the member `name` of a value of type `ty`, accessed at `pos`.
+    ///An extension function is not a member of the type, so it can only be called.
+    pub(crate) fn member_ty(&self, ty: &Ty, name: &str, pos: BiPos) -> Result<Ty, ()>{
+        let member = self.member(ty, name, pos)?;
+        if member.extension.is_some(){
+            let diag_source = self.error_source(pos, format!("{} is an extension function of {}, so it can only be called", name, ty))?;
+            self.emit_diagnostic(&[
+                format!("Call it like `x.{}()`, or use a lambda that calls it, like `{{ x.{}() }}`.", name, name)
+            ], &[diag_source]);
+            return Err(())
+        }
+        Ok(member.ty.at(pos))
+    }
+
+    ///The type inside the nullable type `ty` of the operand of `op` at `pos`. An operand that is not inferred yet is inferred to be nullable.
+    fn nullable_inner(&self, ty: &Ty, op: &str, pos: BiPos) -> Result<Ty, ()>{
+        if self.is_number_literal(ty){
+            let op_source = self.error_source(pos, format!("Operator '{}' cannot be applied to a number, which is never None", op))?;
+            self.emit_diagnostic(&[], &[op_source]);
+            return Err(())
+        }
+        if ty.is_var(){
+            self.unify(&Ty::option(&Ty::var(pos), pos), ty)?;
+        }
+        match ty.option_inner(){
+            Some(inner) => Ok(inner),
+            None => {
+                let op_source = self.error_source(pos, format!("Operator '{}' cannot be applied to type {}", op, ty))?;
+                self.emit_diagnostic(&[
+                    format!("{} is never None, so it can be used as it is.", ty)
+                ], &[op_source]);
+                Err(())
+            }
+        }
+    }
+
+    ///Whether a value of type `from` may turn out to be of type `to`, which is what an `is` check asks.
+    pub fn can_be(&self, from: &Ty, to: &Ty) -> bool{
+        if from == to{
+            return true
+        }
+        match (from.option_inner(), to.option_inner()){
+            (Some(from_inner), Some(to_inner)) => self.can_be(&from_inner, &to_inner),
+            (Some(from_inner), None) => self.can_be(&from_inner, to),
+            (None, None) => self.may_be_class(from, to) || self.may_implement(from, to),
+            _ => false
+        }
+    }
+}
+
+///Check a safe call `receiver?.member`. Since it is None whenever the receiver is, a nullable member does not become doubly nullable.
+pub fn check_safe_access(receiver: &Expr, member: &str, pos: BiPos, typeck: &Typeck) -> Result<Ty, ()>{
+    let inner = typeck.nullable_inner(&receiver.get_ty(), "?.", pos)?;
+    let found = typeck.member(&inner, member, pos)?;
+    //A safe call of an extension function would have to skip the call when the receiver is None, which only a member can do
+    if found.extension.is_some(){
+        let diag_source = typeck.error_source(pos, format!("The extension function {} of {} cannot be called with `?.`", member, inner))?;
+        typeck.emit_diagnostic(&[
+            format!("Check that it is not None first, like `if(x != None){{ x.{}() }}`, or declare the extension on {} and call it like `x.{}()`.", member, receiver.get_ty(), member)
+        ], &[diag_source]);
+        return Err(())
+    }
+    let member_ty = typeck.member_ty(&inner, member, pos)?;
+    match member_ty.option_inner(){
+        Some(_) => Ok(member_ty.at(pos)),
+        None => Ok(Ty::option(&member_ty, pos))
+    }
+}
+
+///Check an assertion `operand!!`, which has the type inside the nullable operand.
+pub fn check_not_null(operand: &Expr, pos: BiPos, typeck: &Typeck) -> Result<Ty, ()>{
+    typeck.nullable_inner(&operand.get_ty(), "!!", pos).map(|inner| inner.at(pos))
+}
+
+///Check `left ?: right`. It is only nullable when the fallback is.
+pub fn check_elvis(left: &Expr, right: &Expr, pos: BiPos, typeck: &Typeck) -> Result<Ty, ()>{
+    let left_ty = left.get_ty();
+    let right_ty = right.get_ty();
+    let inner = typeck.nullable_inner(&left_ty, "?:", pos)?;
+    if right_ty.option_inner().is_some(){
+        typeck.unify(&left_ty, &right_ty)?;
+        return Ok(left_ty.at(pos))
+    }
+    typeck.unify(&inner, &right_ty)?;
+    Ok(inner.at(pos))
+}
+
+///Check `expr is ty`. The check is an error when it could never be true.
+pub fn check_is(expr: &Expr, ty: &Ty, pos: BiPos, typeck: &Typeck) -> Result<Ty, ()>{
+    let expr_ty = expr.get_ty();
+    if expr_ty.is_var(){
+        typeck.unify(ty, &expr_ty)?;
+    }
+    if !typeck.can_be(&expr_ty, ty){
+        let is_source = typeck.error_source(pos, format!("A value of type {} can never be {}", expr_ty, ty))?;
+        let expr_source = typeck.error_source(expr.pos, format!("This has type {}", expr_ty))?;
+        typeck.emit_diagnostic(&[], &[is_source, expr_source]);
+        return Err(())
+    }
+    Ok(Ty::new(TyKind::Bool, pos))
+}
+
+///The symbols that `cond` narrows to a more precise type when it is true, and when it is false.
+///A symbol is narrowed by comparing it with None or by checking it with `is`. Mutable symbols are never narrowed, since they may be assigned after the check.
+pub fn narrow(cond: &Expr, typeck: &Typeck) -> (Vec<Symbol>, Vec<Symbol>){
+    match cond.kind.as_ref(){
+        ExprElement::Grouped(expr) => narrow(expr, typeck),
+        ExprElement::UnaryOp(OpKind::Not, operand) => {
+            let (when_true, when_false) = narrow(operand, typeck);
+            (when_false, when_true)
+        }
+        ExprElement::Binary(OpKind::And, left, right) => {
+            let (mut when_true, _) = narrow(left, typeck);
+            when_true.extend(narrow(right, typeck).0);
+            (when_true, vec![])
+        }
+        ExprElement::Binary(OpKind::Or, left, right) => {
+            let (_, mut when_false) = narrow(left, typeck);
+            when_false.extend(narrow(right, typeck).1);
+            (vec![], when_false)
+        }
+        ExprElement::Binary(op, left, right) => {
+            let checked = match (is_none(left), is_none(right)){
+                (false, true) => left,
+                (true, false) => right,
+                _ => return (vec![], vec![])
+            };
+            let narrowed = narrowable(checked, typeck)
+                .and_then(|symbol| symbol.ty.option_inner().map(|inner| Symbol{ ty: inner, ..symbol }))
+                .into_iter()
+                .collect();
+            match op{
+                OpKind::NotEq => (narrowed, vec![]),
+                OpKind::Eq => (vec![], narrowed),
+                _ => (vec![], vec![])
+            }
+        }
+        //A struct is stored in place, so it is never narrowed to a trait
+        ExprElement::Is(expr, ty) if !typeck.is_trait(ty) => {
+            let narrowed = narrowable(expr, typeck)
+                .map(|symbol| Symbol{ ty: ty.clone(), ..symbol })
+                .into_iter()
+                .collect();
+            (narrowed, vec![])
+        }
+        _ => (vec![], vec![])
+    }
+}
+
+///Check something with the `narrowed` symbols shadowing the ones they narrow.
+pub fn check_narrowed<T>(typeck: &Typeck, narrowed: Vec<Symbol>, check: impl FnOnce() -> Result<T, ()>) -> Result<T, ()>{
+    if narrowed.is_empty(){
+        return check()
+    }
+    typeck.scopes.push();
+    for symbol in narrowed{
+        typeck.scopes.declare(symbol);
+    }
+    let result = check();
+    typeck.scopes.pop();
+    result
+}
+
+///The statements of a body that some conditions are known to hold before, by their position, along with the value each condition is known to have.
+type Guards = Vec<(BiPos, Vec<(Expr, bool)>)>;
+
+///The conditions known to hold before the statements of the bodies being checked, innermost body last.
+///These narrow symbols after the code that checked them, such as after `if(n == None){ return 0 }`, where `n` is not None.
+#[derive(Debug, Default)]
+pub struct SmartCasts{
+    bodies: RefCell<Vec<Guards>>,
+}
+
+impl SmartCasts{
+    ///Find what holds before each statement of `body`, which is checked next. Hand it back to `exit` once it is checked.
+    pub fn enter(&self, body: &Block){
+        let flow = Flow::new(body);
+        let start = flow.graph.solve(&Guarded).start;
+        let mut guarded = vec![];
+        for (block, mut fact) in flow.graph.blocks.iter().zip(start){
+            for item in block.items.iter(){
+                if let (Item::Statement(statement), Some(conds)) = (item, &fact){
+                    if !conds.is_empty(){
+                        guarded.push((statement.pos, conds.iter().map(|cond| (cond.cond.clone(), cond.value)).collect()));
+                    }
+                }
+                Guarded.transfer(item, &mut fact);
+            }
+        }
+        self.bodies.borrow_mut().push(guarded);
+    }
+
+    pub fn exit(&self){
+        self.bodies.borrow_mut().pop();
+    }
+
+    ///The conditions known to hold before the statement at `pos`, along with the value each of them is known to have.
+    pub fn at(&self, pos: BiPos) -> Vec<(Expr, bool)>{
+        self.bodies.borrow().last()
+            .and_then(|guarded| guarded.iter().find(|(at, _)| *at == pos))
+            .map(|(_, conds)| conds.clone())
+            .unwrap_or_default()
+    }
+}
+
+///Declare the symbols narrowed by the conditions known to hold before the statement at `pos`, in the innermost scope.
+pub fn narrow_guarded(pos: BiPos, typeck: &Typeck){
+    for (cond, value) in typeck.smart_casts.at(pos){
+        let (when_true, when_false) = narrow(&cond, typeck);
+        for symbol in if value{ when_true }else{ when_false }{
+            typeck.scopes.declare(symbol);
+        }
+    }
+}
+
+///A condition that control only gets past when it evaluates to `value`.
+#[derive(Debug, Clone, Copy)]
+struct Assumed<'t>{
+    cond: &'t Expr,
+    value: bool,
+}
+
+impl<'t> PartialEq for Assumed<'t>{
+    ///The same condition of the body, rather than one that happens to look the same.
+    fn eq(&self, other: &Self) -> bool{
+        std::ptr::eq(self.cond, other.cond) && self.value == other.value
+    }
+}
+
+impl<'t> Assumed<'t>{
+    ///Whether the condition may narrow the symbol `name`, so that it no longer holds once `name` is given a new value.
+    fn mentions(&self, name: &str) -> bool{
+        mentions(self.cond, name)
+    }
+}
+
+fn mentions(expr: &Expr, name: &str) -> bool{
+    match expr.kind.as_ref(){
+        ExprElement::Value(TyValue{ elem: TyValueElement::Ref(found), .. }) => found == name,
+        ExprElement::Grouped(expr) | ExprElement::UnaryOp(_, expr) | ExprElement::Is(expr, _) => mentions(expr, name),
+        ExprElement::Binary(_, left, right) => mentions(left, name) || mentions(right, name),
+        _ => false
+    }
+}
+
+///The names that `pattern` binds.
+fn bindings(pattern: &Pattern) -> Vec<String>{
+    match &*pattern.kind.borrow(){
+        PatternKind::Binding(ident) => vec![ident.ident.clone()],
+        PatternKind::Variant(_, fields) => fields.iter().flat_map(bindings).collect(),
+        PatternKind::Wildcard | PatternKind::Literal(_) => vec![],
+    }
+}
+
+///Which conditions hold on every path to each point of a body.
+struct Guarded;
+
+impl<'t> Analysis<Item<'t>> for Guarded{
+    type Fact = Option<Vec<Assumed<'t>>>;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    ///Nothing has flowed in yet, which is the top of the lattice of a must analysis.
+    fn bottom(&self) -> Self::Fact{
+        None
+    }
+
+    fn boundary(&self) -> Self::Fact{
+        Some(vec![])
+    }
+
+    fn merge(&self, fact: &mut Self::Fact, other: &Self::Fact){
+        *fact = match (fact.take(), other){
+            (Some(fact), Some(other)) => Some(fact.into_iter().filter(|cond| other.contains(cond)).collect()),
+            (fact, None) => fact,
+            (None, other) => other.clone(),
+        }
+    }
+
+    fn transfer(&self, item: &Item<'t>, fact: &mut Self::Fact){
+        let conds = match fact{
+            Some(conds) => conds,
+            None => return
+        };
+        match item{
+            Item::Assume(cond, value) => {
+                let assumed = Assumed{
+                    cond,
+                    value: *value,
+                };
+                if !conds.contains(&assumed){
+                    conds.push(assumed);
+                }
+            }
+            Item::Define(ident) => conds.retain(|cond| !cond.mentions(&ident.ident)),
+            Item::Matched(pattern) => {
+                let names = bindings(pattern);
+                conds.retain(|cond| !names.iter().any(|name| cond.mentions(name)));
+            }
+            Item::Statement(_) => {}
+        }
+    }
+}
+
+///Whether a declaration of type `ty` is initialized late, like `let x: Int = None`, which is when it is initialized with `None` even though its type is not nullable.
+pub fn is_lateinit(ty: &Ty, expr: &Expr) -> bool{
+    is_none(expr) && !ty.is_var() && ty.option_inner().is_none()
+}
+
+fn is_none(expr: &Expr) -> bool{
+    match expr.kind.as_ref(){
+        ExprElement::Value(TyValue{ elem: TyValueElement::None, .. }) => true,
+        ExprElement::Grouped(expr) => is_none(expr),
+        _ => false
+    }
+}
+
+///The symbol `expr` refers to, if it refers to one that can be narrowed.
+fn narrowable(expr: &Expr, typeck: &Typeck) -> Option<Symbol>{
+    match expr.kind.as_ref(){
+        ExprElement::Value(TyValue{ elem: TyValueElement::Ref(name), .. }) => typeck.scopes.lookup(name).filter(|symbol| !symbol.mutable.mutable),
+        ExprElement::Grouped(expr) => narrowable(expr, typeck),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    fn not_nullable() -> Vec<String>{
+        vec![
+            format!("Operator '+' cannot be applied to types Int? and Int"),
+            format!("This has type Int?"),
+            format!("This has type Int"),
+        ]
+    }
+
+    #[test]
+    fn symbols_are_narrowed_after_an_early_exit() {
+        assert_eq!(errors("fun f(n: Int?): Int{\n    if(n == None){ return 0 }\n    return n + 1\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun f(n: Int?): Int{\n    if(n != None){ }else{ return 0 }\n    let m = n + 1\n    return m\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun f(n: Int?): Int{\n    while(n == None){ }\n    return n + 1\n}"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn symbols_are_not_narrowed_unless_every_path_checked_them() {
+        assert_eq!(errors("fun f(n: Int?, b: Bool): Int{\n    if(b){ return 0 }\n    return n + 1\n}"), not_nullable());
+        assert_eq!(errors("fun f(n: Int?, b: Bool): Int{\n    if(n == None && b){ return 0 }\n    return n + 1\n}"), not_nullable());
+        assert_eq!(errors("fun f(n: Int?): Int{\n    if(n == None){ return 0 }\n    let n: Int? = None\n    return n + 1\n}"), not_nullable());
+        assert_eq!(errors("fun f(n: Int?): Int{\n    var x = n\n    if(x == None){ return 0 }\n    return x + 1\n}"), not_nullable());
+    }
+}
@@ -0,0 +1,590 @@
+use super::{
+    Typeck,
+    Load,
+    Unload,
+    Check,
+    ty::GetTy,
+    scope::Symbol,
+    fun::always_returns,
+};
+
+use ident::Identifier;
+
+use ty::{
+    Ty,
+    TyKind,
+    TyValue,
+    TyValueElement,
+};
+
+use stmt::{
+    block::Block,
+    expr::{
+        Expr,
+        ExprElement,
+        OpKind,
+    },
+    pattern::{
+        Match,
+        Arm,
+        Pattern,
+        PatternKind,
+    },
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+use core::pos::BiPos;
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+};
+
+impl Load for Match{
+    type Output = Match;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let scrutinee = match Expr::load(chunk, typeck){
+            Ok(Some(expr)) => expr,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let mut arms = vec![];
+        loop{
+            if let Some(HIRInstruction::EndMatch) = chunk.read_instruction() as Option<HIRInstruction>{
+                break;
+            }
+            chunk.dec_ins_ptr(1);
+            let pattern = match Pattern::load(chunk, typeck){
+                Ok(Some(pattern)) => pattern,
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            };
+            let body = match Block::load(chunk, typeck){
+                Ok(Some(block)) => block,
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            };
+            arms.push(Arm{
+                pattern,
+                body
+            });
+        }
+        Ok(Some(Match{
+            scrutinee,
+            arms,
+            ty: RefCell::new(Ty::new(TyKind::Unit, pos)),
+            pos
+        }))
+    }
+}
+
+impl Load for Pattern{
+    type Output = Pattern;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let ins = chunk.read_instruction() as Option<HIRInstruction>;
+        let (kind, pos) = match ins{
+            Some(HIRInstruction::PatternWildcard) => match chunk.read_pos(){
+                Ok(pos) => (PatternKind::Wildcard, pos),
+                Err(msg) => {
+                    let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                        .level(DiagnosticLevel::Error)
+                        .message(msg)
+                        .build();
+                    typeck.emit_diagnostic(&[], &[diag_source]);
+                    return Err(())
+                }
+            },
+            Some(HIRInstruction::PatternBinding) => match Identifier::load(chunk, typeck){
+                Ok(Some(ident)) => (PatternKind::Binding(ident.clone()), ident.pos),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            },
+            Some(HIRInstruction::PatternLiteral) => match Expr::load(chunk, typeck){
+                Ok(Some(expr)) => (PatternKind::Literal(expr.clone()), expr.pos),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            },
+            Some(HIRInstruction::PatternVariant) => {
+                let ident = match Identifier::load(chunk, typeck){
+                    Ok(Some(ident)) => ident,
+                    Ok(None) => return Ok(None),
+                    Err(notice) => return Err(notice)
+                };
+                let mut fields = vec![];
+                loop{
+                    if let Some(HIRInstruction::EndParams) = chunk.read_instruction() as Option<HIRInstruction>{
+                        break;
+                    }
+                    chunk.dec_ins_ptr(1);
+                    match Pattern::load(chunk, typeck){
+                        Ok(Some(field)) => fields.push(field),
+                        Ok(None) => return Ok(None),
+                        Err(notice) => return Err(notice)
+                    }
+                }
+                let pos = ident.pos;
+                (PatternKind::Variant(ident, fields), pos)
+            }
+            ins => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected a pattern but instead got {:?}", ins))
+                    .build();
+                typeck.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diag_source]);
+                return Err(())
+            }
+        };
+        Ok(Some(Pattern{
+            kind: RefCell::new(kind),
+            ty: RefCell::new(Ty::var(pos)),
+            pos
+        }))
+    }
+}
+
+impl<'a> Check<'a> for Match{
+    ///Each arm is checked with the bindings of its pattern in scope. The match then evaluates to the type that the arms which finish have in common.
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        self.scrutinee.check(typeck)?;
+        let scrutinee_ty = self.scrutinee.get_ty();
+        for arm in self.arms.iter(){
+            typeck.scopes.push();
+            let result = check_pattern(&arm.pattern, &scrutinee_ty, &mut HashMap::new(), typeck).and_then(|_| arm.body.check(typeck));
+            typeck.scopes.pop();
+            result?;
+        }
+        check_arms(self, &scrutinee_ty, typeck)?;
+        let mut tys = self.arms.iter().filter(|arm| !always_returns(&arm.body)).map(|arm| arm.body.get_ty());
+        let mut joined = match tys.next(){
+            Some(ty) => ty,
+            None => return Ok(())
+        };
+        for ty in tys{
+            //When only some arms are nullable, the others are coerced into them
+            let other = match (joined.option_inner(), ty.option_inner()){
+                (None, Some(_)) => std::mem::replace(&mut joined, ty),
+                _ => ty
+            };
+            //Objects of different classes join as the nearest class they have in common
+            joined = typeck.common_class(&joined, &other).unwrap_or(joined);
+            //A match that is only used as a statement may have arms of different types
+            if joined.coerce(&typeck.upcast(&joined, &other)).is_err(){
+                return Ok(())
+            }
+        }
+        self.ty.replace(joined.at(self.pos));
+        Ok(())
+    }
+}
+
+///Check `pattern` against values of type `expected`, declaring the names it binds in the innermost scope.
+///`bound` holds the names that the pattern of the arm has bound so far, since a pattern may bind each name only once.
+fn check_pattern(pattern: &Pattern, expected: &Ty, bound: &mut HashMap<String, BiPos>, typeck: &Typeck) -> Result<(), ()>{
+    pattern.ty.replace(expected.clone());
+    let kind = pattern.kind.borrow().clone();
+    match kind{
+        PatternKind::Wildcard => Ok(()),
+        PatternKind::Binding(ident) => {
+            let variant = typeck.variants(expected).and_then(|variants| variants.into_iter().find(|(name, _)| *name == ident.ident));
+            match variant{
+                //A name of a variant without fields matches that variant rather than binding it
+                Some((_, fields)) if fields.is_empty() => {
+                    pattern.kind.replace(PatternKind::Variant(ident, vec![]));
+                    return Ok(())
+                }
+                Some((_, fields)) => {
+                    let diag_source = typeck.error_source(ident.pos, format!("{} is a variant of {} with {} fields, so it cannot be matched without them", ident.ident, expected, fields.len()))?;
+                    let wildcards = vec!["_"; fields.len()].join(", ");
+                    typeck.emit_diagnostic(&[
+                        format!("Match its fields, like `{}({})`.", ident.ident, wildcards)
+                    ], &[diag_source]);
+                    return Err(())
+                }
+                None => {}
+            }
+            if let Some(first) = bound.insert(ident.ident.clone(), ident.pos){
+                let duplicate_source = typeck.error_source(ident.pos, format!("{} is bound more than once in this pattern", ident.ident))?;
+                let first_source = typeck.error_source(first, format!("{} is first bound here", ident.ident))?;
+                typeck.emit_diagnostic(&[], &[duplicate_source, first_source]);
+                return Err(())
+            }
+            typeck.scopes.declare(Symbol::immutable(ident, expected.clone()));
+            Ok(())
+        }
+        PatternKind::Literal(literal) => {
+            literal.check(typeck)?;
+            if let Err(mismatch) = expected.unify(&literal.get_ty()){
+                let found_message = format!("This pattern has type {}", mismatch.found);
+                let expected_message = format!("But what is matched has type {}", mismatch.expected);
+                return typeck.emit_mismatch(&mismatch, found_message, expected_message)
+            }
+            pattern.kind.replace(PatternKind::Literal(literal));
+            Ok(())
+        }
+        PatternKind::Variant(ident, fields) => {
+            if expected.is_var(){
+                let diag_source = typeck.error_source(pattern.pos, "The type of what is matched must be known before it can be matched against a variant".to_string())?;
+                typeck.emit_diagnostic(&[
+                    "Declare the type of what is matched, like `val x: Shape = ...`.".to_string()
+                ], &[diag_source]);
+                return Err(())
+            }
+            let variants = match typeck.variants(expected){
+                Some(variants) => variants,
+                None => {
+                    let diag_source = typeck.error_source(ident.pos, format!("{} is not an enum, so it cannot be matched against the variant {}", expected, ident.ident))?;
+                    typeck.emit_diagnostic(&[], &[diag_source]);
+                    return Err(())
+                }
+            };
+            let variant_fields = match variants.iter().find(|(name, _)| *name == ident.ident){
+                Some((_, fields)) => fields.clone(),
+                None => {
+                    let diag_source = typeck.error_source(ident.pos, format!("{} has no variant named {}", expected, ident.ident))?;
+                    let names = variants.iter().map(|(name, _)| name.clone()).collect::<Vec<String>>().join(", ");
+                    typeck.emit_diagnostic(&[
+                        format!("The variants of {} are {}.", expected, names)
+                    ], &[diag_source]);
+                    return Err(())
+                }
+            };
+            if variant_fields.len() != fields.len(){
+                let diag_source = typeck.error_source(pattern.pos, format!("This pattern matches {} fields of {}, but it has {}", fields.len(), ident.ident, variant_fields.len()))?;
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+            for (field, field_ty) in fields.iter().zip(variant_fields.iter()){
+                check_pattern(field, field_ty, bound, typeck)?;
+            }
+            //The literals and the fields were checked on a copy of the pattern
+            pattern.kind.replace(PatternKind::Variant(ident, fields));
+            Ok(())
+        }
+    }
+}
+
+///A pattern as far as which values it matches is concerned. Bindings match anything just like wildcards do.
+#[derive(Debug, Clone)]
+enum Pat{
+    Any,
+    Ctor(Ctor, Vec<Pat>),
+}
+
+///What a pattern that does not match anything checks a value for.
+#[derive(Debug, Clone, PartialEq)]
+enum Ctor{
+    Variant(String),
+    Bool(bool),
+    ///A literal other than a Bool, written as it is in the source.
+    Literal(String),
+}
+
+impl Pat{
+    fn of(pattern: &Pattern) -> Pat{
+        match &*pattern.kind.borrow(){
+            PatternKind::Wildcard | PatternKind::Binding(_) => Pat::Any,
+            PatternKind::Literal(literal) => Pat::Ctor(literal_ctor(literal), vec![]),
+            PatternKind::Variant(ident, fields) => Pat::Ctor(Ctor::Variant(ident.ident.clone()), fields.iter().map(Pat::of).collect()),
+        }
+    }
+}
+
+impl std::fmt::Display for Pat{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        match self{
+            Pat::Any => write!(f, "_"),
+            Pat::Ctor(Ctor::Bool(value), _) => write!(f, "{}", value),
+            Pat::Ctor(Ctor::Literal(literal), _) => write!(f, "{}", literal),
+            Pat::Ctor(Ctor::Variant(name), fields) if fields.is_empty() => write!(f, "{}", name),
+            Pat::Ctor(Ctor::Variant(name), fields) => write!(f, "{}({})", name, fields.iter().map(|field| field.to_string()).collect::<Vec<String>>().join(", ")),
+        }
+    }
+}
+
+fn literal_ctor(literal: &Expr) -> Ctor{
+    match literal.kind.as_ref(){
+        ExprElement::Value(TyValue{ elem: TyValueElement::Bool(value), .. }) => Ctor::Bool(*value),
+        ExprElement::Value(TyValue{ elem, .. }) => Ctor::Literal(match elem{
+            TyValueElement::Integer(value) => value.to_string(),
+            TyValueElement::Float(value) => format!("{:?}", value),
+            TyValueElement::Char(value) => format!("{:?}", value),
+            TyValueElement::String(value) => format!("{:?}", value),
+            elem => format!("{:?}", elem),
+        }),
+        ExprElement::UnaryOp(OpKind::Neg, operand) => match literal_ctor(operand){
+            Ctor::Literal(literal) => Ctor::Literal(format!("-{}", literal)),
+            ctor => ctor
+        },
+        _ => Ctor::Literal(format!("{:?}", literal.pos))
+    }
+}
+
+///Report the first arm that is never reached, since the arms before it match everything it does, and then any value that no arm matches.
+///Whether a pattern matches anything new is found as in "Warnings for pattern matching" by Maranget, which also gives an example of a value that it matches.
+fn check_arms(match_: &Match, scrutinee_ty: &Ty, typeck: &Typeck) -> Result<(), ()>{
+    let mut rows: Vec<Vec<Pat>> = vec![];
+    for arm in match_.arms.iter(){
+        let row = vec![Pat::of(&arm.pattern)];
+        if typeck.useful(&rows, &row, std::slice::from_ref(scrutinee_ty)).is_none(){
+            let diag_source = typeck.error_source(arm.pattern.pos, "This arm is never reached, since the arms before it match everything that it does".to_string())?;
+            typeck.emit_diagnostic(&[
+                "Remove it, or move it above the arms that match what it does.".to_string()
+            ], &[diag_source]);
+            return Err(())
+        }
+        rows.push(row);
+    }
+    let witness = match typeck.useful(&rows, &[Pat::Any], std::slice::from_ref(scrutinee_ty)){
+        Some(witness) => witness,
+        None => return Ok(())
+    };
+    let message = match &witness[0]{
+        Pat::Any => format!("This match does not cover every {}", scrutinee_ty),
+        uncovered => format!("This match does not cover {}", uncovered),
+    };
+    let diag_source = typeck.error_source(match_.pos, message)?;
+    typeck.emit_diagnostic(&[
+        "Add an arm for it, or an arm `_ => ...` that matches everything else.".to_string()
+    ], &[diag_source]);
+    Err(())
+}
+
+impl Typeck{
+    ///A row of values of types `tys` that `row` matches but none of the rows of `matrix` do, if there is one.
+    fn useful(&self, matrix: &[Vec<Pat>], row: &[Pat], tys: &[Ty]) -> Option<Vec<Pat>>{
+        let (head, rest) = match row.split_first(){
+            Some(split) => split,
+            None => return match matrix.is_empty(){
+                true => Some(vec![]),
+                false => None
+            }
+        };
+        match head{
+            Pat::Ctor(ctor, fields) => {
+                let field_tys = self.ctor_fields(&tys[0], ctor);
+                self.useful_ctor(matrix, ctor, fields.clone(), rest, &field_tys, &tys[1..])
+            }
+            Pat::Any => {
+                let used = matrix.iter().filter_map(|row| match &row[0]{
+                    Pat::Ctor(ctor, _) => Some(ctor.clone()),
+                    Pat::Any => None
+                }).collect::<Vec<Ctor>>();
+                let ctors = self.ctors(&tys[0]);
+                if let Some(ctors) = ctors.as_ref().filter(|ctors| ctors.iter().all(|(ctor, _)| used.contains(ctor))){
+                    return ctors.iter().find_map(|(ctor, field_tys)| {
+                        self.useful_ctor(matrix, ctor, vec![Pat::Any; field_tys.len()], rest, field_tys, &tys[1..])
+                    })
+                }
+                //Only the rows that match anything here can match what none of the others do
+                let defaults = matrix.iter().filter(|row| matches!(row[0], Pat::Any)).map(|row| row[1..].to_vec()).collect::<Vec<Vec<Pat>>>();
+                let witness = self.useful(&defaults, rest, &tys[1..])?;
+                let missing = ctors.and_then(|ctors| ctors.into_iter().find(|(ctor, _)| !used.contains(ctor))).map(|(ctor, field_tys)| {
+                    Pat::Ctor(ctor, vec![Pat::Any; field_tys.len()])
+                });
+                Some(std::iter::once(missing.unwrap_or(Pat::Any)).chain(witness).collect())
+            }
+        }
+    }
+
+    ///[Typeck::useful] for a row that starts with `ctor` applied to `fields`, which is only matched by the rows that start with `ctor` or match anything.
+    fn useful_ctor(&self, matrix: &[Vec<Pat>], ctor: &Ctor, fields: Vec<Pat>, rest: &[Pat], field_tys: &[Ty], rest_tys: &[Ty]) -> Option<Vec<Pat>>{
+        let arity = fields.len();
+        let specialized = matrix.iter().filter_map(|row| {
+            let head = match &row[0]{
+                Pat::Ctor(found, found_fields) if found == ctor => found_fields.clone(),
+                Pat::Ctor(..) => return None,
+                Pat::Any => vec![Pat::Any; arity]
+            };
+            Some(head.into_iter().chain(row[1..].iter().cloned()).collect())
+        }).collect::<Vec<Vec<Pat>>>();
+        let row = fields.into_iter().chain(rest.iter().cloned()).collect::<Vec<Pat>>();
+        let tys = field_tys.iter().chain(rest_tys.iter()).cloned().collect::<Vec<Ty>>();
+        let mut witness = self.useful(&specialized, &row, &tys)?;
+        let rest = witness.split_off(arity);
+        Some(std::iter::once(Pat::Ctor(ctor.clone(), witness)).chain(rest).collect())
+    }
+
+    ///Every value of type `ty` that a pattern can check for, along with the types of their fields, or `None` if there are too many to list.
+    fn ctors(&self, ty: &Ty) -> Option<Vec<(Ctor, Vec<Ty>)>>{
+        if let TyKind::Bool = ty.resolve().kind().as_ref(){
+            return Some(vec![(Ctor::Bool(true), vec![]), (Ctor::Bool(false), vec![])])
+        }
+        self.variants(ty).map(|variants| variants.into_iter().map(|(name, fields)| (Ctor::Variant(name), fields)).collect())
+    }
+
+    fn ctor_fields(&self, ty: &Ty, ctor: &Ctor) -> Vec<Ty>{
+        match ctor{
+            Ctor::Variant(name) => self.variants(ty).and_then(|variants| variants.into_iter().find(|(found, _)| found == name)).map(|(_, fields)| fields).unwrap_or_default(),
+            _ => vec![]
+        }
+    }
+}
+
+impl Unload for Match{
+    ///TIR expressions start with their position rather than an instruction, so each arm is preceded by `true` and the arms end with `false` instead of `EndMatch`.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Match);
+        match self.scrutinee.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        for arm in self.arms.iter(){
+            chunk.write_bool(true);
+            match arm.pattern.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+            match arm.body.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+        }
+        chunk.write_bool(false);
+        Ok(chunk)
+    }
+}
+
+impl Unload for Pattern{
+    ///A binding is followed by its type, and a variant by the type of the enum it belongs to, so that its tag and the offsets of its fields can be found.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        match &*self.kind.borrow(){
+            PatternKind::Wildcard => {
+                chunk.write_instruction(HIRInstruction::PatternWildcard);
+                chunk.write_pos(self.pos);
+            }
+            PatternKind::Binding(ident) => {
+                chunk.write_instruction(HIRInstruction::PatternBinding);
+                match ident.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                match self.ty.borrow().unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+            }
+            PatternKind::Literal(literal) => {
+                chunk.write_instruction(HIRInstruction::PatternLiteral);
+                match literal.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+            }
+            PatternKind::Variant(ident, fields) => {
+                chunk.write_instruction(HIRInstruction::PatternVariant);
+                match self.ty.borrow().unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                match ident.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+                for field in fields.iter(){
+                    chunk.write_bool(true);
+                    match field.unload(){
+                        Ok(ch) => chunk.write_chunk(ch),
+                        Err(notice) => return Err(notice)
+                    }
+                }
+                chunk.write_bool(false);
+            }
+        }
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    #[test]
+    fn matches_cover_every_variant() {
+        let shape = "enum Shape{ Circle(Int), Rect(Int, Int), Empty }\n";
+        assert_eq!(errors(&format!("{}fun f(s: Shape): Int = match(s){{\n    Circle(r) => r,\n    Rect(w, h) => w * h,\n    Empty => 0\n}}", shape)), Vec::<String>::new());
+        assert_eq!(errors(&format!("{}fun f(s: Shape): Int = match(s){{\n    Circle(r) => r,\n    Rect(w, h) => w * h\n}}", shape)), vec![
+            format!("This match does not cover Empty"),
+        ]);
+    }
+
+    #[test]
+    fn matches_cover_every_combination_of_fields() {
+        assert_eq!(errors("enum Shape{ Circle(Int), Empty }\nfun f(s: Shape?): Int = match(s){\n    Some(Circle(r)) => r,\n    None => 0\n}"), vec![
+            format!("This match does not cover Some(Empty)"),
+        ]);
+        assert_eq!(errors("enum P{ P(Bool, Bool) }\nfun f(p: P): Int = match(p){\n    P(true, _) => 1,\n    P(_, true) => 2\n}"), vec![
+            format!("This match does not cover P(false, false)"),
+        ]);
+    }
+
+    #[test]
+    fn literals_only_cover_every_value_of_bools() {
+        assert_eq!(errors("fun f(b: Bool): Int = match(b){\n    true => 1,\n    false => 0\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun f(b: Bool): Int = match(b){\n    true => 1\n}"), vec![
+            format!("This match does not cover false"),
+        ]);
+        assert_eq!(errors("fun f(n: Int): Int = match(n){\n    1 => 1,\n    2 => 2\n}"), vec![
+            format!("This match does not cover every Int"),
+        ]);
+    }
+
+    #[test]
+    fn arms_after_the_ones_that_match_everything_they_do_are_never_reached() {
+        let unreachable = vec![format!("This arm is never reached, since the arms before it match everything that it does")];
+        assert_eq!(errors("fun f(n: Int): Int = match(n){\n    _ => 1,\n    2 => 2\n}"), unreachable);
+        assert_eq!(errors("enum P{ P(Bool, Bool) }\nfun f(p: P): Int = match(p){\n    P(true, _) => 1,\n    P(_, false) => 2,\n    P(true, false) => 3,\n    _ => 4\n}"), unreachable);
+    }
+
+    #[test]
+    fn patterns_bind_each_name_once() {
+        assert_eq!(errors("enum P{ P(Int, Int) }\nfun f(p: P): Int = match(p){\n    P(x, x) => x\n}"), vec![
+            format!("x is bound more than once in this pattern"),
+            format!("x is first bound here"),
+        ]);
+    }
+
+    #[test]
+    fn nullable_values_are_matched_as_some_or_none() {
+        assert_eq!(errors("fun f(x: Int?): Int = match(x){\n    Some(v) => v,\n    None => 0\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun f(x: Option<Int>): Int = match(x){\n    Some(v) => v,\n    _ => 0\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun f(x: Int?): Int = match(x){\n    Some(v) => v\n}"), vec![
+            format!("This match does not cover None"),
+        ]);
+        assert_eq!(errors("fun f(x: Int?): Int = match(x){\n    Some(v) => v,\n    None => 0,\n    Some(3) => 3\n}"), vec![
+            format!("This arm is never reached, since the arms before it match everything that it does"),
+        ]);
+    }
+}
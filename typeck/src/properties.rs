@@ -2,10 +2,14 @@ use super::{
     Typeck,
     Load,
     Unload,
+    Check,
     ty::{
         GetTy,
         Inference
-    }
+    },
+    scope::Symbol,
+    lambda::expect,
+    nullable::is_lateinit,
 };
 
 use ty::{
@@ -14,7 +18,7 @@ use ty::{
 
 use mutable::Mutability;
 
-use expr::{
+use stmt::expr::{
     Expr,
 };
 
@@ -43,51 +47,49 @@ use stmt::{
 impl Inference for Property{
     fn infer_type(&self, typeck: &Typeck) -> Result<(),()> {
         let ty_inner = self.ty.clone().into_inner();
-        let expr_ty = self.expr.get_ty();
-        if ty_inner.ident == "Unknown"{
-            self.ty.replace(Ty{
-                ident: expr_ty.ident.clone(),
-                pos: ty_inner.pos,
-            });
-            return Ok(());
+        let expr_ty = &self.expr.get_ty();
+        //An unannotated declaration is a type variable, which is inferred from the expression here
+        let mismatch = match ty_inner.coerce(&typeck.upcast(&ty_inner, expr_ty)){
+            Ok(()) => return Ok(()),
+            Err(mismatch) => mismatch
+        };
+        if mismatch.infinite{
+            return typeck.emit_mismatch(&mismatch, String::new(), String::new())
         }
-        if ty_inner != *expr_ty{
-            let ty_source = match typeck.request_source_snippet(ty_inner.pos){
-                Ok(source) => source,
-                Err(diag) => {
-                    typeck.emit_diagnostic(&[], &[diag]);
-                    return Err(())
-                }
-            };
-            let expr_source = match typeck.request_source_snippet(self.expr.pos){
-                Ok(source) => source,
-                Err(diag) => {
-                    typeck.emit_diagnostic(&[], &[diag]);
-                    return Err(())
-                }
-            };
-            let ty_diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), ty_inner.pos.start.0)
-                .level(DiagnosticLevel::Error)
-                .message(format!(
-                    "Expected an assignment of type {:?}", 
-                    ty_inner.ident,
-                ))
-                .source(ty_source)
-                .range(ty_inner.pos.col_range())
-                .build();
-            let expr_diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), ty_inner.pos.start.0)
+        let ty_source = match typeck.request_source_snippet(mismatch.expected.pos){
+            Ok(source) => source,
+            Err(diag) => {
+                typeck.emit_diagnostic(&[], &[diag]);
+                return Err(())
+            }
+        };
+        let expr_source = match typeck.request_source_snippet(mismatch.found.pos){
+            Ok(source) => source,
+            Err(diag) => {
+                typeck.emit_diagnostic(&[], &[diag]);
+                return Err(())
+            }
+        };
+        let ty_diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), mismatch.expected.pos.start.0)
             .level(DiagnosticLevel::Error)
             .message(format!(
-                "But instead found an assignment of type {:?}", 
-                expr_ty.ident,
+                "Expected an assignment of type {}", 
+                mismatch.expected,
+            ))
+            .source(ty_source)
+            .range(mismatch.expected.pos.col_range())
+            .build();
+        let expr_diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), mismatch.found.pos.start.0)
+            .level(DiagnosticLevel::Error)
+            .message(format!(
+                "But instead found an assignment of type {}", 
+                mismatch.found,
             ))
             .source(expr_source)
-            .range(ty_inner.pos.col_range())
+            .range(mismatch.found.pos.col_range())
             .build();
-            typeck.emit_diagnostic(&[], &[ty_diag_source, expr_diag_source]);
-            return Err(())
-        }
-        Ok(())
+        typeck.emit_diagnostic(&[], &[ty_diag_source, expr_diag_source]);
+        Err(())
     }
 }
 
@@ -123,17 +125,7 @@ impl Load for Property{
             Err(msg) => return Err(msg)
         };
 
-        let expr_chunk = if let Ok(Some(expr_chunk)) = typeck.chunk_rx.recv(){
-            expr_chunk
-        }else{
-            let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
-                .level(DiagnosticLevel::Error)
-                .message(format!("Failed to get HIR chunk for expression while loading property"))
-                .build();
-            typeck.emit_diagnostic(&[], &[diag_source]);
-            return Err(())
-        };
-        let expr = match Expr::load(&expr_chunk, typeck){
+        let expr = match Expr::load(chunk, typeck){
             Ok(Some(expr)) => expr,
             Ok(None) => return Ok(None),
             Err(msg) => return Err(msg)
@@ -142,6 +134,7 @@ impl Load for Property{
             Property{
                 ident,
                 pos,
+                lateinit: is_lateinit(&ty, &expr),
                 ty: RefCell::new(ty),
                 expr,
                 mutable
@@ -150,9 +143,21 @@ impl Load for Property{
     }
 }
 
-impl<'a> super::Check<'a> for Property{
+impl<'a> Check<'a> for Property{
     fn check(&self, typeck: &'a Typeck) -> Result<(),()>{
-        self.infer_type(typeck)?;
+        expect(&self.expr, &self.ty.borrow());
+        self.expr.check(typeck)?;
+        //A declaration that is initialized late has no value yet, so `None` does not have to be of its type
+        if !self.lateinit{
+            self.infer_type(typeck)?;
+        }
+        typeck.scopes.declare(Symbol{
+            ident: self.ident.clone(),
+            ty: self.ty.borrow().clone(),
+            mutable: self.mutable.clone(),
+            ty_params: vec![],
+            lateinit: self.lateinit,
+        });
         Ok(())
     }
 }
@@ -174,6 +179,7 @@ impl Unload for Property{
             Ok(ch) => chunk.write_chunk(ch),
             Err(notice) => return Err(notice)
         }
+        chunk.write_bool(self.lateinit);
         match self.expr.unload(){
             Ok(ch) => chunk.write_chunk(ch),
             Err(notice) => return Err(notice)
@@ -0,0 +1,97 @@
+use ident::Identifier;
+use mutable::Mutability;
+use ty::Ty;
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+};
+
+///A named object that has been declared in a scope, such as a property, local, function parameter or loop binding.
+#[derive(Debug, Clone)]
+pub struct Symbol{
+    pub ident: Identifier,
+    pub ty: Ty,
+    ///Whether the symbol can be assigned to. Only `var` properties and `let mut` locals are mutable.
+    pub mutable: Mutability,
+    ///The type params of a generic function, which get fresh type variables wherever the function is referenced.
+    pub ty_params: Vec<String>,
+    ///Whether the symbol is initialized late, which lets it be assigned to once even if it is immutable.
+    pub lateinit: bool,
+}
+
+impl Symbol{
+    ///A symbol that can never be assigned to, such as a function, a param or a loop binding.
+    pub fn immutable(ident: Identifier, ty: Ty) -> Symbol{
+        let pos = ident.pos;
+        Symbol{
+            ident,
+            ty,
+            mutable: Mutability{
+                mutable: false,
+                pos
+            },
+            ty_params: vec![],
+            lateinit: false,
+        }
+    }
+}
+
+///An extension function, which is called like a method on values of its receiver type rather than by its name.
+#[derive(Debug, Clone)]
+pub struct Extension{
+    pub ident: Identifier,
+    pub receiver: Ty,
+    ///The type of the function, without its receiver.
+    pub ty: Ty,
+}
+
+///A single lexical scope. Every module, function and block gets its own.
+#[derive(Debug, Clone, Default)]
+pub struct Scope{
+    symbols: HashMap<String, Symbol>,
+    ///Extension functions are kept apart from the symbols, since several types may each have an extension of the same name.
+    extensions: Vec<Extension>,
+}
+
+///The stack of scopes that are currently visible, innermost last.
+///This lives behind a RefCell because checking only ever has a shared reference to the typeck.
+#[derive(Debug, Default)]
+pub struct ScopeStack{
+    scopes: RefCell<Vec<Scope>>,
+}
+
+impl ScopeStack{
+    pub fn push(&self){
+        self.scopes.borrow_mut().push(Scope::default());
+    }
+
+    pub fn pop(&self){
+        self.scopes.borrow_mut().pop();
+    }
+
+    ///Declare a symbol in the innermost scope. A symbol with the same name in the same scope is shadowed.
+    pub fn declare(&self, symbol: Symbol){
+        if let Some(scope) = self.scopes.borrow_mut().last_mut(){
+            scope.symbols.insert(symbol.ident.ident.clone(), symbol);
+        }
+    }
+
+    ///Declare an extension function in the innermost scope. Declaring the same function again replaces it.
+    pub fn declare_extension(&self, extension: Extension){
+        if let Some(scope) = self.scopes.borrow_mut().last_mut(){
+            scope.extensions.retain(|declared| declared.ident.pos != extension.ident.pos);
+            scope.extensions.push(extension);
+        }
+    }
+
+    ///The extension functions with the given name in each scope, innermost first.
+    pub fn extensions(&self, name: &str) -> Vec<Vec<Extension>>{
+        self.scopes.borrow().iter().rev().map(|scope| scope.extensions.iter().filter(|extension| extension.ident.ident == name).cloned().collect()).collect()
+    }
+
+    ///Find the innermost symbol with the given name.
+    pub fn lookup(&self, name: &str) -> Option<Symbol>{
+        self.scopes.borrow().iter().rev().find_map(|scope| scope.symbols.get(name).cloned())
+    }
+}
@@ -1,7 +1,15 @@
 use stmt::{
     property::Property,
-    fun::Fun,
+    fun::{
+        Fun,
+        Return,
+    },
     local::Local,
+    expr::Expr,
+    loops::Break,
+    assign::Assign,
+    structs::Struct,
+    enums::Enum,
     Statement,
     StatementKind
 };
@@ -15,8 +23,16 @@ use super::{
     Typeck,
     Load,
     Unload,
+    Check,
+    loops::check_continue,
+    assign::load_assign_member,
+    structs::load_class,
+    interfaces::load_interface,
+};
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
 };
-use ir_traits::ReadInstruction;
 use notices::{ 
     DiagnosticLevel,
     DiagnosticSourceBuilder,
@@ -57,6 +73,161 @@ impl Load for Statement{
                 Ok(None) => return Ok(None),
                 Err(msg) => return Err(msg)
             }
+            Some(HIRInstruction::ExprStmt) => match Expr::load(chunk, typeck){
+                Ok(Some(expr)) => {
+                    Ok(Some(Statement{
+                        pos: expr.pos,
+                        kind: StatementKind::Expr(expr),
+                    }))
+                },
+                Ok(None) => return Ok(None),
+                Err(msg) => return Err(msg)
+            }
+            Some(HIRInstruction::Break) => {
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        typeck.emit_diagnostic(&[], &[diag_source]);
+                        return Err(())
+                    }
+                };
+                let value = if chunk.read_bool(){
+                    match Expr::load(chunk, typeck){
+                        Ok(Some(expr)) => Some(expr),
+                        Ok(None) => return Ok(None),
+                        Err(msg) => return Err(msg)
+                    }
+                }else{
+                    None
+                };
+                Ok(Some(Statement{
+                    kind: StatementKind::Break(Break{
+                        value,
+                        pos
+                    }),
+                    pos
+                }))
+            }
+            Some(HIRInstruction::Return) => {
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        typeck.emit_diagnostic(&[], &[diag_source]);
+                        return Err(())
+                    }
+                };
+                let value = if chunk.read_bool(){
+                    match Expr::load(chunk, typeck){
+                        Ok(Some(expr)) => Some(expr),
+                        Ok(None) => return Ok(None),
+                        Err(msg) => return Err(msg)
+                    }
+                }else{
+                    None
+                };
+                Ok(Some(Statement{
+                    kind: StatementKind::Return(Return{
+                        value,
+                        pos
+                    }),
+                    pos
+                }))
+            }
+            Some(HIRInstruction::Struct) => match Struct::load(chunk, typeck){
+                Ok(Some(structure)) => {
+                    Ok(Some(Statement{
+                        pos: structure.pos,
+                        kind: StatementKind::Struct(structure),
+                    }))
+                },
+                Ok(None) => return Ok(None),
+                Err(msg) => return Err(msg)
+            }
+            Some(HIRInstruction::Class) => match load_class(chunk, typeck){
+                Ok(Some(class)) => {
+                    Ok(Some(Statement{
+                        pos: class.pos,
+                        kind: StatementKind::Struct(class),
+                    }))
+                },
+                Ok(None) => return Ok(None),
+                Err(msg) => return Err(msg)
+            }
+            Some(HIRInstruction::Interface) => match load_interface(chunk, typeck, false){
+                Ok(Some(interface)) => {
+                    Ok(Some(Statement{
+                        pos: interface.pos,
+                        kind: StatementKind::Interface(interface),
+                    }))
+                },
+                Ok(None) => return Ok(None),
+                Err(msg) => return Err(msg)
+            }
+            Some(HIRInstruction::Trait) => match load_interface(chunk, typeck, true){
+                Ok(Some(interface)) => {
+                    Ok(Some(Statement{
+                        pos: interface.pos,
+                        kind: StatementKind::Interface(interface),
+                    }))
+                },
+                Ok(None) => return Ok(None),
+                Err(msg) => return Err(msg)
+            }
+            Some(HIRInstruction::Enum) => match Enum::load(chunk, typeck){
+                Ok(Some(enumeration)) => {
+                    Ok(Some(Statement{
+                        pos: enumeration.pos,
+                        kind: StatementKind::Enum(enumeration),
+                    }))
+                },
+                Ok(None) => return Ok(None),
+                Err(msg) => return Err(msg)
+            }
+            Some(HIRInstruction::AssignMember) => match load_assign_member(chunk, typeck){
+                Ok(Some(assign)) => {
+                    Ok(Some(Statement{
+                        pos: assign.pos,
+                        kind: StatementKind::Assign(assign),
+                    }))
+                },
+                Ok(None) => return Ok(None),
+                Err(msg) => return Err(msg)
+            }
+            Some(HIRInstruction::Assign) => match Assign::load(chunk, typeck){
+                Ok(Some(assign)) => {
+                    Ok(Some(Statement{
+                        pos: assign.pos,
+                        kind: StatementKind::Assign(assign),
+                    }))
+                },
+                Ok(None) => return Ok(None),
+                Err(msg) => return Err(msg)
+            }
+            Some(HIRInstruction::Continue) => {
+                let pos = match chunk.read_pos(){
+                    Ok(pos) => pos,
+                    Err(msg) => {
+                        let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                            .level(DiagnosticLevel::Error)
+                            .message(msg)
+                            .build();
+                        typeck.emit_diagnostic(&[], &[diag_source]);
+                        return Err(())
+                    }
+                };
+                Ok(Some(Statement{
+                    kind: StatementKind::Continue,
+                    pos
+                }))
+            }
             _ => {
                 chunk.jump_to(0).unwrap();
                 let message = if chunk.code.is_empty(){
@@ -77,12 +248,20 @@ impl Load for Statement{
     }
 }
 
-impl<'a> super::Check<'a> for Statement{
+impl<'a> Check<'a> for Statement{
     fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
         match &self.kind{
             StatementKind::Local(local) => local.check(typeck),
             StatementKind::Fun(fun) => fun.check(typeck),
             StatementKind::Property(property) => property.check(typeck),
+            StatementKind::Expr(expr) => expr.check(typeck),
+            StatementKind::Break(break_) => break_.check(typeck),
+            StatementKind::Continue => check_continue(self.pos, typeck),
+            StatementKind::Return(return_) => return_.check(typeck),
+            StatementKind::Assign(assign) => assign.check(typeck),
+            StatementKind::Struct(structure) => structure.check(typeck),
+            StatementKind::Interface(interface) => interface.check(typeck),
+            StatementKind::Enum(enumeration) => enumeration.check(typeck),
         }
     }
 }
@@ -103,6 +282,56 @@ impl Unload for Statement{
                 Ok(ch) => chunk.write_chunk(ch),
                 Err(msg) => return Err(msg)
             },
+            StatementKind::Expr(expr) => {
+                chunk.write_instruction(HIRInstruction::ExprStmt);
+                match expr.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(msg) => return Err(msg)
+                }
+            },
+            StatementKind::Break(break_) => {
+                chunk.write_instruction(HIRInstruction::Break);
+                match &break_.value{
+                    Some(value) => {
+                        chunk.write_bool(true);
+                        match value.unload(){
+                            Ok(ch) => chunk.write_chunk(ch),
+                            Err(msg) => return Err(msg)
+                        }
+                    }
+                    None => chunk.write_bool(false)
+                }
+            },
+            StatementKind::Continue => chunk.write_instruction(HIRInstruction::Continue),
+            StatementKind::Return(return_) => {
+                chunk.write_instruction(HIRInstruction::Return);
+                match &return_.value{
+                    Some(value) => {
+                        chunk.write_bool(true);
+                        match value.unload(){
+                            Ok(ch) => chunk.write_chunk(ch),
+                            Err(msg) => return Err(msg)
+                        }
+                    }
+                    None => chunk.write_bool(false)
+                }
+            },
+            StatementKind::Assign(assign) => match assign.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(msg) => return Err(msg)
+            },
+            StatementKind::Struct(structure) => match structure.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(msg) => return Err(msg)
+            },
+            StatementKind::Interface(interface) => match interface.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(msg) => return Err(msg)
+            },
+            StatementKind::Enum(enumeration) => match enumeration.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(msg) => return Err(msg)
+            },
         }
         chunk.write_pos(self.pos);
         Ok(chunk)
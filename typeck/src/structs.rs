@@ -0,0 +1,1044 @@
+use super::{
+    Typeck,
+    Load,
+    Unload,
+    Check,
+    ty::GetTy,
+    lambda::expect,
+    scope::Symbol,
+    fun::fun_ty,
+    interfaces::check_with,
+    extensions::check_method,
+};
+
+use ident::Identifier;
+use mutable::Mutability;
+
+use ty::{
+    Ty,
+    TyKind,
+};
+
+use stmt::{
+    Statement,
+    StatementKind,
+    fun::Fun,
+    generic::TyParam,
+    property::Property,
+    structs::{
+        Struct,
+        Field,
+        Class,
+        Base,
+        Modifier,
+    },
+    expr::Expr,
+};
+
+use ir::{
+    Chunk,
+    hir::HIRInstruction,
+};
+
+use ir_traits::{
+    ReadInstruction,
+    WriteInstruction,
+};
+
+use notices::{
+    DiagnosticLevel,
+    DiagnosticSourceBuilder,
+};
+
+use core::pos::BiPos;
+
+use std::{
+    cell::RefCell,
+    collections::{
+        HashMap,
+        HashSet,
+    },
+};
+
+///A field or a method of a struct, with the type arguments of the struct substituted into its type.
+pub struct Member{
+    pub ident: Identifier,
+    pub ty: Ty,
+    ///How the field was declared, or `None` if this is a method.
+    pub mutable: Option<Mutability>,
+    ///The name that an extension function is unloaded as, or `None` if this is a member of the type itself.
+    pub extension: Option<String>,
+}
+
+impl Load for Struct{
+    type Output = Struct;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let ident = match Identifier::load(chunk, typeck){
+            Ok(Some(ident)) => ident,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let mut ty_params = vec![];
+        while let Some(HIRInstruction::TyParam) = chunk.read_instruction() as Option<HIRInstruction>{
+            match TyParam::load(chunk, typeck){
+                Ok(Some(param)) => ty_params.push(param),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            }
+        }
+        chunk.dec_ins_ptr(1);
+        //The type params are visible in the fields and the methods
+        let outer_ty_params = typeck.ty_params.borrow().clone();
+        typeck.ty_params.borrow_mut().extend(ty_params.iter().map(|param| param.ident.ident.clone()));
+        let result = load_members(chunk, typeck);
+        typeck.ty_params.replace(outer_ty_params);
+        let (fields, methods, _, with) = match result{
+            Ok(Some(members)) => members,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        Ok(Some(Struct{
+            ident,
+            ty_params,
+            fields,
+            methods,
+            class: None,
+            with,
+            pos
+        }))
+    }
+}
+
+///Load a class, which is loaded into a [Struct] along with what makes it a class.
+pub fn load_class(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Struct>, ()>{
+    let pos = match chunk.read_pos(){
+        Ok(pos) => pos,
+        Err(msg) => {
+            let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                .level(DiagnosticLevel::Error)
+                .message(msg)
+                .build();
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+    };
+    let ident = match Identifier::load(chunk, typeck){
+        Ok(Some(ident)) => ident,
+        Ok(None) => return Ok(None),
+        Err(notice) => return Err(notice)
+    };
+    let modifier = match load_modifier(chunk, typeck)?{
+        Some(modifier) => modifier,
+        None => Modifier::Final
+    };
+    let (fields, methods, base, with) = match load_members(chunk, typeck){
+        Ok(Some(members)) => members,
+        Ok(None) => return Ok(None),
+        Err(notice) => return Err(notice)
+    };
+    Ok(Some(Struct{
+        ident,
+        ty_params: vec![],
+        fields,
+        methods,
+        class: Some(Class{
+            modifier,
+            base
+        }),
+        with,
+        pos
+    }))
+}
+
+///Load the `Open`, `Abstract`, `Override` or `Impl` modifier at the current instruction, if there is one.
+fn load_modifier(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Modifier>, ()>{
+    let modifier = match chunk.read_instruction() as Option<HIRInstruction>{
+        Some(HIRInstruction::Open) => Modifier::Open,
+        Some(HIRInstruction::Abstract) => Modifier::Abstract,
+        Some(HIRInstruction::Override) => Modifier::Override,
+        Some(HIRInstruction::Impl) => Modifier::Impl,
+        _ => {
+            chunk.dec_ins_ptr(1);
+            return Ok(None)
+        }
+    };
+    if let Err(msg) = chunk.read_pos(){
+        let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+            .level(DiagnosticLevel::Error)
+            .message(msg)
+            .build();
+        typeck.emit_diagnostic(&[], &[diag_source]);
+        return Err(())
+    }
+    Ok(Some(modifier))
+}
+
+///Whether the member at the current instruction is preceded by `Private`, which is then loaded.
+fn load_private(chunk: &Chunk, typeck: &Typeck) -> Result<bool, ()>{
+    match chunk.read_instruction() as Option<HIRInstruction>{
+        Some(HIRInstruction::Private) => {}
+        _ => {
+            chunk.dec_ins_ptr(1);
+            return Ok(false)
+        }
+    }
+    if let Err(msg) = chunk.read_pos(){
+        let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+            .level(DiagnosticLevel::Error)
+            .message(msg)
+            .build();
+        typeck.emit_diagnostic(&[], &[diag_source]);
+        return Err(())
+    }
+    Ok(true)
+}
+
+///The fields and methods of a struct, along with its base class if it is a class that has one, and what its `with` clause names.
+type Members = (Vec<Field>, Vec<Fun>, Option<Base>, Vec<Ty>);
+
+///Load the constructor fields of a struct, then the fields and methods declared in its body, along with what its `with` clause names. A class may also have a base class.
+fn load_members(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Members>, ()>{
+    let mut fields = vec![];
+    let mut methods = vec![];
+    let mut base = None;
+    let mut with = vec![];
+    loop{
+        let private = load_private(chunk, typeck)?;
+        let modifier = load_modifier(chunk, typeck)?;
+        match chunk.read_instruction() as Option<HIRInstruction>{
+            Some(HIRInstruction::Field) => match Field::load(chunk, typeck){
+                Ok(Some(field)) => fields.push(Field{
+                    private,
+                    ..field
+                }),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            },
+            Some(HIRInstruction::EndParams) => continue,
+            Some(HIRInstruction::Property) => match Property::load(chunk, typeck){
+                Ok(Some(property)) => fields.push(Field{
+                    ident: property.ident,
+                    ty: property.ty,
+                    mutable: property.mutable,
+                    init: Some(property.expr),
+                    private,
+                    pos: property.pos
+                }),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            },
+            Some(HIRInstruction::Fn) => match Fun::load(chunk, typeck){
+                Ok(Some(fun)) => methods.push(Fun{
+                    modifier: modifier.unwrap_or(Modifier::Final),
+                    ..fun
+                }),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            },
+            Some(HIRInstruction::Base) => match Base::load(chunk, typeck){
+                Ok(Some(loaded)) => base = Some(loaded),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            },
+            Some(HIRInstruction::With) => match Ty::load(chunk, typeck){
+                Ok(Some(ty)) => with.push(ty),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            },
+            Some(HIRInstruction::EndStruct) => return Ok(Some((fields, methods, base, with))),
+            ins => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(format!("Expected a field or a method of a struct but instead got {:?}", ins))
+                    .build();
+                typeck.emit_diagnostic(&["This is a bug in the compiler.".to_string()], &[diag_source]);
+                return Err(())
+            }
+        }
+    }
+}
+
+impl Load for Base{
+    type Output = Base;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let ident = match Identifier::load(chunk, typeck){
+            Ok(Some(ident)) => ident,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let mut args = vec![];
+        loop{
+            if let Some(HIRInstruction::EndCall) = chunk.read_instruction() as Option<HIRInstruction>{
+                break;
+            }
+            chunk.dec_ins_ptr(1);
+            match Expr::load(chunk, typeck){
+                Ok(Some(arg)) => args.push(arg),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            }
+        }
+        Ok(Some(Base{
+            pos: ident.pos,
+            ident,
+            args
+        }))
+    }
+}
+
+impl Load for Field{
+    type Output = Field;
+
+    fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
+        let pos = match chunk.read_pos(){
+            Ok(pos) => pos,
+            Err(msg) => {
+                let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
+                    .level(DiagnosticLevel::Error)
+                    .message(msg)
+                    .build();
+                typeck.emit_diagnostic(&[], &[diag_source]);
+                return Err(())
+            }
+        };
+        let mutable = match Mutability::load(chunk, typeck){
+            Ok(Some(mutable)) => mutable,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let ident = match Identifier::load(chunk, typeck){
+            Ok(Some(ident)) => ident,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        let ty = match Ty::load(chunk, typeck){
+            Ok(Some(ty)) => ty,
+            Ok(None) => return Ok(None),
+            Err(notice) => return Err(notice)
+        };
+        Ok(Some(Field{
+            ident,
+            ty: RefCell::new(ty),
+            mutable,
+            init: None,
+            private: false,
+            pos
+        }))
+    }
+}
+
+impl<'a> Check<'a> for Struct{
+    ///Field initializers are checked with the constructor params in scope, and methods with `this` in scope.
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        if !typeck.return_tys.borrow().is_empty(){
+            let kind = if self.class.is_some(){ "Classes" }else{ "Structs" };
+            let diag_source = typeck.error_source(self.pos, format!("{} can only be declared at the top level of a module", kind))?;
+            typeck.emit_diagnostic(&[], &[diag_source]);
+            return Err(())
+        }
+        typeck.check_bounds(&self.ty_params)?;
+        check_duplicates(self, typeck)?;
+        if let Some(class) = &self.class{
+            check_class(self, class, typeck)?;
+        }
+        check_with(self, typeck)?;
+        typeck.enclosing_struct.replace(Some(self.ident.ident.clone()));
+        let result = typeck.with_ty_params(&self.ty_params, || check_members(self, typeck));
+        typeck.enclosing_struct.replace(None);
+        result
+    }
+}
+
+///Check the fields and the methods of `structure`.
+fn check_members(structure: &Struct, typeck: &Typeck) -> Result<(), ()>{
+    typeck.scopes.push();
+    for field in structure.params(){
+        typeck.scopes.declare(Symbol::immutable(field.ident.clone(), field.ty.borrow().clone()));
+    }
+    let result = structure.fields.iter().try_for_each(|field| field.check(typeck)).and_then(|_| match &structure.class{
+        Some(Class{ base: Some(base), .. }) => check_base_args(base, typeck),
+        _ => Ok(())
+    });
+    typeck.scopes.pop();
+    result?;
+    //Declared again now that the types of the fields are inferred
+    declare_struct(structure, typeck);
+    typeck.scopes.push();
+    typeck.scopes.declare(Symbol::immutable(Identifier{
+        ident: "this".to_string(),
+        pos: structure.ident.pos
+    }, struct_ty(structure, typeck)));
+    let result = structure.methods.iter().try_for_each(|method| {
+        check_method(method, typeck)?;
+        if let Some(param) = method.ty_params.first(){
+            let diag_source = typeck.error_source(param.ident.pos, "Methods cannot have type params of their own".to_string())?;
+            typeck.emit_diagnostic(&[
+                format!("Declare {} on the struct instead, like `struct {}<{}>`.", param.ident.ident, structure.ident.ident, param.ident.ident)
+            ], &[diag_source]);
+            return Err(())
+        }
+        //An abstract method has no body to check
+        match method.modifier{
+            Modifier::Abstract => Ok(()),
+            _ => method.check(typeck)
+        }
+    });
+    typeck.scopes.pop();
+    result
+}
+
+impl<'a> Check<'a> for Field{
+    ///Only fields declared in the body of a struct have anything to check, which is their initializer.
+    fn check(&self, typeck: &'a Typeck) -> Result<(), ()> {
+        let init = match &self.init{
+            Some(init) => init,
+            None => return Ok(())
+        };
+        expect(init, &self.ty.borrow());
+        init.check(typeck)?;
+        let ty = self.ty.borrow();
+        match ty.coerce(&typeck.upcast(&ty, &init.get_ty())){
+            Ok(()) => Ok(()),
+            Err(mismatch) => {
+                let found_message = format!("This initializes {} with a value of type {}", self.ident.ident, mismatch.found);
+                let expected_message = format!("But {} is declared as {} because of this", self.ident.ident, mismatch.expected);
+                typeck.emit_mismatch(&mismatch, found_message, expected_message)
+            }
+        }
+    }
+}
+
+///Every field and method of a struct must have a different name.
+fn check_duplicates(structure: &Struct, typeck: &Typeck) -> Result<(), ()>{
+    let mut declared = HashMap::<&str, BiPos>::new();
+    let names = structure.fields.iter().map(|field| &field.ident).chain(structure.methods.iter().map(|method| &method.ident));
+    for ident in names{
+        if let Some(first) = declared.insert(&ident.ident, ident.pos){
+            let duplicate_source = typeck.error_source(ident.pos, format!("{} already has a member named {}", structure.ident.ident, ident.ident))?;
+            let first_source = typeck.error_source(first, format!("{} is first declared here", ident.ident))?;
+            typeck.emit_diagnostic(&[], &[duplicate_source, first_source]);
+            return Err(())
+        }
+    }
+    Ok(())
+}
+
+///Check what a class inherits: its base class must be open, its methods must override the methods they share a name with,
+///and unless it is abstract, every abstract method it inherits must be overridden.
+fn check_class(structure: &Struct, class: &Class, typeck: &Typeck) -> Result<(), ()>{
+    let name = &structure.ident.ident;
+    if let Some(base) = &class.base{
+        let base_class = typeck.structs.borrow().get(&base.ident.ident).cloned();
+        match base_class.as_ref().map(|base_class| (base_class, &base_class.class)){
+            None => {
+                let base_source = typeck.error_source(base.pos, format!("There is no class named {} to inherit from", base.ident.ident))?;
+                typeck.emit_diagnostic(&[], &[base_source]);
+                return Err(())
+            }
+            Some((_, None)) => {
+                let base_source = typeck.error_source(base.pos, format!("Cannot inherit from {} because it is a struct", base.ident.ident))?;
+                typeck.emit_diagnostic(&[
+                    "Only classes can be inherited from.".to_string()
+                ], &[base_source]);
+                return Err(())
+            }
+            Some((base_class, Some(base_info))) if !base_info.modifier.is_open() => {
+                let base_source = typeck.error_source(base.pos, format!("Cannot inherit from {} because it is not open", base.ident.ident))?;
+                let decl_source = typeck.error_source(base_class.ident.pos, format!("{} is declared here", base.ident.ident))?;
+                typeck.emit_diagnostic(&[
+                    format!("Declare it as `open class {}` to allow inheriting from it.", base.ident.ident)
+                ], &[base_source, decl_source]);
+                return Err(())
+            }
+            _ => {}
+        }
+        if base.ident.ident == *name || typeck.inherits(&base.ident.ident, name){
+            let base_source = typeck.error_source(base.pos, format!("Class {} inherits from itself", name))?;
+            typeck.emit_diagnostic(&[], &[base_source]);
+            return Err(())
+        }
+    }
+    let ancestors = typeck.ancestors(name);
+    let inherited = |member: &str| ancestors.iter().find_map(|ancestor| {
+        let field = ancestor.fields.iter().find(|field| field.ident.ident == member).map(|field| (field.ident.clone(), None));
+        field.or_else(|| ancestor.methods.iter().find(|method| method.ident.ident == member).map(|method| (method.ident.clone(), Some(method))))
+            .map(|(ident, method)| (ancestor, ident, method))
+    });
+    for field in structure.fields.iter(){
+        if let Some((owner, ident, _)) = inherited(&field.ident.ident){
+            let field_source = typeck.error_source(field.ident.pos, format!("{} already has a member named {} inherited from {}", name, ident.ident, owner.ident.ident))?;
+            let first_source = typeck.error_source(ident.pos, format!("{} is first declared here", ident.ident))?;
+            typeck.emit_diagnostic(&[], &[field_source, first_source]);
+            return Err(())
+        }
+    }
+    for method in structure.methods.iter(){
+        let method_name = &method.ident.ident;
+        //The drop block of a class is called before those of the classes it inherits from, rather than instead of them
+        if method_name == "drop"{
+            continue
+        }
+        if method.modifier == Modifier::Abstract && class.modifier != Modifier::Abstract{
+            let method_source = typeck.error_source(method.ident.pos, format!("{} is not abstract, so it cannot have the abstract method {}", name, method_name))?;
+            typeck.emit_diagnostic(&[
+                format!("Declare it as `abstract class {}`, or give {} a body.", name, method_name)
+            ], &[method_source]);
+            return Err(())
+        }
+        let (owner, ident, overridden) = match inherited(method_name){
+            Some(found) => found,
+            None if method.modifier == Modifier::Override => {
+                let method_source = typeck.error_source(method.ident.pos, format!("{} overrides nothing, since {} inherits no method named {}", method_name, name, method_name))?;
+                typeck.emit_diagnostic(&[], &[method_source]);
+                return Err(())
+            }
+            None => continue
+        };
+        let overridden = match overridden{
+            Some(overridden) => overridden,
+            None => {
+                let method_source = typeck.error_source(method.ident.pos, format!("{} already has a member named {} inherited from {}", name, method_name, owner.ident.ident))?;
+                let first_source = typeck.error_source(ident.pos, format!("{} is first declared here", method_name))?;
+                typeck.emit_diagnostic(&[], &[method_source, first_source]);
+                return Err(())
+            }
+        };
+        if method.modifier != Modifier::Override{
+            let method_source = typeck.error_source(method.ident.pos, format!("{} hides the method {} of {}", method_name, method_name, owner.ident.ident))?;
+            let first_source = typeck.error_source(ident.pos, format!("{} is first declared here", method_name))?;
+            typeck.emit_diagnostic(&[
+                format!("Declare it as `override fun {}` to override it.", method_name)
+            ], &[method_source, first_source]);
+            return Err(())
+        }
+        if !overridden.modifier.is_open(){
+            let method_source = typeck.error_source(method.ident.pos, format!("Cannot override {} because it is not open in {}", method_name, owner.ident.ident))?;
+            let first_source = typeck.error_source(ident.pos, format!("{} is declared here", method_name))?;
+            typeck.emit_diagnostic(&[
+                format!("Declare it as `open fun {}` to allow overriding it.", method_name)
+            ], &[method_source, first_source]);
+            return Err(())
+        }
+        //An override without a return type annotation infers the return type of the method it overrides
+        if let Err(mismatch) = fun_ty(overridden).unify(&fun_ty(method)){
+            let found_message = format!("{} has type {}", method_name, mismatch.found);
+            let expected_message = format!("But it overrides {} of {}, which has type {}", method_name, owner.ident.ident, mismatch.expected);
+            return typeck.emit_mismatch(&mismatch, found_message, expected_message)
+        }
+    }
+    if class.modifier == Modifier::Abstract{
+        return Ok(())
+    }
+    //The nearest declaration of a method is the one that counts, so an abstract method is implemented when a class closer to this one overrides it
+    let mut implemented = structure.methods.iter().map(|method| method.ident.ident.clone()).collect::<HashSet<String>>();
+    for ancestor in ancestors.iter(){
+        for method in ancestor.methods.iter(){
+            if method.modifier == Modifier::Abstract && !implemented.contains(&method.ident.ident){
+                let class_source = typeck.error_source(structure.ident.pos, format!("{} must override the abstract method {} of {}", name, method.ident.ident, ancestor.ident.ident))?;
+                let method_source = typeck.error_source(method.ident.pos, format!("{} is declared here", method.ident.ident))?;
+                typeck.emit_diagnostic(&[
+                    format!("Override it with `override fun {}`, or declare {} as `abstract class {}`.", method.ident.ident, name, name)
+                ], &[class_source, method_source]);
+                return Err(())
+            }
+            implemented.insert(method.ident.ident.clone());
+        }
+    }
+    Ok(())
+}
+
+///Check the args that a class passes to the constructor of its base class, like the args of a call.
+fn check_base_args(base: &Base, typeck: &Typeck) -> Result<(), ()>{
+    let base_class = match typeck.structs.borrow().get(&base.ident.ident){
+        Some(base_class) => base_class.clone(),
+        None => return Ok(())
+    };
+    let params = base_class.params().map(|field| field.ty.borrow().clone()).collect::<Vec<Ty>>();
+    if params.len() != base.args.len(){
+        let base_source = typeck.error_source(base.pos, format!("This passes {} arguments to the constructor of {}", base.args.len(), base.ident.ident))?;
+        let decl_source = typeck.error_source(base_class.ident.pos, format!("But it takes {}", params.len()))?;
+        typeck.emit_diagnostic(&[], &[base_source, decl_source]);
+        return Err(())
+    }
+    for (arg, param) in base.args.iter().zip(params.iter()){
+        expect(arg, param);
+        arg.check(typeck)?;
+        if let Err(mismatch) = param.coerce(&typeck.upcast(param, &arg.get_ty())){
+            let found_message = format!("This argument has type {}", mismatch.found);
+            let expected_message = format!("But the constructor of {} expects {} here", base.ident.ident, mismatch.expected);
+            return typeck.emit_mismatch(&mismatch, found_message, expected_message)
+        }
+    }
+    Ok(())
+}
+
+///Register `structure` so that its members can be found, and declare its constructor in the innermost scope.
+pub(crate) fn declare_struct(structure: &Struct, typeck: &Typeck){
+    typeck.structs.borrow_mut().insert(structure.ident.ident.clone(), structure.clone());
+    let params = structure.params().map(|field| field.ty.borrow().clone()).collect::<Vec<Ty>>();
+    typeck.scopes.declare(Symbol{
+        ty_params: structure.ty_params.iter().map(|param| param.ident.ident.clone()).collect(),
+        ..Symbol::immutable(structure.ident.clone(), Ty::function(&params, &struct_ty(structure, typeck), structure.pos))
+    });
+}
+
+///The type of the values of `structure`, which is applied to its own type params if it is generic.
+fn struct_ty(structure: &Struct, typeck: &Typeck) -> Ty{
+    let args = structure.ty_params.iter().map(|param| Ty::new(TyKind::Param(param.ident.ident.clone()), param.ident.pos)).collect::<Vec<Ty>>();
+    Ty::named(vec![typeck.module_name.clone(), structure.ident.ident.clone()], &args, structure.ident.pos)
+}
+
+impl Typeck{
+    ///The member `name` of a value of type `ty`, accessed at `pos`.
+    pub(crate) fn member(&self, ty: &Ty, name: &str, pos: BiPos) -> Result<Member, ()>{
+        self.default_literal(ty)?;
+        let ty = self.deref(ty);
+        if ty.is_var(){
+            let diag_source = self.error_source(pos, "The type of this must be known before its members can be used".to_string())?;
+            self.emit_diagnostic(&[
+                "Declare the type of the value this is accessed on, like `val x: Point = ...`.".to_string()
+            ], &[diag_source]);
+            return Err(())
+        }
+        let found = ty.named_args().and_then(|(path, args)| {
+            if path.len() != 2 || path[0] != self.module_name{
+                return None
+            }
+            let structure = self.structs.borrow().get(&path[1])?.clone();
+            let args = structure.ty_params.iter().map(|param| param.ident.ident.clone()).zip(args).collect::<Vec<(String, Ty)>>();
+            //The members of a class include the members it inherits, which it may override
+            std::iter::once(structure.clone()).chain(self.ancestors(&path[1])).find_map(|structure| {
+                if let Some(field) = structure.fields.iter().find(|field| field.ident.ident == name){
+                    let owner = field.private.then(|| structure.ident.ident.clone());
+                    return Some((Member{
+                        ident: field.ident.clone(),
+                        ty: field.ty.borrow().substitute(&args),
+                        mutable: Some(field.mutable.clone()),
+                        extension: None
+                    }, owner))
+                }
+                structure.methods.iter().find(|method| method.ident.ident == name).map(|method| (Member{
+                    ident: method.ident.clone(),
+                    ty: fun_ty(method).substitute(&args),
+                    mutable: None,
+                    extension: None
+                }, None))
+            })
+        });
+        if let Some((member, Some(owner))) = &found{
+            if self.enclosing_struct.borrow().as_ref() != Some(owner){
+                let access_source = self.error_source(pos, format!("{} is private to {}", name, owner))?;
+                let field_source = self.error_source(member.ident.pos, format!("{} is declared private here", name))?;
+                self.emit_diagnostic(&[
+                    format!("Only the methods of {} can use its private fields.", owner)
+                ], &[access_source, field_source]);
+                return Err(())
+            }
+        }
+        let found = found.map(|(member, _)| member).or_else(|| self.interface_member(&ty, name));
+        //Members of the type itself come before its extensions
+        let found = match found{
+            Some(member) => Some(member),
+            None => self.extension_member(&ty, name, pos)?
+        };
+        match found{
+            Some(member) => Ok(member),
+            None => {
+                let diag_source = self.error_source(pos, format!("{} has no member named {}", ty, name))?;
+                let notes = match ty.option_inner(){
+                    Some(_) => vec![format!("The value may be None, so its members can only be accessed with `?.`, like `x?.{}`.", name)],
+                    None => vec![]
+                };
+                self.emit_diagnostic(&notes, &[diag_source]);
+                Err(())
+            }
+        }
+    }
+}
+
+impl Typeck{
+    ///The classes that the class `name` inherits from, starting with its base class. The chain ends early if it would repeat.
+    pub fn ancestors(&self, name: &str) -> Vec<Struct>{
+        let structs = self.structs.borrow();
+        let mut ancestors: Vec<Struct> = vec![];
+        let mut current = structs.get(name);
+        while let Some(Some(base)) = current.map(|structure| structure.class.as_ref().and_then(|class| class.base.as_ref())){
+            current = structs.get(&base.ident.ident);
+            match current{
+                Some(ancestor) if ancestor.ident.ident != name && !ancestors.iter().any(|seen| seen.ident.ident == ancestor.ident.ident) => ancestors.push(ancestor.clone()),
+                _ => break
+            }
+        }
+        ancestors
+    }
+
+    ///Whether the class `from` inherits from the class `to`, directly or through its base class.
+    pub fn inherits(&self, from: &str, to: &str) -> bool{
+        self.ancestors(from).iter().any(|ancestor| ancestor.ident.ident == to)
+    }
+
+    ///Check that the constructor called `name`, if that is what it refers to, does not construct an abstract class.
+    pub(crate) fn check_constructible(&self, name: &str, pos: BiPos) -> Result<(), ()>{
+        let abstract_class = self.structs.borrow().get(name).filter(|structure| match &structure.class{
+            Some(class) => class.modifier == Modifier::Abstract,
+            None => false
+        }).map(|structure| structure.ident.clone());
+        let ident = match abstract_class{
+            Some(ident) if self.scopes.lookup(name).is_some_and(|symbol| symbol.ident.pos == ident.pos) => ident,
+            _ => return Ok(())
+        };
+        let call_source = self.error_source(pos, format!("Cannot construct {} because it is abstract", name))?;
+        let decl_source = self.error_source(ident.pos, format!("{} is declared abstract here", name))?;
+        self.emit_diagnostic(&[
+            format!("Construct a class that inherits from {} instead.", name)
+        ], &[call_source, decl_source]);
+        Err(())
+    }
+
+    ///The name of the class of this module that `ty` is, if it is one.
+    fn class_name(&self, ty: &Ty) -> Option<String>{
+        let (path, _) = ty.resolve().named_args()?;
+        match path.as_slice(){
+            [module, name] if *module == self.module_name => self.structs.borrow().get(name).filter(|structure| structure.class.is_some()).map(|_| name.clone()),
+            _ => None
+        }
+    }
+
+    ///The type that a value of type `found` flows into a place of type `expected` with.
+    ///An object may be used where an object of a class it inherits from, or of an interface it implements, is expected, and it is then treated as one of those.
+    pub fn upcast(&self, expected: &Ty, found: &Ty) -> Ty{
+        let target = expected.option_inner().unwrap_or_else(|| expected.resolve());
+        let found_inner = found.option_inner();
+        let from = self.class_name(found_inner.as_ref().unwrap_or(found));
+        let to = self.class_name(&target);
+        let upcast = match (from, to){
+            (Some(from), Some(to)) if from != to && self.inherits(&from, &to) => Some(target),
+            (Some(from), None) => self.interface_named(&target).and_then(|(interface, _)| self.implemented(&from, &interface.ident.ident)),
+            _ => None
+        };
+        match (upcast, found_inner){
+            (Some(upcast), Some(_)) => Ty::option(&upcast, found.pos),
+            (Some(upcast), None) => upcast.at(found.pos),
+            (None, _) => found.clone()
+        }
+    }
+
+    ///The nearest class that the classes `a` and `b` both are, if they are classes of this module that have one in common.
+    pub fn common_class(&self, a: &Ty, b: &Ty) -> Option<Ty>{
+        let (a_name, b_name) = (self.class_name(a)?, self.class_name(b)?);
+        let b_classes = std::iter::once(b_name.clone()).chain(self.ancestors(&b_name).into_iter().map(|ancestor| ancestor.ident.ident)).collect::<Vec<String>>();
+        let common = std::iter::once(a_name.clone()).chain(self.ancestors(&a_name).into_iter().map(|ancestor| ancestor.ident.ident)).find(|class| b_classes.contains(class))?;
+        Some(Ty::new(TyKind::Named(vec![self.module_name.clone(), common]), a.pos))
+    }
+
+    ///Whether a value of the class `from` may be an object of the class `to`, which is when one of them inherits from the other.
+    pub(crate) fn may_be_class(&self, from: &Ty, to: &Ty) -> bool{
+        match (self.class_name(from), self.class_name(to)){
+            (Some(from), Some(to)) => self.inherits(&from, &to) || self.inherits(&to, &from),
+            _ => false
+        }
+    }
+}
+
+///Move the interface, struct and enum declarations in front of everything else, with the interfaces first and the structs and enums ordered so that each comes after the structs and enums it contains.
+///This way the size of every struct and enum is known before it is used. A struct or an enum that contains itself would have no size, which is an error.
+pub fn order_structs(statements: Vec<Statement>, typeck: &Typeck) -> Result<Vec<Statement>, ()>{
+    let (interfaces, statements): (Vec<Statement>, Vec<Statement>) = statements.into_iter().partition(|statement| matches!(statement.kind, StatementKind::Interface(_)));
+    let (structs, rest): (Vec<Statement>, Vec<Statement>) = statements.into_iter().partition(|statement| matches!(statement.kind, StatementKind::Struct(_) | StatementKind::Enum(_)));
+    let by_name = structs.iter().filter_map(|statement| match &statement.kind{
+        StatementKind::Struct(structure) => Some((structure.ident.ident.clone(), statement)),
+        StatementKind::Enum(enumeration) => Some((enumeration.ident.ident.clone(), statement)),
+        _ => None
+    }).collect::<HashMap<String, &Statement>>();
+    let mut ordered = vec![];
+    let mut done = HashSet::new();
+    for statement in structs.iter(){
+        visit(statement, &by_name, &mut vec![], &mut done, &mut ordered, typeck)?;
+    }
+    Ok(interfaces.into_iter().chain(ordered.into_iter().cloned()).chain(rest).collect())
+}
+
+fn visit<'s>(statement: &Statement, by_name: &HashMap<String, &'s Statement>, visiting: &mut Vec<String>, done: &mut HashSet<String>, ordered: &mut Vec<&'s Statement>, typeck: &Typeck) -> Result<(), ()>{
+    //What the struct or enum stores in place, along with the field or variant it is stored through
+    let (name, contents) = match &statement.kind{
+        StatementKind::Struct(structure) => (&structure.ident.ident, structure.fields.iter().map(|field| (field.ty.borrow().clone(), format!("field {}", field.ident.ident), field.pos)).collect::<Vec<(Ty, String, BiPos)>>()),
+        StatementKind::Enum(enumeration) => (&enumeration.ident.ident, enumeration.variants.iter().flat_map(|variant| variant.fields.iter().map(move |field| (field.clone(), format!("variant {}", variant.ident.ident), field.pos))).collect()),
+        _ => return Ok(())
+    };
+    if done.contains(name){
+        return Ok(())
+    }
+    visiting.push(name.clone());
+    //A class starts with the fields it inherits, so its base class comes first
+    if let StatementKind::Struct(Struct{ class: Some(Class{ base: Some(base), .. }), .. }) = &statement.kind{
+        if let Some(inner) = by_name.get(&base.ident.ident){
+            if !visiting.contains(&base.ident.ident){
+                visit(inner, by_name, visiting, done, ordered, typeck)?;
+            }
+        }
+    }
+    for (ty, through, pos) in contents.iter(){
+        let contained = match contained_struct(ty, typeck){
+            Some(contained) => contained,
+            None => continue
+        };
+        if visiting.contains(&contained){
+            let (kind, article) = match by_name.get(&contained).map(|statement| &statement.kind){
+                Some(StatementKind::Enum(_)) => ("Enum", "An enum"),
+                _ => ("Struct", "A struct")
+            };
+            let field_source = typeck.error_source(*pos, format!("{} {} contains itself through its {}", kind, contained, through))?;
+            typeck.emit_diagnostic(&[
+                format!("{} cannot be stored inside of itself, since it would have no end. Store a function that returns it instead.", article)
+            ], &[field_source]);
+            return Err(())
+        }
+        if let Some(inner) = by_name.get(&contained){
+            visit(inner, by_name, visiting, done, ordered, typeck)?;
+        }
+    }
+    visiting.pop();
+    done.insert(name.clone());
+    ordered.push(by_name[name]);
+    Ok(())
+}
+
+///The name of the struct or enum of this module that a value of type `ty` stores in place, if any. A nullable struct is stored in place as well.
+fn contained_struct(ty: &Ty, typeck: &Typeck) -> Option<String>{
+    let ty = ty.option_inner().unwrap_or_else(|| ty.resolve());
+    match ty.named_args(){
+        Some((path, _)) if path.len() == 2 && path[0] == typeck.module_name => Some(path[1].clone()),
+        _ => None
+    }
+}
+
+impl Unload for Struct{
+    ///A class is written as a struct is, except that it starts with `Class` and its modifier, and its fields are followed by its base class.
+    ///Then come the names of its interfaces or traits, each after a `With`.
+    ///A class then writes the modifier of each method that is not final along with the name of the method, so that the vtable of the class is known before its methods are loaded.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        match self.class{
+            Some(_) => chunk.write_instruction(HIRInstruction::Class),
+            None => chunk.write_instruction(HIRInstruction::Struct),
+        }
+        chunk.write_pos(self.pos);
+        match self.ident.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        if let Some(class) = &self.class{
+            write_modifier(&mut chunk, class.modifier, self.ident.pos);
+        }
+        for field in self.fields.iter(){
+            match field.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+        }
+        chunk.write_instruction(HIRInstruction::EndParams);
+        if let Some(Class{ base: Some(base), .. }) = &self.class{
+            match base.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+        }
+        for with in self.with.iter(){
+            if let Some((path, _)) = with.resolve().named_args(){
+                chunk.write_instruction(HIRInstruction::With);
+                chunk.write_string(path.join("::"));
+            }
+        }
+        for method in self.methods.iter().filter(|method| self.class.is_some() && method.modifier != Modifier::Final){
+            write_modifier(&mut chunk, method.modifier, method.ident.pos);
+            chunk.write_string(method.ident.ident.clone());
+        }
+        for method in self.methods.iter(){
+            match method.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+        }
+        chunk.write_instruction(HIRInstruction::EndStruct);
+        Ok(chunk)
+    }
+}
+
+fn write_modifier(chunk: &mut Chunk, modifier: Modifier, pos: BiPos){
+    match modifier{
+        Modifier::Final => return,
+        Modifier::Open => chunk.write_instruction(HIRInstruction::Open),
+        Modifier::Abstract => chunk.write_instruction(HIRInstruction::Abstract),
+        Modifier::Override => chunk.write_instruction(HIRInstruction::Override),
+        Modifier::Impl => chunk.write_instruction(HIRInstruction::Impl),
+    }
+    chunk.write_pos(pos);
+}
+
+impl Unload for Base{
+    ///The args are written like the args of a call are.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Base);
+        match self.ident.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        for arg in self.args.iter(){
+            chunk.write_bool(true);
+            match arg.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+        }
+        chunk.write_bool(false);
+        Ok(chunk)
+    }
+}
+
+impl Unload for Field{
+    ///Every field is written the same way, followed by whether it has an initializer and then the initializer.
+    fn unload(&self) -> Result<Chunk, ()> {
+        let mut chunk = Chunk::new();
+        chunk.write_instruction(HIRInstruction::Field);
+        chunk.write_pos(self.pos);
+        match self.ident.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        match self.mutable.unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        match self.ty.borrow().unload(){
+            Ok(ch) => chunk.write_chunk(ch),
+            Err(notice) => return Err(notice)
+        }
+        match &self.init{
+            Some(init) => {
+                chunk.write_bool(true);
+                match init.unload(){
+                    Ok(ch) => chunk.write_chunk(ch),
+                    Err(notice) => return Err(notice)
+                }
+            }
+            None => chunk.write_bool(false)
+        }
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    #[test]
+    fn fields_have_the_types_they_are_declared_with() {
+        assert_eq!(errors("struct Point(val x: Int, var y: Int){\n    val origin = false\n    fun sum(): Int = this.x + this.y\n}\nfun f(p: Point): Bool{\n    p.y = p.sum()\n    return p.origin\n}"), Vec::<String>::new());
+        assert_eq!(errors("struct Point(val x: Int, var y: Int)\nfun f(p: Point): String{\n    return p.x\n}"), vec![
+            format!("This returns a value of type Int"),
+            format!("But the function is expected to return String"),
+        ]);
+        assert_eq!(errors("struct Point(val x: Int)\nfun f(p: Point): Int = p.z"), vec![
+            format!("test::Point has no member named z"),
+        ]);
+        assert_eq!(errors("struct Point(val x: Int)\nfun f(p: Point){\n    p.x = 2\n}"), vec![
+            format!("Cannot assign to x because it is declared with val"),
+            format!("x is declared here"),
+        ]);
+    }
+
+    #[test]
+    fn constructors_take_the_fields_without_initializers() {
+        assert_eq!(errors("struct Point(val x: Int, var y: Int){\n    val z = 0\n}\nfun f(): Point = Point(1, 2)"), Vec::<String>::new());
+        assert_eq!(errors("struct Point(val x: Int, var y: Int)\nfun f(): Point = Point(1)"), vec![
+            format!("This call passes 1 arguments"),
+            format!("But (Int, Int) -> test::Point takes 2"),
+        ]);
+    }
+
+    #[test]
+    fn structs_cannot_contain_themselves() {
+        assert_eq!(errors("struct Node(val value: Int, val next: Node)"), vec![
+            format!("Struct Node contains itself through its field next"),
+        ]);
+        assert_eq!(errors("struct Node(val value: Int, val next: () -> Node)"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn private_fields_can_only_be_used_by_their_own_struct() {
+        assert_eq!(errors("struct Box<T>(private val value: T){\n    private var count = 0\n    fun get(): T = this.value\n    fun counted(other: Box<T>): Int = other.count\n}\nfun f(b: Box<Int>): Int = b.get()"), Vec::<String>::new());
+        assert_eq!(errors("struct Box<T>(private val value: T)\nfun f(b: Box<Int>): Int = b.value"), vec![
+            format!("value is private to Box"),
+            format!("value is declared private here"),
+        ]);
+        assert_eq!(errors("struct Counter(private val count: Int)\nfun Counter.peek(): Int = this.count"), vec![
+            format!("count is private to Counter"),
+            format!("count is declared private here"),
+        ]);
+    }
+
+    const SHAPES: &str = "abstract class Shape(val sides: Int){\n    abstract fun area(): Int\n    open fun name(): String = \"shape\"\n    fun count(): Int = this.sides\n}\n";
+
+    #[test]
+    fn classes_only_inherit_from_open_classes() {
+        assert_eq!(errors("class Base(val x: Int)\nclass Derived(val y: Int) : Base(1)"), vec![
+            format!("Cannot inherit from Base because it is not open"),
+            format!("Base is declared here"),
+        ]);
+    }
+
+    #[test]
+    fn methods_override_the_open_methods_they_share_a_name_with() {
+        let square = |body: &str| format!("{}class Square(val side: Int) : Shape(4){{\n    override fun area(): Int = this.side * this.side\n{}}}", SHAPES, body);
+        assert_eq!(errors(&square("    override fun name(): String = \"square\"\n")), Vec::<String>::new());
+        assert_eq!(errors(&square("    fun name(): String = \"square\"\n")), vec![
+            format!("name hides the method name of Shape"),
+            format!("name is first declared here"),
+        ]);
+        assert_eq!(errors(&square("    override fun count(): Int = 4\n")), vec![
+            format!("Cannot override count because it is not open in Shape"),
+            format!("count is declared here"),
+        ]);
+    }
+
+    #[test]
+    fn abstract_methods_must_be_overridden() {
+        assert_eq!(errors(&format!("{}class Square(val side: Int) : Shape(4)", SHAPES)), vec![
+            format!("Square must override the abstract method area of Shape"),
+            format!("area is declared here"),
+        ]);
+        assert_eq!(errors(&format!("{}fun f(): Shape = Shape(3)", SHAPES)), vec![
+            format!("Cannot construct Shape because it is abstract"),
+            format!("Shape is declared abstract here"),
+        ]);
+    }
+
+    #[test]
+    fn objects_are_upcast_to_the_classes_they_inherit_from() {
+        let classes = "open class Base(val x: Int)\nclass Derived(val y: Int) : Base(1)\nclass Other(val z: Int) : Base(2)\n";
+        assert_eq!(errors(&format!("{}fun f(){{\n    val a: Base = Derived(2)\n    val b: Base? = Derived(3)\n    val c: Int = a.x\n}}", classes)), Vec::<String>::new());
+        assert_eq!(errors(&format!("{}fun f(c: Bool): Int{{\n    val a = if(c) Derived(2) else Other(3)\n    return a.x\n}}", classes)), Vec::<String>::new());
+        assert_eq!(errors(&format!("{}fun f(b: Base): Derived = b", classes)), vec![
+            format!("The function body evaluates to a value of type test::Base"),
+            format!("But the function is expected to return test::Derived"),
+        ]);
+    }
+}
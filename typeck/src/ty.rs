@@ -13,6 +13,7 @@ use ir_traits::{ WriteInstruction, ReadInstruction };
 
 use ty::{
     Ty,
+    TyKind,
     TyValueElement,
     TyValue
 };
@@ -27,24 +28,74 @@ pub trait Inference{
 }
 
 impl Unload for Ty{
+    ///The position of the type followed by its structure, with every inferred type variable substituted.
+    ///Type variables that were never inferred are written as `Unknown`.
     fn unload(&self) -> Result<Chunk, ()> {
         let mut chunk = Chunk::new();
         chunk.write_pos(self.pos);
-        match self.ident.clone().as_str(){
-            "Int" => chunk.write_instruction(HIRInstruction::Integer),
-            "Float" => chunk.write_instruction(HIRInstruction::Float),
-            "Bool" => chunk.write_instruction(HIRInstruction::Bool),
-            "String" => chunk.write_instruction(HIRInstruction::String),
-            _ => chunk.write_instruction(HIRInstruction::Custom),
-        }
-        chunk.write_string(self.ident.clone());
+        unload_kind(&self.resolve().kind(), &mut chunk);
         Ok(chunk)
     }
 }
 
+fn unload_kind(kind: &TyKind, chunk: &mut Chunk){
+    match kind{
+        TyKind::Unit => chunk.write_instruction(HIRInstruction::Unit),
+        TyKind::Bool => chunk.write_instruction(HIRInstruction::Bool),
+        TyKind::Int => chunk.write_instruction(HIRInstruction::Integer),
+        TyKind::Float => chunk.write_instruction(HIRInstruction::Float),
+        TyKind::String => chunk.write_instruction(HIRInstruction::String),
+        TyKind::Int8 => chunk.write_instruction(HIRInstruction::Int8),
+        TyKind::Int16 => chunk.write_instruction(HIRInstruction::Int16),
+        TyKind::Int64 => chunk.write_instruction(HIRInstruction::Int64),
+        TyKind::UInt8 => chunk.write_instruction(HIRInstruction::UInt8),
+        TyKind::UInt16 => chunk.write_instruction(HIRInstruction::UInt16),
+        TyKind::UInt32 => chunk.write_instruction(HIRInstruction::UInt32),
+        TyKind::UInt64 => chunk.write_instruction(HIRInstruction::UInt64),
+        TyKind::Float64 => chunk.write_instruction(HIRInstruction::Float64),
+        TyKind::Char => chunk.write_instruction(HIRInstruction::Char),
+        TyKind::Named(path) => {
+            chunk.write_instruction(HIRInstruction::Custom);
+            chunk.write_string(path.join("::"));
+        }
+        TyKind::App(path, args) => {
+            chunk.write_instruction(HIRInstruction::TyApp);
+            chunk.write_string(path.join("::"));
+            for arg in args.iter(){
+                chunk.write_instruction(HIRInstruction::FnParam);
+                unload_kind(arg, chunk);
+            }
+            chunk.write_instruction(HIRInstruction::EndParams);
+        }
+        TyKind::Function(params, ret) => {
+            chunk.write_instruction(HIRInstruction::FnType);
+            for param in params.iter(){
+                chunk.write_instruction(HIRInstruction::FnParam);
+                unload_kind(param, chunk);
+            }
+            chunk.write_instruction(HIRInstruction::EndParams);
+            unload_kind(ret, chunk);
+        }
+        TyKind::Option(inner) => {
+            chunk.write_instruction(HIRInstruction::Nullable);
+            unload_kind(inner, chunk);
+        }
+        TyKind::Ref(term) => {
+            chunk.write_instruction(HIRInstruction::Reference);
+            chunk.write_string(term.clone());
+        }
+        TyKind::Param(name) => {
+            chunk.write_instruction(HIRInstruction::Custom);
+            chunk.write_string(name.clone());
+        }
+        TyKind::Var(_) => chunk.write_instruction(HIRInstruction::Unknown),
+    }
+}
+
 impl Load for Ty{
     type Output = Ty;
 
+    ///Load a type annotation. A missing annotation becomes a fresh type variable.
     fn load(chunk: &Chunk, typeck: &Typeck) -> Result<Option<Self::Output>, ()> {
         let pos = match chunk.read_pos(){
             Ok(pos) => pos,
@@ -58,15 +109,69 @@ impl Load for Ty{
             }
         };
         let ins = chunk.read_instruction() as Option<HIRInstruction>;
-        let ident = match ins{
-            Some(type_) => {
-                if type_ == HIRInstruction::Custom{
-                    chunk.read_string().to_string()
+        let ty = match ins{
+            Some(HIRInstruction::FnType) => {
+                let mut params = vec![];
+                while let Some(HIRInstruction::FnParam) = chunk.read_instruction() as Option<HIRInstruction>{
+                    match Ty::load(chunk, typeck){
+                        Ok(Some(param)) => params.push(param),
+                        Ok(None) => return Ok(None),
+                        Err(notice) => return Err(notice)
+                    }
+                }
+                let ret = match Ty::load(chunk, typeck){
+                    Ok(Some(ret)) => ret,
+                    Ok(None) => return Ok(None),
+                    Err(notice) => return Err(notice)
+                };
+                Ty::function(&params, &ret, pos)
+            }
+            Some(HIRInstruction::Nullable) => match Ty::load(chunk, typeck){
+                Ok(Some(inner)) => Ty::option(&inner, pos),
+                Ok(None) => return Ok(None),
+                Err(notice) => return Err(notice)
+            },
+            Some(HIRInstruction::Unknown) => Ty::var(pos),
+            Some(HIRInstruction::Unit) => Ty::new(TyKind::Unit, pos),
+            Some(HIRInstruction::Bool) => Ty::new(TyKind::Bool, pos),
+            Some(HIRInstruction::Integer) => Ty::new(TyKind::Int, pos),
+            Some(HIRInstruction::Float) => Ty::new(TyKind::Float, pos),
+            Some(HIRInstruction::String) => Ty::new(TyKind::String, pos),
+            Some(HIRInstruction::Int8) => Ty::new(TyKind::Int8, pos),
+            Some(HIRInstruction::Int16) => Ty::new(TyKind::Int16, pos),
+            Some(HIRInstruction::Int64) => Ty::new(TyKind::Int64, pos),
+            Some(HIRInstruction::UInt8) => Ty::new(TyKind::UInt8, pos),
+            Some(HIRInstruction::UInt16) => Ty::new(TyKind::UInt16, pos),
+            Some(HIRInstruction::UInt32) => Ty::new(TyKind::UInt32, pos),
+            Some(HIRInstruction::UInt64) => Ty::new(TyKind::UInt64, pos),
+            Some(HIRInstruction::Float64) => Ty::new(TyKind::Float64, pos),
+            Some(HIRInstruction::Char) => Ty::new(TyKind::Char, pos),
+            Some(HIRInstruction::Custom) => {
+                let name = chunk.read_string().to_owned();
+                if typeck.ty_params.borrow().contains(&name){
+                    Ty::new(TyKind::Param(name), pos)
                 }else{
-                    format!("{:?}", type_)
+                    Ty::new(TyKind::Named(vec![typeck.module_name.clone(), name]), pos)
+                }
+            }
+            Some(HIRInstruction::TyApp) => {
+                let name = chunk.read_string().to_owned();
+                let mut args = vec![];
+                while let Some(HIRInstruction::FnParam) = chunk.read_instruction() as Option<HIRInstruction>{
+                    match Ty::load(chunk, typeck){
+                        Ok(Some(arg)) => args.push(arg),
+                        Ok(None) => return Ok(None),
+                        Err(notice) => return Err(notice)
+                    }
+                }
+                //`Option<T>` is another way to write `T?`
+                match (name.as_str(), args.as_slice()){
+                    ("Option", [inner]) => Ty::option(inner, pos),
+                    ("Result", _) | ("Range", _) => Ty::app(&name, &args, pos),
+                    _ => Ty::named(vec![typeck.module_name.clone(), name], &args, pos)
                 }
             }
-            None => {
+            ins => {
                 let source = match typeck.request_source_snippet(pos){
                     Ok(source) => source,
                     Err(msg) => {
@@ -76,23 +181,20 @@ impl Load for Ty{
                 };
                 let diag_source = DiagnosticSourceBuilder::new(typeck.module_name.clone(), 0)
                     .level(DiagnosticLevel::Error)
-                    .message(format!("Expected a param type annotation but instead got none. This is a bug in the compiler."))
+                    .message(format!("Expected a type annotation but instead got {:?}. This is a bug in the compiler.", ins))
                     .source(source)
                     .build();
                 typeck.emit_diagnostic(&[], &[diag_source]);
                 return Err(())
             }
         };
-        Ok(Some(Ty{
-            ident,
-            pos
-        }))
+        Ok(Some(ty))
     }
 }
 
 ///A trait that provides a method called `get_ty` which is a convenience method for quickly getting an IR element's type info.
 pub trait GetTy{
-    fn get_ty(&self) -> &Ty;
+    fn get_ty(&self) -> Ty;
 }
 
 impl Unload for TyValueElement{
@@ -105,11 +207,15 @@ impl Unload for TyValueElement{
             }
             TyValueElement::Integer(i) => {
                 chunk.write_instruction(HIRInstruction::Integer);
-                chunk.write_int(*i);
+                chunk.write_long(*i);
             }
             TyValueElement::Float(f) => {
                 chunk.write_instruction(HIRInstruction::Float);
-                chunk.write_float(*f);
+                chunk.write_double(*f);
+            }
+            TyValueElement::Char(c) => {
+                chunk.write_instruction(HIRInstruction::Char);
+                chunk.write_char(*c);
             }
             TyValueElement::String(s) => {
                 chunk.write_instruction(HIRInstruction::String);
@@ -122,6 +228,9 @@ impl Unload for TyValueElement{
             TyValueElement::Unit => {
                 chunk.write_instruction(HIRInstruction::Unit);
             }
+            TyValueElement::None => {
+                chunk.write_instruction(HIRInstruction::None);
+            }
             TyValueElement::Ref(term) => {
                 chunk.write_instruction(HIRInstruction::Reference);
                 chunk.write_string(term.clone());
@@ -134,6 +243,7 @@ impl Unload for TyValueElement{
 
 
 impl Unload for TyValue{
+    ///Number literals are followed by the type they were inferred as, since that decides their width.
     fn unload(&self) -> Result<Chunk, ()> {
         let mut chunk = Chunk::new();
         let tyval_chunk = match self.elem.unload(){
@@ -141,6 +251,12 @@ impl Unload for TyValue{
             Err(notice) => return Err(notice)
         };
         chunk.write_chunk(tyval_chunk);
+        if let TyValueElement::Integer(_) | TyValueElement::Float(_) = self.elem{
+            match self.ty.unload(){
+                Ok(ch) => chunk.write_chunk(ch),
+                Err(notice) => return Err(notice)
+            }
+        }
         Ok(chunk)
     }
 }
\ No newline at end of file
@@ -0,0 +1,307 @@
+use super::Typeck;
+
+use core::pos::BiPos;
+
+use ty::{
+    Ty,
+    TyKind,
+    TyValueElement,
+    Mismatch,
+};
+
+///A check on a type that has to wait until the type has been inferred.
+#[derive(Debug, Clone)]
+pub enum Obligation{
+    ///The type of the lambda param `ident` must be inferred by the end of the top level statement that declares it.
+    Inferred{
+        ident: String,
+        ty: Ty,
+    },
+    ///The operands of the operator `op`, which have been unified with each other, must be numeric.
+    ///`+` may also be applied to Strings.
+    Operand{
+        op: &'static str,
+        ty: Ty,
+        allows_string: bool,
+        pos: BiPos,
+    },
+    ///A number literal must be inferred as a number type that can hold it. Literals that are not inferred at all become Int or Float.
+    Literal{
+        value: TyValueElement,
+        ty: Ty,
+        pos: BiPos,
+    },
+    ///A cast of an expression whose type was not known yet, see [Typeck::check_cast].
+    Cast{
+        from: Ty,
+        to: Ty,
+        pos: BiPos,
+    },
+}
+
+///The type of a number literal that nothing else decides the type of.
+fn default_kind(value: &TyValueElement) -> TyKind{
+    match value{
+        TyValueElement::Float(_) => TyKind::Float,
+        _ => TyKind::Int
+    }
+}
+
+impl Typeck{
+    ///Unify `expected` with `found`. When they cannot be unified, both conflicting types are reported where they originate from.
+    pub(crate) fn unify(&self, expected: &Ty, found: &Ty) -> Result<(), ()>{
+        let (expected, found) = self.deref_both(expected, found);
+        self.report_mismatch(expected.unify(&found))
+    }
+
+    ///The type of the object `x` that a reference of type `r(x)` binds to, since a reference can be used wherever its object can. Any other type is its own.
+    pub fn deref(&self, ty: &Ty) -> Ty{
+        let mut ty = ty.resolve();
+        let mut seen = vec![];
+        while let TyKind::Ref(term) = ty.kind().as_ref(){
+            //A reference that binds to itself through shadowing has nothing further to bind to
+            if seen.contains(term){
+                break
+            }
+            seen.push(term.clone());
+            ty = match self.scopes.lookup(term){
+                Some(symbol) => symbol.ty.resolve().at(ty.pos),
+                None => break
+            };
+        }
+        ty
+    }
+
+    ///Dereference both `expected` and `found` before they are unified, unless either of them is yet to be inferred, which is then inferred as the reference type itself.
+    fn deref_both(&self, expected: &Ty, found: &Ty) -> (Ty, Ty){
+        if expected.resolve().is_var() || found.resolve().is_var(){
+            return (expected.clone(), found.clone())
+        }
+        (self.deref(expected), self.deref(found))
+    }
+
+    fn report_mismatch(&self, result: Result<(), Mismatch>) -> Result<(), ()>{
+        match result{
+            Ok(()) => Ok(()),
+            Err(mismatch) => {
+                let found_message = format!("This has type {}", mismatch.found);
+                let expected_message = format!("But {} is expected because of this", mismatch.expected);
+                self.emit_mismatch(&mismatch, found_message, expected_message)
+            }
+        }
+    }
+
+    ///Report `mismatch` with a message at the origin of each type. A type that would contain itself is always reported the same way.
+    pub(crate) fn emit_mismatch(&self, mismatch: &Mismatch, found_message: String, expected_message: String) -> Result<(), ()>{
+        if mismatch.infinite{
+            let found_source = self.error_source(mismatch.found.pos, format!("Cannot infer an infinite type: {} would have to be {}", mismatch.expected, mismatch.found))?;
+            let expected_source = self.error_source(mismatch.expected.pos, format!("{} is inferred from here", mismatch.expected))?;
+            self.emit_diagnostic(&[
+                "A type cannot contain itself.".to_string()
+            ], &[found_source, expected_source]);
+            return Err(())
+        }
+        let found_source = self.error_source(mismatch.found.pos, found_message)?;
+        let expected_source = self.error_source(mismatch.expected.pos, expected_message)?;
+        self.emit_diagnostic(&[], &[found_source, expected_source]);
+        Err(())
+    }
+
+    ///Wait to check `obligation` until the end of the current top level statement.
+    pub fn defer(&self, obligation: Obligation){
+        self.obligations.borrow_mut().push(obligation);
+    }
+
+    ///Whether `ty` is the type of a number literal whose width has not been inferred yet.
+    pub fn is_number_literal(&self, ty: &Ty) -> bool{
+        ty.is_var() && self.obligations.borrow().iter().any(|obligation| match obligation{
+            Obligation::Literal{ ty: literal, .. } => literal == ty,
+            _ => false
+        })
+    }
+
+    ///Give every number literal deferred since there were `len` obligations, whose type is still not inferred, its default type of Int or Float.
+    ///This is done once the declaration that owns the literals is checked, so that later uses of the declaration cannot change what the literals are.
+    pub(crate) fn default_literals(&self, len: usize) -> Result<(), ()>{
+        let literals = self.obligations.borrow().iter().skip(len).filter_map(|obligation| match obligation{
+            Obligation::Literal{ value, ty, pos } if ty.is_var() => Some((ty.clone(), Ty::new(default_kind(value), *pos))),
+            _ => None
+        }).collect::<Vec<(Ty, Ty)>>();
+        for (ty, default) in literals.iter(){
+            self.unify(ty, default)?;
+        }
+        Ok(())
+    }
+
+    ///Give `ty` its default type if it is the type of a number literal that has not been inferred yet, since it is about to be used as it is.
+    pub(crate) fn default_literal(&self, ty: &Ty) -> Result<(), ()>{
+        let default = self.obligations.borrow().iter().find_map(|obligation| match obligation{
+            Obligation::Literal{ value, ty: literal, pos } if literal.is_var() && literal == ty => Some(Ty::new(default_kind(value), *pos)),
+            _ => None
+        });
+        match default{
+            Some(default) => self.unify(ty, &default),
+            None => Ok(())
+        }
+    }
+
+    ///Check every number literal whose type has been inferred by now, so that it is reported even when a later error stops the checking of its declaration.
+    pub(crate) fn check_inferred_literals(&self) -> Result<(), ()>{
+        let (inferred, pending) = self.obligations.replace(vec![]).into_iter().partition::<Vec<Obligation>, _>(|obligation| match obligation{
+            Obligation::Literal{ ty, .. } => !ty.is_var(),
+            _ => false
+        });
+        self.obligations.replace(pending);
+        for obligation in inferred.iter(){
+            if let Obligation::Literal{ value, ty, pos } = obligation{
+                self.check_literal(value, ty, *pos)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///Check every obligation deferred since the last discharge, now that the types in them are inferred as far as they can be.
+    pub(crate) fn discharge(&self) -> Result<(), ()>{
+        let obligations = self.obligations.replace(vec![]);
+        //Literals that nothing decided the type of take the default width first, since other types may have been inferred from them
+        for obligation in obligations.iter(){
+            if let Obligation::Literal{ value, ty, pos } = obligation{
+                if ty.is_var(){
+                    self.unify(ty, &Ty::new(default_kind(value), *pos))?;
+                }
+            }
+        }
+        for obligation in obligations.iter(){
+            match obligation{
+                Obligation::Inferred{ ident, ty } if ty.has_vars() => {
+                    let diag_source = self.error_source(ty.pos, format!("Cannot infer the type of the lambda param {}", ident))?;
+                    self.emit_diagnostic(&[format!("Give it a type annotation, like `{{ {}: Int -> ... }}`", ident)], &[diag_source]);
+                    return Err(())
+                }
+                Obligation::Operand{ op, ty, allows_string, pos } => {
+                    if ty.is_var() || ty.is_numeric() || (*allows_string && *ty.kind() == TyKind::String){
+                        continue
+                    }
+                    let resolved = ty.resolve();
+                    let op_source = self.error_source(*pos, format!("Operator '{}' cannot be applied to type {}", op, resolved))?;
+                    let ty_source = self.error_source(resolved.pos, format!("{} is inferred from here", resolved))?;
+                    self.emit_diagnostic(&[], &[op_source, ty_source]);
+                    return Err(())
+                }
+                Obligation::Literal{ value, ty, pos } => self.check_literal(value, ty, *pos)?,
+                Obligation::Cast{ from, to, pos } => self.check_cast(from, to, *pos)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    ///Check that the number literal `value` at `pos` fits in the type `ty` that it was inferred as.
+    fn check_literal(&self, value: &TyValueElement, ty: &Ty, pos: BiPos) -> Result<(), ()>{
+        let resolved = ty.resolve();
+        let does_not_fit = format!("This literal does not fit in {}", resolved);
+        let (message, notes) = match value{
+            TyValueElement::Integer(int) => match resolved.int_bounds(){
+                Some((min, max)) if min <= *int as i128 && *int as i128 <= max => return Ok(()),
+                Some((min, max)) => (does_not_fit, vec![format!("{} holds values from {} to {}.", resolved, min, max)]),
+                None if resolved.is_float() => (format!("This integer literal cannot have type {}", resolved), vec![format!("Write it as a float literal instead, like `{}.0`.", int)]),
+                None => (format!("This integer literal cannot have type {}", resolved), vec![])
+            },
+            TyValueElement::Float(float) => match resolved.kind().as_ref(){
+                TyKind::Float if !(*float as f32).is_finite() => (does_not_fit, vec![format!("Float is a 32 bit float, use Float64 for larger values.")]),
+                TyKind::Float | TyKind::Float64 => return Ok(()),
+                _ => (format!("This float literal cannot have type {}", resolved), vec![])
+            },
+            _ => return Ok(())
+        };
+        let literal_source = self.error_source(pos, message)?;
+        let ty_source = self.error_source(resolved.pos, format!("{} is expected because of this", resolved))?;
+        self.emit_diagnostic(&notes, &[literal_source, ty_source]);
+        Err(())
+    }
+
+    ///Check that a value of type `from` can be converted to `to` with `as`. Numbers convert to each other, and chars convert to and from integers.
+    pub(crate) fn check_cast(&self, from: &Ty, to: &Ty, pos: BiPos) -> Result<(), ()>{
+        let is_char = |ty: &Ty| *ty.kind() == TyKind::Char;
+        let castable = from == to
+            || (from.is_numeric() && to.is_numeric())
+            || (is_char(from) && to.is_integral())
+            || (from.is_integral() && is_char(to));
+        if castable{
+            return Ok(())
+        }
+        let resolved = from.resolve();
+        let cast_source = self.error_source(pos, format!("Cannot cast {} to {}", resolved, to))?;
+        let from_source = self.error_source(resolved.pos, format!("This has type {}", resolved))?;
+        self.emit_diagnostic(&[
+            "Only numbers can be cast with `as`, and chars can only be cast to and from integers.".to_string()
+        ], &[cast_source, from_source]);
+        Err(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<String>{
+        Typeck::check_source(source).1
+    }
+
+    #[test]
+    fn literals_are_inferred_from_where_they_are_used() {
+        assert_eq!(errors("fun f(): Int8{\n    let x: Int8 = 1\n    val y = x + 2\n    let z: Float64 = 2.5 * 2.0\n    return y\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun f(){\n    let x: Int8 = 300\n}"), vec![
+            format!("This literal does not fit in Int8"),
+            format!("Int8 is expected because of this"),
+        ]);
+    }
+
+    #[test]
+    fn literals_of_locals_default_once_the_local_is_declared() {
+        assert_eq!(errors("fun f(){\n    let mut x = 1\n    x = \"s\"\n}"), vec![
+            format!("This value has type String"),
+            format!("But x is declared as Int because of this"),
+        ]);
+        assert_eq!(errors("fun Int.double(): Int = this * 2\nfun f(): Int{\n    let three = 3\n    return three.double()\n}"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn literals_do_not_take_the_type_of_operands_that_are_not_numbers() {
+        assert_eq!(errors("fun f(n: Int?): Int{\n    return n + 1\n}"), vec![
+            format!("Operator '+' cannot be applied to types Int? and Int"),
+            format!("This has type Int?"),
+            format!("This has type Int"),
+        ]);
+    }
+
+    #[test]
+    fn literals_are_checked_before_later_errors_stop_the_function() {
+        assert_eq!(errors("fun f(e: Int64){\n    let a: Int8 = 300\n    let g: Int32 = e\n}"), vec![
+            format!("This literal does not fit in Int8"),
+            format!("Int8 is expected because of this"),
+        ]);
+    }
+
+    #[test]
+    fn only_numbers_and_chars_can_be_cast() {
+        assert_eq!(errors("fun f(a: Int8, c: Char): Float64{\n    val n = c as Int32\n    val d = n as Char\n    return a as Float64\n}"), Vec::<String>::new());
+        assert_eq!(errors("fun f(s: String): Int{\n    return s as Int\n}"), vec![
+            format!("Cannot cast String to Int"),
+            format!("This has type String"),
+        ]);
+        assert_eq!(errors("fun f(c: Char): Float{\n    return c as Float\n}"), vec![
+            format!("Cannot cast Char to Float"),
+            format!("This has type Char"),
+        ]);
+    }
+
+    #[test]
+    fn numbers_of_different_widths_are_never_converted_implicitly() {
+        assert_eq!(errors("fun f(a: Int8, b: Int64): Int64{\n    return a + b\n}"), vec![
+            format!("Operator '+' cannot be applied to types Int8 and Int64"),
+            format!("This has type Int8"),
+            format!("This has type Int64"),
+        ]);
+    }
+}